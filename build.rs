@@ -0,0 +1,64 @@
+//! Compiles the dictionaries in src/storage into fst sets, see `storage::WordSet`,
+//! so they're built into the binary ready to look words up in, rather than hashed when Ares starts.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The English dictionaries. Every file in it is read for words, as it always has been.
+const DICTIONARIES_DIR: &str = "src/storage/dictionaries";
+/// The dictionaries of the other languages, one file per ISO 639-1 code
+const LANGUAGES_DIR: &str = "src/storage/languages";
+/// The languages other than English we ship, each behind its `lang-<code>` feature
+const LANGUAGES: [&str; 4] = ["de", "fr", "es", "it"];
+
+fn main() {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo sets OUT_DIR"));
+
+    println!("cargo:rerun-if-changed={DICTIONARIES_DIR}");
+    let mut english = Vec::new();
+    let mut entries: Vec<PathBuf> = fs::read_dir(DICTIONARIES_DIR)
+        .expect("the dictionaries directory exists")
+        .map(|entry| {
+            entry
+                .expect("the dictionaries directory is readable")
+                .path()
+        })
+        .collect();
+    entries.sort();
+    for path in entries {
+        println!("cargo:rerun-if-changed={}", path.display());
+        english.push(read(&path));
+    }
+    compile(english.iter().map(String::as_str), &out_dir.join("en.fst"));
+
+    for code in LANGUAGES {
+        let path = Path::new(LANGUAGES_DIR).join(format!("{code}.txt"));
+        println!("cargo:rerun-if-changed={}", path.display());
+        compile([read(&path).as_str()], &out_dir.join(format!("{code}.fst")));
+    }
+}
+
+/// Reads a dictionary, which must be UTF-8
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|error| {
+        panic!(
+            "{} can't be read, dictionaries must be UTF-8: {error}",
+            path.display()
+        )
+    })
+}
+
+/// Writes the whitespace separated words of the dictionaries to `out` as one fst set
+fn compile<'a>(dictionaries: impl IntoIterator<Item = &'a str>, out: &Path) {
+    // An fst set is built from its words in order, without repeats
+    let mut words: Vec<&str> = dictionaries
+        .into_iter()
+        .flat_map(str::split_ascii_whitespace)
+        .collect();
+    words.sort_unstable();
+    words.dedup();
+    let set = fst::Set::from_iter(words).expect("the words are sorted and distinct");
+    fs::write(out, set.as_fst().as_bytes())
+        .unwrap_or_else(|error| panic!("{} can't be written: {error}", out.display()));
+}
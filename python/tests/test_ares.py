@@ -0,0 +1,99 @@
+# Runs against the module maturin built, see pyproject.toml:
+#   maturin develop --features python && pytest python/tests
+import threading
+
+import pytest
+
+import ares
+
+
+def test_cracks_two_layers():
+    # "hello there general" in base64, then hexadecimal
+    outcome = ares.crack("61475673624738676447686c636d55675a3256755a584a6862413d3d", timeout=10)
+    assert outcome.status == "found"
+    assert outcome.plaintext == "hello there general"
+    assert [step["decoder"] for step in outcome.path] == ["Hexadecimal", "Base64"]
+    assert outcome.path[0]["intermediate"] == "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="
+    assert outcome.path[1]["key"] is None
+    assert outcome.elapsed >= 0
+
+
+def test_regex_and_only():
+    # "flag{zqxv_9}" in base64
+    outcome = ares.crack("ZmxhZ3t6cXh2Xzl9", regex=r"^flag\{\w+\}$", only=["base64"])
+    assert outcome.plaintext == "flag{zqxv_9}"
+    assert repr(outcome) == "CrackOutcome(status=\"found\", plaintext=\"flag{zqxv_9}\")"
+
+
+def test_wordlist(tmp_path):
+    words = tmp_path / "words.txt"
+    words.write_text("hunter2\n")
+    # "hunter2" in base64, which isn't English
+    outcome = ares.crack("aHVudGVyMg==", wordlist=words)
+    assert outcome.plaintext == "hunter2"
+
+    with pytest.raises(OSError):
+        ares.crack("aHVudGVyMg==", wordlist=tmp_path / "missing.txt")
+
+
+def test_nothing_found():
+    outcome = ares.crack("zqxv kjhg wpfd", timeout=1, regex="^flag", exclude=["caesar"])
+    assert outcome.status != "found"
+    assert outcome.plaintext is None
+    assert outcome.path == []
+
+
+def test_bad_arguments():
+    with pytest.raises(ValueError, match="base46"):
+        ares.crack("aGVsbG8=", only=["base46"])
+    with pytest.raises(ValueError):
+        ares.crack("aGVsbG8=", regex="(")
+    with pytest.raises(ValueError):
+        ares.crack("aGVsbG8=", timeout=-1)
+
+
+def test_the_gil_is_released():
+    ticks = []
+    stop = threading.Event()
+
+    def tick():
+        while not stop.is_set():
+            ticks.append(1)
+            stop.wait(0.01)
+
+    ticker = threading.Thread(target=tick)
+    ticker.start()
+    # Random looking text, so the search runs until it times out
+    ares.crack("q8Zk3vX0pL2mN7rT5yW1", timeout=1)
+    stop.set()
+    ticker.join()
+    assert len(ticks) > 10
+
+
+def test_threads_crack_with_their_own_options_at_once():
+    outcomes = {}
+
+    def crack(name, *args, **kwargs):
+        outcomes[name] = ares.crack(*args, **kwargs)
+
+    # "flag{zqxv_9}" in base64, then hexadecimal, and "hello there general" in base64
+    flag = threading.Thread(
+        target=crack, args=("flag", "5a6d78685a33743663586832587a6c39"), kwargs={"regex": r"^flag\{"}
+    )
+    hello = threading.Thread(target=crack, args=("hello", "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="))
+    threads = [flag, hello]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    assert outcomes["flag"].plaintext == "flag{zqxv_9}"
+    assert outcomes["hello"].plaintext == "hello there general"
+
+
+def test_list_decoders():
+    decoders = ares.list_decoders()
+    names = [decoder["name"] for decoder in decoders]
+    assert "Base64" in names
+    base64 = decoders[names.index("Base64")]
+    assert "base" in base64["tags"]
+    assert set(base64) == {"name", "tags", "popularity", "link", "description"}
@@ -0,0 +1,69 @@
+//! Lets someone pressing Ctrl-C, or a program using Ares, stop a search early
+//! and still get back what it found so far.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Stops a search when it's cancelled. Clones share the same flag,
+/// so one can be given to the search and another kept to cancel it with.
+/// ```
+/// use ares::cancellation::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let given_to_the_search = token.clone();
+/// token.cancel();
+/// assert!(given_to_the_search.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Whether the search should stop
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// A token which hasn't been cancelled
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Asks the search to stop. It stops before it decodes the next text.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the search has been asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Shares a flag the program already has, so setting it to true cancels the search
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(cancelled: Arc<AtomicBool>) -> Self {
+        CancellationToken { cancelled }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancellationToken;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn clones_share_the_flag() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[test]
+    fn a_shared_flag_cancels_the_token() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::from(Arc::clone(&flag));
+        flag.store(true, Ordering::Relaxed);
+        assert!(token.is_cancelled());
+    }
+}
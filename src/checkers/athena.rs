@@ -1,66 +1,291 @@
-use crate::{checkers::checker_result::CheckResult, config::get_config};
+use crate::{
+    checkers::checker_result::CheckResult,
+    config::{get_config, Config},
+};
 use lemmeknow::Identifier;
 use log::trace;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
 use super::{
-    checker_type::{Check, Checker},
+    checker_type::{Check, Checker, CheckerParams},
+    dictionary_checker::{Dictionary, Language},
     english::EnglishChecker,
-    human_checker,
+    file_signature_checker::FileSignatureChecker,
+    identifier_checker::IdentifierChecker,
     lemmeknow_checker::LemmeKnow,
+    quadgram_checker::EnglishQuadgrams,
     regex_checker::RegexChecker,
+    shannon_entropy,
+    structured_data_checker::StructuredDataChecker,
+    wordlist_checker::WordlistChecker,
 };
 
 /// Athena checker runs all other checkers
+/// and accepts the text if the most confident of them is above its confidence threshold.
+/// When it doesn't, it still gives the most confident result, as text which is nearly
+/// plaintext is worth decoding before text which is nothing like it.
 pub struct Athena;
 
-impl Check for Checker<Athena> {
-    fn new() -> Self {
+// The checkers Athena always runs are built once rather than on every check, so nothing of the config
+// they're built with is used: Athena decides with its own confidence threshold,
+// and those which use `CheckerParams` are given Athena's own when it checks.
+/// Athena's File Signature Checker
+static FILE_SIGNATURE: Lazy<Checker<FileSignatureChecker>> =
+    Lazy::new(Checker::<FileSignatureChecker>::new);
+/// Athena's LemmeKnow Checker
+static LEMMEKNOW: Lazy<Checker<LemmeKnow>> = Lazy::new(Checker::<LemmeKnow>::new);
+/// Athena's English Checker
+static ENGLISH: Lazy<Checker<EnglishChecker>> = Lazy::new(Checker::<EnglishChecker>::new);
+/// Athena's English Quadgram Checker
+static QUADGRAMS: Lazy<Checker<EnglishQuadgrams>> = Lazy::new(Checker::<EnglishQuadgrams>::new);
+/// Athena's Identifier Checker
+static IDENTIFIER: Lazy<Checker<IdentifierChecker>> = Lazy::new(Checker::<IdentifierChecker>::new);
+/// Athena's Structured Data Checker
+static STRUCTURED_DATA: Lazy<Checker<StructuredDataChecker>> =
+    Lazy::new(Checker::<StructuredDataChecker>::new);
+
+impl Checker<Athena> {
+    /// Athena with the config's confidence threshold, languages and params,
+    /// and its regex and wordlist, which turn the other checkers off
+    /// # Panics
+    /// Panics if the config's regex is invalid.
+    pub fn from_config(config: &Config) -> Self {
+        let regex = config.regex.as_deref().map(|pattern| {
+            Regex::new(pattern).unwrap_or_else(|err| panic!("Invalid regex {pattern}: {err}"))
+        });
         Checker {
-            // TODO: Update fields with proper values
             name: "Athena Checker",
             description: "Runs all available checkers",
             link: "",
             tags: vec!["athena", "all"],
             expected_runtime: 0.01,
             popularity: 1.0,
+            confidence_threshold: config.confidence_threshold,
             lemmeknow_config: Identifier::default(),
+            regex,
+            wordlist: config.wordlist.clone(),
+            languages: config
+                .languages
+                .iter()
+                .copied()
+                .map(Language::from)
+                .collect(),
+            params: config.checker_params.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Whether the user is looking for something of their own, with a regex or a wordlist,
+    /// which turns the other checkers off
+    fn user_checkers_only(&self) -> bool {
+        self.regex.is_some() || self.wordlist.is_some()
+    }
+
+    /// Checks for words of our languages. English on its own goes to the English Checker,
+    /// which knows more words than the Dictionary Checker's English.
+    fn check_languages(&self, text: &str) -> CheckResult {
+        if self.languages.iter().all(Language::is_english) {
+            return ENGLISH.check_with_params(text, &self.params);
+        }
+        Checker::<Dictionary>::new()
+            .with_languages(self.languages.clone())
+            .with_params(self.params.clone())
+            .check(text)
+    }
+
+    /// Checks the bytes a decoder produced when they aren't text.
+    /// Files like PNGs are identified by their signature, anything else is checked as lossy text.
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        // A regex or wordlist turns the other checkers off, so it doesn't look for files either
+        if !self.user_checkers_only() {
+            let mut result = FILE_SIGNATURE.check_bytes(bytes);
+            if result.confidence > self.confidence_threshold {
+                result.is_identified = true;
+                return result;
+            }
+        }
+        self.check(&String::from_utf8_lossy(bytes))
+    }
+}
+
+/// Why the text is clearly not language, or None if it might be.
+/// This only counts characters, so it takes microseconds.
+fn not_language(text: &str, params: &CheckerParams) -> Option<String> {
+    let entropy = shannon_entropy(text.as_bytes());
+    if entropy > params.max_entropy {
+        return Some(format!("its entropy is {entropy:.2} bits per byte"));
+    }
+
+    let (mut total, mut printable, mut non_whitespace, mut alphabetic) = (0, 0, 0, 0);
+    for c in text.chars() {
+        total += 1;
+        if !c.is_control() || c.is_whitespace() {
+            printable += 1;
+        }
+        if !c.is_whitespace() {
+            non_whitespace += 1;
+            if c.is_alphabetic() {
+                alphabetic += 1;
+            }
+        }
+    }
+    if (printable as f64) < total as f64 * params.min_printable_ratio {
+        return Some(format!(
+            "only {printable} of {total} characters are printable"
+        ));
+    }
+    if (alphabetic as f64) < non_whitespace as f64 * params.min_alphabetic_ratio {
+        return Some(format!(
+            "only {alphabetic} of {non_whitespace} characters are letters"
+        ));
+    }
+    None
+}
+
+impl Check for Checker<Athena> {
+    /// Athena with the settings in the config, see `from_config`
+    fn new() -> Self {
+        Self::from_config(&get_config())
+    }
+
     fn check(&self, text: &str) -> CheckResult {
-        let config = get_config();
-        // Only run regex if its in the config
-        if config.regex.is_some() {
-            trace!("running regex");
-            let regex_checker = Checker::<RegexChecker>::new();
-            let regex_result = regex_checker.check(text);
-            if regex_result.is_identified {
-                let mut check_res = CheckResult::new(&regex_checker);
-                check_res.is_identified = human_checker::human_checker(&regex_result);
-                return check_res;
+        if self.user_checkers_only() {
+            // Only run regex if its in the config
+            if let Some(regex) = &self.regex {
+                trace!("running regex");
+                let regex_result =
+                    Checker::<RegexChecker>::with_regex(Some(regex.clone())).check(text);
+                if regex_result.is_identified {
+                    return regex_result;
+                }
+            }
+            if let Some(wordlist) = &self.wordlist {
+                trace!("running wordlist");
+                let wordlist_result =
+                    Checker::<WordlistChecker>::with_wordlist(Some(wordlist.clone())).check(text);
+                if wordlist_result.is_identified {
+                    return wordlist_result;
+                }
             }
         } else {
             // In Ciphey if the user uses the regex checker all the other checkers turn off
             // This is because they are looking for one specific bit of information so will not want the other checkers
-            // TODO: wrap all checkers in oncecell so we only create them once!
-            let lemmeknow = Checker::<LemmeKnow>::new();
-            let lemmeknow_result = lemmeknow.check(text);
-            if lemmeknow_result.is_identified {
-                let mut check_res = CheckResult::new(&lemmeknow);
-                check_res.is_identified = human_checker::human_checker(&lemmeknow_result);
-                return check_res;
+            // Room for every checker, so it's only allocated once
+            let mut results = Vec::with_capacity(6);
+            // Only a few byte comparisons, so it goes first
+            results.push(FILE_SIGNATURE.check(text));
+            results.push(LEMMEKNOW.check(text));
+            // Looking for language is slow, so don't bother when the text is clearly garbage.
+            // The other checkers look for things which aren't language, so they always run.
+            match not_language(text, &self.params) {
+                Some(why) => trace!("Not looking for language in {}, {}", text, why),
+                None => {
+                    results.push(self.check_languages(text));
+                    // Catches English the dictionaries miss, like words joined by underscores
+                    results.push(QUADGRAMS.check_with_params(text, &self.params));
+                }
             }
-
-            let english = Checker::<EnglishChecker>::new();
-            let english_result = english.check(text);
-            if english_result.is_identified {
-                let mut check_res = CheckResult::new(&english);
-                check_res.is_identified = human_checker::human_checker(&english_result);
-                return check_res;
+            results.push(IDENTIFIER.check(text));
+            results.push(STRUCTURED_DATA.check(text));
+            // Ties go to the checker which ran first
+            let best = results.into_iter().reduce(|best, result| {
+                if result.confidence > best.confidence {
+                    result
+                } else {
+                    best
+                }
+            });
+            if let Some(mut best) = best {
+                if best.confidence > self.confidence_threshold {
+                    // Our threshold may be lower than the checker's own
+                    best.is_identified = true;
+                    best.text = text.to_string();
+                    trace!(
+                        "{} was the most confident at {:.2}",
+                        best.checker_name,
+                        best.confidence
+                    );
+                    return best;
+                }
+                // Not plaintext, but how close it came tells the search what's worth decoding first
+                best.is_identified = false;
+                best.text = String::new();
+                return best;
             }
         }
 
         CheckResult::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{not_language, Athena};
+    use crate::checkers::checker_type::{Check, Checker, CheckerParams};
+
+    /// Bytes from a simple generator, as Latin-1 characters, so plenty are control characters
+    fn random_bytes_string() -> String {
+        let mut state: u32 = 0x2545_f491;
+        (0..512)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                char::from((state >> 24) as u8)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_bytes_are_rejected_before_looking_for_language() {
+        let text = random_bytes_string();
+        assert!(not_language(&text, &CheckerParams::default()).is_some());
+        assert!(!Checker::<Athena>::new().check(&text).is_identified);
+    }
+
+    #[test]
+    fn english_is_passed_to_the_language_checkers() {
+        let text = "the quick brown fox jumps over the lazy dog";
+        assert!(not_language(text, &CheckerParams::default()).is_none());
+        let result = Checker::<Athena>::new().check(text);
+        assert_eq!(result.checker_name, "English Checker");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_still_reaches_the_structured_data_checker() {
+        let json = r#"{"ids": [3141, 5926, 5358, 9793], "port": 8080, "ttl": 3600}"#;
+        // Too few letters to be language, but it is still plaintext
+        assert!(not_language(json, &CheckerParams::default()).is_some());
+        let result = Checker::<Athena>::new().check(json);
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "Structured Data Checker");
+    }
+
+    #[test]
+    fn text_which_is_nearly_english_keeps_its_confidence() {
+        let result =
+            Checker::<Athena>::new().check("the gate at dawn qxzv kwpj bnrt lmzq vwxk zzqt");
+        assert!(!result.is_identified);
+        assert!(result.confidence > 0.5);
+        assert_eq!(result.checker_name, "English Checker");
+        assert!(result.text.is_empty());
+    }
+
+    #[test]
+    fn thresholds_come_from_the_params() {
+        let text = "the quick brown fox jumps over the lazy dog";
+        let strict = CheckerParams {
+            max_entropy: 3.0,
+            ..CheckerParams::default()
+        };
+        assert!(not_language(text, &strict).is_some());
+        assert!(
+            !Checker::<Athena>::new()
+                .with_params(strict)
+                .check(text)
+                .is_identified
+        );
+    }
+}
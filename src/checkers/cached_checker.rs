@@ -0,0 +1,228 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::checkers::checker_result::CheckResult;
+use log::trace;
+
+use super::CheckerTypes;
+
+/// How many verdicts we keep by default, so memory stays bounded on huge runs
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// Checks the bytes a decoder produced
+type BytesCheck = Box<dyn Fn(&[u8]) -> CheckResult + Send + Sync>;
+
+/// Remembers what the checker it wraps said about each text.
+/// Different decoder paths often end up with the same text, so in a deep search
+/// the same text would otherwise be checked over and over.
+/// Once it holds `capacity` verdicts, the oldest are forgotten first.
+pub struct CachedChecker {
+    /// Checks text we haven't seen before
+    inner: Box<dyn Fn(&str) -> CheckResult + Send + Sync>,
+    /// Checks bytes, which aren't cached as different decoders rarely produce the same binary
+    inner_bytes: BytesCheck,
+    /// The most verdicts we keep
+    capacity: usize,
+    /// The verdicts, behind a lock as decoders check text in parallel
+    cache: Mutex<Cache>,
+}
+
+/// Verdicts keyed by the hash of the text they are about
+#[derive(Default)]
+struct Cache {
+    /// The text and what the checker said about it, keyed by the hash of the text
+    verdicts: HashMap<u64, (String, CheckResult)>,
+    /// The keys, oldest first, so we know what to forget
+    order: VecDeque<u64>,
+}
+
+impl CachedChecker {
+    /// Caches up to `DEFAULT_CACHE_CAPACITY` verdicts of the checker
+    pub fn wrapping(checker: CheckerTypes) -> Self {
+        CachedChecker::with_capacity(checker, DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Caches up to `capacity` verdicts of the checker
+    pub fn with_capacity(checker: CheckerTypes, capacity: usize) -> Self {
+        let checker = Arc::new(checker);
+        let bytes_checker = Arc::clone(&checker);
+        let mut cached = CachedChecker::from_fn(move |text| checker.check(text), capacity);
+        cached.inner_bytes = Box::new(move |bytes| bytes_checker.check_bytes(bytes));
+        cached
+    }
+
+    /// Caches the verdicts of any function which checks text.
+    /// Bytes are checked as lossy text.
+    fn from_fn(
+        check: impl Fn(&str) -> CheckResult + Send + Sync + 'static,
+        capacity: usize,
+    ) -> Self {
+        let check = Arc::new(check);
+        let bytes_check = Arc::clone(&check);
+        CachedChecker {
+            inner: Box::new(move |text| check(text)),
+            inner_bytes: Box::new(move |bytes| bytes_check(&String::from_utf8_lossy(bytes))),
+            capacity,
+            cache: Mutex::new(Cache::default()),
+        }
+    }
+
+    /// Returns what the checker said about the text last time, or checks it now.
+    /// Either way the result is the same, down to the checker's name and reason.
+    pub fn check(&self, text: &str) -> CheckResult {
+        let key = hash_text(text);
+        if let Some((cached_text, result)) = self.lock().verdicts.get(&key) {
+            // Two texts can share a hash, so make sure it really is this one
+            if cached_text == text {
+                trace!("Using the cached verdict for {}", text);
+                return result.clone();
+            }
+        }
+
+        // Don't hold the lock while checking, so other decoders can use the cache
+        let result = (self.inner)(text);
+        if self.capacity > 0 {
+            let mut cache = self.lock();
+            let previous = cache
+                .verdicts
+                .insert(key, (text.to_string(), result.clone()));
+            if previous.is_none() {
+                cache.order.push_back(key);
+            }
+            while cache.order.len() > self.capacity {
+                if let Some(oldest) = cache.order.pop_front() {
+                    cache.verdicts.remove(&oldest);
+                }
+            }
+        }
+        result
+    }
+
+    /// Checks the bytes a decoder produced when they aren't text, without caching the verdict
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        (self.inner_bytes)(bytes)
+    }
+
+    /// How many verdicts are cached
+    pub fn len(&self) -> usize {
+        self.lock().verdicts.len()
+    }
+
+    /// Whether no verdicts are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Locks the cache
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache> {
+        self.cache
+            .lock()
+            .expect("the checker cache is never poisoned")
+    }
+}
+
+/// The key we cache the text's verdict under
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[cfg(feature = "parallel")]
+    use rayon::prelude::*;
+
+    use super::CachedChecker;
+    use crate::checkers::{
+        athena::Athena,
+        checker_result::CheckResult,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+
+    /// A checker which counts how often it is called, and identifies text starting with "yes"
+    fn counting_checker(capacity: usize) -> (CachedChecker, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let checker = CachedChecker::from_fn(
+            move |text| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut result = CheckResult::new(&Checker::<Athena>::new());
+                result.is_identified = text.starts_with("yes");
+                result.reason = format!("looked at {text}");
+                result
+            },
+            capacity,
+        );
+        (checker, calls)
+    }
+
+    #[test]
+    fn checks_repeated_text_once() {
+        let (checker, calls) = counting_checker(10);
+        for _ in 0..5 {
+            assert!(checker.check("yes please").is_identified);
+            assert!(!checker.check("no thanks").is_identified);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(checker.len(), 2);
+    }
+
+    #[test]
+    fn cached_result_matches_a_fresh_check() {
+        let checker = CachedChecker::wrapping(CheckerTypes::CheckAthena(Checker::<Athena>::new()));
+        let text = "the quick brown fox jumps over the lazy dog";
+        let fresh = checker.check(text);
+        let cached = checker.check(text);
+        assert!(cached.is_identified);
+        assert_eq!(cached.checker_name, fresh.checker_name);
+        assert_eq!(cached.reason, fresh.reason);
+        assert_eq!(cached.confidence, fresh.confidence);
+        assert_eq!(checker.len(), 1);
+    }
+
+    #[test]
+    fn evicts_the_oldest_verdict_when_full() {
+        let (checker, calls) = counting_checker(2);
+        checker.check("first");
+        checker.check("second");
+        checker.check("third");
+        assert_eq!(checker.len(), 2);
+
+        // "third" and "second" are still cached, "first" was forgotten
+        checker.check("third");
+        checker.check("second");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        checker.check("first");
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let (checker, calls) = counting_checker(0);
+        checker.check("yes");
+        checker.check("yes");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(checker.is_empty());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn duplicate_heavy_parallel_workload_checks_each_text_once() {
+        let (checker, calls) = counting_checker(10_000);
+        let texts: Vec<String> = (0..1_000).map(|i| format!("candidate {i}")).collect();
+        // The first round fills the cache, every round after only hits it
+        for _ in 0..10 {
+            texts.par_iter().for_each(|text| {
+                assert_eq!(checker.check(text).reason, format!("looked at {text}"));
+            });
+        }
+        assert!(calls.load(Ordering::SeqCst) <= 1_000);
+    }
+}
@@ -1,12 +1,12 @@
 use super::checker_type::Checker;
 
 /// The checkerResult struct is used to store the results of a checker.
+#[derive(Clone)]
 pub struct CheckResult {
     /// If our checkers return success, we change this bool to True
     pub is_identified: bool,
-    /// text is the text before we check it.
-    // we can make this &'text str
-    // but then crack requires lifetime annotations.
+    /// The text we identified. It's empty when we didn't identify it,
+    /// so most checks don't copy the text.
     pub text: String,
     /// Description of the checked text.
     pub description: String,
@@ -16,6 +16,13 @@ pub struct CheckResult {
     pub checker_description: &'static str,
     /// Link to more info about checker
     pub link: &'static str,
+    /// The score the checker gave the text, for checkers which score it, such as quadgrams.
+    /// Higher is more likely to be plaintext.
+    pub score: Option<f64>,
+    /// Why the checker decided the text is plaintext, such as "matched 7 of 8 words in the dictionaries"
+    pub reason: String,
+    /// How sure the checker is that the text is plaintext, from 0 to 1
+    pub confidence: f32,
 }
 
 /// To save time we have a default
@@ -33,6 +40,9 @@ impl CheckResult {
             checker_description: checker_used.description,
             description: "".to_string(),
             link: checker_used.link,
+            score: None,
+            reason: String::new(),
+            confidence: 0.0,
         }
     }
 }
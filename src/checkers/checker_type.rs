@@ -1,7 +1,12 @@
 /// Checker_type is a type used to define checkers
 /// This means that we can standardise the way we check for plaintext
 use crate::checkers::checker_result::CheckResult;
+use crate::checkers::dictionary_checker::Language;
+use crate::checkers::english::PLAINTEXT_DETECTION_PERCENTAGE;
+use crate::checkers::quadgram_checker::{ENGLISH_AVERAGE_SCORE, MIN_LETTERS};
+use crate::checkers::wordlist_checker::Wordlist;
 use lemmeknow::Identifier;
+use regex::Regex;
 
 /// Every checker is of type CheckerType
 /// This will let us pick & choose which checkers to use
@@ -23,8 +28,18 @@ pub struct Checker<Type> {
     pub expected_runtime: f32,
     /// The popularity of the checker
     pub popularity: f32,
+    /// The text is identified when the checker's confidence, from 0 to 1, is above this
+    pub confidence_threshold: f32,
     /// lemmeknow config object
     pub lemmeknow_config: Identifier,
+    /// The compiled regex, only used by the Regex Checker
+    pub regex: Option<Regex>,
+    /// The words to look for, only used by the Wordlist Checker
+    pub wordlist: Option<Wordlist>,
+    /// The languages to look for words of, only used by the Dictionary Checker and Athena
+    pub languages: Vec<Language>,
+    /// How the English and Quadgram Checkers and Athena tell English from gibberish
+    pub params: CheckerParams,
     /// https://doc.rust-lang.org/std/marker/struct.PhantomData.html
     /// Let's us save memory by telling the compiler that our type
     /// acts like a type <T> even though it doesn't.
@@ -33,6 +48,85 @@ pub struct Checker<Type> {
     pub _phantom: std::marker::PhantomData<Type>,
 }
 
+/// The confidence checkers give text at the point where they used to accept it,
+/// so with this threshold checkers accept the same text they always have
+pub const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.8;
+
+/// The tunables the English and Quadgram Checkers and Athena use to tell English from gibberish.
+/// The defaults accept the same text the checkers always have.
+/// ```
+/// use ares::checkers::checker_type::{Check, Checker, CheckerParams};
+/// use ares::checkers::athena::Athena;
+///
+/// let strict = Checker::<Athena>::new().with_params(CheckerParams {
+///     strict_short_strings: true,
+///     ..CheckerParams::default()
+/// });
+/// assert!(strict.check("Dog!").is_identified);
+/// // Stripping the dot would make it a word, which is too easy for a few characters
+/// assert!(!strict.check("d.og").is_identified);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckerParams {
+    /// Text with fewer letters than this is too short for the Quadgram Checker to score
+    pub min_statistical_length: usize,
+    /// The share of words, from 0 to 1, which must be in the dictionaries for text to be English
+    pub min_dictionary_ratio: f64,
+    /// The average log10 probability per quadgram English text scores above
+    pub quadgram_threshold: f64,
+    /// Whether text which is a single dictionary word, such as "and", counts as English
+    pub single_words_count: bool,
+    /// Strict short-string mode, where text of 1 to 4 characters is only English
+    /// if it is exactly a dictionary word, as a few characters match by chance too easily
+    pub strict_short_strings: bool,
+    /// Athena doesn't look for language in text with more bits of Shannon entropy per byte than this,
+    /// as it is too random to be language
+    pub max_entropy: f64,
+    /// Athena doesn't look for language in text where a smaller share of the characters
+    /// than this, from 0 to 1, are printable
+    pub min_printable_ratio: f64,
+    /// Athena doesn't look for language in text where a smaller share of the characters
+    /// which aren't whitespace than this, from 0 to 1, are letters
+    pub min_alphabetic_ratio: f64,
+}
+
+impl Default for CheckerParams {
+    fn default() -> Self {
+        CheckerParams {
+            min_statistical_length: MIN_LETTERS,
+            min_dictionary_ratio: PLAINTEXT_DETECTION_PERCENTAGE,
+            quadgram_threshold: ENGLISH_AVERAGE_SCORE,
+            single_words_count: true,
+            strict_short_strings: false,
+            max_entropy: 6.0,
+            min_printable_ratio: 0.9,
+            min_alphabetic_ratio: 0.25,
+        }
+    }
+}
+
+impl<Type> Checker<Type> {
+    /// Sets how confident, from 0 to 1, the checker has to be to identify the text
+    pub fn with_confidence_threshold(mut self, confidence_threshold: f32) -> Self {
+        self.confidence_threshold = confidence_threshold;
+        self
+    }
+
+    /// Looks for words of each of these languages, rather than just English.
+    /// Only the Dictionary Checker and Athena look for words.
+    pub fn with_languages(mut self, languages: Vec<Language>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Sets how the checker tells English from gibberish.
+    /// Only the English and Quadgram Checkers and Athena use them.
+    pub fn with_params(mut self, params: CheckerParams) -> Self {
+        self.params = params;
+        self
+    }
+}
+
 /// Every checker must implement this trait
 /// Which checks the given text to see if its plaintext
 /// and returns CheckResult, which is our results object.
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::checkers::checker_result::CheckResult;
+use log::trace;
+
+/// Decides if the text is plaintext, returning why it is or None if it isn't
+pub type CustomCheck = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+/// A checker made from a closure, so library users can decide what plaintext
+/// looks like without adding a checker type to Ares.
+/// Use `CheckerTypes::from_closure` to make one.
+#[derive(Clone)]
+pub struct CustomChecker {
+    /// What the checker is called in results
+    name: &'static str,
+    /// Returns why the text is plaintext, or None if it isn't
+    check: Arc<CustomCheck>,
+}
+
+impl CustomChecker {
+    /// Makes a checker called `name` which identifies text when `check` returns a reason
+    pub fn new(
+        name: &'static str,
+        check: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        CustomChecker {
+            name,
+            check: Arc::new(check),
+        }
+    }
+
+    /// Runs the closure, turning what it says into a full result
+    pub fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {} with the custom checker {}", text, self.name);
+        let reason = (self.check)(text);
+        CheckResult {
+            is_identified: reason.is_some(),
+            text: if reason.is_some() {
+                text.to_string()
+            } else {
+                String::new()
+            },
+            description: "custom check".to_string(),
+            checker_name: self.name,
+            checker_description: "A check given by the program using Ares",
+            link: "",
+            score: None,
+            confidence: if reason.is_some() { 1.0 } else { 0.0 },
+            reason: reason.unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Debug for CustomChecker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomChecker")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CustomChecker;
+
+    fn secret_checker() -> CustomChecker {
+        CustomChecker::new("Secret Checker", |text| {
+            text.contains("secret")
+                .then(|| "mentions a secret".to_string())
+        })
+    }
+
+    #[test]
+    fn identifies_text_the_closure_accepts() {
+        let result = secret_checker().check("the secret is out");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "Secret Checker");
+        assert_eq!(result.reason, "mentions a secret");
+        assert_eq!(result.confidence, 1.0);
+        assert_eq!(result.text, "the secret is out");
+    }
+
+    #[test]
+    fn rejects_text_the_closure_refuses() {
+        let result = secret_checker().check("nothing to see here");
+        assert!(!result.is_identified);
+        assert_eq!(result.checker_name, "Secret Checker");
+        assert!(result.reason.is_empty());
+        assert_eq!(result.confidence, 0.0);
+    }
+
+    #[test]
+    fn clones_share_the_closure() {
+        let checker = secret_checker();
+        let clone = checker.clone();
+        drop(checker);
+        assert!(clone.check("top secret").is_identified);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::get_config;
 use lemmeknow::Identifier;
 
 use super::{
@@ -7,7 +8,6 @@ use super::{
 
 /// The default checker is used to check if the text is plaintext
 /// Based on what the Ares team has found to be the best checker.
-
 pub struct DefaultChecker;
 
 impl Check for Checker<DefaultChecker> {
@@ -19,7 +19,12 @@ impl Check for Checker<DefaultChecker> {
             tags: vec![],
             expected_runtime: 0.0,
             popularity: 0.0,
+            confidence_threshold: get_config().confidence_threshold,
             lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -0,0 +1,366 @@
+use crate::config::get_config;
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::english::ratio_to_confidence;
+use crate::checkers::text_normalize;
+use crate::checkers::word_set::WordSet;
+use crate::storage;
+use lemmeknow::Identifier;
+use log::{debug, trace, warn};
+use once_cell::sync::Lazy;
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// The Dictionary Checker checks if enough of the words in the text are in the
+/// dictionary of one of its languages. It checks English unless told otherwise.
+/// ```
+/// use ares::checkers::checker_type::{Check, Checker};
+/// use ares::checkers::dictionary_checker::{Dictionary, Lang};
+///
+/// let checker = Checker::<Dictionary>::with_language(Lang::De);
+/// let result = checker.check("Wir treffen uns morgen um acht Uhr am Bahnhof");
+/// assert!(result.is_identified);
+/// assert!(result.reason.ends_with("in the German dictionary"));
+/// ```
+pub struct Dictionary;
+
+/// The languages we ship dictionaries for.
+/// Languages other than English are behind the `lang-de`, `lang-fr`, `lang-es` and `lang-it` features,
+/// and have no words without them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(try_from = "String")
+)]
+pub enum Lang {
+    /// English
+    En,
+    /// German
+    De,
+    /// French
+    Fr,
+    /// Spanish
+    Es,
+    /// Italian
+    It,
+}
+
+impl Lang {
+    /// The ISO 639-1 code of the language, such as "de"
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::De => "de",
+            Lang::Fr => "fr",
+            Lang::Es => "es",
+            Lang::It => "it",
+        }
+    }
+
+    /// The English name of the language, such as "German"
+    pub fn name(self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::De => "German",
+            Lang::Fr => "French",
+            Lang::Es => "Spanish",
+            Lang::It => "Italian",
+        }
+    }
+
+    /// The language with this ISO 639-1 code, if we ship it
+    pub fn from_code(code: &str) -> Option<Lang> {
+        [Lang::En, Lang::De, Lang::Fr, Lang::Es, Lang::It]
+            .into_iter()
+            .find(|lang| lang.code().eq_ignore_ascii_case(code))
+    }
+}
+
+impl TryFrom<String> for Lang {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Lang::from_code(&code).ok_or_else(|| {
+            format!("no language has the code \"{code}\", Ares knows en, de, fr, es and it")
+        })
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The dictionary of languages we were built without
+static NO_WORDS: Lazy<WordSet> = Lazy::new(WordSet::empty);
+
+/// A language and its dictionary, either one we ship or one loaded from a file
+#[derive(Clone)]
+pub struct Language {
+    /// What we call the language in the reason, such as "German"
+    pub name: String,
+    /// The words of the language
+    words: LanguageWords,
+}
+
+/// Where a language's words live
+#[derive(Clone)]
+enum LanguageWords {
+    /// A dictionary built into the binary, which is only loaded once however many checkers use it
+    Shipped(Lang, &'static WordSet),
+    /// A dictionary read from a file, shared so the file is only read once
+    Loaded(WordSet),
+}
+
+impl Language {
+    /// Loads the dictionary of a language we don't ship from a file of
+    /// whitespace separated words. The words are lowercased.
+    /// # Errors
+    /// Returns the IO error if the file can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(name: &str, path: impl AsRef<Path>) -> std::io::Result<Language> {
+        let contents = std::fs::read_to_string(path)?;
+        let words = WordSet::from_words(contents.split_whitespace().map(str::to_lowercase));
+        Ok(Language {
+            name: name.to_string(),
+            words: LanguageWords::Loaded(words),
+        })
+    }
+
+    /// Whether this is the English we ship, which the English Checker already knows
+    pub fn is_english(&self) -> bool {
+        matches!(self.words, LanguageWords::Shipped(Lang::En, _))
+    }
+
+    /// Whether the word, which must be lowercase, is in the dictionary
+    fn contains(&self, word: &str) -> bool {
+        match &self.words {
+            LanguageWords::Shipped(_, words) => words.contains(word),
+            LanguageWords::Loaded(words) => words.contains(word),
+        }
+    }
+}
+
+impl From<Lang> for Language {
+    fn from(lang: Lang) -> Self {
+        let words = if lang == Lang::En {
+            Some(&*storage::DICTIONARY)
+        } else {
+            storage::LANGUAGE_DICTIONARIES.get(lang.code())
+        };
+        let words = words.unwrap_or_else(|| {
+            warn!(
+                "Ares was built without the lang-{} feature, so it knows no {} words",
+                lang.code(),
+                lang.name()
+            );
+            &NO_WORDS
+        });
+        Language {
+            name: lang.name().to_string(),
+            words: LanguageWords::Shipped(lang, words),
+        }
+    }
+}
+
+impl Checker<Dictionary> {
+    /// Checks for words of one of the languages we ship
+    pub fn with_language(lang: Lang) -> Self {
+        Self::new().with_languages(vec![lang.into()])
+    }
+
+    /// Checks for words of a language we don't ship, from a file of whitespace separated words
+    /// # Errors
+    /// Returns the IO error if the file can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_language_file(name: &str, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new().with_languages(vec![Language::from_file(name, path)?]))
+    }
+}
+
+impl Check for Checker<Dictionary> {
+    /// Creates a Dictionary Checker for English
+    fn new() -> Self {
+        Checker {
+            name: "Dictionary Checker",
+            description: "Checks for words in the dictionaries of its languages",
+            link: "https://en.wikipedia.org/wiki/Most_common_words_in_English",
+            tags: vec!["dictionary", "language"],
+            expected_runtime: 0.1,
+            popularity: 1.0,
+            confidence_threshold: get_config().confidence_threshold,
+            lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist: None,
+            languages: vec![Lang::En.into()],
+            params: get_config().checker_params.clone(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {} against the dictionaries", text);
+        let mut result = CheckResult::new(self);
+
+        let words = words_of(text);
+        if words.is_empty() {
+            return result;
+        }
+
+        // The language with the most words in the text, the first given on a tie
+        let mut best: Option<(&Language, usize)> = None;
+        for language in &self.languages {
+            let found = words.iter().filter(|word| language.contains(word)).count();
+            if best.is_none_or(|(_, best_found)| found > best_found) {
+                best = Some((language, found));
+            }
+        }
+        let Some((language, found)) = best else {
+            return result;
+        };
+
+        let ratio = found as f64 / words.len() as f64;
+        result.confidence = ratio_to_confidence(ratio, self.params.min_dictionary_ratio);
+        if result.confidence > self.confidence_threshold {
+            debug!("{} looks {}", text, language.name);
+            result.is_identified = true;
+            result.text = text.to_string();
+        }
+        result.description = format!("{} text", language.name);
+        result.reason = format!(
+            "matched {found} of {} words in the {} dictionary",
+            words.len(),
+            language.name
+        );
+        result
+    }
+}
+
+/// The normalised words of the text, see `text_normalize`. Anything but letters and digits splits words,
+/// so "l'homme" is "l" and "homme", as our dictionaries have them.
+fn words_of(text: &str) -> Vec<String> {
+    text_normalize(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dictionary, Lang, Language, LanguageWords};
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+    };
+
+    const GERMAN: &str = "Wir treffen uns morgen um acht Uhr am Bahnhof";
+
+    #[test]
+    fn english_is_the_default_language() {
+        let result =
+            Checker::<Dictionary>::new().check("the quick brown fox jumps over the lazy dog");
+        assert!(result.is_identified);
+        assert_eq!(
+            result.reason,
+            "matched 9 of 9 words in the English dictionary"
+        );
+    }
+
+    #[cfg(feature = "lang-de")]
+    #[test]
+    fn german_is_accepted_only_when_german_is_enabled() {
+        let result = Checker::<Dictionary>::with_language(Lang::De).check(GERMAN);
+        assert!(result.is_identified);
+        assert!(result.reason.ends_with("in the German dictionary"));
+
+        assert!(
+            !Checker::<Dictionary>::with_language(Lang::En)
+                .check(GERMAN)
+                .is_identified
+        );
+    }
+
+    #[cfg(all(feature = "lang-de", feature = "lang-fr"))]
+    #[test]
+    fn athena_tries_each_of_its_languages() {
+        let english_only = Checker::<Athena>::new();
+        assert!(!english_only.check(GERMAN).is_identified);
+
+        let athena = Checker::<Athena>::new().with_languages(vec![
+            Lang::En.into(),
+            Lang::Fr.into(),
+            Lang::De.into(),
+        ]);
+        let result = athena.check(GERMAN);
+        assert!(result.is_identified);
+        assert!(result.reason.ends_with("in the German dictionary"));
+
+        let result = athena.check("Je ne sais pas où est la gare, mais je vais demander");
+        assert!(result.is_identified);
+        assert!(result.reason.ends_with("in the French dictionary"));
+
+        // English still works, with the English Checker's dictionaries
+        assert!(
+            athena
+                .check("the quick brown fox jumps over the lazy dog")
+                .is_identified
+        );
+    }
+
+    #[test]
+    fn languages_we_dont_ship_load_from_a_file() {
+        let path = std::env::temp_dir().join("ares_dictionary_checker_test_nl.txt");
+        std::fs::write(&path, "ik je het een de en is niet van morgen\nZIJN wij\n").unwrap();
+        let checker = Checker::<Dictionary>::with_language_file("Dutch", &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let result = checker.check("Wij zijn morgen niet thuis");
+        assert!(result.is_identified);
+        assert_eq!(
+            result.reason,
+            "matched 4 of 5 words in the Dutch dictionary"
+        );
+        assert!(!checker.check(GERMAN).is_identified);
+        assert!(Language::from_file("Dutch", &path).is_err());
+    }
+
+    #[cfg(feature = "lang-fr")]
+    #[test]
+    fn french_with_combining_accents_is_identified() {
+        // "où" and "déjà" written with combining accents rather than accented letters
+        let text = "Ou\u{300} est la gare? Je suis de\u{301}ja\u{300} en retard";
+        let result = Checker::<Dictionary>::with_language(Lang::Fr).check(text);
+        assert!(result.is_identified);
+        assert_eq!(
+            result.reason,
+            "matched 8 of 9 words in the French dictionary"
+        );
+    }
+
+    #[test]
+    fn checkers_share_the_shipped_dictionaries() {
+        let words_of = |checker: Checker<Athena>| match &checker.languages[0].words {
+            LanguageWords::Shipped(_, words) => *words as *const _,
+            LanguageWords::Loaded(_) => panic!("English is shipped"),
+        };
+        assert!(std::ptr::eq(
+            words_of(Checker::<Athena>::new()),
+            words_of(Checker::<Athena>::new())
+        ));
+    }
+
+    #[test]
+    fn lang_codes_round_trip() {
+        for lang in [Lang::En, Lang::De, Lang::Fr, Lang::Es, Lang::It] {
+            assert_eq!(Lang::from_code(lang.code()), Some(lang));
+        }
+        assert_eq!(Lang::from_code("DE"), Some(Lang::De));
+        assert_eq!(Lang::from_code("nl"), None);
+    }
+}
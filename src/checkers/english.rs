@@ -1,13 +1,22 @@
 use crate::checkers::checker_result::CheckResult;
+use crate::checkers::text_normalize;
+use crate::config::get_config;
 use crate::storage;
 use lemmeknow::Identifier;
 use log::{debug, trace};
 
-use crate::checkers::checker_type::{Check, Checker};
+use crate::checkers::checker_type::{Check, Checker, CheckerParams, DEFAULT_CONFIDENCE_THRESHOLD};
 
 /// Checks English plaintext.
 pub struct EnglishChecker;
 
+/// If 40% of the words are in the english list, then we consider it english.
+/// This is the default `CheckerParams.min_dictionary_ratio`.
+pub(crate) const PLAINTEXT_DETECTION_PERCENTAGE: f64 = 0.4;
+
+/// Strict short-string mode applies to text with at most this many characters
+const SHORT_STRING_LENGTH: usize = 4;
+
 /// given an input, check every item in the array and return true if any of them match
 impl Check for Checker<EnglishChecker> {
     fn new() -> Self {
@@ -17,22 +26,31 @@ impl Check for Checker<EnglishChecker> {
             link: "https://en.wikipedia.org/wiki/List_of_English_words",
             tags: vec!["english"],
             expected_runtime: 0.1,
-            /// English is the most popular language
+            // English is the most popular language
             popularity: 1.0,
+            confidence_threshold: get_config().confidence_threshold,
             lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
 
     fn check(&self, input: &str) -> CheckResult {
+        self.check_with_params(input, &self.params)
+    }
+}
+
+impl Checker<EnglishChecker> {
+    /// Checks the input with these params rather than the checker's own,
+    /// so Athena can share one English Checker whatever its params
+    pub fn check_with_params(&self, input: &str, params: &CheckerParams) -> CheckResult {
         let original_input = input;
         // Normalise the string
         let input = normalise_string(input);
         trace!("Checking English for sentence {}", input);
-        /// If 40% of the words are in the english list, then we consider it english.
-        /// This is the threshold at which we consider it english.
-        /// TODO: Do we want to put this into a config somewhere?
-        const PLAINTEXT_DETECTION_PERCENTAGE: f64 = 0.4;
         let mut words_found: f64 = 0.0;
 
         // TODO: Change this when the below bugs are fixed.
@@ -40,13 +58,35 @@ impl Check for Checker<EnglishChecker> {
 
         let mut result = CheckResult {
             is_identified: false,
-            text: original_input.to_string(),
+            text: String::new(),
             checker_name: self.name,
             checker_description: self.description,
             description: filename.to_string(),
             link: self.link,
+            score: None,
+            reason: String::new(),
+            confidence: 0.0,
         };
 
+        if params.strict_short_strings {
+            let candidate = text_normalize(original_input);
+            let candidate = candidate.trim();
+            if (1..=SHORT_STRING_LENGTH).contains(&candidate.chars().count()) {
+                // A few characters are too easily words once punctuation is stripped out of them,
+                // so only punctuation around the word is ignored
+                let word = candidate.trim_matches(|c: char| c.is_ascii_punctuation());
+                if is_dictionary_word(word) {
+                    result.is_identified = true;
+                    result.text = original_input.to_string();
+                    result.confidence = 1.0;
+                    result.reason = format!("{word} is a dictionary word");
+                } else {
+                    result.reason = format!("{candidate} is short and not a dictionary word");
+                }
+                return result;
+            }
+        }
+
         // After we've normalised our string, if we find it's a length 0 we don't do anything
         // This can happen if our string is a single puncuation mark, for example.
         if input.is_empty() {
@@ -54,6 +94,11 @@ impl Check for Checker<EnglishChecker> {
         }
 
         let split_input = input.split(' ');
+        let total_words = input.split(' ').count();
+        if total_words == 1 && !params.single_words_count {
+            result.reason = "single words don't count as English".to_string();
+            return result;
+        }
 
         // loop through all the words in the input
         for word in split_input {
@@ -64,59 +109,106 @@ impl Check for Checker<EnglishChecker> {
             // We should probably just use a single dictionary and assign the filenames to the values in the dictionary.
             // Like {"hello": "English.txt"} etc.
             // If we're using muiltiple dictionaries we may also have duplicated words which is inefficient.
-            if storage::DICTIONARIES
-                .iter()
-                .any(|(_, words)| words.contains(word))
-            {
+            if is_dictionary_word(word) {
                 trace!("Found word {} in English", word);
                 words_found += 1.0;
             }
+        }
 
-            trace!(
-                "Checking word {} with words_found {} and input length: {}",
-                word,
-                words_found,
-                input.len()
+        // TODO: We are also typecasting to f64 instead of usize, which costs CPU cycles.
+        let ratio = words_found / total_words as f64;
+        result.confidence = ratio_to_confidence(ratio, params.min_dictionary_ratio);
+        if result.confidence > self.confidence_threshold {
+            debug!("Found {} words in {}", words_found, original_input);
+            debug!(
+                "Returning from English chekcer successfully with {}",
+                original_input
             );
-            // TODO: We are also typecasting to f64 instead of usize, which costs CPU cycles.
-            if words_found / (input.split(' ').count()) as f64 > PLAINTEXT_DETECTION_PERCENTAGE {
-                debug!("Found {} words in {}", words_found, original_input);
-                debug!(
-                    "Returning from English chekcer successfully with {}",
-                    original_input
-                );
-                result.is_identified = true;
-                break;
-            }
+            result.is_identified = true;
+            result.text = original_input.to_string();
         }
+        // Kept even when we don't identify it, in case Athena accepts it with a lower threshold
+        result.reason = format!("matched {words_found} of {total_words} words in the dictionaries");
 
         result
     }
 }
 
-///! Strings look funny, they might have commas, be uppercase etc
-///! This normalises the string so English checker can work on it
-///! In particular it:
-///! Removes puncuation from the string
-///! Lowercases the string
+/// Whether the normalised word is in any of our dictionaries
+fn is_dictionary_word(word: &str) -> bool {
+    storage::DICTIONARY.contains(word)
+}
+
+/// Turns the share of words we found into a confidence, so that finding
+/// `plaintext_ratio` of the words, where we draw the line, is `DEFAULT_CONFIDENCE_THRESHOLD`
+/// and finding every word is 1.
+pub(crate) fn ratio_to_confidence(ratio: f64, plaintext_ratio: f64) -> f32 {
+    let threshold = f64::from(DEFAULT_CONFIDENCE_THRESHOLD);
+    let confidence = if ratio <= plaintext_ratio {
+        threshold * ratio / plaintext_ratio
+    } else {
+        threshold + (1.0 - threshold) * (ratio - plaintext_ratio) / (1.0 - plaintext_ratio)
+    };
+    confidence as f32
+}
+
+/// Strings look funny, they might have commas, be uppercase etc
+/// This normalises the string so English checker can work on it
+/// In particular it:
+/// Removes puncuation from the string
+/// Normalises it with `text_normalize`, which also lowercases it
+/// Empties words which are more punctuation than anything else, like "&(f",
+/// as they're noise however their letters read
 fn normalise_string(input: &str) -> String {
-    // The replace function supports patterns https://doc.rust-lang.org/std/str/pattern/trait.Pattern.html#impl-Pattern%3C%27a%3E-3
     // TODO add more puncuation
-    input
-        .to_ascii_lowercase()
-        .chars()
-        .filter(|x| !x.is_ascii_punctuation())
-        .collect()
+    let normalised = text_normalize(input);
+    let mut words = String::with_capacity(normalised.len());
+    for (index, word) in normalised.split(' ').enumerate() {
+        if index > 0 {
+            words.push(' ');
+        }
+        let punctuation = word.chars().filter(char::is_ascii_punctuation).count();
+        if punctuation <= word.chars().count() - punctuation {
+            words.extend(word.chars().filter(|c| !c.is_ascii_punctuation()));
+        }
+    }
+    words
 }
 
 #[cfg(test)]
 mod tests {
     use crate::checkers::english::normalise_string;
     use crate::checkers::{
-        checker_type::{Check, Checker},
+        checker_type::{Check, Checker, CheckerParams},
         english::EnglishChecker,
     };
 
+    #[test]
+    fn english_sentence_is_high_confidence() {
+        let checker = Checker::<EnglishChecker>::new();
+        let result = checker.check("the quick brown fox jumps over the lazy dog");
+        assert!(result.is_identified);
+        assert!(result.confidence > 0.9);
+    }
+
+    #[test]
+    fn fullwidth_english_is_identified() {
+        let checker = Checker::<EnglishChecker>::new();
+        let fullwidth =
+            "\u{ff48}\u{ff45}\u{ff4c}\u{ff4c}\u{ff4f} \u{ff57}\u{ff4f}\u{ff52}\u{ff4c}\u{ff44}";
+        assert!(checker.check(fullwidth).is_identified);
+    }
+
+    #[test]
+    fn english_with_zero_width_spaces_between_letters_is_identified() {
+        let checker = Checker::<EnglishChecker>::new();
+        let spaced: String = "hello world"
+            .chars()
+            .flat_map(|c| [c, '\u{200b}'])
+            .collect();
+        assert!(checker.check(&spaced).is_identified);
+    }
+
     #[test]
     fn test_check_basic() {
         let checker = Checker::<EnglishChecker>::new();
@@ -166,6 +258,12 @@ mod tests {
         assert_eq!(x, "hello dear")
     }
 
+    #[test]
+    fn words_which_are_mostly_punctuation_are_emptied() {
+        let x = normalise_string("don't &(f s%_%o(s e.g.");
+        assert_eq!(x, "dont   eg")
+    }
+
     #[test]
     fn test_checker_works_with_puncuation_and_lowercase() {
         let checker = Checker::<EnglishChecker>::new();
@@ -182,6 +280,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lower_dictionary_ratio_accepts_more_unknown_words() {
+        let text = "hello zqxv wpkt";
+        assert!(!Checker::<EnglishChecker>::new().check(text).is_identified);
+        let lenient = Checker::<EnglishChecker>::new().with_params(CheckerParams {
+            min_dictionary_ratio: 0.3,
+            ..CheckerParams::default()
+        });
+        assert!(lenient.check(text).is_identified);
+    }
+
+    #[test]
+    fn single_words_can_be_turned_off() {
+        let checker = Checker::<EnglishChecker>::new().with_params(CheckerParams {
+            single_words_count: false,
+            ..CheckerParams::default()
+        });
+        assert!(!checker.check("and").is_identified);
+        assert!(checker.check("ok boss").is_identified);
+    }
+
+    #[test]
+    fn strict_short_strings_need_an_exact_dictionary_word() {
+        let checker = Checker::<EnglishChecker>::new().with_params(CheckerParams {
+            strict_short_strings: true,
+            ..CheckerParams::default()
+        });
+        assert!(Checker::<EnglishChecker>::new().check("d.og").is_identified);
+        assert!(!checker.check("d.og").is_identified);
+
+        let result = checker.check("Dog!");
+        assert!(result.is_identified);
+        assert_eq!(result.reason, "dog is a dictionary word");
+        // Longer text is checked as usual
+        assert!(checker.check("ok boss").is_identified);
+    }
+
     #[test]
     fn test_check_fail_single_puncuation_char() {
         let checker = Checker::<EnglishChecker>::new();
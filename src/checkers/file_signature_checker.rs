@@ -0,0 +1,302 @@
+use crate::checkers::checker_result::CheckResult;
+use crate::config::get_config;
+use lemmeknow::Identifier;
+use log::{debug, trace};
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Checks if the candidate is a file we recognise by the magic bytes it starts with,
+/// such as a PNG or a PDF. When decoding ends in a file, the file is the answer,
+/// even though it looks like gibberish to the checkers which look for text.
+pub struct FileSignatureChecker;
+
+/// A kind of file and the bytes every one of them starts with
+struct Signature {
+    /// What we call the file, such as "PNG image"
+    name: &'static str,
+    /// The bytes at offset 0
+    magic: &'static [u8],
+    /// What files like it are usually called, such as "png", if they have an extension
+    extension: Option<&'static str>,
+    /// How sure we are it's the answer when it matches
+    confidence: f32,
+    /// Checks more than the magic bytes, for signatures which are easy to hit by chance
+    also: Option<fn(&[u8]) -> bool>,
+}
+
+/// The files we recognise, most specific first
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "PNG image",
+        magic: b"\x89PNG\r\n\x1a\n",
+        extension: Some("png"),
+        confidence: 1.0,
+        also: None,
+    },
+    Signature {
+        name: "JPEG image",
+        magic: b"\xff\xd8\xff",
+        extension: Some("jpg"),
+        confidence: 1.0,
+        also: None,
+    },
+    Signature {
+        name: "GIF image",
+        magic: b"GIF87a",
+        extension: Some("gif"),
+        confidence: 1.0,
+        also: None,
+    },
+    Signature {
+        name: "GIF image",
+        magic: b"GIF89a",
+        extension: Some("gif"),
+        confidence: 1.0,
+        also: None,
+    },
+    Signature {
+        name: "PDF document",
+        magic: b"%PDF-",
+        extension: Some("pdf"),
+        confidence: 1.0,
+        also: None,
+    },
+    Signature {
+        name: "ZIP archive (or docx, xlsx, jar, apk)",
+        magic: b"PK\x03\x04",
+        extension: Some("zip"),
+        confidence: 1.0,
+        also: Some(has_zip_end_of_central_directory),
+    },
+    Signature {
+        name: "empty ZIP archive",
+        magic: b"PK\x05\x06",
+        extension: Some("zip"),
+        confidence: 1.0,
+        also: Some(has_zip_end_of_central_directory),
+    },
+    // The Deflate decoder decompresses gzip, so it's rarely the answer.
+    // It's below the default threshold so the search carries on into it.
+    Signature {
+        name: "gzip compressed data",
+        magic: b"\x1f\x8b\x08",
+        extension: Some("gz"),
+        confidence: 0.6,
+        also: None,
+    },
+    Signature {
+        name: "ELF executable",
+        magic: b"\x7fELF",
+        extension: None,
+        confidence: 1.0,
+        also: None,
+    },
+    Signature {
+        name: "Windows PE executable",
+        magic: b"MZ",
+        extension: Some("exe"),
+        confidence: 1.0,
+        also: Some(has_pe_header),
+    },
+    Signature {
+        name: "SQLite database",
+        magic: b"SQLite format 3\0",
+        extension: Some("sqlite"),
+        confidence: 1.0,
+        also: None,
+    },
+];
+
+/// The end of central directory record is 22 bytes, followed by a comment of up to this many
+const MAX_ZIP_COMMENT: usize = 0xffff;
+/// Where a DOS header keeps the offset of the PE header
+const PE_HEADER_OFFSET: usize = 0x3c;
+
+impl Checker<FileSignatureChecker> {
+    /// Checks the bytes a decoder produced, which usually aren't text
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        trace!("Checking {} bytes for a file signature", bytes.len());
+        let mut result = CheckResult::new(self);
+
+        let Some(signature) = signature_of(bytes) else {
+            return result;
+        };
+        debug!("Found the signature of a {}", signature.name);
+        result.confidence = signature.confidence;
+        result.is_identified = result.confidence > self.confidence_threshold;
+        if result.is_identified {
+            result.text = String::from_utf8_lossy(bytes).into_owned();
+        }
+        result.description = signature.name.to_string();
+        result.reason = format!(
+            "starts with the magic bytes of a {}, {} bytes long",
+            signature.name,
+            bytes.len()
+        );
+        result
+    }
+}
+
+impl Check for Checker<FileSignatureChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "File Signature Checker",
+            description: "Checks if the data starts with the magic bytes of a known file type",
+            link: "https://en.wikipedia.org/wiki/List_of_file_signatures",
+            tags: vec!["file", "magic", "binary"],
+            expected_runtime: 0.01,
+            popularity: 0.5,
+            confidence_threshold: get_config().confidence_threshold,
+            lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Checks the raw bytes of the text, for when the decoder produced text rather than bytes
+    fn check(&self, text: &str) -> CheckResult {
+        self.check_bytes(text.as_bytes())
+    }
+}
+
+/// The signature the bytes start with, if we know it
+fn signature_of(bytes: &[u8]) -> Option<&'static Signature> {
+    SIGNATURES.iter().find(|signature| {
+        bytes.starts_with(signature.magic) && signature.also.is_none_or(|also| also(bytes))
+    })
+}
+
+/// What kind of file the bytes are, such as "PNG image", and the extension files like it
+/// usually have, such as "png", if it's a file we know with an extension
+/// ```
+/// use ares::checkers::file_signature_checker::file_type;
+///
+/// assert_eq!(file_type(b"%PDF-1.7\n"), Some(("PDF document", "pdf")));
+/// assert_eq!(file_type(b"hello"), None);
+/// ```
+pub fn file_type(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    signature_of(bytes).and_then(|signature| Some((signature.name, signature.extension?)))
+}
+
+/// Whether a ZIP ends with its end of central directory record, so text which
+/// happens to start with "PK" isn't a ZIP
+fn has_zip_end_of_central_directory(bytes: &[u8]) -> bool {
+    const RECORD: &[u8] = b"PK\x05\x06";
+    const RECORD_LENGTH: usize = 22;
+    if bytes.len() < RECORD_LENGTH {
+        return false;
+    }
+    let search_from = bytes.len().saturating_sub(RECORD_LENGTH + MAX_ZIP_COMMENT);
+    // The record can't start closer to the end than its own length
+    bytes[search_from..bytes.len() - RECORD_LENGTH + RECORD.len()]
+        .windows(RECORD.len())
+        .any(|window| window == RECORD)
+}
+
+/// Whether the DOS header points at a PE header, as plenty of text starts with "MZ"
+fn has_pe_header(bytes: &[u8]) -> bool {
+    let Some(offset) = bytes.get(PE_HEADER_OFFSET..PE_HEADER_OFFSET + 4) else {
+        return false;
+    };
+    let offset = u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+    bytes
+        .get(offset..)
+        .is_some_and(|header| header.starts_with(b"PE\0\0"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        file_signature_checker::FileSignatureChecker,
+    };
+
+    /// The signature and IHDR chunk of a 1x1 PNG
+    const PNG_HEADER: &[u8] =
+        b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0\x1f\x15\xc4\x89";
+
+    #[test]
+    fn identifies_png_header() {
+        let result = Checker::<FileSignatureChecker>::new().check_bytes(PNG_HEADER);
+        assert!(result.is_identified);
+        assert_eq!(result.description, "PNG image");
+        assert_eq!(
+            result.reason,
+            "starts with the magic bytes of a PNG image, 33 bytes long"
+        );
+    }
+
+    #[test]
+    fn gzip_is_recognised_but_left_for_the_deflate_decoder() {
+        // gzip of "hello world"
+        let gzip =
+            b"\x1f\x8b\x08\0\0\0\0\0\0\x03\xcbH\xcd\xc9\xc9W(\xcf/\xcaI\x01\0\x85\x11J\r\x0b\0\0\0";
+        let result = Checker::<FileSignatureChecker>::new().check_bytes(gzip);
+        assert!(!result.is_identified);
+        assert_eq!(result.description, "gzip compressed data");
+
+        let lenient = Checker::<FileSignatureChecker>::new().with_confidence_threshold(0.5);
+        assert!(lenient.check_bytes(gzip).is_identified);
+    }
+
+    #[test]
+    fn text_starting_with_mz_is_not_an_executable() {
+        let checker = Checker::<FileSignatureChecker>::new();
+        assert!(
+            !checker
+                .check("MZ is a prefix of many things, but this is just text")
+                .is_identified
+        );
+    }
+
+    #[test]
+    fn signatures_must_be_at_the_start() {
+        let checker = Checker::<FileSignatureChecker>::new();
+        assert!(
+            !checker
+                .check("the file was PK\x03\x04 but not really")
+                .is_identified
+        );
+        assert!(
+            !checker
+                .check("PK\x03\x04 without the end of a zip")
+                .is_identified
+        );
+        assert!(
+            !checker
+                .check_bytes(&[b"hi ", PNG_HEADER].concat())
+                .is_identified
+        );
+    }
+
+    #[test]
+    fn identifies_zip_with_end_of_central_directory() {
+        let mut zip = b"PK\x03\x04\x14\0\0\0\0\0".to_vec();
+        zip.extend_from_slice(&[0; 20]);
+        zip.extend_from_slice(b"PK\x05\x06\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
+        let result = Checker::<FileSignatureChecker>::new().check_bytes(&zip);
+        assert!(result.is_identified);
+        assert!(result.description.starts_with("ZIP archive"));
+    }
+
+    #[test]
+    fn identifies_pe_executable() {
+        let mut exe = vec![0; 0x80];
+        exe[..2].copy_from_slice(b"MZ");
+        exe[0x3c] = 0x40;
+        exe[0x40..0x44].copy_from_slice(b"PE\0\0");
+        let result = Checker::<FileSignatureChecker>::new().check_bytes(&exe);
+        assert!(result.is_identified);
+        assert_eq!(result.description, "Windows PE executable");
+    }
+
+    #[test]
+    fn empty_candidate_is_nothing() {
+        let checker = Checker::<FileSignatureChecker>::new();
+        assert!(!checker.check("").is_identified);
+        assert!(!checker.check_bytes(&[]).is_identified);
+    }
+}
@@ -1,29 +1,230 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::sync::Mutex;
+
 use crate::checkers::checker_result::CheckResult;
-use crate::cli_pretty_printing::human_checker_check;
 use crate::config::get_config;
 use crate::timer;
-use text_io::read;
+
+use super::{
+    athena::Athena,
+    checker_type::{Check, Checker},
+    CheckerTypes,
+};
+use log::trace;
 
 /// The Human Checker asks humans if the expected plaintext is real plaintext
 /// We can use all the automated checkers in the world, but sometimes they get false positives
 /// Humans have the last say.
-/// TODO: Add a way to specify a list of checkers to use in the library. This checker is not library friendly!
-// compile this if we are not running tests
-pub fn human_checker(input: &CheckResult) -> bool {
-    timer::pause();
-    // wait instead of get so it waits for config being set
-    let config = get_config();
-    // We still call human checker, just if config is false we return True
-    if !config.human_checker_on || config.api_mode {
-        return true;
-    }
-    human_checker_check(&input.description, &input.text);
-
-    let reply: String = read!("{}\n");
-    let result = reply.to_ascii_lowercase().starts_with('y');
-    if !result {
+///
+/// It wraps another checker, usually Athena, so that only text which gets past the
+/// automated checkers is shown to the human. If they say no, the search carries on.
+/// It never asks about the same text twice, and when nobody is there to answer,
+/// because stdin is not a terminal, it trusts the checker it wraps.
+pub struct HumanChecker {
+    /// The checker which has to identify the text before we ask
+    inner: CheckerTypes,
+    /// Whether to ask at all
+    enabled: bool,
+    /// Where we ask, and where the answer comes from
+    prompt: Mutex<Prompt>,
+    /// Every text we have asked about
+    asked: Mutex<HashSet<String>>,
+}
+
+/// The reader and writer we ask the human with
+struct Prompt {
+    /// Where the human's answers come from
+    reader: Box<dyn BufRead + Send>,
+    /// Where the questions go
+    writer: Box<dyn Write + Send>,
+}
+
+impl Prompt {
+    /// Asks the human whether the text is the plaintext.
+    /// Anything but an answer starting with "y" is a no, including not being able to ask.
+    fn confirm(&mut self, text: &str) -> bool {
+        crate::cli_pretty_printing::clear_search_progress();
+        if write!(
+            self.writer,
+            "Possible plaintext: '{text}' \u{2014} accept? [y/N] "
+        )
+        .and_then(|_| self.writer.flush())
+        .is_err()
+        {
+            return false;
+        }
+        let mut reply = String::new();
+        match self.reader.read_line(&mut reply) {
+            Ok(_) => reply.trim_start().to_ascii_lowercase().starts_with('y'),
+            Err(_) => false,
+        }
+    }
+}
+
+impl HumanChecker {
+    /// Asks on stderr and reads the answer from stdin, if the config turned the
+    /// human checker on and stdin is a terminal. Otherwise it just runs the inner checker.
+    pub fn wrapping(inner: CheckerTypes) -> Self {
+        let config = get_config();
+        let enabled = config.human_checker_on && !config.api_mode && io::stdin().is_terminal();
+        let mut checker = HumanChecker::with_io(inner, BufReader::new(io::stdin()), io::stderr());
+        checker.enabled = enabled;
+        checker
+    }
+
+    /// Asks with the given writer and reads the answers from the given reader,
+    /// for library users with their own interface, and for tests.
+    pub fn with_io(
+        inner: CheckerTypes,
+        reader: impl BufRead + Send + 'static,
+        writer: impl Write + Send + 'static,
+    ) -> Self {
+        HumanChecker {
+            inner,
+            enabled: true,
+            prompt: Mutex::new(Prompt {
+                reader: Box::new(reader),
+                writer: Box::new(writer),
+            }),
+            asked: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl Check for HumanChecker {
+    /// Wraps Athena, asking on stderr if the config turned the human checker on
+    fn new() -> Self {
+        HumanChecker::wrapping(CheckerTypes::CheckAthena(Checker::<Athena>::new()))
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        let result = self.inner.check(text);
+        self.confirm(result, text, text)
+    }
+}
+
+impl HumanChecker {
+    /// Asks about the bytes a decoder produced when they aren't text, if the inner checker identifies them.
+    /// The human is told what kind of data it is rather than shown the bytes.
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        let result = self.inner.check_bytes(bytes);
+        let shown = format!("{}, {} bytes", result.description, bytes.len());
+        self.confirm(result, &String::from_utf8_lossy(bytes), &shown)
+    }
+
+    /// Asks the human to confirm what the inner checker identified, showing them `shown`.
+    /// Each `text` is only asked about once.
+    fn confirm(&self, mut result: CheckResult, text: &str, shown: &str) -> CheckResult {
+        if !result.is_identified || !self.enabled {
+            return result;
+        }
+
+        // If we asked before they said no, as a yes would have ended the search
+        let first_time = self
+            .asked
+            .lock()
+            .expect("the human checker's list of asked texts is never poisoned")
+            .insert(text.to_string());
+        if !first_time {
+            trace!("Already asked about {}, not asking again", text);
+            result.is_identified = false;
+            return result;
+        }
+
+        // Don't count the time the human spends thinking
+        timer::pause();
+        result.is_identified = self
+            .prompt
+            .lock()
+            .expect("the human checker's prompt is never poisoned")
+            .confirm(shown);
         timer::resume();
-        return false;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::HumanChecker;
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+    use crate::test_helpers::SharedWriter;
+
+    // helper for tests
+    fn human_checker(answers: &str) -> (HumanChecker, SharedWriter) {
+        let writer = SharedWriter::default();
+        let checker = HumanChecker::with_io(
+            CheckerTypes::CheckAthena(Checker::<Athena>::new()),
+            Cursor::new(answers.to_string().into_bytes()),
+            writer.clone(),
+        );
+        (checker, writer)
+    }
+
+    #[test]
+    fn accepts_when_the_human_says_yes() {
+        let (checker, writer) = human_checker("y\n");
+        let result = checker.check("the quick brown fox jumps over the lazy dog");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "English Checker");
+        assert_eq!(
+            writer.contents(),
+            "Possible plaintext: 'the quick brown fox jumps over the lazy dog' \u{2014} accept? [y/N] "
+        );
+    }
+
+    #[test]
+    fn rejects_when_the_human_says_no() {
+        for answer in ["n\n", "\n", ""] {
+            let (checker, _) = human_checker(answer);
+            assert!(
+                !checker
+                    .check("the quick brown fox jumps over the lazy dog")
+                    .is_identified,
+                "{answer:?} should be a no"
+            );
+        }
+    }
+
+    #[test]
+    fn does_not_ask_about_garbage() {
+        let (checker, writer) = human_checker("y\n");
+        assert!(!checker.check("zqxv wpkt").is_identified);
+        assert!(writer.contents().is_empty());
+    }
+
+    #[test]
+    fn never_asks_about_the_same_text_twice() {
+        let (checker, writer) = human_checker("n\ny\n");
+        let text = "the quick brown fox jumps over the lazy dog";
+        assert!(!checker.check(text).is_identified);
+        assert!(!checker.check(text).is_identified);
+        assert_eq!(writer.contents().matches("Possible plaintext").count(), 1);
+
+        // Something new is still asked about, and gets the next answer
+        assert!(checker.check("hello there my friend").is_identified);
+    }
+
+    #[test]
+    fn wraps_athena_by_default() {
+        // Tests don't run in a terminal, so this never asks
+        let checker =
+            CheckerTypes::human_confirmed(CheckerTypes::CheckAthena(Checker::<Athena>::new()));
+        assert!(
+            checker
+                .check("the quick brown fox jumps over the lazy dog")
+                .is_identified
+        );
+        assert!(
+            HumanChecker::new()
+                .check("https://www.google.com")
+                .is_identified
+        );
     }
-    true
 }
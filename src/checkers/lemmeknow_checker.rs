@@ -1,5 +1,6 @@
 use super::checker_type::{Check, Checker};
 use crate::checkers::checker_result::CheckResult;
+use crate::config::get_config;
 use lemmeknow::{Data, Identifier};
 
 /// The LemmeKnow Checker checks if the text matches a known Regex pattern.
@@ -16,7 +17,12 @@ impl Check for Checker<LemmeKnow> {
             tags: vec!["lemmeknow", "regex"],
             expected_runtime: 0.01,
             popularity: 1.0,
+            confidence_threshold: get_config().confidence_threshold,
             lemmeknow_config: Identifier::default().min_rarity(0.1),
+            regex: None,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -25,19 +31,28 @@ impl Check for Checker<LemmeKnow> {
         let lemmeknow_result = self.lemmeknow_config.identify(text);
         let mut is_identified = false;
         let mut description = "".to_string();
+        let mut reason = String::new();
         if !lemmeknow_result.is_empty() {
             is_identified = true;
-            description = format_data_result(&lemmeknow_result[0].data)
+            description = format_data_result(&lemmeknow_result[0].data);
+            reason = format!("matched the {description} pattern");
         }
 
         CheckResult {
             is_identified,
-            text: text.to_owned(),
+            text: if is_identified {
+                text.to_owned()
+            } else {
+                String::new()
+            },
             checker_name: self.name,
             checker_description: self.description,
             // Returns a vector of matches
             description,
             link: self.link,
+            score: None,
+            reason,
+            confidence: if is_identified { 1.0 } else { 0.0 },
         }
     }
 }
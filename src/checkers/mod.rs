@@ -1,28 +1,65 @@
+use std::sync::Arc;
+
+use unicode_normalization::UnicodeNormalization;
+
 use self::{
     athena::Athena,
+    cached_checker::CachedChecker,
     checker_result::CheckResult,
-    checker_type::{Check, Checker},
+    checker_type::{Check, Checker, CheckerParams},
+    custom_checker::CustomChecker,
+    dictionary_checker::Dictionary,
     english::EnglishChecker,
+    file_signature_checker::FileSignatureChecker,
+    human_checker::HumanChecker,
+    identifier_checker::IdentifierChecker,
     lemmeknow_checker::LemmeKnow,
+    preview_checker::PreviewChecker,
+    quadgram_checker::EnglishQuadgrams,
     regex_checker::RegexChecker,
+    sensitive_data_checker::SensitiveDataChecker,
+    structured_data_checker::StructuredDataChecker,
+    wordlist_checker::WordlistChecker,
 };
 
 /// The default checker we use which simply calls all other checkers in order.
 pub mod athena;
+/// The Cached Checker remembers what the checker it wraps said about each text
+pub mod cached_checker;
 /// The checkerResult struct is used to store the results of a checker.
 pub mod checker_result;
 /// This is the base checker that all other checkers inherit from.
 pub mod checker_type;
+/// The Custom Checker runs a closure given by the program using Ares
+pub mod custom_checker;
 /// The default checker we use which simply calls all other checkers in order.
 pub mod default_checker;
+/// The Dictionary Checker checks for words of one or more languages, such as German
+pub mod dictionary_checker;
 /// The English Checker is a checker that checks if the input is English
 pub mod english;
+/// The File Signature Checker checks if the data is a file, such as a PNG, by its magic bytes
+pub mod file_signature_checker;
 /// The Human Checker asks humans if the expected plaintext is real plaintext
 pub mod human_checker;
+/// The Identifier Checker checks for identifiers like JWTs, UUIDs and URLs
+pub mod identifier_checker;
 /// The LemmeKnow Checker checks if the text matches a known Regex pattern.
 pub mod lemmeknow_checker;
+/// The Preview Checker checks only the start of very large text
+pub mod preview_checker;
+/// The Quadgram Checker checks if the text looks English by its groups of 4 letters
+pub mod quadgram_checker;
 /// The Regex checker checks to see if the intended text matches the plaintext
 pub mod regex_checker;
+/// The Sensitive Data Checker checks for secrets and personal data like passwords and card numbers
+pub mod sensitive_data_checker;
+/// The Structured Data Checker checks if the text is JSON, XML or YAML
+pub mod structured_data_checker;
+/// A set of words to look words up in, like a dictionary or a wordlist
+pub mod word_set;
+/// The Wordlist Checker checks if the text is in a wordlist the user gave us
+pub mod wordlist_checker;
 
 /// CheckerTypes is a wrapper enum for Checker
 pub enum CheckerTypes {
@@ -30,22 +67,362 @@ pub enum CheckerTypes {
     CheckLemmeKnow(Checker<LemmeKnow>),
     /// Wrapper for English Checker
     CheckEnglish(Checker<EnglishChecker>),
+    /// Wrapper for Dictionary Checker
+    CheckDictionary(Checker<Dictionary>),
     /// Wrapper for Athena Checker
     CheckAthena(Checker<Athena>),
     /// Wrapper for Regex
     CheckRegex(Checker<RegexChecker>),
+    /// Wrapper for English Quadgrams Checker
+    CheckEnglishQuadgrams(Checker<EnglishQuadgrams>),
+    /// Wrapper for Wordlist Checker
+    CheckWordlist(Checker<WordlistChecker>),
+    /// Wrapper for Structured Data Checker
+    CheckStructuredData(Checker<StructuredDataChecker>),
+    /// Wrapper for Identifier Checker
+    CheckIdentifier(Checker<IdentifierChecker>),
+    /// Wrapper for Sensitive Data Checker
+    CheckSensitiveData(Checker<SensitiveDataChecker>),
+    /// Wrapper for File Signature Checker
+    CheckFileSignature(Checker<FileSignatureChecker>),
+    /// Runs a closure given by the program using Ares, see `CheckerTypes::from_closure`
+    CheckCustom(CustomChecker),
+    /// Runs several checkers in order and succeeds on the first one which identifies the text.
+    /// They are kept in an `Arc` so the composite is cheap to clone.
+    CheckComposite(Arc<Vec<CheckerTypes>>),
+    /// Asks a human to confirm whatever the checker inside identifies
+    CheckHumanConfirmed(Arc<HumanChecker>),
+    /// Remembers what the checker inside said about each text, so it is only checked once
+    CheckCached(Arc<CachedChecker>),
+    /// Checks only the start of very large text with the checker inside, see `CheckerTypes::previewed`
+    CheckPreviewed(Arc<PreviewChecker>),
 }
 
 impl CheckerTypes {
+    /// A checker which identifies the text if any of the checkers do, such as
+    /// "English text or my flag format or valid JSON".
+    /// The checkers run in the order given and the first hit wins.
+    /// ```
+    /// use ares::checkers::{athena::Athena, regex_checker::RegexChecker, CheckerTypes};
+    /// use ares::checkers::checker_type::{Check, Checker};
+    ///
+    /// let checker = CheckerTypes::any_of(vec![
+    ///     CheckerTypes::CheckRegex(Checker::<RegexChecker>::with_pattern(r"flag\{[^}]*\}").unwrap()),
+    ///     CheckerTypes::CheckAthena(Checker::<Athena>::new()),
+    /// ]);
+    /// let result = checker.check("flag{zqxv}");
+    /// assert!(result.is_identified);
+    /// assert_eq!(result.checker_name, "Regex Checker");
+    /// ```
+    pub fn any_of(checkers: Vec<CheckerTypes>) -> Self {
+        CheckerTypes::CheckComposite(Arc::new(checkers))
+    }
+
+    /// A checker called `name` which identifies text when the closure returns why it's plaintext,
+    /// for library users who know what their plaintext looks like.
+    /// ```
+    /// use ares::checkers::{athena::Athena, CheckerTypes};
+    /// use ares::checkers::checker_type::{Check, Checker};
+    ///
+    /// let secret = CheckerTypes::from_closure("Secret Checker", |text| {
+    ///     text.contains("secret").then(|| "mentions a secret".to_string())
+    /// });
+    /// let result = secret.check("the secret is in the vault");
+    /// assert!(result.is_identified);
+    /// assert_eq!(result.checker_name, "Secret Checker");
+    /// assert_eq!(result.reason, "mentions a secret");
+    /// assert!(!secret.check("nothing to see here").is_identified);
+    ///
+    /// // Like any other checker, it can be part of a composite
+    /// let checker = CheckerTypes::any_of(vec![secret, CheckerTypes::CheckAthena(Checker::<Athena>::new())]);
+    /// assert!(checker.check("a secret").is_identified);
+    /// ```
+    pub fn from_closure(
+        name: &'static str,
+        check: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        CheckerTypes::CheckCustom(CustomChecker::new(name, check))
+    }
+
+    /// Wraps the checker so a human confirms what it identifies,
+    /// when the config turned the human checker on and stdin is a terminal
+    pub fn human_confirmed(checker: CheckerTypes) -> Self {
+        CheckerTypes::CheckHumanConfirmed(Arc::new(HumanChecker::wrapping(checker)))
+    }
+
+    /// Wraps the checker so each text is only checked once,
+    /// remembering up to `DEFAULT_CACHE_CAPACITY` verdicts
+    pub fn cached(checker: CheckerTypes) -> Self {
+        CheckerTypes::CheckCached(Arc::new(CachedChecker::wrapping(checker)))
+    }
+
+    /// Wraps the checker so it only checks the first `preview_size` bytes of bigger text,
+    /// see `Config::preview_size`. A checker which looks at the end of the text,
+    /// like a regex ending in `$`, is left as it is, as it would never see the end.
+    pub fn previewed(checker: CheckerTypes, preview_size: usize) -> Self {
+        if checker.reads_to_end() {
+            return checker;
+        }
+        CheckerTypes::CheckPreviewed(Arc::new(PreviewChecker::wrapping(checker, preview_size)))
+    }
+
+    /// Whether the checker has to see the end of the text, as it has a regex anchored to the end.
+    /// What a wrapped checker or a custom one looks at can't be told, so they are assumed not to.
+    fn reads_to_end(&self) -> bool {
+        match self {
+            CheckerTypes::CheckRegex(checker) => checker.is_anchored_to_end(),
+            // Athena checks with the regex from its config
+            CheckerTypes::CheckAthena(athena) => athena
+                .regex
+                .as_ref()
+                .is_some_and(regex_checker::is_anchored_to_end),
+            CheckerTypes::CheckComposite(checkers) => {
+                checkers.iter().any(CheckerTypes::reads_to_end)
+            }
+            _ => false,
+        }
+    }
+
+    /// Sets how the checker tells English from gibberish, see `CheckerParams`.
+    /// Checkers in a composite get them too, but set them before wrapping a checker
+    /// to be cached or confirmed by a human, as those are left as they are.
+    pub fn with_params(self, params: CheckerParams) -> Self {
+        match self {
+            CheckerTypes::CheckLemmeKnow(checker) => {
+                CheckerTypes::CheckLemmeKnow(checker.with_params(params))
+            }
+            CheckerTypes::CheckEnglish(checker) => {
+                CheckerTypes::CheckEnglish(checker.with_params(params))
+            }
+            CheckerTypes::CheckDictionary(checker) => {
+                CheckerTypes::CheckDictionary(checker.with_params(params))
+            }
+            CheckerTypes::CheckAthena(checker) => {
+                CheckerTypes::CheckAthena(checker.with_params(params))
+            }
+            CheckerTypes::CheckRegex(checker) => {
+                CheckerTypes::CheckRegex(checker.with_params(params))
+            }
+            CheckerTypes::CheckEnglishQuadgrams(checker) => {
+                CheckerTypes::CheckEnglishQuadgrams(checker.with_params(params))
+            }
+            CheckerTypes::CheckWordlist(checker) => {
+                CheckerTypes::CheckWordlist(checker.with_params(params))
+            }
+            CheckerTypes::CheckStructuredData(checker) => {
+                CheckerTypes::CheckStructuredData(checker.with_params(params))
+            }
+            CheckerTypes::CheckIdentifier(checker) => {
+                CheckerTypes::CheckIdentifier(checker.with_params(params))
+            }
+            CheckerTypes::CheckSensitiveData(checker) => {
+                CheckerTypes::CheckSensitiveData(checker.with_params(params))
+            }
+            CheckerTypes::CheckFileSignature(checker) => {
+                CheckerTypes::CheckFileSignature(checker.with_params(params))
+            }
+            // Only a composite nobody else holds can be changed
+            CheckerTypes::CheckComposite(checkers) => match Arc::try_unwrap(checkers) {
+                Ok(checkers) => CheckerTypes::any_of(
+                    checkers
+                        .into_iter()
+                        .map(|checker| checker.with_params(params.clone()))
+                        .collect(),
+                ),
+                Err(checkers) => CheckerTypes::CheckComposite(checkers),
+            },
+            // Custom checkers decide for themselves
+            unchanged @ (CheckerTypes::CheckCustom(_)
+            | CheckerTypes::CheckHumanConfirmed(_)
+            | CheckerTypes::CheckCached(_)
+            | CheckerTypes::CheckPreviewed(_)) => unchanged,
+        }
+    }
+
     /// This functions calls appropriate check function of Checker
     pub fn check(&self, text: &str) -> CheckResult {
         match self {
             CheckerTypes::CheckLemmeKnow(lemmeknow_checker) => lemmeknow_checker.check(text),
             CheckerTypes::CheckEnglish(english_checker) => english_checker.check(text),
+            CheckerTypes::CheckDictionary(dictionary_checker) => dictionary_checker.check(text),
             CheckerTypes::CheckAthena(athena_checker) => athena_checker.check(text),
             CheckerTypes::CheckRegex(regex_checker) => regex_checker.check(text),
+            CheckerTypes::CheckEnglishQuadgrams(quadgram_checker) => quadgram_checker.check(text),
+            CheckerTypes::CheckWordlist(wordlist_checker) => wordlist_checker.check(text),
+            CheckerTypes::CheckStructuredData(structured_data_checker) => {
+                structured_data_checker.check(text)
+            }
+            CheckerTypes::CheckIdentifier(identifier_checker) => identifier_checker.check(text),
+            CheckerTypes::CheckSensitiveData(sensitive_data_checker) => {
+                sensitive_data_checker.check(text)
+            }
+            CheckerTypes::CheckFileSignature(file_signature_checker) => {
+                file_signature_checker.check(text)
+            }
+            CheckerTypes::CheckCustom(custom_checker) => custom_checker.check(text),
+            CheckerTypes::CheckComposite(checkers) => {
+                check_any(checkers, text, |checker| checker.check(text))
+            }
+            CheckerTypes::CheckHumanConfirmed(human_checker) => human_checker.check(text),
+            CheckerTypes::CheckCached(cached_checker) => cached_checker.check(text),
+            CheckerTypes::CheckPreviewed(preview_checker) => preview_checker.check(text),
         }
     }
+
+    /// Checks the bytes a decoder produced when they aren't text, such as a PNG.
+    /// Checkers which only look at text check a lossy copy of the bytes.
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        match self {
+            CheckerTypes::CheckFileSignature(file_signature_checker) => {
+                file_signature_checker.check_bytes(bytes)
+            }
+            CheckerTypes::CheckAthena(athena_checker) => athena_checker.check_bytes(bytes),
+            CheckerTypes::CheckComposite(checkers) => {
+                let text = String::from_utf8_lossy(bytes);
+                check_any(checkers, &text, |checker| checker.check_bytes(bytes))
+            }
+            CheckerTypes::CheckHumanConfirmed(human_checker) => human_checker.check_bytes(bytes),
+            CheckerTypes::CheckCached(cached_checker) => cached_checker.check_bytes(bytes),
+            CheckerTypes::CheckPreviewed(preview_checker) => preview_checker.check_bytes(bytes),
+            text_checker => text_checker.check(&String::from_utf8_lossy(bytes)),
+        }
+    }
+}
+
+/// What a checker is, for listing every checker, like `ares --list-checkers`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CheckerInfo {
+    /// The checker's name, like "English Checker"
+    pub name: &'static str,
+    /// The tags it has, like "english"
+    pub tags: Vec<&'static str>,
+    /// How popular it is, from 0 to 1
+    pub popularity: f32,
+    /// Where to read about it
+    pub link: &'static str,
+    /// What it checks for
+    pub description: &'static str,
+}
+
+impl<Type> From<&Checker<Type>> for CheckerInfo {
+    fn from(checker: &Checker<Type>) -> Self {
+        CheckerInfo {
+            name: checker.name,
+            tags: checker.tags.clone(),
+            popularity: checker.popularity,
+            link: checker.link,
+            description: checker.description,
+        }
+    }
+}
+
+/// Every checker there is, the most popular first: Athena, which the search uses
+/// unless told otherwise, and the checkers it runs.
+/// Checkers which wrap others, like the Human Checker, aren't listed.
+/// ```
+/// use ares::checkers::list_checkers;
+///
+/// let checkers = list_checkers();
+/// assert!(checkers.iter().any(|checker| checker.name == "Athena Checker"));
+/// ```
+pub fn list_checkers() -> Vec<CheckerInfo> {
+    let mut checkers = vec![
+        CheckerInfo::from(&Checker::<Athena>::new()),
+        CheckerInfo::from(&Checker::<EnglishChecker>::new()),
+        CheckerInfo::from(&Checker::<Dictionary>::new()),
+        CheckerInfo::from(&Checker::<EnglishQuadgrams>::new()),
+        CheckerInfo::from(&Checker::<LemmeKnow>::new()),
+        CheckerInfo::from(&Checker::<IdentifierChecker>::new()),
+        CheckerInfo::from(&Checker::<RegexChecker>::new()),
+        CheckerInfo::from(&Checker::<WordlistChecker>::new()),
+        CheckerInfo::from(&Checker::<StructuredDataChecker>::new()),
+        CheckerInfo::from(&Checker::<SensitiveDataChecker>::new()),
+        CheckerInfo::from(&Checker::<FileSignatureChecker>::new()),
+    ];
+    checkers.sort_by(|a, b| b.popularity.total_cmp(&a.popularity));
+    checkers
+}
+
+/// Normalises text before we look for words in it, so odd encodings of the same text check the same.
+/// It strips zero-width and bidi control characters, applies NFKC normalisation so fullwidth
+/// letters become ASCII and combining accents join their letters, and folds case.
+/// Only checking uses this, the decoders keep the text they decoded.
+/// ```
+/// use ares::checkers::text_normalize;
+///
+/// assert_eq!(text_normalize("\u{ff28}\u{ff45}\u{ff4c}\u{ff4c}\u{ff4f}"), "hello");
+/// assert_eq!(text_normalize("h\u{200b}i\u{200b}!"), "hi!");
+/// assert_eq!(text_normalize("Cafe\u{301}"), "caf\u{e9}");
+/// ```
+pub fn text_normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    normalized.extend(
+        text.chars()
+            .filter(|c| !is_invisible_control(*c))
+            .nfkc()
+            // Lowercasing is case folding for all but a few letters, like the German ß
+            .flat_map(char::to_lowercase),
+    );
+    normalized
+}
+
+/// The Shannon entropy of the bytes, in bits per byte, from 0 for one repeated byte to 8
+pub(crate) fn shannon_entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Whether the character is a zero-width or bidi control character, which don't change how text reads
+fn is_invisible_control(c: char) -> bool {
+    matches!(
+        c,
+        // Zero-width space, non-joiner and joiner, word joiner, byte order mark and soft hyphen
+        '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}' | '\u{ad}'
+        // Bidi marks, embeddings, overrides and isolates
+        | '\u{200e}' | '\u{200f}' | '\u{61c}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Returns the result of the first checker to identify the text,
+/// so its name, reason and confidence are kept.
+/// When none do, the confidence is the most any of them had.
+fn check_any(
+    checkers: &[CheckerTypes],
+    text: &str,
+    check: impl Fn(&CheckerTypes) -> CheckResult,
+) -> CheckResult {
+    let mut confidence: f32 = 0.0;
+    for checker in checkers {
+        let result = check(checker);
+        if result.is_identified {
+            return result;
+        }
+        confidence = confidence.max(result.confidence);
+    }
+    // None of them identified it, or there were no checkers to ask
+    CheckResult {
+        is_identified: false,
+        text: text.to_string(),
+        description: String::new(),
+        checker_name: "Composite Checker",
+        checker_description: "Runs several checkers, succeeding if any of them do",
+        link: "",
+        score: None,
+        reason: String::new(),
+        confidence,
+    }
 }
 
 // test
@@ -53,9 +430,25 @@ impl CheckerTypes {
 mod tests {
     use crate::checkers::{
         athena::Athena,
-        checker_type::{Check, Checker},
-        CheckerTypes,
+        checker_type::{Check, Checker, CheckerParams},
+        list_checkers,
+        regex_checker::RegexChecker,
+        shannon_entropy, CheckerTypes,
     };
+    use crate::decoders::{
+        base64_decoder::Base64Decoder,
+        interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn flag_or_athena() -> CheckerTypes {
+        CheckerTypes::any_of(vec![
+            CheckerTypes::CheckRegex(
+                Checker::<RegexChecker>::with_pattern(r"flag\{[^}]*\}").unwrap(),
+            ),
+            CheckerTypes::CheckAthena(Checker::<Athena>::new()),
+        ])
+    }
 
     #[test]
     fn test_check_ip_address() {
@@ -68,4 +461,152 @@ mod tests {
         let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
         assert!(athena.check("and").is_identified);
     }
+
+    #[test]
+    fn athena_says_which_checker_fired_and_why() {
+        let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        let result = athena.check("the quick brown fox jumps over the lazy dog");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "English Checker");
+        assert_eq!(result.checker_description, "Checks for english words");
+        assert!(result.reason.starts_with("matched "));
+        assert!(result.reason.ends_with(" of 9 words in the dictionaries"));
+
+        let result = athena.check("base16_is_hex");
+        assert_eq!(result.checker_name, "English Quadgram Checker");
+        assert!(result.reason.starts_with("averaged "));
+    }
+
+    #[test]
+    fn athena_passes_its_params_to_its_checkers() {
+        let text = "hello zqxv wpkt";
+        let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        assert!(!athena.check(text).is_identified);
+
+        let lenient = athena.with_params(CheckerParams {
+            min_dictionary_ratio: 0.3,
+            ..CheckerParams::default()
+        });
+        let result = lenient.check(text);
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "English Checker");
+    }
+
+    #[test]
+    fn params_reach_checkers_in_a_composite() {
+        let strict = CheckerParams {
+            single_words_count: false,
+            ..CheckerParams::default()
+        };
+        assert!(flag_or_athena().check("and").is_identified);
+        let checker = flag_or_athena().with_params(strict);
+        assert!(!checker.check("and").is_identified);
+        assert!(checker.check("flag{and}").is_identified);
+    }
+
+    #[test]
+    fn athena_accepts_borderline_text_only_with_a_lower_threshold() {
+        // One word in three is in the dictionaries, which is below where we usually draw the line
+        let text = "hello zqxv wpkt";
+        let lenient = Checker::<Athena>::new().with_confidence_threshold(0.5);
+        let result = lenient.check(text);
+        assert!(result.is_identified);
+        assert!(result.confidence > 0.5 && result.confidence < 0.9);
+
+        let strict = Checker::<Athena>::new().with_confidence_threshold(0.9);
+        assert!(!strict.check(text).is_identified);
+    }
+
+    #[test]
+    fn composite_succeeds_when_only_the_regex_matches() {
+        let result = flag_or_athena().check("flag{zqxv}");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "Regex Checker");
+        assert!(result.reason.starts_with("regex "));
+        assert_eq!(result.confidence, 1.0);
+    }
+
+    #[test]
+    fn composite_succeeds_when_only_athena_matches() {
+        let result = flag_or_athena().check("the quick brown fox jumps over the lazy dog");
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "English Checker");
+        assert!(result.reason.starts_with("matched "));
+    }
+
+    #[test]
+    fn empty_composite_always_fails() {
+        let checker = CheckerTypes::any_of(vec![]);
+        assert!(
+            !checker
+                .check("the quick brown fox jumps over the lazy dog")
+                .is_identified
+        );
+        assert!(!checker.check("").is_identified);
+    }
+
+    #[test]
+    fn composite_inside_a_composite() {
+        let checker = CheckerTypes::any_of(vec![
+            CheckerTypes::any_of(vec![]),
+            CheckerTypes::any_of(vec![CheckerTypes::CheckRegex(
+                Checker::<RegexChecker>::with_pattern(r"HTB\{[^}]*\}").unwrap(),
+            )]),
+            flag_or_athena(),
+        ]);
+        assert_eq!(checker.check("HTB{zqxv}").checker_name, "Regex Checker");
+        assert_eq!(
+            checker
+                .check("the quick brown fox jumps over the lazy dog")
+                .checker_name,
+            "English Checker"
+        );
+        assert!(!checker.check("zqxv wpkt").is_identified);
+    }
+
+    #[test]
+    fn checking_normalises_but_the_decoded_text_is_kept_as_is() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let fullwidth =
+            "\u{ff48}\u{ff45}\u{ff4c}\u{ff4c}\u{ff4f} \u{ff57}\u{ff4f}\u{ff52}\u{ff4c}\u{ff44}";
+        let athena = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        let result = decoder.crack(&decoder.encode(fullwidth).unwrap(), &athena);
+        assert!(result.success);
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0].as_bytes(),
+            fullwidth.as_bytes()
+        );
+    }
+
+    #[test]
+    fn constructing_athena_is_cheap() {
+        // Load everything once, so we only time the constructor
+        Checker::<Athena>::new().check("hello world");
+        let started = std::time::Instant::now();
+        let checkers: Vec<_> = (0..1_000).map(|_| Checker::<Athena>::new()).collect();
+        assert_eq!(checkers.len(), 1_000);
+        assert!(started.elapsed() < std::time::Duration::from_millis(500));
+    }
+
+    #[test]
+    fn entropy_is_in_bits_per_byte() {
+        assert_eq!(shannon_entropy(b""), 0.0);
+        assert_eq!(shannon_entropy(b"aaaa"), 0.0);
+        assert!((shannon_entropy(b"abab") - 1.0).abs() < 1e-9);
+        let every_byte: Vec<u8> = (0..=255).collect();
+        assert!((shannon_entropy(&every_byte) - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn every_checker_is_listed_once_the_most_popular_first() {
+        let checkers = list_checkers();
+        let mut names: Vec<&str> = checkers.iter().map(|checker| checker.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), checkers.len());
+        assert!(names.contains(&"English Checker"));
+        assert!(checkers
+            .windows(2)
+            .all(|pair| pair[0].popularity >= pair[1].popularity));
+    }
 }
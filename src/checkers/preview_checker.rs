@@ -0,0 +1,137 @@
+use crate::checkers::checker_result::CheckResult;
+use log::trace;
+
+use super::CheckerTypes;
+
+/// How many bytes of text checkers look at by default, see `Config::preview_size`
+pub const DEFAULT_PREVIEW_SIZE: usize = 16 * 1024;
+
+/// Checks only the start of very large text with the checker it wraps.
+/// A few kilobytes are enough to tell English or JSON from gibberish, and a search of a big input
+/// checks hundreds of candidates as big as it, so checking all of each would take most of the search.
+/// When the start is identified, the result has all of the text, not just the start.
+pub struct PreviewChecker {
+    /// The checker which checks the start of the text
+    inner: CheckerTypes,
+    /// How many bytes of the text the checker sees
+    preview_size: usize,
+}
+
+impl PreviewChecker {
+    /// Shows the checker only the first `preview_size` bytes of the text
+    pub fn wrapping(inner: CheckerTypes, preview_size: usize) -> Self {
+        PreviewChecker {
+            inner,
+            preview_size,
+        }
+    }
+
+    /// Checks the start of the text
+    pub fn check(&self, text: &str) -> CheckResult {
+        let preview = preview(text, self.preview_size);
+        if preview.len() == text.len() {
+            return self.inner.check(text);
+        }
+        trace!(
+            "Checking the first {} of {} bytes",
+            preview.len(),
+            text.len()
+        );
+        let mut result = self.inner.check(preview);
+        if result.is_identified {
+            result.text = text.to_string();
+        }
+        result
+    }
+
+    /// Checks the start of the bytes a decoder produced when they aren't text
+    pub fn check_bytes(&self, bytes: &[u8]) -> CheckResult {
+        if bytes.len() <= self.preview_size {
+            return self.inner.check_bytes(bytes);
+        }
+        let mut result = self.inner.check_bytes(&bytes[..self.preview_size]);
+        if result.is_identified {
+            result.text = String::from_utf8_lossy(bytes).into_owned();
+        }
+        result
+    }
+}
+
+/// The start of the text, at most `size` bytes of it, without splitting a character
+fn preview(text: &str, size: usize) -> &str {
+    if text.len() <= size {
+        return text;
+    }
+    let mut end = size;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{preview, PreviewChecker};
+    use crate::checkers::{checker_type::Checker, regex_checker::RegexChecker, CheckerTypes};
+
+    /// A checker which records the length of every text it sees, and identifies text starting with "yes"
+    fn recording_checker() -> (CheckerTypes, Arc<Mutex<Vec<usize>>>) {
+        let lengths = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&lengths);
+        let checker = CheckerTypes::from_closure("Recording Checker", move |text| {
+            seen.lock().unwrap().push(text.len());
+            text.starts_with("yes")
+                .then(|| "starts with yes".to_string())
+        });
+        (checker, lengths)
+    }
+
+    #[test]
+    fn only_the_start_of_big_text_is_checked() {
+        let (checker, lengths) = recording_checker();
+        let checker = PreviewChecker::wrapping(checker, 10);
+        let text = format!("yes {}", "a".repeat(100));
+        let result = checker.check(&text);
+        assert!(result.is_identified);
+        assert_eq!(result.text, text);
+        assert_eq!(*lengths.lock().unwrap(), vec![10]);
+    }
+
+    #[test]
+    fn small_text_is_checked_whole() {
+        let (checker, lengths) = recording_checker();
+        let checker = PreviewChecker::wrapping(checker, 10);
+        assert!(!checker.check("no thanks").is_identified);
+        assert_eq!(*lengths.lock().unwrap(), vec![9]);
+    }
+
+    #[test]
+    fn the_preview_never_splits_a_character() {
+        // "é" is 2 bytes, so 3 bytes in is the middle of the second one
+        assert_eq!(preview("ééé", 3), "é");
+        assert_eq!(preview("ééé", 4), "éé");
+        assert_eq!(preview("ééé", 100), "ééé");
+    }
+
+    #[test]
+    fn a_regex_anchored_to_the_end_sees_all_of_the_text() {
+        let regex = CheckerTypes::CheckRegex(
+            Checker::<RegexChecker>::with_pattern(r"flag\{[^}]*\}$").unwrap(),
+        );
+        let checker = CheckerTypes::previewed(regex, 10);
+        let text = format!("{} flag{{at_the_end}}", "a".repeat(100));
+        assert!(checker.check(&text).is_identified);
+    }
+
+    #[test]
+    fn a_regex_which_isnt_anchored_to_the_end_sees_the_start() {
+        let regex = CheckerTypes::CheckRegex(
+            Checker::<RegexChecker>::with_pattern(r"flag\{[^}]*\}").unwrap(),
+        );
+        let checker = CheckerTypes::previewed(regex, 10);
+        let text = format!("{} flag{{at_the_end}}", "a".repeat(100));
+        assert!(!checker.check(&text).is_identified);
+    }
+}
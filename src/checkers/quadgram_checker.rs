@@ -0,0 +1,190 @@
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::text_normalize;
+use crate::config::get_config;
+use crate::storage::quadgrams::average_quadgram_score;
+use lemmeknow::Identifier;
+use log::{debug, trace};
+
+use crate::checkers::checker_type::{Check, Checker, CheckerParams, DEFAULT_CONFIDENCE_THRESHOLD};
+
+/// Checks English plaintext by how English its quadgrams look.
+/// This catches English with no dictionary words in it, such as `base16_is_hex`.
+pub struct EnglishQuadgrams;
+
+/// Fewer letters than this are too noisy to score, by default
+pub(crate) const MIN_LETTERS: usize = 8;
+/// At least this share of the characters which aren't whitespace must be letters
+const MIN_LETTER_RATIO: f64 = 0.6;
+/// English text averages above this log10 probability per quadgram, by default
+pub(crate) const ENGLISH_AVERAGE_SCORE: f64 = -5.5;
+/// A few quadgrams can look English by chance, so short text has to average higher,
+/// by this much divided by the number of quadgrams
+const SHORT_TEXT_MARGIN: f64 = 2.0;
+/// How quickly the confidence rises as the score beats the threshold
+const CONFIDENCE_STEEPNESS: f64 = 4.0;
+
+impl Check for Checker<EnglishQuadgrams> {
+    fn new() -> Self {
+        Checker {
+            name: "English Quadgram Checker",
+            description:
+                "Scores how English text looks using the frequencies of groups of 4 letters",
+            link: "http://practicalcryptography.com/cryptanalysis/text-characterisation/quadgrams/",
+            tags: vec!["english", "quadgrams"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            confidence_threshold: get_config().confidence_threshold,
+            lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        self.check_with_params(text, &self.params)
+    }
+}
+
+impl Checker<EnglishQuadgrams> {
+    /// Checks the text with these params rather than the checker's own,
+    /// so Athena can share one Quadgram Checker whatever its params
+    pub fn check_with_params(&self, text: &str, params: &CheckerParams) -> CheckResult {
+        trace!("Checking English quadgrams for {}", text);
+        let mut result = CheckResult::new(self);
+        result.description = "English quadgrams".to_string();
+
+        // Fullwidth letters and zero-width spaces would otherwise not count as English
+        let original = text;
+        let text = &text_normalize(text);
+        let letters = text.chars().filter(char::is_ascii_alphabetic).count();
+        let non_whitespace = text.chars().filter(|c| !c.is_whitespace()).count();
+        // Scoring needs at least one quadgram, whatever the params say
+        if letters < params.min_statistical_length.max(4)
+            || (letters as f64) < non_whitespace as f64 * MIN_LETTER_RATIO
+        {
+            return result;
+        }
+
+        let Some(score) = average_quadgram_score(text) else {
+            return result;
+        };
+        result.score = Some(score);
+        let threshold = english_threshold(letters, params.quadgram_threshold);
+        result.confidence = score_to_confidence(score, threshold);
+        if result.confidence > self.confidence_threshold {
+            debug!(
+                "Quadgrams scored {} at {}, which looks English",
+                text, score
+            );
+            result.is_identified = true;
+            result.text = original.to_string();
+        }
+        let side = if score > threshold { "above" } else { "below" };
+        result.reason = format!(
+            "averaged {score:.2} per quadgram, {side} the English threshold of {threshold:.2}"
+        );
+        result
+    }
+}
+
+/// Maps the score through a sigmoid, so scoring `threshold`, where we draw the line,
+/// is `DEFAULT_CONFIDENCE_THRESHOLD`, and scores above it quickly approach 1
+fn score_to_confidence(score: f64, threshold: f64) -> f32 {
+    let threshold_confidence = f64::from(DEFAULT_CONFIDENCE_THRESHOLD);
+    // The sigmoid of this is the default confidence threshold
+    let offset = (threshold_confidence / (1.0 - threshold_confidence)).ln();
+    let x = CONFIDENCE_STEEPNESS * (score - threshold) + offset;
+    (1.0 / (1.0 + (-x).exp())) as f32
+}
+
+/// The average score text with this many letters needs to beat to look English,
+/// when long English text averages above `average_score`
+fn english_threshold(letters: usize, average_score: f64) -> f64 {
+    let quadgrams = (letters - 3) as f64;
+    average_score + SHORT_TEXT_MARGIN / quadgrams
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker, CheckerParams},
+        quadgram_checker::{score_to_confidence, EnglishQuadgrams},
+    };
+
+    #[test]
+    fn identifies_english_without_dictionary_words() {
+        let checker = Checker::<EnglishQuadgrams>::new();
+        assert!(checker.check("base16_is_hex").is_identified);
+    }
+
+    #[test]
+    fn params_decide_what_looks_english() {
+        let text = "base16_is_hex";
+        let too_short = Checker::<EnglishQuadgrams>::new().with_params(CheckerParams {
+            min_statistical_length: 12,
+            ..CheckerParams::default()
+        });
+        assert!(!too_short.check(text).is_identified);
+
+        let stricter = Checker::<EnglishQuadgrams>::new().with_params(CheckerParams {
+            quadgram_threshold: -4.0,
+            ..CheckerParams::default()
+        });
+        assert!(!stricter.check(text).is_identified);
+        assert!(stricter.check(text).reason.contains("below"));
+    }
+
+    #[test]
+    fn confidence_is_the_default_threshold_at_the_english_threshold() {
+        assert!((score_to_confidence(-5.0, -5.0) - 0.8).abs() < 1e-6);
+        assert!(score_to_confidence(-4.0, -5.0) > 0.95);
+        assert!(score_to_confidence(-6.0, -5.0) < 0.5);
+    }
+
+    #[test]
+    fn identifies_english_sentence() {
+        let checker = Checker::<EnglishQuadgrams>::new();
+        assert!(
+            checker
+                .check("it was the best of times, it was the worst of times")
+                .is_identified
+        );
+    }
+
+    #[test]
+    fn random_letters_score_below_threshold() {
+        let checker = Checker::<EnglishQuadgrams>::new();
+        for text in [
+            "kmfhwqzrtbplxv",
+            "qzx vbnm kjhg wpqz xcvb nmlk",
+            "svool gsviv tvmvizo",
+        ] {
+            assert!(!checker.check(text).is_identified, "{text} looked English");
+        }
+    }
+
+    #[test]
+    fn base64_scores_below_threshold() {
+        let checker = Checker::<EnglishQuadgrams>::new();
+        for text in [
+            "aGVsbG8gd29ybGQ=",
+            "SGVscCBJIG5lZWQgc29tZWJvZHkh",
+            "ZmxhZ19pc19oZXJlX2luX3RoZSBtaWRkbGU=",
+        ] {
+            assert!(!checker.check(text).is_identified, "{text} looked English");
+        }
+    }
+
+    #[test]
+    fn exposes_the_score() {
+        let checker = Checker::<EnglishQuadgrams>::new();
+        let english = checker.check("hello there general").score.unwrap();
+        let gibberish = checker.check("kmfhwqzrtbplxv").score.unwrap();
+        assert!(english > gibberish);
+        // Too short to score
+        assert!(checker.check("hi").score.is_none());
+    }
+}
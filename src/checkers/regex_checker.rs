@@ -4,13 +4,30 @@ use super::checker_type::{Check, Checker};
 use crate::{checkers::checker_result::CheckResult, config::get_config};
 use log::trace;
 use regex::Regex;
+use regex_syntax::hir::{Anchor, Hir, HirKind};
 
 /// The Regex Checker checks if the text matches a known Regex pattern.
 /// This is the struct for it.
 pub struct RegexChecker;
 
-impl Check for Checker<RegexChecker> {
-    fn new() -> Self {
+impl Checker<RegexChecker> {
+    /// Creates a Regex Checker which succeeds as soon as the text matches the pattern,
+    /// such as `flag\{.*\}` when you know the format of the flag.
+    /// # Errors
+    /// Returns the regex error if the pattern is invalid,
+    /// so it is caught before the search starts rather than in the middle of it.
+    pub fn with_pattern(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self::with_regex(Some(Regex::new(pattern)?)))
+    }
+
+    /// Whether the regex has to see the end of the text to match, like `flag\{.*\}$`,
+    /// so it can't be shown only the start of it
+    pub fn is_anchored_to_end(&self) -> bool {
+        self.regex.as_ref().is_some_and(is_anchored_to_end)
+    }
+
+    /// Creates a Regex Checker with the compiled regex
+    pub(crate) fn with_regex(regex: Option<Regex>) -> Self {
         Checker {
             name: "Regex Checker",
             description: "Uses Regex to check for regex matches, useful for finding cribs.",
@@ -18,32 +35,119 @@ impl Check for Checker<RegexChecker> {
             tags: vec!["crib", "regex"],
             expected_runtime: 0.01,
             popularity: 1.0,
+            confidence_threshold: get_config().confidence_threshold,
             lemmeknow_config: Identifier::default(),
+            regex,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
             _phantom: std::marker::PhantomData,
         }
     }
+}
+
+/// Whether the regex has a `$` or `\z` anywhere in it. A multi-line `$` counts too,
+/// as it matches at the end of the text as well as at the end of each line.
+pub(crate) fn is_anchored_to_end(regex: &Regex) -> bool {
+    // The regex compiled, so it parses
+    regex_syntax::Parser::new()
+        .parse(regex.as_str())
+        .map_or(true, |hir| has_end_anchor(&hir))
+}
+
+/// Whether any part of the parsed regex is anchored to the end of the text or of a line
+fn has_end_anchor(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Anchor(Anchor::EndText | Anchor::EndLine) => true,
+        HirKind::Repetition(repetition) => has_end_anchor(&repetition.hir),
+        HirKind::Group(group) => has_end_anchor(&group.hir),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().any(has_end_anchor),
+        _ => false,
+    }
+}
+
+impl Check for Checker<RegexChecker> {
+    /// Uses the regex from the config
+    /// # Panics
+    /// Panics if the config's regex is invalid.
+    fn new() -> Self {
+        Self::with_regex(get_config().regex.as_deref().map(|pattern| {
+            Regex::new(pattern).unwrap_or_else(|err| panic!("Invalid regex {pattern}: {err}"))
+        }))
+    }
 
     fn check(&self, text: &str) -> CheckResult {
         trace!("Checking {} with regex", text);
-        // TODO put this into a lazy static so we don't generate it everytime
-        let config = get_config();
-        let regex_to_parse = config.regex.clone();
-        let re = Regex::new(&regex_to_parse.unwrap()).unwrap();
-
-        let regex_check_result = re.is_match(text);
-        let mut plaintext_found = false;
-        let printed_name = format!("Regex matched: {re}");
-        if regex_check_result {
-            plaintext_found = true;
+        let mut result = CheckResult::new(self);
+
+        let Some(re) = &self.regex else {
+            return result;
+        };
+        if let Some(matched) = re.find(text) {
+            result.is_identified = true;
+            result.text = text.to_string();
+            result.confidence = 1.0;
+            result.description = format!("Regex {re} matched: {}", matched.as_str());
+            result.reason = format!("regex `{re}` matched at offset {}", matched.start());
         }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        regex_checker::RegexChecker,
+        CheckerTypes,
+    };
+
+    #[test]
+    fn matches_flag_format() {
+        let checker = CheckerTypes::CheckRegex(
+            Checker::<RegexChecker>::with_pattern(r"flag\{[^}]*\}").unwrap(),
+        );
+        let result = checker.check("the answer is flag{r3g3x_r0cks} so well done");
+        assert!(result.is_identified);
+        assert!(result.description.ends_with("flag{r3g3x_r0cks}"));
+        assert_eq!(result.checker_name, "Regex Checker");
+        assert_eq!(result.reason, r"regex `flag\{[^}]*\}` matched at offset 14");
+    }
 
-        CheckResult {
-            is_identified: plaintext_found,
-            text: text.to_string(),
-            checker_name: self.name,
-            checker_description: self.description,
-            description: printed_name,
-            link: self.link,
+    #[test]
+    fn does_not_match_other_text() {
+        let checker = Checker::<RegexChecker>::with_pattern(r"HTB\{[^}]*\}").unwrap();
+        let result = checker.check("flag{not the right format}");
+        assert!(!result.is_identified);
+    }
+
+    #[test]
+    fn matches_case_insensitive_flag() {
+        let checker = Checker::<RegexChecker>::with_pattern(r"(?i)flag\{[^}]*\}").unwrap();
+        let result = checker.check("FLAG{shouting}");
+        assert!(result.is_identified);
+        assert!(result.description.ends_with("FLAG{shouting}"));
+    }
+
+    #[test]
+    fn patterns_ending_in_an_anchor_are_anchored_to_the_end() {
+        for pattern in [
+            r"flag\{.*\}$",
+            r"(?i)(flag|htb)\{.*\}\z",
+            r"(?m)^end$",
+            r"a|(b$)",
+        ] {
+            let checker = Checker::<RegexChecker>::with_pattern(pattern).unwrap();
+            assert!(checker.is_anchored_to_end(), "{pattern}");
         }
+        for pattern in [r"^flag\{.*\}", r"flag\$", r"[$]"] {
+            let checker = Checker::<RegexChecker>::with_pattern(pattern).unwrap();
+            assert!(!checker.is_anchored_to_end(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_error() {
+        assert!(Checker::<RegexChecker>::with_pattern(r"flag\{(unclosed").is_err());
     }
 }
@@ -0,0 +1,212 @@
+use crate::checkers::checker_result::CheckResult;
+use crate::config::get_config;
+use lemmeknow::Identifier;
+use log::{debug, trace};
+use quick_xml::{events::Event, Reader};
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// Checks if the text is structured data, JSON, XML or YAML,
+/// which is plaintext but won't look like English.
+pub struct StructuredDataChecker;
+
+/// YAML needs at least this many `key: value` lines, so a sentence with a colon in isn't YAML
+const MIN_YAML_KEYS: usize = 2;
+
+impl Check for Checker<StructuredDataChecker> {
+    fn new() -> Self {
+        Checker {
+            name: "Structured Data Checker",
+            description: "Checks if the text is a JSON, XML or YAML document",
+            link: "https://en.wikipedia.org/wiki/Serialization",
+            tags: vec!["json", "xml", "yaml"],
+            expected_runtime: 0.01,
+            popularity: 0.5,
+            confidence_threshold: get_config().confidence_threshold,
+            lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist: None,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking if {} is structured data", text);
+        let mut result = CheckResult::new(self);
+
+        let format = if is_json_document(text) {
+            "JSON"
+        } else if is_xml_document(text) {
+            "XML"
+        } else if is_yaml_mapping(text) {
+            "YAML"
+        } else {
+            return result;
+        };
+        debug!("{} is valid {}", text, format);
+        result.is_identified = true;
+        result.text = text.to_string();
+        result.confidence = 1.0;
+        result.description = format!("valid {format}");
+        result.reason = format!("parsed as a {format} document");
+        result
+    }
+}
+
+/// Whether the text is a JSON object or array with something in it.
+/// Bare scalars like `5` or `"hi"` are valid JSON but don't count.
+#[cfg(feature = "serde")]
+fn is_json_document(text: &str) -> bool {
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(serde_json::Value::Object(object)) => !object.is_empty(),
+        Ok(serde_json::Value::Array(array)) => !array.is_empty(),
+        _ => false,
+    }
+}
+
+/// JSON needs serde_json, which comes with the serde feature
+#[cfg(not(feature = "serde"))]
+fn is_json_document(_text: &str) -> bool {
+    false
+}
+
+/// Whether the text is one well-formed XML element, optionally after a declaration or comments
+fn is_xml_document(text: &str) -> bool {
+    let text = text.trim();
+    if !text.starts_with('<') || !text.ends_with('>') {
+        return false;
+    }
+
+    let mut reader = Reader::from_str(text);
+    reader.trim_text(true);
+    let mut depth = 0usize;
+    let mut roots = 0;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(_)) => {
+                if depth == 0 {
+                    roots += 1;
+                }
+                depth += 1;
+            }
+            Ok(Event::End(_)) => match depth.checked_sub(1) {
+                Some(new_depth) => depth = new_depth,
+                None => return false,
+            },
+            Ok(Event::Empty(_)) if depth == 0 => roots += 1,
+            // Text outside of the root element isn't XML
+            Ok(Event::Text(_) | Event::CData(_)) if depth == 0 => return false,
+            Ok(Event::Eof) => return depth == 0 && roots == 1,
+            Ok(_) => {}
+            Err(_) => return false,
+        }
+    }
+}
+
+/// Whether the text is a YAML mapping, checked conservatively because almost anything is valid YAML.
+/// Every line has to be a `key: value`, a `key:` starting a nested block or a `- item`,
+/// the document has to start with a key and there have to be a few keys.
+fn is_yaml_mapping(text: &str) -> bool {
+    let mut lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .skip_while(|line| line.trim_end() == "---")
+        .peekable();
+    match lines.peek() {
+        Some(first) if !first.starts_with(' ') && yaml_key(first).is_some() => {}
+        _ => return false,
+    }
+
+    let mut keys = 0;
+    for line in lines {
+        let line = line.trim_start();
+        if yaml_key(line).is_some() {
+            keys += 1;
+        } else if !(line == "-" || line.starts_with("- ")) {
+            return false;
+        }
+    }
+    keys >= MIN_YAML_KEYS
+}
+
+/// The key of a `key: value` or `key:` line, where the key is a plain identifier
+fn yaml_key(line: &str) -> Option<&str> {
+    let (key, value) = line.split_once(':')?;
+    let mut chars = key.chars();
+    let starts_well = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    let identifier = chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    let separated = value.is_empty() || value.starts_with(' ');
+    (starts_well && identifier && separated).then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        structured_data_checker::StructuredDataChecker,
+    };
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn identifies_json_object() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        let result = checker.check(r#"{"user": "admin", "roles": ["read", "write"]}"#);
+        assert!(result.is_identified);
+        assert_eq!(result.description, "valid JSON");
+    }
+
+    #[test]
+    fn json_bare_number_is_not_identified() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        assert!(!checker.check("5").is_identified);
+        assert!(!checker.check("{}").is_identified);
+    }
+
+    #[test]
+    fn identifies_xml_document() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        let result = checker
+            .check(r#"<?xml version="1.0"?><note to="ares"><body>hello</body><empty/></note>"#);
+        assert!(result.is_identified);
+        assert_eq!(result.description, "valid XML");
+    }
+
+    #[test]
+    fn broken_xml_is_not_identified() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        for text in [
+            "<note><body>hello</note>",
+            "<note>",
+            "<a></a><b></b>",
+            "<a> & </b>",
+        ] {
+            assert!(!checker.check(text).is_identified, "{text} was XML");
+        }
+    }
+
+    #[test]
+    fn identifies_yaml_mapping() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        let result = checker.check(
+            "---\nname: ares\n# a comment\nversion: 0.10.0\ndecoders:\n  - base64\n  - caesar\n",
+        );
+        assert!(result.is_identified);
+        assert_eq!(result.description, "valid YAML");
+    }
+
+    #[test]
+    fn english_is_not_yaml() {
+        let checker = Checker::<StructuredDataChecker>::new();
+        for text in [
+            "The meeting is at noon, please bring the slides.",
+            "Note: the meeting is at noon.",
+            "Dear team: the meeting moved.\nIt is now at noon: see you there.",
+        ] {
+            assert!(!checker.check(text).is_identified, "{text} was YAML");
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! The words of a dictionary or a wordlist, kept as an fst set rather than a set of Strings.
+//! The words are stored once, sharing their prefixes and suffixes, and looking a word up
+//! walks the set byte by byte, so it doesn't hash or allocate.
+//! The dictionaries we ship are compiled into sets by build.rs, so loading them is just using their bytes.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use fst::Set;
+
+/// A set of words, which is cheap to clone as clones share the words.
+/// ```
+/// use ares::checkers::word_set::WordSet;
+///
+/// let words = WordSet::from_words(["hunter2", "swordfish", "hunter2"]);
+/// assert!(words.contains("swordfish"));
+/// assert!(!words.contains("sword"));
+/// assert_eq!(words.len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct WordSet {
+    /// The words, in the bytes of the binary for the dictionaries we ship
+    set: Arc<Set<Cow<'static, [u8]>>>,
+}
+
+impl WordSet {
+    /// The set of the words, in any order and with any repeats
+    /// # Panics
+    /// Doesn't, as the words are sorted and repeats removed before they go into the set.
+    pub fn from_words<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        let mut words: Vec<S> = words.into_iter().collect();
+        // An fst set is built from its words in order, without repeats
+        words.sort_unstable_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        words.dedup_by(|a, b| a.as_ref() == b.as_ref());
+        let set = Set::from_iter(words.iter().map(AsRef::as_ref))
+            .expect("the words are sorted and distinct");
+        WordSet::from_set(set.into_fst().into_inner().into())
+    }
+
+    /// The set build.rs compiled into these bytes
+    /// # Panics
+    /// Panics if they aren't an fst set, which would be a bug in build.rs.
+    pub(crate) fn from_fst(bytes: &'static [u8]) -> Self {
+        WordSet::from_set(bytes.into())
+    }
+
+    /// The set in the bytes
+    fn from_set(bytes: Cow<'static, [u8]>) -> Self {
+        WordSet {
+            set: Arc::new(Set::new(bytes).expect("the bytes are an fst set")),
+        }
+    }
+
+    /// The empty set
+    pub fn empty() -> Self {
+        WordSet::from_words::<&str>([])
+    }
+
+    /// Whether the word is in the set, exactly as it's written
+    pub fn contains(&self, word: &str) -> bool {
+        self.set.contains(word)
+    }
+
+    /// How many words there are
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Whether there are no words
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// The words, in order
+    /// # Panics
+    /// Doesn't, as only words, which are UTF-8, go into the set.
+    pub fn words(&self) -> Vec<String> {
+        self.set
+            .stream()
+            .into_strs()
+            .expect("only words, which are UTF-8, go into the set")
+    }
+}
+
+/// How many words there are, as there are too many to print
+impl fmt::Debug for WordSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WordSet({} words)", self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordSet;
+
+    #[test]
+    fn words_are_matched_exactly() {
+        let words = WordSet::from_words(vec!["Hello".to_string(), "wörld".to_string()]);
+        assert!(words.contains("Hello"));
+        assert!(words.contains("wörld"));
+        assert!(!words.contains("hello"));
+        assert!(!words.contains("Hell"));
+        assert!(!words.contains(""));
+        assert_eq!(words.words(), ["Hello", "wörld"]);
+    }
+
+    #[test]
+    fn the_empty_set_has_no_words() {
+        assert!(WordSet::empty().is_empty());
+        assert!(!WordSet::empty().contains(""));
+        assert!(WordSet::from_words(Vec::<String>::new()).is_empty());
+    }
+}
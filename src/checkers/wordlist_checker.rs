@@ -0,0 +1,195 @@
+use crate::config::get_config;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use crate::checkers::checker_result::CheckResult;
+use crate::checkers::word_set::WordSet;
+use lemmeknow::Identifier;
+use log::{debug, trace};
+
+use crate::checkers::checker_type::{Check, Checker};
+
+/// The Wordlist Checker checks if the text is one of the words in a user's wordlist,
+/// such as passwords or hostnames which English detection would never spot.
+pub struct WordlistChecker;
+
+/// The words the Wordlist Checker looks for, and how it matches them
+#[derive(Clone)]
+pub struct Wordlist {
+    /// The words, shared so the file is only read once however many checkers use it
+    pub words: WordSet,
+    /// Lowercase the text and the words before matching
+    pub lowercase: bool,
+    /// Also succeed if any whitespace separated token of the text is a word
+    pub any_token: bool,
+}
+
+impl Wordlist {
+    /// Reads the words from a file with one word per line.
+    /// Lines are trimmed and empty lines are skipped.
+    /// # Errors
+    /// Returns the IO error if the file can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Wordlist::from_words(contents.lines()))
+    }
+
+    /// The words, trimmed, skipping empty ones
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let words = words
+            .into_iter()
+            .map(str::trim)
+            .filter(|word| !word.is_empty());
+        Wordlist {
+            words: WordSet::from_words(words),
+            lowercase: false,
+            any_token: false,
+        }
+    }
+}
+
+impl Checker<WordlistChecker> {
+    /// Creates a Wordlist Checker which looks for the words, if there are any
+    pub(crate) fn with_wordlist(wordlist: Option<Wordlist>) -> Self {
+        Checker {
+            name: "Wordlist Checker",
+            description: "Checks if the text is in a wordlist the user gave us",
+            link: "https://en.wikipedia.org/wiki/Wordlist",
+            tags: vec!["wordlist"],
+            expected_runtime: 0.01,
+            popularity: 1.0,
+            confidence_threshold: get_config().confidence_threshold,
+            lemmeknow_config: Identifier::default(),
+            regex: None,
+            wordlist,
+            languages: Vec::new(),
+            params: get_config().checker_params.clone(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Creates a Wordlist Checker from a file with one word per line, see `Wordlist::from_file`.
+    /// # Errors
+    /// Returns the IO error if the file can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut checker = Self::new();
+        checker.wordlist = Some(Wordlist::from_file(path)?);
+        Ok(checker)
+    }
+
+    /// Matches without caring about case
+    pub fn with_lowercase(mut self) -> Self {
+        if let Some(wordlist) = &mut self.wordlist {
+            // Lowercase the words once here, rather than on every check
+            wordlist.words =
+                WordSet::from_words(wordlist.words.words().iter().map(|w| w.to_lowercase()));
+            wordlist.lowercase = true;
+        }
+        self
+    }
+
+    /// Also succeeds if any whitespace separated token of the text is a word
+    pub fn with_any_token(mut self) -> Self {
+        if let Some(wordlist) = &mut self.wordlist {
+            wordlist.any_token = true;
+        }
+        self
+    }
+}
+
+impl Check for Checker<WordlistChecker> {
+    /// Creates a Wordlist Checker with the words in the config, if there are any, see `Config::wordlist`.
+    /// Use `from_file` to give it other words.
+    fn new() -> Self {
+        Self::with_wordlist(get_config().wordlist.clone())
+    }
+
+    fn check(&self, text: &str) -> CheckResult {
+        trace!("Checking {} against the wordlist", text);
+        let mut result = CheckResult::new(self);
+
+        let Some(wordlist) = &self.wordlist else {
+            return result;
+        };
+        let candidate = if wordlist.lowercase {
+            text.trim().to_lowercase()
+        } else {
+            text.trim().to_string()
+        };
+
+        let found = if wordlist.words.contains(&candidate) {
+            Some(candidate.as_str())
+        } else if wordlist.any_token {
+            candidate
+                .split_whitespace()
+                .find(|token| wordlist.words.contains(token))
+        } else {
+            None
+        };
+        if let Some(word) = found {
+            debug!("Found {} in the wordlist", word);
+            result.is_identified = true;
+            result.text = text.to_string();
+            result.confidence = 1.0;
+            result.description = format!("Wordlist word: {word}");
+            result.reason = format!("`{word}` is in the wordlist");
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::checkers::{
+        checker_type::{Check, Checker},
+        wordlist_checker::WordlistChecker,
+    };
+
+    /// Writes the words to a file in the temp directory, named after the test so tests don't clash
+    fn wordlist_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("ares_wordlist_{name}.txt"));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn matches_a_word_in_the_file() {
+        let path = wordlist_file("match", "hunter2\nCorrectHorse\n\n  db01.internal  \n");
+        let checker = Checker::<WordlistChecker>::from_file(&path).unwrap();
+        assert!(checker.check("hunter2").is_identified);
+        assert!(checker.check(" db01.internal\n").is_identified);
+        // Case matters unless we lowercase
+        assert!(!checker.check("correcthorse").is_identified);
+        let checker = checker.with_lowercase();
+        assert!(checker.check("correcthorse").is_identified);
+    }
+
+    #[test]
+    fn misses_text_not_in_the_file() {
+        let path = wordlist_file("miss", "hunter2\n");
+        let checker = Checker::<WordlistChecker>::from_file(&path).unwrap();
+        assert!(!checker.check("hunter3").is_identified);
+        assert!(!checker.check("the password is hunter2").is_identified);
+    }
+
+    #[test]
+    fn any_token_matches_a_word_inside_the_text() {
+        let path = wordlist_file("token", "hunter2\n");
+        let checker = Checker::<WordlistChecker>::from_file(&path)
+            .unwrap()
+            .with_any_token();
+        let result = checker.check("the password is hunter2");
+        assert!(result.is_identified);
+        assert_eq!(result.description, "Wordlist word: hunter2");
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let path = std::env::temp_dir().join("ares_wordlist_which_does_not_exist.txt");
+        assert!(Checker::<WordlistChecker>::from_file(path).is_err());
+    }
+}
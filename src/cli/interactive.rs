@@ -0,0 +1,294 @@
+//! Lets the user pick the plaintext from what Ares found, for `ares --interactive`.
+//! Each plaintext is listed with how sure the checker was, how many decoders deep it is
+//! and how it was decoded. The one picked can be cracked further, for when the checker
+//! accepted text which was still encoded.
+
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+
+use crate::decoders::interface::Decoder;
+use crate::output::preview;
+use crate::{CrackOutcome, CrackSuccess};
+
+/// What the user did with the plaintexts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    /// This one is the plaintext
+    Chosen(usize),
+    /// This one is still encoded, so crack it further
+    Continue(usize),
+}
+
+/// Asks the user which of the plaintexts is the real one
+pub struct Picker {
+    /// Where the answers come from
+    reader: Box<dyn BufRead>,
+    /// Where the plaintexts and questions go
+    writer: Box<dyn Write>,
+    /// Whether anyone is there to ask. When nobody is, the best plaintext is picked without asking.
+    asking: bool,
+}
+
+impl Picker {
+    /// Asks on stderr and reads the answers from stdin, but only when both are terminals,
+    /// so `ares --interactive` in a script never waits for an answer
+    pub fn for_terminal() -> Self {
+        let asking = io::stdin().is_terminal() && io::stderr().is_terminal();
+        Picker::new(BufReader::new(io::stdin()), io::stderr(), asking)
+    }
+
+    /// Asks with the writer and reads the answers from the reader if `asking` is true,
+    /// otherwise the best plaintext is picked without reading or writing anything
+    pub fn new(reader: impl BufRead + 'static, writer: impl Write + 'static, asking: bool) -> Self {
+        Picker {
+            reader: Box::new(reader),
+            writer: Box::new(writer),
+            asking,
+        }
+    }
+
+    /// Lists the plaintexts and asks which is the real one, then whether to crack it further.
+    /// Enter picks the first, the best. When the answers run out, or can't be read, it's the first too.
+    pub fn pick(&mut self, plaintexts: &[CrackSuccess]) -> Pick {
+        if !self.asking || plaintexts.is_empty() {
+            return Pick::Chosen(0);
+        }
+        crate::cli_pretty_printing::clear_search_progress();
+        let _ = self.list(plaintexts);
+        let chosen = loop {
+            let question = format!(
+                "Which is the plaintext? [1-{}, Enter for 1] ",
+                plaintexts.len()
+            );
+            let Some(answer) = self.ask(&question) else {
+                return Pick::Chosen(0);
+            };
+            if answer.is_empty() {
+                break 0;
+            }
+            match answer.parse::<usize>() {
+                Ok(number) if (1..=plaintexts.len()).contains(&number) => break number - 1,
+                _ => {
+                    let _ = writeln!(self.writer, "{answer} isn't one of them");
+                }
+            }
+        };
+        let again = self.ask("Press c to carry on cracking it, or Enter to keep it ");
+        if again.is_some_and(|again| again.eq_ignore_ascii_case("c")) {
+            Pick::Continue(chosen)
+        } else {
+            Pick::Chosen(chosen)
+        }
+    }
+
+    /// Picks the plaintext from the outcome, cracking the one picked further with `carry_on`
+    /// for as long as the user asks to. When cracking further finds nothing, the one picked is kept.
+    /// Outcomes without plaintexts are given back as they are.
+    pub fn choose(
+        &mut self,
+        outcome: CrackOutcome,
+        mut carry_on: impl FnMut(&CrackSuccess) -> CrackOutcome,
+    ) -> CrackOutcome {
+        let CrackOutcome::Found(mut plaintexts) = outcome else {
+            return outcome;
+        };
+        loop {
+            match self.pick(&plaintexts) {
+                Pick::Chosen(index) => {
+                    return CrackOutcome::Found(vec![plaintexts.swap_remove(index)])
+                }
+                Pick::Continue(index) => {
+                    let picked = plaintexts.swap_remove(index);
+                    match carry_on(&picked) {
+                        CrackOutcome::Found(further) => plaintexts = further,
+                        _ => {
+                            let _ = writeln!(
+                                self.writer,
+                                "Nothing more was found in it, so it's the plaintext"
+                            );
+                            return CrackOutcome::Found(vec![picked]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes the plaintexts, numbered from 1, with how sure the checker was,
+    /// how many decoders deep each is and how it was decoded
+    fn list(&mut self, plaintexts: &[CrackSuccess]) -> io::Result<()> {
+        match plaintexts.len() {
+            1 => writeln!(self.writer, "Ares found 1 plaintext:")?,
+            found => writeln!(self.writer, "Ares found {found} plaintexts:")?,
+        }
+        for (index, plaintext) in plaintexts.iter().enumerate() {
+            let confidence = plaintext
+                .path
+                .last()
+                .map_or(0.0, |step| step.checker_confidence);
+            // The input itself being the plaintext is no decoders deep
+            let route: Vec<String> = plaintext
+                .path
+                .iter()
+                .filter(|step| step.decoder != Decoder::default().name)
+                .map(ToString::to_string)
+                .collect();
+            writeln!(
+                self.writer,
+                "{}. {} (confidence {confidence:.2}, depth {}) {}",
+                index + 1,
+                preview(&plaintext.plaintext),
+                route.len(),
+                route.join(" → ")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Asks the question and reads the answer, trimmed, or None if there isn't one to read
+    fn ask(&mut self, question: &str) -> Option<String> {
+        write!(self.writer, "{question}").ok()?;
+        self.writer.flush().ok()?;
+        let mut answer = String::new();
+        match self.reader.read_line(&mut answer) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(answer.trim().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{Pick, Picker};
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::{DecoderBuilder, DefaultDecoder};
+    use crate::test_helpers::SharedWriter;
+    use crate::{CrackOutcome, CrackSuccess, RunStats};
+
+    /// A picker which reads the answers and writes to the writer it gives back
+    fn answering(answers: &str) -> (Picker, SharedWriter) {
+        let writer = SharedWriter::default();
+        let picker = Picker::new(
+            Cursor::new(answers.to_string().into_bytes()),
+            writer.clone(),
+            true,
+        );
+        (picker, writer)
+    }
+
+    /// A plaintext `depth` decoders from the input, which the checker was `confidence` sure of
+    fn plaintext(text: &str, depth: usize, confidence: f32) -> CrackSuccess {
+        let base64 = DecoderBuilder::new("Base64")
+            .with_description("N/A")
+            .with_link("N/A")
+            .build::<DefaultDecoder>();
+        let mut path = vec![CrackResult::new(&base64); depth];
+        if let Some(last) = path.last_mut() {
+            last.checker_confidence = confidence;
+        }
+        CrackSuccess {
+            plaintext: text.to_string(),
+            bytes: None,
+            path,
+            stats: RunStats::default(),
+        }
+    }
+
+    #[test]
+    fn the_user_picks_by_number() {
+        let (mut picker, writer) = answering("2\n\n");
+        let plaintexts = [plaintext("aGVsbG8=", 1, 0.9), plaintext("hello", 2, 0.6)];
+        assert_eq!(picker.pick(&plaintexts), Pick::Chosen(1));
+        let written = writer.contents();
+        assert!(
+            written.starts_with("Ares found 2 plaintexts:\n"),
+            "{written}"
+        );
+        assert!(
+            written.contains("1. \"aGVsbG8=\" (confidence 0.90, depth 1) Base64\n"),
+            "{written}"
+        );
+        assert!(written.contains("2. \"hello\" (confidence 0.60, depth 2) Base64 → Base64\n"));
+        assert!(written.contains("Which is the plaintext? [1-2, Enter for 1] "));
+    }
+
+    #[test]
+    fn enter_picks_the_best_and_nonsense_is_asked_again() {
+        let plaintexts = [plaintext("one", 1, 0.9), plaintext("two", 1, 0.8)];
+        let (mut picker, _) = answering("\n\n");
+        assert_eq!(picker.pick(&plaintexts), Pick::Chosen(0));
+
+        let (mut picker, writer) = answering("3\nzero\n2\n\n");
+        assert_eq!(picker.pick(&plaintexts), Pick::Chosen(1));
+        assert!(writer.contents().contains("3 isn't one of them\n"));
+        assert!(writer.contents().contains("zero isn't one of them\n"));
+    }
+
+    #[test]
+    fn c_cracks_the_pick_further() {
+        let (mut picker, writer) = answering("1\nc\n2\n\n");
+        let outcome = CrackOutcome::Found(vec![
+            plaintext("aGVsbG8=", 1, 0.9),
+            plaintext("something else", 1, 0.5),
+        ]);
+        let mut carried_on = Vec::new();
+        let chosen = picker.choose(outcome, |picked| {
+            carried_on.push(picked.plaintext.clone());
+            CrackOutcome::Found(vec![plaintext("hel", 2, 0.4), plaintext("hello", 2, 0.8)])
+        });
+        assert_eq!(carried_on, ["aGVsbG8="]);
+        assert_eq!(chosen.found().unwrap().plaintext, "hello");
+        // Asked about the first plaintexts, then about what cracking further found
+        assert_eq!(
+            writer.contents().matches("Ares found 2 plaintexts").count(),
+            2
+        );
+    }
+
+    #[test]
+    fn the_pick_is_kept_when_cracking_further_finds_nothing() {
+        let (mut picker, writer) = answering("1\nc\n");
+        let outcome = CrackOutcome::Found(vec![plaintext("hello", 1, 0.9)]);
+        let chosen = picker.choose(outcome, |_| CrackOutcome::Exhausted(RunStats::default()));
+        assert_eq!(chosen.found().unwrap().plaintext, "hello");
+        assert!(writer
+            .contents()
+            .ends_with("Nothing more was found in it, so it's the plaintext\n"));
+    }
+
+    #[test]
+    fn nobody_is_asked_without_a_terminal() {
+        let writer = SharedWriter::default();
+        // Reading from it would fail the test, rather than wait
+        let mut picker = Picker::new(FailingReader, writer.clone(), false);
+        let outcome = CrackOutcome::Found(vec![plaintext("one", 1, 0.9), plaintext("two", 1, 0.8)]);
+        let chosen = picker.choose(outcome, |_| panic!("nobody asked to carry on"));
+        assert_eq!(chosen.found().unwrap().plaintext, "one");
+        assert!(writer.contents().is_empty());
+    }
+
+    #[test]
+    fn running_out_of_answers_picks_the_best() {
+        let (mut picker, _) = answering("");
+        let plaintexts = [plaintext("one", 1, 0.9), plaintext("two", 1, 0.8)];
+        assert_eq!(picker.pick(&plaintexts), Pick::Chosen(0));
+    }
+
+    /// A reader which panics if it's read
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            panic!("the picker read an answer without a terminal")
+        }
+    }
+
+    impl std::io::BufRead for FailingReader {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            panic!("the picker read an answer without a terminal")
+        }
+
+        fn consume(&mut self, _: usize) {}
+    }
+}
@@ -1,17 +1,46 @@
-use std::{fs::File, io::Read};
+/// Lets the user pick from the plaintexts Ares found, for `--interactive`
+pub mod interactive;
+/// Cracks one line after another in one session, for `ares repl`
+pub mod repl;
 
-use crate::{cli_pretty_printing::panic_failure_both_input_and_fail_provided, config::Config};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, IsTerminal, Read},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use crate::config::file::{self as config_file, ConfigFile};
+use crate::{
+    checkers::{file_signature_checker::file_type, list_checkers, wordlist_checker::Wordlist},
+    cli_pretty_printing::{benchmark_suggestions, benchmark_table, checker_table, decoder_table},
+    config::{set_global_config, Config, OutputFormat},
+    decoders::{
+        benchmark::benchmark_decoders,
+        chain::{decode_chain, encode_chain},
+        list_decoders,
+    },
+    CrackOutcome,
+};
 /// This doc string acts as a help message when the usees run '--help' in CLI mode
 /// as do all doc strings on fields
-use clap::Parser;
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand};
 use lemmeknow::Identifier;
-use log::trace;
+use log::{trace, warn};
+use repl::Repl;
 
 /// The struct for Clap CLI arguments
 #[derive(Parser)]
 #[command(author = "Bee <bee@skerritt.blog>", about, long_about = None)]
 pub struct Opts {
-    /// Some input. Because this isn't an Option<T> it's required to be used
+    /// The text to decode. Without it, or `--text` or `--file`, the text is read from stdin,
+    /// so `cat blob | ares` works
+    #[arg(value_name = "TEXT", conflicts_with = "text")]
+    positional_text: Option<String>,
+
+    /// The text to decode, like the TEXT argument
     #[arg(short, long)]
     text: Option<String>,
 
@@ -29,26 +58,138 @@ pub struct Opts {
     // I propose we just call it `cracking_timeout`.
     #[arg(short, long)]
     cracking_timeout: Option<u32>,
+    /// The most decoders in a row to try before giving up on a path.
+    /// Default is 25.
+    #[arg(long)]
+    max_depth: Option<u32>,
+    /// How many different plaintexts to look for, the best is shown first.
+    /// Default is 1.
+    #[arg(long)]
+    results_wanted: Option<usize>,
+    /// The most texts waiting to be decoded to keep, past this the least promising are dropped.
+    /// Default is 100000.
+    #[arg(long)]
+    max_frontier: Option<usize>,
+    /// The biggest input to crack, in bytes, bigger inputs aren't cracked at all.
+    /// Default is 1048576, 1 MiB.
+    #[arg(long)]
+    max_input_size: Option<usize>,
     /// Run in API mode, this will return the results instead of printing them
     /// Default is False
     #[arg(short, long)]
     api_mode: Option<bool>,
     /// Opens a file for decoding
     /// Use instead of `--text`
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with_all = ["text", "positional_text"])]
     file: Option<String>,
     /// If you have a crib (you know a piece of information in the plaintext)
     /// Or you want to create a custom regex to check against, you can use the Regex checker below.
     /// This turns off other checkers (English, LemmeKnow)
     #[arg(short, long)]
     regex: Option<String>,
+    /// A file of words the plaintext may be, one per line, like passwords or hostnames.
+    /// Like the regex, this turns off the other checkers.
+    #[arg(long, value_parser = read_wordlist)]
+    wordlist: Option<Wordlist>,
+    /// Crack each line on its own if the whole input can't be cracked,
+    /// for files where every line was encoded separately.
+    /// This happens anyway for inputs with 3 or more lines.
+    #[arg(long)]
+    split_lines: bool,
+    /// Only use these decoders, by name or tag, separated by commas, like `--only base,caesar`
+    #[arg(long, value_delimiter = ',')]
+    only: Vec<String>,
+    /// Never use these decoders, by name or tag, separated by commas.
+    /// With `--only`, these are taken out of the decoders it picked.
+    #[arg(long, value_delimiter = ',')]
+    exclude: Vec<String>,
+    /// How to print the outcome. `json` prints one JSON object on stdout for scripts,
+    /// with everything else on stderr, and exits with 1 if no plaintext was found.
+    /// Default is text.
+    #[arg(long, value_enum)]
+    output_format: Option<OutputFormat>,
+    /// Only print the plaintext, one per line if there are several, for pipelines like
+    /// `decoded=$(ares -q "$blob")`. Exits with 1 if no plaintext was found, or 2 if it ran out of time.
+    /// The human checker is turned off, as there's nobody to ask. The same as `--output-format plain`.
+    #[arg(short, long, conflicts_with = "output_format")]
+    quiet: bool,
+    /// Write the plaintext to this file rather than printing it, as bytes if it's a file like a PNG,
+    /// so only how it was decoded is printed. An existing file isn't overwritten without `--force`.
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+    /// List the plaintexts found, with how sure the checker was and how each was decoded, and ask which
+    /// is the real one, or whether to carry on cracking one which is still encoded. With `--results-wanted`
+    /// there's more than one to pick from. This replaces the human checker, and only asks on a terminal.
+    #[arg(short, long, conflicts_with = "quiet")]
+    interactive: bool,
+    /// Overwrite the `--output` file if it's already there
+    #[arg(long, requires = "output")]
+    force: bool,
+    /// List every decoder with its tags, popularity and link, the most popular first, then exit.
+    /// With `--output-format json` it's a JSON array.
+    #[arg(long)]
+    list_decoders: bool,
+    /// List every checker with its tags, popularity and description, then exit.
+    /// With `--output-format json` it's a JSON array.
+    #[arg(long, conflicts_with = "list_decoders")]
+    list_checkers: bool,
+    /// What to do instead of decoding
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// What Ares can do besides decoding. To decode text which is one of these, like "encode", use `--text`.
+#[derive(Subcommand)]
+enum Command {
+    /// Encode the text with a chain of decoders, to make test data or check what an encoding looks like,
+    /// like `ares encode --chain base64,hex,rot13 "hello world"`
+    Encode(EncodeOpts),
+    /// Time every decoder on text it can decode and on text it can't,
+    /// to check the runtimes each is built with, which the search uses to pick what to decode next
+    Benchmark(BenchmarkOpts),
+    /// Crack one line after another, with commands like `:set timeout 10` and `:use 3` between them,
+    /// without loading the dictionaries each time. The flags set what the session starts with,
+    /// and `:help` lists the commands.
+    Repl,
+}
+
+/// The arguments of `ares encode`
+#[derive(Args)]
+struct EncodeOpts {
+    /// The decoders to encode with, in order, by name or by a tag only one decoder has,
+    /// separated by commas. Only decoders which can encode can be used.
+    #[arg(long, value_delimiter = ',', required = true)]
+    chain: Vec<String>,
+    /// Decode the text with the chain the other way round instead,
+    /// to check what was encoded with it decodes back
+    #[arg(long)]
+    reverse: bool,
+    /// The text to encode. Without it, the text is read from stdin.
+    #[arg(value_name = "TEXT")]
+    text: Option<String>,
+}
+
+/// The arguments of `ares benchmark`
+#[derive(Args)]
+struct BenchmarkOpts {
+    /// How many times to time each decoder on each text, after warming up
+    #[arg(long, default_value_t = 10)]
+    iterations: usize,
+    /// Print the report as one JSON object
+    #[cfg(feature = "serde")]
+    #[arg(long, conflicts_with = "suggest")]
+    json: bool,
+    /// Print the runtimes decoders are built with which are far from what was measured,
+    /// with what to change them to
+    #[arg(long)]
+    suggest: bool,
 }
 
 /// Parse CLI Arguments turns a Clap Opts struct, seen above
 /// Into a library Struct for use within the program
 /// The library struct can be found in the [config](../config) folder.
-/// # Panics
-/// This function can panic when it gets both a file and text input at the same time.
+/// The text comes from the arguments, the file, or stdin, in that order.
+/// If there's no text and stdin is a terminal, or the file can't be read, it prints why and exits.
 pub fn parse_cli_args() -> (String, Config) {
     let mut opts: Opts = Opts::parse();
     let min_log_level = match opts.verbose {
@@ -61,68 +202,391 @@ pub fn parse_cli_args() -> (String, Config) {
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, min_log_level),
     );
 
-    // If both the file and text are proivded, panic because we're not sure which one to use
-    if opts.file.is_some() && opts.text.is_some() {
-        panic_failure_both_input_and_fail_provided();
+    if opts.list_decoders || opts.list_checkers {
+        list_and_exit(opts);
+    }
+    match opts.command.take() {
+        Some(Command::Encode(encode)) => encode_and_exit(opts, encode),
+        Some(Command::Benchmark(benchmark)) => benchmark_and_exit(opts, benchmark),
+        Some(Command::Repl) => repl_and_exit(opts),
+        None => {}
     }
 
-    let input_text: String = if opts.file.is_some() {
-        read_and_parse_file(opts.file.unwrap())
+    // Clap already refused a file together with text, as we wouldn't know which to use
+    let input_text: String = if let Some(text) = opts.text.take().or(opts.positional_text.take()) {
+        text
+    } else if let Some(file) = opts.file.take() {
+        File::open(&file)
+            .and_then(read_input)
+            .unwrap_or_else(|error| {
+                Opts::command()
+                    .error(ErrorKind::Io, format!("couldn't read {file}: {error}"))
+                    .exit()
+            })
     } else {
-        opts.text
-            .expect("Error. No input was provided. Please use ares --help")
+        read_stdin("no input was given, give the TEXT to decode, use --file, or pipe it in")
     };
 
-    // Fixes bug where opts.text and opts.file are partially borrowed
-    opts.text = None;
-    opts.file = None;
-
     trace!("Program was called with CLI 😉");
     trace!("Parsed the arguments");
     trace!("The inputted text is {}", &input_text);
 
-    cli_args_into_config_struct(opts, input_text)
+    let force = opts.force;
+    let (text, config) = cli_args_into_config_struct(opts, input_text);
+    // Checked before cracking, rather than finding out after
+    if let Some(path) = config.output_file.as_ref().filter(|path| path.exists()) {
+        if !force {
+            Opts::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "{} already exists, use --force to overwrite it",
+                        path.display()
+                    ),
+                )
+                .exit()
+        }
+    }
+    // A misspelt decoder would otherwise quietly pick nothing
+    if let Err(unknown) = config.check_decoder_names() {
+        Opts::command()
+            .error(ErrorKind::InvalidValue, unknown)
+            .exit()
+    }
+    (text, config)
+}
+
+/// Prints every decoder for `--list-decoders`, or every checker for `--list-checkers`, then exits.
+/// The config is set first, so decoders are listed with the popularity the search would give them.
+fn list_and_exit(opts: Opts) -> ! {
+    let decoders = opts.list_decoders;
+    let (_, config) = cli_args_into_config_struct(opts, String::new());
+    let output_format = config.output_format;
+    set_global_config(config);
+    match output_format {
+        OutputFormat::Text if decoders => decoder_table(&list_decoders()),
+        OutputFormat::Text => checker_table(&list_checkers()),
+        // Just the names, one per line
+        OutputFormat::Plain if decoders => {
+            for decoder in list_decoders() {
+                println!("{}", decoder.name);
+            }
+        }
+        OutputFormat::Plain => {
+            for checker in list_checkers() {
+                println!("{}", checker.name);
+            }
+        }
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => {
+            let json = if decoders {
+                serde_json::to_string(&list_decoders())
+            } else {
+                serde_json::to_string(&list_checkers())
+            };
+            println!("{}", json.expect("the list only has strings and numbers"));
+        }
+    }
+    std::process::exit(0)
+}
+
+/// Encodes the text with the chain for `ares encode`, or decodes it with `--reverse`, prints it, then exits.
+/// A chain which can't be used is reported like any other bad argument.
+fn encode_and_exit(opts: Opts, encode: EncodeOpts) -> ! {
+    let (_, mut config) = cli_args_into_config_struct(opts, String::new());
+    // Decoding to check a chain only needs the candidate which encodes back, not a person's opinion
+    config.human_checker_on = false;
+    set_global_config(config);
+    let text = encode.text.unwrap_or_else(|| {
+        read_stdin("no input was given, give the TEXT to encode, or pipe it in")
+    });
+    let result = if encode.reverse {
+        decode_chain(&encode.chain, &text)
+    } else {
+        encode_chain(&encode.chain, &text)
+    };
+    match result {
+        Ok(text) => println!("{text}"),
+        Err(error) => Opts::command().error(ErrorKind::InvalidValue, error).exit(),
+    }
+    std::process::exit(0)
+}
+
+/// Cracks one line after another for `ares repl`, until `:quit` or the end of stdin, then exits.
+/// Ctrl-C stops the crack running, rather than the session.
+fn repl_and_exit(opts: Opts) -> ! {
+    let (_, config) = cli_args_into_config_struct(opts, String::new());
+    let mut repl = Repl::new(config);
+    let cancel = repl.canceller();
+    ctrlc::set_handler(cancel).expect("the Ctrl-C handler is only set once");
+    let prompt = io::stdin().is_terminal();
+    if prompt {
+        eprintln!("Type text to crack it, or :help for the commands");
+    }
+    if let Err(error) = repl.run(io::stdin().lock(), io::stdout(), prompt) {
+        eprintln!("{error}");
+        std::process::exit(1)
+    }
+    std::process::exit(0)
+}
+
+/// Times every decoder for `ares benchmark`, prints the report or what to change, then exits
+fn benchmark_and_exit(opts: Opts, benchmark: BenchmarkOpts) -> ! {
+    let (_, mut config) = cli_args_into_config_struct(opts, String::new());
+    config.human_checker_on = false;
+    set_global_config(config);
+    let report = benchmark_decoders(benchmark.iterations);
+    #[cfg(feature = "serde")]
+    if benchmark.json {
+        println!(
+            "{}",
+            serde_json::to_string(&report).expect("the report only has strings and numbers")
+        );
+        std::process::exit(0)
+    }
+    if benchmark.suggest {
+        benchmark_suggestions(&report.suggestions());
+    } else {
+        benchmark_table(&report);
+    }
+    std::process::exit(0)
+}
+
+/// Reads the text from stdin, see `read_input`.
+/// If nobody is piping anything in, reading it would wait forever, so this exits saying `missing` instead.
+fn read_stdin(missing: &str) -> String {
+    if std::io::stdin().is_terminal() {
+        Opts::command()
+            .error(ErrorKind::MissingRequiredArgument, missing)
+            .exit()
+    }
+    read_input(std::io::stdin().lock()).unwrap_or_else(|error| {
+        Opts::command()
+            .error(ErrorKind::Io, format!("couldn't read stdin: {error}"))
+            .exit()
+    })
 }
 
 /// When the CLI is called with `-f` to open a file
-/// this function opens it
+/// this function opens it, see `read_input`
 /// # Panics
 /// This can panic when opening a file which does not exist!
 pub fn read_and_parse_file(file_path: String) -> String {
-    // TODO pretty match on the errors to provide better output
-    // Else it'll panic
-    let mut file = File::open(file_path).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-    // We can just put the file into the `Opts.text` and the program will work as normal
+    File::open(&file_path)
+        .and_then(read_input)
+        .unwrap_or_else(|error| panic!("couldn't read {file_path}: {error}"))
+}
+
+/// Reads all of the input from a file or stdin.
+/// Bytes which aren't UTF-8 are replaced rather than refused, as encoded binary is still worth decoding.
+/// A UTF-8 byte order mark at the start is dropped, as it marks how the file is stored, not what it says.
+/// # Errors
+/// Returns the IO error if it can't be read.
+pub fn read_input(mut reader: impl Read) -> std::io::Result<String> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+    let contents = String::from_utf8_lossy(bytes);
     // On Unix systems a line is defined as "\n{text}\n"
     // https://stackoverflow.com/a/729795
-    // Which means if a user creates a file on Unix, it'll have a new line appended.
-    // This is probably not what they wanted to decode (it is not what I wanted) so we are removing them
-    if contents.ends_with(['\n', '\r']) {
-        contents.strip_suffix(['\n', '\r']).unwrap().to_owned()
-    } else {
-        contents
+    // Which means if a user creates a file on Unix, it'll have a new line appended, as does `echo`.
+    // This is probably not what they wanted to decode (it is not what I wanted) so we are removing it,
+    // but only the one, as the newlines inside may split the input into lines worth cracking on their own
+    let contents = contents
+        .strip_suffix("\r\n")
+        .or_else(|| contents.strip_suffix('\n'))
+        .unwrap_or(&contents);
+    Ok(contents.to_string())
+}
+
+/// Writes the plaintext to the `--output` file: the bytes when it's a file like a PNG,
+/// otherwise the text as UTF-8. When the input was cracked line by line, the plaintext of each line
+/// is on the line it was on, with lines which couldn't be cracked left empty.
+/// When it's a kind of file the path's extension doesn't match, this suggests one.
+/// Nothing is written if there's no plaintext.
+/// # Errors
+/// Returns the IO error if the file can't be written.
+pub fn write_output(outcome: &CrackOutcome, path: &Path) -> std::io::Result<()> {
+    let contents: Cow<[u8]> = match outcome {
+        CrackOutcome::Found(results) => match results.first() {
+            Some(result) => result
+                .bytes
+                .as_deref()
+                .unwrap_or(result.plaintext.as_bytes())
+                .into(),
+            None => return Ok(()),
+        },
+        CrackOutcome::Lines(lines) => {
+            let plaintexts: Vec<&str> = lines
+                .iter()
+                .map(|line| match &line.outcome {
+                    CrackOutcome::Found(results) => {
+                        results.first().map_or("", |result| &result.plaintext)
+                    }
+                    _ => "",
+                })
+                .collect();
+            plaintexts.join("\n").into_bytes().into()
+        }
+        _ => return Ok(()),
+    };
+    std::fs::write(path, &contents)?;
+    if let Some((name, extension)) = file_type(&contents) {
+        if path.extension().is_none_or(|given| given != extension) {
+            warn!(
+                "{} looks like a {name}, consider naming it .{extension}",
+                path.display()
+            );
+        }
     }
+    Ok(())
+}
+
+/// Reads the `--wordlist` file, so a missing file is reported like any other bad argument
+fn read_wordlist(path: &str) -> Result<Wordlist, String> {
+    Wordlist::from_file(path).map_err(|error| format!("couldn't read {path}: {error}"))
 }
 
-/// Turns our CLI arguments into a config stuct
+/// Turns our CLI arguments into a config stuct.
+/// Flags which were given win over the config file, which wins over the defaults.
 fn cli_args_into_config_struct(opts: Opts, text: String) -> (String, Config) {
-    (
-        text,
-        Config {
-            verbose: opts.verbose,
-            lemmeknow_config: Identifier::default(),
-            // default is false, we want default to be true
-            human_checker_on: !opts.disable_human_checker,
-            // These if statements act as defaults
-            timeout: if opts.cracking_timeout.is_none() {
-                30
-            } else {
-                opts.cracking_timeout.unwrap()
-            },
-            api_mode: opts.api_mode.is_some(),
-            regex: opts.regex,
-        },
-    )
+    let mut config = Config {
+        verbose: opts.verbose,
+        lemmeknow_config: Identifier::default(),
+        // default is false, we want default to be true
+        human_checker_on: !opts.disable_human_checker,
+        timeout: Duration::from_secs(30),
+        api_mode: opts.api_mode.is_some(),
+        split_lines: opts.split_lines,
+        ..Config::default()
+    };
+    #[cfg(feature = "serde")]
+    config_file_into_config(&mut config);
+
+    if let Some(timeout) = opts.cracking_timeout {
+        config.timeout = Duration::from_secs(timeout.into());
+    }
+    if let Some(max_depth) = opts.max_depth {
+        config.max_depth = max_depth;
+    }
+    if let Some(results_wanted) = opts.results_wanted {
+        config.results_wanted = results_wanted;
+    }
+    if let Some(max_frontier) = opts.max_frontier {
+        config.max_frontier = max_frontier;
+    }
+    if let Some(max_input_size) = opts.max_input_size {
+        config.max_input_size = max_input_size;
+    }
+    if opts.regex.is_some() {
+        config.regex = opts.regex;
+    }
+    if opts.wordlist.is_some() {
+        config.wordlist = opts.wordlist;
+    }
+    if !opts.only.is_empty() {
+        config.include_decoders = opts.only;
+    }
+    if !opts.exclude.is_empty() {
+        config.exclude_decoders = opts.exclude;
+    }
+    if let Some(output_format) = opts.output_format {
+        config.output_format = output_format;
+    }
+    if opts.quiet {
+        config.output_format = OutputFormat::Plain;
+    }
+    config.output_file = opts.output;
+    config.interactive = opts.interactive;
+    // The user is asked once the search is over instead
+    if config.interactive {
+        config.human_checker_on = false;
+    }
+    // Whoever reads only the plaintext can't be asked whether it is
+    if config.output_format.is_quiet() {
+        config.human_checker_on = false;
+    }
+    (text, config)
+}
+
+/// Sets what the config file sets, if there is one, see `config::file`.
+/// A file which can't be used is an error rather than quietly ignored, as its settings would be.
+#[cfg(feature = "serde")]
+fn config_file_into_config(config: &mut Config) {
+    let Some(path) = config_file::find() else {
+        return;
+    };
+    let file = ConfigFile::load(&path).unwrap_or_else(|error| {
+        Opts::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("{}: {error}", path.display()),
+            )
+            .exit()
+    });
+    for key in &file.unknown_keys {
+        warn!("{}: unknown key `{key}` is ignored", path.display());
+    }
+    file.apply(config).unwrap_or_else(|error| {
+        Opts::command()
+            .error(
+                ErrorKind::InvalidValue,
+                format!("{}: {error}", path.display()),
+            )
+            .exit()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cli_args_into_config_struct, read_input, Opts};
+    use crate::config::OutputFormat;
+    use clap::Parser;
+
+    #[test]
+    fn one_trailing_newline_is_dropped() {
+        assert_eq!(read_input(&b"aGVsbG8=\n"[..]).unwrap(), "aGVsbG8=");
+        assert_eq!(read_input(&b"aGVsbG8=\r\n"[..]).unwrap(), "aGVsbG8=");
+        assert_eq!(read_input(&b"aGVsbG8=\n\n"[..]).unwrap(), "aGVsbG8=\n");
+        assert_eq!(
+            read_input(&b"one\r\ntwo\nthree"[..]).unwrap(),
+            "one\r\ntwo\nthree"
+        );
+    }
+
+    #[test]
+    fn a_byte_order_mark_is_dropped() {
+        assert_eq!(
+            read_input(&b"\xEF\xBB\xBFaGVsbG8=\n"[..]).unwrap(),
+            "aGVsbG8="
+        );
+    }
+
+    #[test]
+    fn binary_is_read_lossily() {
+        assert_eq!(
+            read_input(&b"ok\xFF\xFEok"[..]).unwrap(),
+            "ok\u{FFFD}\u{FFFD}ok"
+        );
+    }
+
+    #[test]
+    fn quiet_turns_the_human_checker_off() {
+        let opts = Opts::parse_from(["ares", "-q", "aGVsbG8="]);
+        let (_, config) = cli_args_into_config_struct(opts, "aGVsbG8=".to_string());
+        assert_eq!(config.output_format, OutputFormat::Plain);
+        assert!(!config.human_checker_on);
+
+        let opts = Opts::parse_from(["ares", "aGVsbG8="]);
+        let (_, config) = cli_args_into_config_struct(opts, "aGVsbG8=".to_string());
+        assert!(config.human_checker_on);
+    }
+
+    #[test]
+    fn interactive_replaces_the_human_checker() {
+        let opts = Opts::parse_from(["ares", "--interactive", "aGVsbG8="]);
+        let (_, config) = cli_args_into_config_struct(opts, "aGVsbG8=".to_string());
+        assert!(config.interactive);
+        assert!(!config.human_checker_on);
+    }
 }
@@ -0,0 +1,441 @@
+//! `ares repl`, for cracking one string after another without starting Ares each time.
+//! The dictionaries, checkers and decoders are loaded once for the whole session.
+//! Results are numbered as they're found, so `:use 3` cracks result 3 further,
+//! and commands like `:set timeout 10` change the settings between cracks.
+//! Reading lines and writing to the terminal is left to `Repl::run`,
+//! so `Repl::handle_line` can be driven by a script.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+use crate::cancellation::CancellationToken;
+use crate::config::{replace_global_config, Config, SearchStrategy};
+use crate::decoders::interface::Decoder;
+use crate::{continue_cracking, perform_cracking_with_cancellation, CrackOutcome, CrackSuccess};
+
+/// What `:help` prints
+const HELP: &str = "Type text to crack it. Each plaintext found is numbered, to use it again.
+  :use N            crack plaintext N further, for when it's still encoded
+  :set              show the settings
+  :set NAME VALUE   change timeout, max-depth, results-wanted, max-frontier, max-input-size,
+                    strategy or split-lines
+  :only NAMES       only use these decoders, by name or tag, separated by commas, or all with none
+  :exclude NAMES    never use these decoders, or none with none
+  :history          list the plaintexts found so far
+  :save PATH        write every plaintext found to the file, one per line
+  :help             show this
+  :quit             leave, as Ctrl-D does. Ctrl-C stops a crack.";
+
+/// Whether the session carries on after a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Read the next line
+    Continue,
+    /// The user asked to leave
+    Quit,
+}
+
+/// A session of cracking one line after another with the same settings
+pub struct Repl {
+    /// The session's settings, which `:set`, `:only` and `:exclude` change
+    config: Config,
+    /// Whether `config` has changed since it was last made the global config
+    changed: bool,
+    /// Every plaintext found, numbered from 1
+    history: Vec<CrackSuccess>,
+    /// Cancels the crack running now
+    cancel: Arc<Mutex<CancellationToken>>,
+}
+
+impl Repl {
+    /// A session with these settings. Cracking prints nothing of its own and never asks
+    /// whether text is plaintext, as the session prints the results and reads the lines.
+    pub fn new(config: Config) -> Self {
+        Repl {
+            config: Config {
+                api_mode: true,
+                human_checker_on: false,
+                ..config
+            },
+            changed: true,
+            history: Vec::new(),
+            cancel: Arc::default(),
+        }
+    }
+
+    /// The session's settings
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Every plaintext found so far, the first is number 1
+    pub fn history(&self) -> &[CrackSuccess] {
+        &self.history
+    }
+
+    /// Stops the crack running now, if there is one, such as when Ctrl-C is pressed
+    pub fn canceller(&self) -> impl Fn() + Send + 'static {
+        let cancel = Arc::clone(&self.cancel);
+        move || {
+            cancel
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .cancel()
+        }
+    }
+
+    /// Reads lines and handles them until `:quit` or the end of the input.
+    /// With `prompt`, "ares> " is written before each line is read, for a terminal.
+    /// # Errors
+    /// Returns the error if a line can't be read, or the output can't be written.
+    pub fn run(
+        &mut self,
+        input: impl BufRead,
+        mut out: impl Write,
+        prompt: bool,
+    ) -> io::Result<()> {
+        let mut lines = input.lines();
+        loop {
+            if prompt {
+                write!(out, "ares> ")?;
+                out.flush()?;
+            }
+            let Some(line) = lines.next().transpose()? else {
+                return Ok(());
+            };
+            if self.handle_line(&line, &mut out)? == Flow::Quit {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Cracks the line, or runs it if it's a command, writing what happened.
+    /// # Errors
+    /// Returns the error if the output can't be written.
+    pub fn handle_line(&mut self, line: &str, out: &mut impl Write) -> io::Result<Flow> {
+        let line = line.trim();
+        let Some(command) = line.strip_prefix(':') else {
+            if !line.is_empty() {
+                let config = self.config.clone();
+                let outcome =
+                    self.crack(|cancel| perform_cracking_with_cancellation(line, config, cancel));
+                self.report(outcome, out)?;
+            }
+            return Ok(Flow::Continue);
+        };
+        let (name, argument) = command
+            .trim()
+            .split_once(char::is_whitespace)
+            .map_or((command.trim(), ""), |(name, argument)| {
+                (name, argument.trim())
+            });
+        match name {
+            "quit" | "q" | "exit" => return Ok(Flow::Quit),
+            "help" | "h" => writeln!(out, "{HELP}")?,
+            "use" => self.use_plaintext(argument, out)?,
+            "set" if argument.is_empty() => self.settings(out)?,
+            "set" => match self.set(argument) {
+                Ok(set) => writeln!(out, "{set}")?,
+                Err(error) => writeln!(out, "{error}")?,
+            },
+            "only" | "exclude" => self.pick_decoders(name, argument, out)?,
+            "history" => {
+                for (index, found) in self.history.iter().enumerate() {
+                    write_plaintext(index + 1, found, out)?;
+                }
+            }
+            "save" if argument.is_empty() => writeln!(out, ":save needs the file to write to")?,
+            "save" => {
+                let plaintexts: String = self
+                    .history
+                    .iter()
+                    .map(|found| format!("{}\n", found.plaintext))
+                    .collect();
+                match fs::write(argument, plaintexts) {
+                    Ok(()) => {
+                        writeln!(out, "wrote {} plaintexts to {argument}", self.history.len())?
+                    }
+                    Err(error) => writeln!(out, "couldn't write {argument}: {error}")?,
+                }
+            }
+            _ => writeln!(out, "there's no :{name} command, :help lists them")?,
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Makes the session's settings the global config if they've changed, for what's written after cracking,
+    /// then cracks with `crack`, which can be cancelled with `canceller`
+    fn crack(&mut self, crack: impl FnOnce(CancellationToken) -> CrackOutcome) -> CrackOutcome {
+        if self.changed {
+            replace_global_config(self.config.clone());
+            self.changed = false;
+        }
+        let cancel = CancellationToken::new();
+        *self.cancel.lock().unwrap_or_else(PoisonError::into_inner) = cancel.clone();
+        crack(cancel)
+    }
+
+    /// Numbers the plaintexts found and writes them, or writes why there aren't any
+    fn report(&mut self, outcome: CrackOutcome, out: &mut impl Write) -> io::Result<()> {
+        let config = &self.config;
+        let found = match outcome {
+            CrackOutcome::Found(found) => found,
+            CrackOutcome::Lines(lines) => lines
+                .into_iter()
+                .filter_map(|line| line.outcome.found())
+                .collect(),
+            CrackOutcome::Exhausted(_) => {
+                return writeln!(out, "nothing it decoded to was plaintext")
+            }
+            CrackOutcome::TimedOut(_) => {
+                return writeln!(
+                    out,
+                    "ran out of time after {}s, try :set timeout {}",
+                    config.timeout.as_secs(),
+                    config.timeout.as_secs() * 2
+                )
+            }
+            CrackOutcome::DepthLimited(_) => {
+                return writeln!(
+                    out,
+                    "nothing within {} decoders was plaintext, try :set max-depth {}",
+                    config.max_depth,
+                    config.max_depth * 2
+                )
+            }
+            CrackOutcome::FrontierLimited(_) => {
+                return writeln!(
+                    out,
+                    "too many texts were waiting to be decoded, try :set max-frontier {}",
+                    config.max_frontier * 2
+                )
+            }
+            CrackOutcome::SeenLimited(_) => {
+                return writeln!(
+                    out,
+                    "too many texts were decoded to remember them all, try a lower max-depth with :set, or fewer decoders with :only"
+                )
+            }
+            CrackOutcome::Cancelled(_) => return writeln!(out, "cancelled"),
+            CrackOutcome::TooLarge(too_large) => {
+                return writeln!(
+                    out,
+                    "the line is {} bytes, try :set max-input-size {}",
+                    too_large.size, too_large.size
+                )
+            }
+        };
+        for found in found {
+            self.history.push(found);
+            write_plaintext(
+                self.history.len(),
+                &self.history[self.history.len() - 1],
+                out,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Cracks plaintext `argument` further for `:use`, starting from it without checking it again,
+    /// so what's found has the whole path from the input it was found in
+    fn use_plaintext(&mut self, argument: &str, out: &mut impl Write) -> io::Result<()> {
+        let found = argument
+            .trim_start_matches('#')
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| self.history.get(number.checked_sub(1)?))
+            .cloned();
+        let Some(found) = found else {
+            if self.history.is_empty() {
+                return writeln!(out, "no plaintexts have been found to use yet");
+            }
+            return writeln!(
+                out,
+                ":use needs the number of a plaintext, from 1 to {}",
+                self.history.len()
+            );
+        };
+        let config = self.config.clone();
+        let outcome = self.crack(|cancel| continue_cracking(&found, config, cancel, None));
+        self.report(outcome, out)
+    }
+
+    /// Writes every setting `:set` changes
+    fn settings(&self, out: &mut impl Write) -> io::Result<()> {
+        let config = &self.config;
+        let strategy = config
+            .search_strategy
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+        writeln!(out, "timeout {}", config.timeout.as_secs())?;
+        writeln!(out, "max-depth {}", config.max_depth)?;
+        writeln!(out, "results-wanted {}", config.results_wanted)?;
+        writeln!(out, "max-frontier {}", config.max_frontier)?;
+        writeln!(out, "max-input-size {}", config.max_input_size)?;
+        writeln!(out, "strategy {strategy}")?;
+        writeln!(out, "split-lines {}", config.split_lines)?;
+        writeln!(out, "only {}", config.include_decoders.join(","))?;
+        writeln!(out, "exclude {}", config.exclude_decoders.join(","))
+    }
+
+    /// Changes a setting for `:set`, like "timeout 10", returning what it's set to
+    /// or why it couldn't be
+    fn set(&mut self, argument: &str) -> Result<String, String> {
+        let Some((name, value)) = argument.split_once(char::is_whitespace) else {
+            return Err(format!(":set {argument} needs a value"));
+        };
+        let value = value.trim();
+        let invalid = |error: &dyn std::fmt::Display| format!("{value} isn't a {name}: {error}");
+        let config = &mut self.config;
+        match name.replace('_', "-").as_str() {
+            "timeout" => {
+                config.timeout = Duration::from_secs(value.parse().map_err(|e| invalid(&e))?);
+            }
+            "max-depth" => config.max_depth = value.parse().map_err(|e| invalid(&e))?,
+            "results-wanted" => match value.parse().map_err(|e| invalid(&e))? {
+                0 => return Err("results-wanted has to be at least 1".to_string()),
+                wanted => config.results_wanted = wanted,
+            },
+            "max-frontier" => config.max_frontier = value.parse().map_err(|e| invalid(&e))?,
+            "max-input-size" => config.max_input_size = value.parse().map_err(|e| invalid(&e))?,
+            "strategy" => {
+                config.search_strategy =
+                    SearchStrategy::from_str(value, true).map_err(|e| invalid(&e))?;
+            }
+            "split-lines" => config.split_lines = value.parse().map_err(|e| invalid(&e))?,
+            _ => return Err(format!("there's no setting called {name}, :set lists them")),
+        }
+        self.changed = true;
+        Ok(format!("{name} is {value}"))
+    }
+
+    /// Picks the decoders for `:only` and `:exclude`, refusing names which aren't decoders
+    fn pick_decoders(
+        &mut self,
+        command: &str,
+        argument: &str,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let names: Vec<String> = argument
+            .split([',', ' '])
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        let mut config = self.config.clone();
+        if command == "only" {
+            config.include_decoders = names;
+        } else {
+            config.exclude_decoders = names;
+        }
+        if let Err(unknown) = config.check_decoder_names() {
+            return writeln!(out, "{unknown}");
+        }
+        self.config = config;
+        self.changed = true;
+        let names = if command == "only" {
+            &self.config.include_decoders
+        } else {
+            &self.config.exclude_decoders
+        };
+        match (command, names.is_empty()) {
+            ("only", true) => writeln!(out, "using every decoder"),
+            ("only", false) => writeln!(out, "only using {}", names.join(", ")),
+            (_, true) => writeln!(out, "excluding no decoders"),
+            (_, false) => writeln!(out, "excluding {}", names.join(", ")),
+        }
+    }
+}
+
+/// Writes the plaintext with its number, and how it was decoded under it
+fn write_plaintext(number: usize, found: &CrackSuccess, out: &mut impl Write) -> io::Result<()> {
+    let mut route = String::new();
+    for step in &found.path {
+        // The input itself being the plaintext isn't a step on the way
+        if step.decoder == Decoder::default().name {
+            continue;
+        }
+        if !route.is_empty() {
+            route.push_str(" → ");
+        }
+        let _ = write!(route, "{step}");
+    }
+    if route.is_empty() {
+        route.push_str("it was already plaintext");
+    }
+    writeln!(out, "#{number} {}\n   {route}", found.plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Flow, Repl};
+    use crate::config::{Config, SearchStrategy};
+    use std::time::Duration;
+
+    /// Handles the line, returning what was written
+    fn handle(repl: &mut Repl, line: &str) -> String {
+        let mut out = Vec::new();
+        assert_eq!(repl.handle_line(line, &mut out).unwrap(), Flow::Continue);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn set_changes_the_settings() {
+        let mut repl = Repl::new(Config::default());
+        assert_eq!(handle(&mut repl, ":set timeout 10"), "timeout is 10\n");
+        assert_eq!(handle(&mut repl, ":set max_depth 4"), "max_depth is 4\n");
+        assert_eq!(
+            handle(&mut repl, ":set strategy depth-first"),
+            "strategy is depth-first\n"
+        );
+        let config = repl.config();
+        assert_eq!(config.timeout, Duration::from_secs(10));
+        assert_eq!(config.max_depth, 4);
+        assert_eq!(config.search_strategy, SearchStrategy::DepthFirst);
+        assert!(handle(&mut repl, ":set").contains("max-depth 4\n"));
+    }
+
+    #[test]
+    fn bad_settings_are_refused() {
+        let mut repl = Repl::new(Config::default());
+        assert!(handle(&mut repl, ":set timeout soon").starts_with("soon isn't a timeout"));
+        assert!(
+            handle(&mut repl, ":set colour blue").starts_with("there's no setting called colour")
+        );
+        assert_eq!(
+            handle(&mut repl, ":set results-wanted 0"),
+            "results-wanted has to be at least 1\n"
+        );
+        assert_eq!(repl.config().timeout, Config::default().timeout);
+    }
+
+    #[test]
+    fn only_and_exclude_check_the_names() {
+        let mut repl = Repl::new(Config::default());
+        assert_eq!(
+            handle(&mut repl, ":only base, caesar"),
+            "only using base, caesar\n"
+        );
+        assert!(handle(&mut repl, ":exclude base46").contains("Base64"));
+        assert!(repl.config().exclude_decoders.is_empty());
+        assert_eq!(handle(&mut repl, ":only"), "using every decoder\n");
+        assert!(repl.config().include_decoders.is_empty());
+    }
+
+    #[test]
+    fn use_needs_a_plaintext_found() {
+        let mut repl = Repl::new(Config::default());
+        assert_eq!(
+            handle(&mut repl, ":use 1"),
+            "no plaintexts have been found to use yet\n"
+        );
+        assert!(handle(&mut repl, ":frobnicate").starts_with("there's no :frobnicate command"));
+        let mut out = Vec::new();
+        assert_eq!(repl.handle_line(":quit", &mut out).unwrap(), Flow::Quit);
+    }
+}
@@ -2,35 +2,75 @@
 /// and make sure each one is up to our standards. Previously a rogue print statement that went off at an edge case
 /// would look a bit ugly and not the same UI as others.
 /// We can also do things like check for logic or share information / functions which would be a bit messy in the main code.
-use crate::DecoderResult;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::checkers::CheckerInfo;
+use crate::decoders::benchmark::{BenchmarkReport, Suggestion, Timing};
+use crate::decoders::DecoderInfo;
+use crate::filtration_system::invisible_characters::CleanedInput;
+use crate::output::PathRenderer;
+use crate::progress::ProgressEvent;
+use crate::{CrackOutcome, CrackSuccess, LineOutcome, PartialReport};
+
+/// Prints a line for people to read, on stdout, unless a program reads stdout,
+/// when it goes to stderr, or nothing is to be said, see `Config::output_format`
+macro_rules! say {
+    ($($arg:tt)*) => {{
+        let output_format = crate::config::get_config().output_format;
+        if output_format.is_for_people() {
+            println!($($arg)*)
+        } else if !output_format.is_quiet() {
+            eprintln!($($arg)*)
+        }
+    }};
+}
+
+/// Whether the status line is on stderr, so it's cleared before anything else is printed
+static STATUS_LINE_SHOWN: AtomicBool = AtomicBool::new(false);
 
 /// The output function is used to print the output of the program.
 /// If the API mode is on, it will not print.
-pub fn program_exiting_successful_decoding(result: DecoderResult) {
+pub fn program_exiting_successful_decoding(result: CrackSuccess) {
     let config = crate::config::get_config();
     if config.api_mode {
         return;
     }
-    let plaintext = result.text;
-    // calculate path
-    let decoded_path = result
-        .path
-        .iter()
-        .map(|c| c.decoder)
-        .collect::<Vec<_>>()
-        .join(" → ");
-
-    let decoded_path_coloured = ansi_term::Colour::Yellow.bold().paint(&decoded_path);
-    let decoded_path_string = if !decoded_path.contains('→') {
-        // handles case where only 1 decoder is used
-        format!("the decoder used is {decoded_path_coloured}")
-    } else {
-        format!("the decoders used are {decoded_path_coloured}")
-    };
-    println!(
-        "The plaintext is: \n{}\nand {}",
-        ansi_term::Colour::Yellow.bold().paint(&plaintext[0]),
-        decoded_path_string
+    say!(
+        "The plaintext is: \n{}\nand it was decoded like this:\n{}",
+        ansi_term::Colour::Yellow.bold().paint(&result.plaintext),
+        PathRenderer::for_stdout().render(&result.path)
+    );
+}
+
+/// When the plaintext was written to the `--output` file, print how it was decoded rather than the plaintext
+pub fn wrote_plaintext(result: &CrackSuccess, path: &Path) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    say!(
+        "The plaintext was written to {}, and it was decoded like this:\n{}",
+        ansi_term::Colour::Yellow
+            .bold()
+            .paint(path.display().to_string()),
+        PathRenderer::for_stdout().render(&result.path)
+    );
+}
+
+/// When the input was cracked line by line and the plaintexts written to the `--output` file,
+/// print how many lines were cracked
+pub fn wrote_lines(lines: &[LineOutcome], path: &Path) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    let cracked = lines.iter().filter(|line| line.outcome.is_found()).count();
+    say!(
+        "Ares couldn't crack the whole input, so it cracked each line on its own.\nIt cracked {cracked} of the {} lines, and wrote their plaintexts to {}",
+        lines.len(),
+        ansi_term::Colour::Yellow.bold().paint(path.display().to_string())
     );
 }
 
@@ -43,25 +83,15 @@ pub fn decoded_how_many_times(depth: u32) {
 
     // Gets how many decoders we have
     // Then we add 25 for Caesar
-    let decoders = crate::filtration_system::filter_and_get_decoders(&DecoderResult::default());
-    let decoded_times_int = depth * (decoders.components.len() as u32 + 25);
+    let decoders = crate::decoders::get_all_decoders();
+    let decoded_times_int = depth * (decoders.len() as u32 + 25);
     let decoded_times_str = format!("{decoded_times_int} times");
 
     let time_took = calculate_time_took(decoded_times_int);
 
     // TODO add colour to the times
-    println!("\n🥳 Ares has decoded {decoded_times_str} times.\nIf you would have used Ciphey, it would have taken you {time_took}\n");
-}
-
-/// Whenever the human checker checks for text, this function is run.
-/// The human checker checks to see if API mdoe is runnign inside of it
-/// rather than doing it here at the printing level
-pub fn human_checker_check(description: &str, text: &str) {
-    println!(
-        "🕵️ I think the plaintext is {}.\nPossible plaintext: '{}' (y/N): ",
-        ansi_term::Colour::Yellow.bold().paint(description),
-        ansi_term::Colour::Yellow.bold().paint(text)
-    )
+    clear_search_progress();
+    say!("\n🥳 Ares has decoded {decoded_times_str} times.\nIf you would have used Ciphey, it would have taken you {time_took}\n");
 }
 
 /// When Ares has failed to decode something, print this message
@@ -71,9 +101,93 @@ pub fn failed_to_decode() {
         return;
     }
 
-    println!("⛔️ Ares has failed to decode the text.");
-    println!("If you want more help, please ask in #coded-messages in our Discord http://discord.skerritt.blog");
+    say!("⛔️ Ares has failed to decode the text.");
+    say!("If you want more help, please ask in #coded-messages in our Discord http://discord.skerritt.blog");
+}
+/// When Ares stopped at the timeout or the maximum depth, was cancelled, or the input was too big to crack,
+/// without finding the plaintext, print this message
+pub fn failed_to_decode_within_limits(outcome: &CrackOutcome) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+
+    match outcome {
+        CrackOutcome::TimedOut(_) => say!(
+            "⛔️ Ares found no plaintext within its limits, it ran out of time after {:?}.\nTry a longer --cracking-timeout.",
+            config.timeout
+        ),
+        CrackOutcome::DepthLimited(_) => say!(
+            "⛔️ Ares found no plaintext within its limits, it tried everything up to {} decoders deep.\nTry a bigger --max-depth.",
+            config.max_depth
+        ),
+        CrackOutcome::FrontierLimited(_) => say!(
+            "⛔️ Ares found no plaintext within its limits, more than {} texts were waiting to be decoded so it dropped the least promising.\nThe results may be incomplete, try a bigger --max-frontier.",
+            config.max_frontier
+        ),
+        CrackOutcome::SeenLimited(_) => say!(
+            "⛔️ Ares found no plaintext within its limits, it had seen so many texts that it stopped queueing new ones.\nThe results may be incomplete, try a smaller --max-depth, or fewer decoders with --only."
+        ),
+        CrackOutcome::Cancelled(report) => cancelled(report),
+        CrackOutcome::TooLarge(too_large) => say!(
+            "⛔️ Ares didn't crack the text, {too_large} with --max-input-size."
+        ),
+        CrackOutcome::Found(_) | CrackOutcome::Exhausted(_) | CrackOutcome::Lines(_) => {
+            failed_to_decode()
+        }
+    }
+}
+
+/// When the input was cracked line by line, print each line's plaintext,
+/// or that it couldn't be cracked, numbering the lines from 1 like an editor does
+pub fn cracked_lines(lines: Vec<LineOutcome>) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    say!("Ares couldn't crack the whole input, so it cracked each line on its own.");
+    for line in lines {
+        let number = line.index + 1;
+        match line.outcome {
+            CrackOutcome::Found(results) => {
+                say!("\nLine {number}:");
+                for result in results {
+                    program_exiting_successful_decoding(result);
+                }
+            }
+            _ => say!("\n⛔️ Line {number} couldn't be cracked: {}", line.line),
+        }
+    }
+}
+
+/// When the search was cancelled, such as with Ctrl-C, print what it did
+/// and the most promising text it was about to decode
+fn cancelled(report: &PartialReport) {
+    say!(
+        "⛔️ Ares was cancelled after {:.1?}, before it found the plaintext.\nIt decoded {} text{} with {} decoder run{}.",
+        report.stats.elapsed,
+        report.stats.nodes_expanded,
+        plural(report.stats.nodes_expanded),
+        report.stats.decoder_invocations,
+        plural(report.stats.decoder_invocations)
+    );
+    if report.candidates.is_empty() {
+        return;
+    }
+    say!("It was about to decode:");
+    for candidate in &report.candidates {
+        // Like "input → Base64 → Caesar Cipher (shift 3)"
+        let route: Vec<String> = std::iter::once("input".to_string())
+            .chain(candidate.path.iter().map(|c| c.to_string()))
+            .collect();
+        say!(
+            "  {}\n    {}",
+            ansi_term::Colour::Yellow.bold().paint(&candidate.text),
+            route.join(" → ")
+        );
+    }
 }
+
 /// Calculate how long it would take to decode this in Ciphey
 fn calculate_time_took(decoded_times_int: u32) -> String {
     // TODO if we grab how long the programs been running for (see timer) we can make some nice stats like:
@@ -107,12 +221,49 @@ pub fn countdown_until_program_ends(seconds_spent_running: u32, duration: u32) {
     if config.api_mode {
         return;
     }
-    if seconds_spent_running % 5 == 0 && seconds_spent_running != 0 {
+    if seconds_spent_running.is_multiple_of(5) && seconds_spent_running != 0 {
         let time_left = duration - seconds_spent_running;
         if time_left == 0 {
             return;
         }
-        println!("{seconds_spent_running} seconds have passed. {time_left} remaining");
+        clear_search_progress();
+        say!("{seconds_spent_running} seconds have passed. {time_left} remaining");
+    }
+}
+
+/// Draws a status line on stderr with how the search is going, over the last one
+pub fn search_progress(event: &ProgressEvent) {
+    let config = crate::config::get_config();
+    if config.api_mode {
+        return;
+    }
+    let spinner = ['|', '/', '-', '\\'][(event.elapsed.as_millis() / 100 % 4) as usize];
+    // Newlines in the text would break the line
+    let text: String = event
+        .current_text
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let mut stderr = std::io::stderr().lock();
+    // Nothing else can be shown if stderr is gone, so a failed write is ignored
+    let _ = write!(
+        stderr,
+        "\r\x1b[K{spinner} {:.1}s, decoded {} texts with {} decoder runs, {} waiting, depth {}: {}",
+        event.elapsed.as_secs_f32(),
+        event.nodes_expanded,
+        event.decoders_invoked,
+        event.frontier_size,
+        event.depth,
+        ansi_term::Colour::Yellow.paint(text)
+    )
+    .and_then(|_| stderr.flush());
+    STATUS_LINE_SHOWN.store(true, Ordering::Relaxed);
+}
+
+/// Clears the status line `search_progress` drew, if there is one
+pub fn clear_search_progress() {
+    if STATUS_LINE_SHOWN.swap(false, Ordering::Relaxed) {
+        eprint!("\r\x1b[K");
     }
 }
 
@@ -123,7 +274,175 @@ pub fn return_early_because_input_text_is_plaintext() {
     if config.api_mode {
         return;
     }
-    println!("Your input text is the plaintext 🥳");
+    say!("Your input text is the plaintext 🥳");
+}
+
+/// Ares cleaned characters nobody can see out of the input, which is only mentioned in verbose mode
+pub fn cleaned_input(input: &CleanedInput) {
+    let config = crate::config::get_config();
+    if config.api_mode || config.verbose == 0 {
+        return;
+    }
+    let mut changes = Vec::new();
+    let zero_width = input.zero_width_removed();
+    if zero_width > 0 {
+        changes.push(format!(
+            "removed {zero_width} zero-width character{}",
+            plural(zero_width)
+        ));
+    }
+    let bidi = input.bidi_controls_removed();
+    if bidi > 0 {
+        changes.push(format!("removed {bidi} bidi control{}", plural(bidi)));
+    }
+    if input.spaces_replaced > 0 {
+        changes.push(format!(
+            "replaced {} unusual space{} with plain spaces",
+            input.spaces_replaced,
+            plural(input.spaces_replaced)
+        ));
+    }
+    if !changes.is_empty() {
+        say!("Before decoding, Ares {}.", changes.join(", "));
+    }
+    if let Some(message) = input
+        .hidden_message
+        .as_ref()
+        .and_then(|hidden| hidden.unencrypted_text.as_ref())
+    {
+        say!(
+            "The zero-width characters hide a message, which Ares will decode too: {}",
+            ansi_term::Colour::Yellow.bold().paint(&message[0])
+        );
+    }
+}
+
+/// Prints every decoder with its tags, popularity and link, for `--list-decoders`
+pub fn decoder_table(decoders: &[DecoderInfo]) {
+    let rows = decoders.iter().map(|decoder| {
+        [
+            decoder.name.clone(),
+            decoder.tags.join(", "),
+            format!("{:.2}", decoder.popularity),
+            decoder.link.clone(),
+        ]
+    });
+    print!("{}", table(["Name", "Tags", "Popularity", "Link"], rows));
+}
+
+/// Prints every checker with its tags, popularity and description, for `--list-checkers`
+pub fn checker_table(checkers: &[CheckerInfo]) {
+    let rows = checkers.iter().map(|checker| {
+        [
+            checker.name.to_string(),
+            checker.tags.join(", "),
+            format!("{:.2}", checker.popularity),
+            checker.description.to_string(),
+        ]
+    });
+    print!(
+        "{}",
+        table(["Name", "Tags", "Popularity", "Description"], rows)
+    );
+}
+
+/// Prints how long every decoder took, the slowest first, for `ares benchmark`
+pub fn benchmark_table(report: &BenchmarkReport) {
+    let mean = |timing: &Option<Timing>| {
+        timing
+            .as_ref()
+            .map_or("-".to_string(), |timing| seconds(timing.mean_seconds))
+    };
+    let rows = report.decoders.iter().map(|decoder| {
+        [
+            decoder.name.clone(),
+            mean(&decoder.success),
+            mean(&decoder.failure),
+            seconds(decoder.expected_runtime.into()),
+            seconds(decoder.failure_runtime.into()),
+        ]
+    });
+    print!(
+        "{}",
+        table(
+            [
+                "Name",
+                "Decoding",
+                "Giving up",
+                "Expected",
+                "Expected giving up"
+            ],
+            rows
+        )
+    );
+    println!(
+        "Each is the mean of {} run{} on each text.",
+        report.iterations,
+        plural(report.iterations)
+    );
+}
+
+/// Prints the runtimes to change in the decoders' source, for `ares benchmark --suggest`
+pub fn benchmark_suggestions(suggestions: &[Suggestion]) {
+    if suggestions.is_empty() {
+        println!("Every decoder's runtimes are within twice what was measured.");
+        return;
+    }
+    for suggestion in suggestions {
+        println!(
+            "{}: .{}({:.2e}) rather than {}",
+            suggestion.decoder, suggestion.method, suggestion.measured, suggestion.current
+        );
+    }
+}
+
+/// The seconds in the unit which reads best, like "1.23ms" or "45.6µs"
+fn seconds(seconds: f64) -> String {
+    if seconds >= 1.0 {
+        format!("{seconds:.2}s")
+    } else if seconds >= 1e-3 {
+        format!("{:.2}ms", seconds * 1e3)
+    } else if seconds >= 1e-6 {
+        format!("{:.2}µs", seconds * 1e6)
+    } else {
+        format!("{:.0}ns", seconds * 1e9)
+    }
+}
+
+/// Lines up the rows under the headings, with two spaces between the columns
+fn table<const COLUMNS: usize>(
+    headings: [&str; COLUMNS],
+    rows: impl Iterator<Item = [String; COLUMNS]>,
+) -> String {
+    let rows: Vec<[String; COLUMNS]> = std::iter::once(headings.map(str::to_string))
+        .chain(rows)
+        .collect();
+    let mut widths = [0; COLUMNS];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// The "s" on the end of a word, if there are several
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
 }
 
 /// The user has provided both textual input and file input
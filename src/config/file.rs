@@ -0,0 +1,283 @@
+//! The CLI's config file, so flags used every time needn't be typed every time.
+//! Ares reads `ares.toml` in the current directory, or if there isn't one,
+//! `ares/config.toml` in the platform's config directory, like `~/.config/ares/config.toml`.
+//! Flags given on the command line win over the file, which wins over the defaults.
+//! ```toml
+//! timeout = 10
+//! regex = 'flag\{[^}]*\}'
+//! exclude = ["reverse"]
+//! ```
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::{Config, OutputFormat, SearchStrategy};
+use crate::checkers::dictionary_checker::Lang;
+use crate::checkers::wordlist_checker::Wordlist;
+
+/// What a config file can set. Every key is optional, so the file only has what the user wants to change.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct ConfigFile {
+    /// How many seconds to crack for, see `Config::timeout`
+    pub timeout: Option<u64>,
+    /// See `Config::max_depth`
+    pub max_depth: Option<u32>,
+    /// See `Config::results_wanted`
+    pub results_wanted: Option<usize>,
+    /// See `Config::max_frontier`
+    pub max_frontier: Option<usize>,
+    /// See `Config::max_input_size`
+    pub max_input_size: Option<usize>,
+    /// How the search picks what to decode next, like "best-first" or "depth-first"
+    pub strategy: Option<SearchStrategy>,
+    /// See `Config::regex`
+    pub regex: Option<String>,
+    /// A file of words the plaintext may be, one per line.
+    /// A relative path is relative to the config file.
+    pub wordlist: Option<PathBuf>,
+    /// The codes of the languages to look for words of, like `["en", "de"]`
+    pub languages: Option<Vec<Lang>>,
+    /// Only use these decoders, by name or tag, see `Config::include_decoders`
+    pub only: Option<Vec<String>>,
+    /// Never use these decoders, by name or tag, see `Config::exclude_decoders`
+    pub exclude: Option<Vec<String>>,
+    /// How the CLI prints the outcome, "text", "json" or "plain"
+    pub output_format: Option<OutputFormat>,
+    /// See `Config::confidence_threshold`
+    pub confidence_threshold: Option<f32>,
+    /// Keys Ares doesn't know, like a misspelt `timout`, which are ignored
+    #[serde(skip)]
+    pub unknown_keys: Vec<String>,
+}
+
+/// Why a config file couldn't be used
+#[derive(Debug)]
+pub enum ConfigFileError {
+    /// The file couldn't be read
+    Read(io::Error),
+    /// The file isn't TOML, or a key has the wrong kind of value.
+    /// The message says which key, and where in the file it is.
+    Parse(toml::de::Error),
+    /// The wordlist the file names couldn't be read
+    Wordlist(PathBuf, io::Error),
+}
+
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFileError::Read(error) => write!(f, "{error}"),
+            ConfigFileError::Parse(error) => write!(f, "{error}"),
+            ConfigFileError::Wordlist(path, error) => write!(
+                f,
+                "couldn't read the wordlist {} for key `wordlist`: {error}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigFileError {}
+
+/// The config file Ares uses, if there is one: `ares.toml` in the current directory,
+/// or `ares/config.toml` in the platform's config directory
+pub fn find() -> Option<PathBuf> {
+    let local = PathBuf::from("ares.toml");
+    if local.is_file() {
+        return Some(local);
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join("ares").join("config.toml"))
+        .filter(|path| path.is_file())
+}
+
+impl ConfigFile {
+    /// Parses the TOML of a config file. Keys Ares doesn't know go in `unknown_keys`,
+    /// so a newer file still works with an older Ares.
+    /// ```rust
+    /// use ares::config::file::ConfigFile;
+    /// use ares::config::SearchStrategy;
+    ///
+    /// let file = ConfigFile::parse("timeout = 10\nstrategy = \"depth-first\"\ntimout = 5").unwrap();
+    /// assert_eq!(file.timeout, Some(10));
+    /// assert_eq!(file.strategy, Some(SearchStrategy::DepthFirst));
+    /// assert_eq!(file.unknown_keys, ["timout"]);
+    ///
+    /// let error = ConfigFile::parse("max_depth = \"deep\"").unwrap_err();
+    /// assert!(error.to_string().contains("max_depth"));
+    /// ```
+    /// # Errors
+    /// Returns the toml error if it isn't TOML, or a key has the wrong kind of value.
+    pub fn parse(text: &str) -> Result<ConfigFile, toml::de::Error> {
+        let mut unknown_keys = Vec::new();
+        let mut file: ConfigFile =
+            serde_ignored::deserialize(&mut toml::Deserializer::new(text), |key| {
+                unknown_keys.push(key.to_string())
+            })?;
+        file.unknown_keys = unknown_keys;
+        Ok(file)
+    }
+
+    /// Reads and parses the config file at `path`, see `ConfigFile::parse`
+    /// # Errors
+    /// Returns why the file couldn't be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<ConfigFile, ConfigFileError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(ConfigFileError::Read)?;
+        let mut file = ConfigFile::parse(&text).map_err(ConfigFileError::Parse)?;
+        // So the file works wherever Ares is run from
+        if let (Some(wordlist), Some(dir)) = (&mut file.wordlist, path.parent()) {
+            *wordlist = dir.join(&*wordlist);
+        }
+        Ok(file)
+    }
+
+    /// Sets what the file sets in the config, leaving the rest as it is
+    /// # Errors
+    /// Returns the IO error if the wordlist can't be read.
+    pub fn apply(self, config: &mut Config) -> Result<(), ConfigFileError> {
+        if let Some(path) = self.wordlist {
+            let wordlist = Wordlist::from_file(&path)
+                .map_err(|error| ConfigFileError::Wordlist(path.clone(), error))?;
+            config.wordlist = Some(wordlist);
+        }
+        if let Some(timeout) = self.timeout {
+            config.timeout = Duration::from_secs(timeout);
+        }
+        if let Some(max_depth) = self.max_depth {
+            config.max_depth = max_depth;
+        }
+        if let Some(results_wanted) = self.results_wanted {
+            config.results_wanted = results_wanted;
+        }
+        if let Some(max_frontier) = self.max_frontier {
+            config.max_frontier = max_frontier;
+        }
+        if let Some(max_input_size) = self.max_input_size {
+            config.max_input_size = max_input_size;
+        }
+        if let Some(strategy) = self.strategy {
+            config.search_strategy = strategy;
+        }
+        if let Some(regex) = self.regex {
+            config.regex = Some(regex);
+        }
+        if let Some(languages) = self.languages {
+            config.languages = languages;
+        }
+        if let Some(only) = self.only {
+            config.include_decoders = only;
+        }
+        if let Some(exclude) = self.exclude {
+            config.exclude_decoders = exclude;
+        }
+        if let Some(output_format) = self.output_format {
+            config.output_format = output_format;
+        }
+        if let Some(confidence_threshold) = self.confidence_threshold {
+            config.confidence_threshold = confidence_threshold;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigFile, ConfigFileError};
+    use crate::checkers::dictionary_checker::Lang;
+    use crate::config::{Config, OutputFormat, SearchStrategy};
+    use std::time::Duration;
+
+    #[test]
+    fn every_key_is_set_in_the_config() {
+        let dir = std::env::temp_dir().join("ares_config_file_every_key");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("words.txt"), "hunter2\ncorrect horse\n").unwrap();
+        let path = dir.join("ares.toml");
+        std::fs::write(
+            &path,
+            r#"
+            timeout = 12
+            max_depth = 7
+            results_wanted = 3
+            max_frontier = 500
+            max_input_size = 4096
+            strategy = "iterative-deepening"
+            regex = 'flag\{[^}]*\}'
+            wordlist = "words.txt"
+            languages = ["en", "de"]
+            only = ["base", "caesar"]
+            exclude = ["base91"]
+            output_format = "json"
+            confidence_threshold = 0.6
+            "#,
+        )
+        .unwrap();
+        let file = ConfigFile::load(&path).unwrap();
+        assert!(file.unknown_keys.is_empty(), "{:?}", file.unknown_keys);
+        let mut config = Config::default();
+        file.apply(&mut config).unwrap();
+
+        assert_eq!(config.timeout, Duration::from_secs(12));
+        assert_eq!(config.max_depth, 7);
+        assert_eq!(config.results_wanted, 3);
+        assert_eq!(config.max_frontier, 500);
+        assert_eq!(config.max_input_size, 4096);
+        assert_eq!(config.search_strategy, SearchStrategy::IterativeDeepening);
+        assert_eq!(config.regex.as_deref(), Some(r"flag\{[^}]*\}"));
+        let wordlist = config.wordlist.unwrap();
+        assert!(wordlist.words.contains("hunter2"));
+        assert!(wordlist.words.contains("correct horse"));
+        assert_eq!(config.languages, [Lang::En, Lang::De]);
+        assert_eq!(config.include_decoders, ["base", "caesar"]);
+        assert_eq!(config.exclude_decoders, ["base91"]);
+        assert_eq!(config.output_format, OutputFormat::Json);
+        assert_eq!(config.confidence_threshold, 0.6);
+    }
+
+    #[test]
+    fn an_empty_file_changes_nothing() {
+        let file = ConfigFile::parse("").unwrap();
+        assert_eq!(file, ConfigFile::default());
+        let mut config = Config::default();
+        file.apply(&mut config).unwrap();
+        assert_eq!(config.timeout, Config::default().timeout);
+        assert_eq!(config.search_strategy, SearchStrategy::default());
+    }
+
+    #[test]
+    fn unknown_keys_are_collected() {
+        let file =
+            ConfigFile::parse("timout = 5\nmax_depth = 3\n[checkers]\nenglish = true").unwrap();
+        assert_eq!(file.max_depth, Some(3));
+        assert_eq!(file.unknown_keys, ["timout", "checkers"]);
+    }
+
+    #[test]
+    fn bad_values_name_their_key() {
+        for (text, key) in [
+            ("timeout = \"ten\"", "timeout"),
+            ("strategy = \"sideways\"", "strategy"),
+            ("languages = [\"en\", \"xx\"]", "languages"),
+            ("only = \"base\"", "only"),
+        ] {
+            let error = ConfigFile::parse(text).unwrap_err().to_string();
+            assert!(error.contains(key), "{text}: {error}");
+        }
+        let error = ConfigFile::parse("languages = [\"xx\"]").unwrap_err();
+        assert!(error.to_string().contains("\"xx\""), "{error}");
+        // Not TOML at all
+        assert!(ConfigFile::parse("timeout = = 5").is_err());
+    }
+
+    #[test]
+    fn a_missing_wordlist_is_an_error() {
+        let file = ConfigFile::parse("wordlist = \"/no/such/wordlist.txt\"").unwrap();
+        let error = file.apply(&mut Config::default()).unwrap_err();
+        assert!(matches!(error, ConfigFileError::Wordlist(..)));
+        assert!(error.to_string().contains("`wordlist`"), "{error}");
+    }
+}
@@ -1,6 +1,23 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+use crate::checkers::checker_type::{CheckerParams, DEFAULT_CONFIDENCE_THRESHOLD};
+use crate::checkers::dictionary_checker::Lang;
+use crate::checkers::preview_checker::DEFAULT_PREVIEW_SIZE;
+use crate::checkers::wordlist_checker::Wordlist;
+use crate::decoders::{check_decoder_names, UnknownDecoder};
+use crate::searchers::frontier::DEFAULT_MAX_FRONTIER;
+use crate::DEFAULT_MAX_INPUT_SIZE;
+
 /// import general checker
 use lemmeknow::Identifier;
-use once_cell::sync::OnceCell;
+
+/// The CLI's config file, ares.toml
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub mod file;
 
 /// Library input is the default API input
 /// The CLI turns its arguments into a LibraryInput struct
@@ -12,7 +29,6 @@ use once_cell::sync::OnceCell;
 /// let config = get_config();
 /// assert_eq!(config.verbose, 0);
 /// ```
-
 pub struct Config {
     /// A level of verbosity to determine.
     /// How much we print in logs.
@@ -22,29 +38,222 @@ pub struct Config {
     /// Should the human checker be on?
     /// This asks yes/no for plaintext. Turn off for API
     pub human_checker_on: bool,
-    /// The timeout threshold before Ares quites
-    /// This is in seconds
-    pub timeout: u32,
+    /// The timeout threshold before Ares quites.
+    /// Time the human checker spends waiting for an answer isn't counted.
+    pub timeout: Duration,
+    /// The most decoders in a row the search tries, so garbage isn't decoded forever
+    pub max_depth: u32,
+    /// How many different plaintexts to look for. Above 1 the search carries on after it finds one,
+    /// in case what it found is only part of the way to the real plaintext.
+    pub results_wanted: usize,
+    /// The most texts waiting to be decoded the search keeps. Past this the least promising
+    /// are dropped, so an input which decodes into endless garbage can't use up all the memory.
+    /// Defaults to 100,000.
+    pub max_frontier: usize,
+    /// The biggest input, in bytes, Ares cracks. Bigger inputs aren't searched at all,
+    /// see `CrackOutcome::TooLarge`. Defaults to 1 MiB.
+    pub max_input_size: usize,
+    /// Checkers only look at the first this many bytes of bigger text, as a few kilobytes are
+    /// enough to tell English or JSON from gibberish. Decoders still decode all of it,
+    /// so the plaintext is whole. A regex anchored to the end, like `\}$`, sees all of it.
+    /// Defaults to 16 KiB.
+    pub preview_size: usize,
     /// Is the program being run in API mode?
     /// This is used to determine if we should print to stdout
     /// Or return the values
     pub api_mode: bool,
     /// Regex enables the user to search for a specific regex or crib
     pub regex: Option<String>,
+    /// Words the plaintext may be, such as passwords or hostnames which English detection
+    /// would never spot, see `Wordlist::from_file`. Like the regex, this turns the other checkers off.
+    pub wordlist: Option<Wordlist>,
+    /// Only the decoders with these names or tags are used, like "Base64" or "base",
+    /// ignoring case. A decoder's name picks that decoder alone, even if it's a tag too.
+    /// When it's empty every decoder is used. See `Config::check_decoder_names`.
+    pub include_decoders: Vec<String>,
+    /// The decoders with these names or tags are never used, even if they are in `include_decoders`
+    pub exclude_decoders: Vec<String>,
+    /// The languages Athena looks for words of. Defaults to English.
+    pub languages: Vec<Lang>,
+    /// Overrides the popularity of decoders, keyed by decoder name,
+    /// so the search can be biased toward decoders the user expects
+    pub decoder_popularity: HashMap<String, f32>,
+    /// Decoded text with a smaller share of printable characters than this is thrown away.
+    /// Lower it to keep more of what decoders produce.
+    pub min_printable_ratio: f64,
+    /// Text is plaintext when a checker's confidence, from 0 to 1, is above this.
+    /// Lower it to accept more borderline text.
+    pub confidence_threshold: f32,
+    /// How the checkers tell English from gibberish, such as whether a single word counts.
+    /// Tighten these if short gibberish is being accepted.
+    pub checker_params: CheckerParams,
+    /// How the search picks what to decode next
+    pub search_strategy: SearchStrategy,
+    /// Cracks each line on its own when the whole input can't be cracked, for files where every line
+    /// was encoded separately. Inputs with 3 or more lines are cracked line by line anyway
+    /// when the whole input fails, this does it for any input with more than one line.
+    pub split_lines: bool,
+    /// Who reads what the CLI prints. When it's a program, what Ares says to people goes to stderr,
+    /// so stdout only has the outcome.
+    pub output_format: OutputFormat,
+    /// Where the CLI writes the plaintext rather than printing it, see `ares --output`.
+    /// Cracking never writes it, this is for the program using Ares.
+    pub output_file: Option<PathBuf>,
+    /// Whether the CLI lets the user pick from the plaintexts it found, see `ares --interactive`.
+    /// Like `output_file`, cracking doesn't read it.
+    pub interactive: bool,
+}
+
+/// How the CLI prints the outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum OutputFormat {
+    /// Sentences for people, with colours
+    #[default]
+    Text,
+    /// One JSON object for scripts, see `CrackOutcome::to_json`
+    #[cfg(feature = "serde")]
+    Json,
+    /// Only the plaintext, one per line, for shell pipelines like `decoded=$(ares -q "$blob")`.
+    /// Ares says nothing else, and exits with 2 if it ran out of time.
+    Plain,
 }
 
-/// Cell for storing global Config
-static CONFIG: OnceCell<Config> = OnceCell::new();
+impl OutputFormat {
+    /// Whether stdout is for people, so Ares can talk to them there
+    pub fn is_for_people(self) -> bool {
+        self == OutputFormat::Text
+    }
+
+    /// Whether Ares only prints the plaintext, and doesn't talk to people at all
+    pub fn is_quiet(self) -> bool {
+        self == OutputFormat::Plain
+    }
+}
 
-/// To initialize global config with custom values
+impl Config {
+    /// Checks every name in `include_decoders` and `exclude_decoders` is a decoder's name or tag,
+    /// as a misspelt name would otherwise quietly pick no decoders.
+    /// ```rust
+    /// use ares::config::Config;
+    ///
+    /// let config = Config {
+    ///     include_decoders: vec!["base".to_string()],
+    ///     exclude_decoders: vec!["base46".to_string()],
+    ///     ..Config::default()
+    /// };
+    /// let unknown = config.check_decoder_names().unwrap_err();
+    /// assert_eq!(unknown.near_matches[0], "Base64");
+    /// ```
+    /// # Errors
+    /// Returns the first name which isn't, with the names and tags closest to it.
+    pub fn check_decoder_names(&self) -> Result<(), UnknownDecoder> {
+        check_decoder_names(&self.include_decoders)?;
+        check_decoder_names(&self.exclude_decoders)
+    }
+}
+
+/// How the search decides which decoded text to decode next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SearchStrategy {
+    /// Decodes the most promising text first, judged by how popular the decoders which produced it are,
+    /// how confident the checker was about it, how random it looks and how deep it is
+    #[default]
+    BestFirst,
+    /// Decodes every text at one depth before any at the next
+    BreadthFirst,
+    /// Follows the most promising decoding as deep as `Config::max_depth` allows before trying the next.
+    /// It only keeps the text next to the chain it's following, so deep, narrow chains take little memory.
+    DepthFirst,
+    /// Depth first searches one level deeper each time, up to `Config::max_depth`,
+    /// so it finds the shallowest plaintext like breadth first search but with the memory of depth first
+    IterativeDeepening,
+}
+
+/// The global config, once it's set or first read
+static CONFIG: RwLock<Option<Arc<Config>>> = RwLock::new(None);
+
+thread_local! {
+    /// The config of the crack running on this thread, which `get_config` returns
+    /// rather than the global one, see `with_config`
+    static CRACK_CONFIG: RefCell<Option<Arc<Config>>> = const { RefCell::new(None) };
+}
+
+/// To initialize global config with custom values.
+/// Only the first config set is kept, see `replace_global_config` to change it.
+/// Cracking with `perform_cracking` uses the config it's given rather than this one.
 pub fn set_global_config(config: Config) {
-    CONFIG.set(config).ok(); // ok() used to make compiler happy about using Result
+    let mut global = CONFIG.write().unwrap_or_else(PoisonError::into_inner);
+    if global.is_none() {
+        *global = Some(Arc::new(config));
+    }
 }
 
-/// Get the global config.
+/// Replaces the global config, even if one was already set, for sessions which change
+/// their settings between cracks like `ares repl`.
+/// ```rust
+/// use ares::config::{get_config, replace_global_config, set_global_config, Config};
+///
+/// set_global_config(Config::default());
+/// replace_global_config(Config {
+///     max_depth: 3,
+///     ..Config::default()
+/// });
+/// assert_eq!(get_config().max_depth, 3);
+/// ```
+pub fn replace_global_config(config: Config) {
+    *CONFIG.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(config));
+}
+
+/// Runs `f` with `config` as the config `get_config` returns on this thread, rather than the global one.
+/// Each crack runs in its own, so cracks with different configs can run at once, on different threads,
+/// and what a crack reads deep in the decoders is from the config it was given.
+/// ```rust
+/// use std::sync::Arc;
+/// use ares::config::{get_config, with_config, Config};
+///
+/// let config = Arc::new(Config {
+///     max_depth: 3,
+///     ..Config::default()
+/// });
+/// assert_eq!(with_config(config, || get_config().max_depth), 3);
+/// assert_eq!(get_config().max_depth, Config::default().max_depth);
+/// ```
+pub fn with_config<T>(config: Arc<Config>, f: impl FnOnce() -> T) -> T {
+    /// Puts back the config the thread had before, even if `f` panics
+    struct Restore(Option<Arc<Config>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CRACK_CONFIG.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CRACK_CONFIG.with(|current| current.replace(Some(config))));
+    f()
+}
+
+/// Get the config of the crack running on this thread, see `with_config`,
+/// or else the global config.
 /// This will return default config if the config wasn't already initialized
-pub fn get_config() -> &'static Config {
-    CONFIG.get_or_init(Config::default)
+pub fn get_config() -> Arc<Config> {
+    if let Some(config) = CRACK_CONFIG.with(|current| current.borrow().clone()) {
+        return config;
+    }
+    if let Some(config) = &*CONFIG.read().unwrap_or_else(PoisonError::into_inner) {
+        return Arc::clone(config);
+    }
+    // Someone else may have set theirs since it was read
+    let mut global = CONFIG.write().unwrap_or_else(PoisonError::into_inner);
+    Arc::clone(global.get_or_insert_with(|| Arc::new(Config::default())))
 }
 
 /// Creates a default lemmeknow config
@@ -63,9 +272,52 @@ impl Default for Config {
             verbose: 0,
             lemmeknow_config: LEMMEKNOW_DEFAULT_CONFIG,
             human_checker_on: false,
-            timeout: 5,
+            timeout: Duration::from_secs(5),
+            max_depth: 25,
+            results_wanted: 1,
+            max_frontier: DEFAULT_MAX_FRONTIER,
+            max_input_size: DEFAULT_MAX_INPUT_SIZE,
+            preview_size: DEFAULT_PREVIEW_SIZE,
             api_mode: true,
             regex: None,
+            wordlist: None,
+            include_decoders: Vec::new(),
+            exclude_decoders: Vec::new(),
+            languages: vec![Lang::En],
+            decoder_popularity: HashMap::new(),
+            min_printable_ratio: 0.7,
+            confidence_threshold: DEFAULT_CONFIDENCE_THRESHOLD,
+            checker_params: CheckerParams::default(),
+            search_strategy: SearchStrategy::default(),
+            split_lines: false,
+            output_format: OutputFormat::default(),
+            output_file: None,
+            interactive: false,
+        }
+    }
+}
+
+/// lemmeknow's `Identifier` isn't `Clone`, so its settings are copied one by one
+impl Clone for Config {
+    fn clone(&self) -> Self {
+        Config {
+            lemmeknow_config: Identifier {
+                min_rarity: self.lemmeknow_config.min_rarity,
+                max_rarity: self.lemmeknow_config.max_rarity,
+                tags: self.lemmeknow_config.tags.clone(),
+                exclude_tags: self.lemmeknow_config.exclude_tags.clone(),
+                file_support: self.lemmeknow_config.file_support,
+                boundaryless: self.lemmeknow_config.boundaryless,
+            },
+            regex: self.regex.clone(),
+            wordlist: self.wordlist.clone(),
+            include_decoders: self.include_decoders.clone(),
+            exclude_decoders: self.exclude_decoders.clone(),
+            languages: self.languages.clone(),
+            decoder_popularity: self.decoder_popularity.clone(),
+            checker_params: self.checker_params.clone(),
+            output_file: self.output_file.clone(),
+            ..*self
         }
     }
 }
@@ -0,0 +1,270 @@
+//! Decode an A1Z26 string
+//! Performs error handling and returns a string
+//! Call a1z26_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The A1Z26 decoder, call:
+/// `let a1z26_decoder = Decoder::<A1Z26Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = a1z26_decoder.crack(input)` to decode an A1Z26 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::a1z26_decoder::{A1Z26Decoder};
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_a1z26 = Decoder::<A1Z26Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_a1z26.crack("8-5-12-12-15 23-15-18-12-4", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct A1Z26Decoder;
+
+impl Crack for Decoder<A1Z26Decoder> {
+    fn new() -> Decoder<A1Z26Decoder> {
+        DecoderBuilder::new("A1Z26")
+            .with_description("A1Z26 is a simple substitution cipher where each letter is replaced by its position in the alphabet, so A is 1 and Z is 26. The numbers are usually separated by hyphens, spaces or dots.")
+            .with_link("https://www.dcode.fr/letter-number-cipher")
+            .with_tags(&["a1z26", "substitution", "classical", "decoder"])
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying A1Z26 with text {:?}", text);
+        let decoded_text = decode_a1z26_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode A1Z26 because A1Z26Decoder::decode_a1z26_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode A1Z26 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+    /// A1Z26 is numbers, with separators between letters and words.
+    /// A '+' is allowed as the numbers are parsed with it.
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars().all(|c| {
+            c.is_ascii_digit()
+                || c == '/'
+                || c == '+'
+                || LETTER_SEPARATORS.contains(&c)
+                || c.is_whitespace()
+        })
+    }
+    /// Encodes every letter as its position in the alphabet, with '-' between letters and ' ' between words.
+    /// Returns None for anything which isn't a letter or a space.
+    fn encode(&self, text: &str) -> Option<String> {
+        text.split(' ')
+            .map(|word| {
+                word.chars()
+                    .map(|c| {
+                        c.is_ascii_alphabetic()
+                            .then(|| (c.to_ascii_lowercase() as u8 - b'a' + 1).to_string())
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(|numbers| numbers.join("-"))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|words| words.join(" "))
+    }
+}
+
+/// The characters which separate two letters of the same word
+const LETTER_SEPARATORS: [char; 4] = ['-', '.', ',', ' '];
+
+/// The fewest numbers we will decode, to avoid mangling short numeric data
+const MIN_A1Z26_TOKENS: usize = 3;
+
+/// helper function
+/// Words are separated by `/` or by two or more spaces.
+/// If the letters are separated by `-`, `.` or `,` a single space also separates words.
+fn decode_a1z26_no_error_handling(text: &str) -> Option<String> {
+    let mut words: Vec<&str> = text
+        .split('/')
+        .flat_map(|group| group.split("  "))
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.iter().any(|word| word.contains(['-', '.', ','])) {
+        words = words
+            .into_iter()
+            .flat_map(|word| word.split_whitespace())
+            .collect();
+    }
+
+    let mut token_count = 0;
+    let mut decoded_words = Vec::with_capacity(words.len());
+    for word in words {
+        let decoded_word = word
+            .split(LETTER_SEPARATORS)
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                token_count += 1;
+                number_to_letter(token)
+            })
+            .collect::<Option<String>>()?;
+        decoded_words.push(decoded_word);
+    }
+
+    if token_count < MIN_A1Z26_TOKENS {
+        return None;
+    }
+    Some(decoded_words.join(" "))
+}
+
+/// Maps 1 - 26 to a - z, anything else is invalid
+fn number_to_letter(token: &str) -> Option<char> {
+    match token.parse::<u8>() {
+        Ok(number @ 1..=26) => Some((b'a' + number - 1) as char),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::A1Z26Decoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn a1z26_encode_round_trips() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        for text in ["hello world", "the quick brown fox"] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
+    #[test]
+    fn a1z26_decodes_hyphen_separated() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("8-5-12-12-15 23-15-18-12-4", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn a1z26_decodes_space_separated() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("8 5 12 12 15", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn a1z26_decodes_dot_separated() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("8.5.12.12.15", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn a1z26_decodes_word_boundaries() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("8 5 12 12 15  23 15 18 12 4", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+
+        let result = decoder.crack("8 5 12 12 15 / 23 15 18 12 4", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn a1z26_rejects_numbers_above_26() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder
+            .crack("27 99 3", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn a1z26_rejects_zero() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder
+            .crack("8 0 12 12", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn a1z26_skips_fewer_than_three_numbers() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("8 9", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn a1z26_handles_panics() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn a1z26_handles_panic_if_empty_string() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn a1z26_handles_panic_if_emoji() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn a1z26_only_attempts_numbers() {
+        let decoder = Decoder::<A1Z26Decoder>::new();
+        assert!(!decoder.can_attempt("8 5 12 12 15 world"));
+        assert!(decoder.can_attempt("8-5-12-12-15 / 23.15,18\t12 4"));
+    }
+}
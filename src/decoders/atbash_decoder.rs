@@ -1,36 +1,61 @@
+//! Decode an atbash string
+//! Performs error handling and returns a string
+//! Call atbash_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{info, trace};
 
-///! Atbash Decoder
+/// The atbash decoder, call:
+/// `let atbash_decoder = Decoder::<AtbashDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = atbash_decoder.crack(input)` to decode an atbash string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::atbash_decoder::AtbashDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_atbash = Decoder::<AtbashDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_atbash.crack("svool dliow", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
 pub struct AtbashDecoder;
 
 impl Crack for Decoder<AtbashDecoder> {
     fn new() -> Decoder<AtbashDecoder> {
-        Decoder {
-            name: "Atbash",
-            description: "Atbash is a monoalphabetic substitution cipher originally used to encrypt the Hebrew alphabet. It can be modified for use with any known writing system with a standard collating order.",
-            link: "https://en.wikipedia.org/wiki/Atbash",
-            tags: vec!["atbash", "substitution", "decoder", "reciprocal"],
-            popularity: 1.0,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Atbash")
+            .with_description("Atbash is a monoalphabetic substitution cipher originally used to encrypt the Hebrew alphabet. It can be modified for use with any known writing system with a standard collating order.")
+            .with_link("https://en.wikipedia.org/wiki/Atbash")
+            .with_tags(&[
+                "atbash",
+                "substitution",
+                "classical",
+                "decoder",
+                "reciprocal",
+            ])
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying atbash with text {:?}", text);
         let decoded_text = atbash_to_alphabet(text);
 
         trace!("Decoded text for atbash: {:?}", decoded_text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if !check_string_success(&decoded_text, text) {
             info!(
@@ -41,19 +66,16 @@ impl Crack for Decoder<AtbashDecoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Atbash is its own inverse, so encoding is decoding
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(atbash_to_alphabet(text))
     }
 }
 
@@ -86,6 +108,20 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn atbash_encode_round_trips() {
+        let decoder = Decoder::<AtbashDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn test_atbash() {
         let decoder = Decoder::<AtbashDecoder>::new();
@@ -130,6 +166,16 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn atbash_fails_if_output_equals_input() {
+        // Atbash is its own inverse, so a string without letters decodes to itself
+        let atbash_decoder = Decoder::<AtbashDecoder>::new();
+        let result = atbash_decoder
+            .crack("!?#$ 123 ,.;", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
     #[test]
     fn atbash_decode_handles_panics() {
         let atbash_decoder = Decoder::<AtbashDecoder>::new();
@@ -0,0 +1,255 @@
+//! Decode a Baconian cipher string
+//! Performs error handling and returns a string
+//! Call baconian_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{check_candidates, check_string_success};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The pairs of symbols used to write Bacon's cipher, the first of each pair is 0
+const BACONIAN_SYMBOL_SETS: [(char, char); 3] = [('A', 'B'), ('a', 'b'), ('0', '1')];
+
+/// The original 24 letter alphabet, where I/J and U/V share a code
+const BACONIAN_24_LETTER_ALPHABET: &[u8; 24] = b"ABCDEFGHIKLMNOPQRSTUWXYZ";
+
+/// The modern 26 letter alphabet where every letter has its own code
+const BACONIAN_26_LETTER_ALPHABET: &[u8; 26] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// The Baconian decoder, call:
+/// `let baconian_decoder = Decoder::<BaconianDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = baconian_decoder.crack(input)` to decode a Baconian string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::baconian_decoder::BaconianDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_baconian = Decoder::<BaconianDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_baconian.crack("AABBB AABAA ABABB ABABB ABBBA", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert!(result.unwrap().contains(&"HELLO".to_string()));
+/// ```
+pub struct BaconianDecoder;
+
+impl Crack for Decoder<BaconianDecoder> {
+    fn new() -> Decoder<BaconianDecoder> {
+        DecoderBuilder::new("Baconian")
+            .with_description("Bacon's cipher is a method of steganographic message encoding devised by Francis Bacon in 1605. Each letter is replaced by a group of five A or B symbols. The original version uses 24 letters, with I/J and U/V sharing a code, while the modern version gives every letter its own code.")
+            .with_link("https://en.wikipedia.org/wiki/Bacon%27s_cipher")
+            .with_tags(&["baconian", "substitution", "classical", "decoder"])
+            .with_popularity(0.4)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Baconian with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        let mut decoded_strings: Vec<String> = decode_baconian_no_error_handling(text)
+            .into_iter()
+            .filter(|decoded_text| check_string_success(decoded_text, text))
+            .collect();
+        if decoded_strings.is_empty() {
+            debug!("Failed to decode Baconian because BaconianDecoder::decode_baconian_no_error_handling returned nothing");
+            return results;
+        }
+
+        // The decoding the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            if results.success {
+                info!("Baconian decoded to {}", decoded_strings[index]);
+            }
+            decoded_strings[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(decoded_strings);
+        results
+    }
+    decoder_getters!();
+    /// Baconian is made of one of its pairs of symbols, with whitespace between groups
+    fn can_attempt(&self, text: &str) -> bool {
+        BACONIAN_SYMBOL_SETS.iter().any(|&(zero, one)| {
+            text.chars()
+                .all(|c| c == zero || c == one || c.is_whitespace())
+        })
+    }
+}
+
+/// helper function
+/// Returns the 26 letter decoding followed by the 24 letter decoding, if they are valid and differ.
+fn decode_baconian_no_error_handling(text: &str) -> Vec<String> {
+    let symbols: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if symbols.is_empty() || !symbols.len().is_multiple_of(5) {
+        return Vec::new();
+    }
+
+    let one = match BACONIAN_SYMBOL_SETS
+        .iter()
+        .find(|(zero, one)| symbols.iter().all(|c| c == zero || c == one))
+    {
+        Some(&(_, one)) => one,
+        None => return Vec::new(),
+    };
+
+    let codes: Vec<usize> = symbols
+        .chunks(5)
+        .map(|group| {
+            group
+                .iter()
+                .fold(0, |code, &symbol| (code << 1) | usize::from(symbol == one))
+        })
+        .collect();
+
+    let mut candidates = Vec::new();
+    for alphabet in [
+        &BACONIAN_26_LETTER_ALPHABET[..],
+        &BACONIAN_24_LETTER_ALPHABET[..],
+    ] {
+        let decoded: Option<String> = codes
+            .iter()
+            .map(|&code| alphabet.get(code).map(|&letter| letter as char))
+            .collect();
+        if let Some(decoded) = decoded {
+            if !candidates.contains(&decoded) {
+                candidates.push(decoded);
+            }
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BaconianDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn baconian_decodes_a_and_b() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder.crack("AABBB AABAA ABABB ABABB ABBBA", &get_athena_checker());
+        assert!(result
+            .unencrypted_text
+            .unwrap()
+            .contains(&"HELLO".to_string()));
+    }
+
+    #[test]
+    fn baconian_decodes_lowercase_without_spaces() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder.crack("aabbbaabaaababbababbabbba", &get_athena_checker());
+        assert!(result
+            .unencrypted_text
+            .unwrap()
+            .contains(&"HELLO".to_string()));
+    }
+
+    #[test]
+    fn baconian_decodes_binary() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder.crack("00111 00100 01011 01011 01110", &get_athena_checker());
+        assert!(result
+            .unencrypted_text
+            .unwrap()
+            .contains(&"HELLO".to_string()));
+    }
+
+    #[test]
+    fn baconian_returns_24_and_26_letter_candidates() {
+        // HELLO in the 24 letter alphabet is HEKKN in the 26 letter alphabet
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder.crack("AABBB AABAA ABABA ABABA ABBAB", &get_athena_checker());
+        let decoded_strings = result.unencrypted_text.unwrap();
+        assert_eq!(decoded_strings.len(), 2);
+        assert!(decoded_strings.contains(&"HELLO".to_string()));
+        assert!(decoded_strings.contains(&"HEKKN".to_string()));
+    }
+
+    #[test]
+    fn baconian_24_letter_alphabet_rejects_codes_above_23() {
+        // BBBBB is 31 which isn't a letter in either alphabet
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder
+            .crack("AABBB BBBBB", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn baconian_rejects_wrong_length() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder
+            .crack("AABBB AABA", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn baconian_rejects_more_than_two_symbols() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder
+            .crack("AABBC AABAA", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn baconian_handles_panics() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn baconian_handles_panic_if_empty_string() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn baconian_handles_panic_if_emoji() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn baconian_only_attempts_its_symbols() {
+        let decoder = Decoder::<BaconianDecoder>::new();
+        assert!(!decoder.can_attempt("AABBB aaabb"));
+        assert!(!decoder.can_attempt("a baby"));
+        assert!(decoder.can_attempt("aabbb\naabaa\tababa"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Decodes a Crockford base32 string
+//! Performs error handling and returns a string
+//! Call base32_crockford_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_candidates, check_string_success,
+};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The 32 symbols used to encode data in Crockford's base32.
+/// I, L, O and U are excluded to avoid confusion with 1, 1, 0 and V.
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// The 5 extra symbols which can only appear as a check symbol (values 32 - 36)
+const CROCKFORD_CHECK_SYMBOLS: &[u8; 5] = b"*~$=U";
+
+/// The Crockford Base32 decoder, call:
+/// `let base32_crockford_decoder = Decoder::<Base32CrockfordDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = base32_crockford_decoder.crack(input)` to decode a Crockford base32 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::base32_crockford_decoder::{Base32CrockfordDecoder};
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base32_crockford = Decoder::<Base32CrockfordDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base32_crockford.crack("D1JPRV3F41VPYWKCCG", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct Base32CrockfordDecoder;
+
+impl Crack for Decoder<Base32CrockfordDecoder> {
+    fn new() -> Decoder<Base32CrockfordDecoder> {
+        DecoderBuilder::new("Base32 Crockford")
+            .with_description("Crockford's Base32 is a variant of base32 designed to be human readable. It excludes the letters I, L, O and U, decodes them as their look-alike digits, ignores hyphens and supports an optional check symbol. It is commonly used in ULIDs and licence keys.")
+            .with_link("https://www.crockford.com/base32.html")
+            .with_tags(&["base32_crockford", "base32", "crockford", "decoder", "base"])
+            .with_popularity(0.2)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base32 Crockford with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        let decoded_strings = decode_base32_crockford_no_error_handling(text);
+        if decoded_strings.is_empty() {
+            debug!("Failed to decode base32 crockford because Base32CrockfordDecoder::decode_base32_crockford_no_error_handling returned nothing");
+            return results;
+        }
+
+        let mut decoded_strings: Vec<String> = decoded_strings
+            .into_iter()
+            .filter(|decoded_text| check_string_success(decoded_text, text))
+            .collect();
+        if decoded_strings.is_empty() {
+            info!(
+                "Failed to decode base32 crockford because check_string_success returned false on every candidate for {}",
+                text
+            );
+            return results;
+        }
+
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            // If checkers return true, exit early with the correct result
+            if results.success {
+                return results.with_candidate(decoded_strings.swap_remove(index));
+            }
+            // Otherwise the one the checker came closest to identifying goes first
+            decoded_strings[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(decoded_strings);
+        results
+    }
+    decoder_getters!();
+    /// Crockford is case-insensitive letters and digits, hyphens, and the check symbols
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars().all(|c| {
+            c.is_ascii_alphanumeric() || c == '-' || CROCKFORD_CHECK_SYMBOLS.contains(&(c as u8))
+        })
+    }
+}
+
+/// helper function
+/// Returns every plausible decoding of the text.
+/// If the last symbol could be a check symbol we return the decoding without it first,
+/// followed by the decoding of the whole string when the check symbol does not validate.
+fn decode_base32_crockford_no_error_handling(text: &str) -> Vec<String> {
+    let symbols = match normalise_crockford_string(text) {
+        Some(symbols) if !symbols.is_empty() => symbols,
+        _ => return Vec::new(),
+    };
+
+    let (body, last) = symbols.split_at(symbols.len() - 1);
+    let last = last[0];
+    let mut candidates = Vec::new();
+
+    if body.iter().any(|&symbol| symbol >= 32) {
+        // Check symbols may only appear at the end of the string
+        return Vec::new();
+    }
+
+    if last >= 32 {
+        // The last symbol can only ever be a check symbol
+        // so we always decode without it, even if it does not validate.
+        if !check_symbol_is_valid(body, last) {
+            debug!("Base32 Crockford check symbol did not validate, decoding without it");
+        }
+        candidates.extend(crockford_symbols_to_string(body));
+        return candidates;
+    }
+
+    if !body.is_empty() && check_symbol_is_valid(body, last) {
+        candidates.extend(crockford_symbols_to_string(body));
+    }
+    candidates.extend(crockford_symbols_to_string(&symbols));
+    candidates
+}
+
+/// Turns the text into a list of Crockford symbol values (0 - 36)
+/// Hyphens are ignored, the text is case-insensitive and
+/// the ambiguous characters I, L and O are mapped to 1, 1 and 0.
+/// Returns None if any character is not part of the alphabet.
+fn normalise_crockford_string(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .filter(|c| *c != '-')
+        .map(|c| {
+            let c = match c.to_ascii_uppercase() {
+                'I' | 'L' => '1',
+                'O' => '0',
+                other => other,
+            };
+            if !c.is_ascii() {
+                return None;
+            }
+            let c = c as u8;
+            CROCKFORD_ALPHABET
+                .iter()
+                .chain(CROCKFORD_CHECK_SYMBOLS.iter())
+                .position(|&symbol| symbol == c)
+                .map(|position| position as u8)
+        })
+        .collect()
+}
+
+/// The check symbol is the value of the whole number encoded by the body modulo 37
+fn check_symbol_is_valid(body: &[u8], check_symbol: u8) -> bool {
+    let remainder = body
+        .iter()
+        .fold(0u32, |acc, &symbol| (acc * 32 + u32::from(symbol)) % 37);
+    remainder == u32::from(check_symbol)
+}
+
+/// Packs the 5-bit symbols into bytes, discarding any trailing padding bits
+fn crockford_symbols_to_string(symbols: &[u8]) -> Option<String> {
+    let mut bytes = Vec::with_capacity(symbols.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits_in_buffer = 0;
+    for &symbol in symbols {
+        buffer = (buffer << 5) | u32::from(symbol);
+        bits_in_buffer += 5;
+        if bits_in_buffer >= 8 {
+            bits_in_buffer -= 8;
+            bytes.push((buffer >> bits_in_buffer) as u8);
+            buffer &= (1 << bits_in_buffer) - 1;
+        }
+    }
+    bytes_to_candidate_string(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base32CrockfordDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn base32_crockford_decodes_successfully() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("D1JPRV3F41VPYWKCCG", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn base32_crockford_decodes_hyphenated_input() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("D1JP-RV3F-41VP-YWKC-CG", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn base32_crockford_decodes_lowercase_input() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("d1jprv3f41vpywkccg", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn base32_crockford_decodes_o_as_zero() {
+        // The encoded form of "good morning" is CXQPYS10DNQQ4VK9DSKG
+        // Here the 0 has been written as an O and the 1 as an l
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("CXQPYSlODNQQ4VK9DSKG", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "good morning");
+    }
+
+    #[test]
+    fn base32_crockford_decodes_with_valid_check_symbol() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("D1JPRV3F41VPYWKCCGT", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn base32_crockford_decodes_without_invalid_check_symbol() {
+        // `*` can only be a check symbol, and it is the wrong one for this input
+        // so the decoder should fall back to decoding without it.
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("D1JPRV3F41VPYWKCCG*", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn base32_crockford_fails_on_u() {
+        // U is excluded from the Crockford alphabet
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder
+            .crack("D1JPRUV3F41VPYWKCCG", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_crockford_fails_on_a_check_symbol_before_the_end() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        for text in ["AB*CD~", "AB*CD", "D1JPRV3F41V=PYWKCCGT"] {
+            let result = decoder.crack(text, &get_athena_checker()).unencrypted_text;
+            assert!(result.is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn base32_crockford_handles_panics() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_crockford_handles_panic_if_empty_string() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_crockford_handles_panic_if_emoji() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_crockford_only_attempts_its_alphabet() {
+        let decoder = Decoder::<Base32CrockfordDecoder>::new();
+        assert!(!decoder.can_attempt("hello world"));
+        // Lowercase, hyphens and a check symbol are all part of it
+        assert!(decoder.can_attempt("91jprv3f-41bpywkccggg~"));
+    }
+}
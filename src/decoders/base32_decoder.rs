@@ -1,16 +1,19 @@
+//! Decodes a base32 string
+//! Performs error handling and returns a string
+//! Call base32_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_string_success, normalize_base_input,
+};
 
 use super::crack_results::CrackResult;
-///! Decodes a base32 string
-///! Performs error handling and returns a string
-///! Call base32_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
+use super::interface::only_uses_alphabet;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
-use data_encoding::BASE32_NOPAD;
+use data_encoding::{BASE32, BASE32_NOPAD};
 use log::{debug, info, trace};
 
 /// The Base32 decoder, call:
@@ -35,23 +38,21 @@ pub struct Base32Decoder;
 
 impl Crack for Decoder<Base32Decoder> {
     fn new() -> Decoder<Base32Decoder> {
-        Decoder {
-            name: "Base32",
-            description: "Base32 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.",
-            link: "https://en.wikipedia.org/wiki/Base32",
-            tags: vec!["base32", "decoder", "base"],
-            popularity: 0.8,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Base32")
+            .with_description("Base32 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.")
+            .with_link("https://en.wikipedia.org/wiki/Base32")
+            .with_tags(&["base32", "decoder", "base"])
+            .with_popularity(0.8)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Base32 with text {:?}", text);
         let decoded_text = decode_base32_no_error_handling(text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode base32 because Base32Decoder::decode_base32_no_error_handling returned None");
@@ -68,30 +69,36 @@ impl Crack for Decoder<Base32Decoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// Base32 is uppercase letters, 2 to 7 and padding
+    fn can_attempt(&self, text: &str) -> bool {
+        only_uses_alphabet(text, "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567=")
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes the text as padded base32
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(BASE32.encode(text.as_bytes()))
     }
 }
 
 /// helper function
 fn decode_base32_no_error_handling(text: &str) -> Option<String> {
-    // Strip all padding
-    let text = text.replace('=', "");
+    // Strip any whitespace from wrapping and the padding at the end
+    let text = normalize_base_input(text);
+    let text = text.trim_end_matches('=');
+    // Padding anywhere else means this isn't one base32 string
+    if text.contains('=') {
+        return None;
+    }
     // Runs the code to decode base32
     // Doesn't perform error handling, call from_base32
     if let Ok(decoded_text) = &BASE32_NOPAD.decode(text.as_bytes()) {
-        return Some(String::from_utf8_lossy(decoded_text).to_string());
+        return bytes_to_candidate_string(decoded_text);
     }
     None
 }
@@ -105,7 +112,12 @@ mod tests {
             checker_type::{Check, Checker},
             CheckerTypes,
         },
-        decoders::interface::{Crack, Decoder},
+        decoders::{
+            base58_bitcoin_decoder::Base58BitcoinDecoder,
+            base64_decoder::Base64Decoder,
+            hexadecimal_decoder::HexadecimalDecoder,
+            interface::{Crack, Decoder},
+        },
     };
 
     // helper for tests
@@ -114,6 +126,63 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn base32_encode_round_trips() {
+        let decoder = Decoder::<Base32Decoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
+    #[test]
+    fn base32_with_interior_padding_fails() {
+        let base32_decoder = Decoder::<Base32Decoder>::new();
+        let result = base32_decoder
+            .crack("NBSW=Y3DP", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_only_padding_fails() {
+        let base32_decoder = Decoder::<Base32Decoder>::new();
+        let result = base32_decoder
+            .crack("========", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base32_decodes_split_across_lines() {
+        let base32_decoder = Decoder::<Base32Decoder>::new();
+        let result = base32_decoder.crack(
+            "NBSWY3DPEB3W64TMMQWCA5DINFZSA2LT\nEBZXA3DJOQQG65TFOIQGY2LOMVZQ====\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world, this is split over lines"
+        );
+    }
+
+    #[test]
+    fn base32_with_invalid_characters_between_lines_fails() {
+        let base32_decoder = Decoder::<Base32Decoder>::new();
+        let result = base32_decoder
+            .crack(
+                "NBSWY3DPEB3W64TMMQWCA5DINFZSA2LT\n-- \nEBZXA3DJOQQG65TFOIQGY2LOMVZQ====",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
     #[test]
     fn base32_decodes_successfully() {
         // This tests if Base32 can decode Base32 successfully
@@ -140,15 +209,21 @@ mod tests {
         assert_eq!(result.unencrypted_text.unwrap()[0], "Incorrect padding");
     }
 
-    #[ignore]
     #[test]
     fn base32_decodes_tryhackme_base32_successfully() {
         // This tests if Base32 can decode Base32 with no padding successfully
-        // The string is from the "breakit" THM room
-        // TODO: Ignoring this until we have quadgrams
-        let base32_decoder = Decoder::<Base32Decoder>::new();
-        let result = base32_decoder.crack("GM4HOU3VHBAW6OKNJJFW6SS2IZ3VAMTYORFDMUC2G44EQULIJI3WIVRUMNCWI6KGK5XEKZDTN5YU2RT2MR3E45KKI5TXSOJTKZJTC4KRKFDWKZTZOF3TORJTGZTXGNKCOE", &get_athena_checker());
-        assert_eq!(result.unencrypted_text.unwrap()[0], "base16_is_hex");
+        // The string is from the "breakit" THM room, which is base32 → base58 → hex → base64.
+        // The plaintext has no dictionary words in it, so only the quadgram checker can spot it
+        let checker = get_athena_checker();
+        let base32 = Decoder::<Base32Decoder>::new().crack("GM4HOU3VHBAW6OKNJJFW6SS2IZ3VAMTYORFDMUC2G44EQULIJI3WIVRUMNCWI6KGK5XEKZDTN5YU2RT2MR3E45KKI5TXSOJTKZJTC4KRKFDWKZTZOF3TORJTGZTXGNKCOE", &checker);
+        let base58 = Decoder::<Base58BitcoinDecoder>::new()
+            .crack(&base32.unencrypted_text.unwrap()[0], &checker);
+        let hex = Decoder::<HexadecimalDecoder>::new()
+            .crack(&base58.unencrypted_text.unwrap()[0], &checker);
+        let base64 =
+            Decoder::<Base64Decoder>::new().crack(&hex.unencrypted_text.unwrap()[0], &checker);
+        assert!(base64.success);
+        assert_eq!(base64.unencrypted_text.unwrap()[0], "base16_is_hex");
     }
 
     #[test]
@@ -186,4 +261,13 @@ mod tests {
             .unencrypted_text;
         assert!(result.is_none());
     }
+
+    #[test]
+    fn base32_only_attempts_its_alphabet() {
+        let decoder = Decoder::<Base32Decoder>::new();
+        assert!(!decoder.can_attempt("nbswy3dp lowercase isn't base32"));
+        assert!(!decoder.can_attempt("NBSWY3DP8"));
+        // Wrapped lines and padding are tolerated
+        assert!(decoder.can_attempt("NBSWY3DPEB3W64TM\nMQ======\n"));
+    }
 }
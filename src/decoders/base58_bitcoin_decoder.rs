@@ -1,14 +1,17 @@
+//! Decodes a base58 bitcoin string
+//! Performs error handling and returns a string
+//! Call base58_bitcoin_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_string_success, normalize_base_input,
+};
 
 use super::crack_results::CrackResult;
-///! Decodes a base58 bitcoin string
-///! Performs error handling and returns a string
-///! Call base58_bitcoin_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+use super::interface::{only_uses_alphabet, BASE58_CHARACTERS};
 
 use log::{debug, info, trace};
 
@@ -34,23 +37,21 @@ pub struct Base58BitcoinDecoder;
 
 impl Crack for Decoder<Base58BitcoinDecoder> {
     fn new() -> Decoder<Base58BitcoinDecoder> {
-        Decoder {
-            name: "Base58 Bitcoin",
-            description: "Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.",
-            link: "https://en.wikipedia.org/wiki/Base58",
-            tags: vec!["base58_bitcoin", "base58", "bitcoin", "cryptocurrency", "decoder", "base"],
-            popularity: 0.8,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Base58 Bitcoin")
+            .with_description("Base58 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-32 representation.")
+            .with_link("https://en.wikipedia.org/wiki/Base58")
+            .with_tags(&["base58_bitcoin", "base58", "bitcoin", "cryptocurrency", "decoder", "base"])
+            .with_popularity(0.8)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Base58_bitcoin with text {:?}", text);
         let decoded_text = decode_base58_bitcoin_no_error_handling(text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode base58_bitcoin because Base58BitcoinDecoder::decode_base58_bitcoin_no_error_handling returned None");
@@ -67,31 +68,38 @@ impl Crack for Decoder<Base58BitcoinDecoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// Base58 only has letters and digits, and no 0, O, I or l
+    fn can_attempt(&self, text: &str) -> bool {
+        only_uses_alphabet(text, BASE58_CHARACTERS)
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes the text with the Bitcoin base58 alphabet
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(
+            bs58::encode(text)
+                .with_alphabet(bs58::Alphabet::BITCOIN)
+                .into_string(),
+        )
     }
 }
 
 /// helper function
 fn decode_base58_bitcoin_no_error_handling(text: &str) -> Option<String> {
+    // Strip any whitespace from wrapping
+    let text = normalize_base_input(text);
     // Runs the code to decode base58_bitcoin
     // Doesn't perform error handling, call from_base58_bitcoin
-    if let Ok(decoded_text) = bs58::decode(text)
+    if let Ok(decoded_text) = bs58::decode(&text)
         .with_alphabet(bs58::Alphabet::BITCOIN)
         .into_vec()
     {
-        return Some(String::from_utf8_lossy(&decoded_text).to_string());
+        return bytes_to_candidate_string(&decoded_text);
     }
     None
 }
@@ -114,6 +122,20 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn base58_bitcoin_encode_round_trips() {
+        let decoder = Decoder::<Base58BitcoinDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn successful_decoding() {
         let base58_bitcoin_decoder = Decoder::<Base58BitcoinDecoder>::new();
@@ -192,4 +214,12 @@ mod tests {
             assert_eq!(true, true);
         }
     }
+
+    #[test]
+    fn base58_bitcoin_only_attempts_its_alphabet() {
+        let decoder = Decoder::<Base58BitcoinDecoder>::new();
+        assert!(!decoder.can_attempt("0OIl are not base58"));
+        // Line breaks from wrapping are stripped before decoding
+        assert!(decoder.can_attempt("StV1DL6CwTryKyV\n"));
+    }
 }
@@ -0,0 +1,200 @@
+//! Decode base64 written with one of the well known alternative alphabets
+//! Performs error handling and returns a string
+//! Call base64_custom_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! The standard and URL safe alphabets have their own decoders.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_candidates, check_string_success,
+};
+use base64::{
+    alphabet,
+    engine::general_purpose::{GeneralPurpose, NO_PAD},
+    Engine as _,
+};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The alphabets we try, with the name which is stored as the key when one works
+const BASE64_CUSTOM_ALPHABETS: [(&str, GeneralPurpose); 3] = [
+    ("IMAP", GeneralPurpose::new(&alphabet::IMAP_MUTF7, NO_PAD)),
+    ("crypt", GeneralPurpose::new(&alphabet::CRYPT, NO_PAD)),
+    ("bcrypt", GeneralPurpose::new(&alphabet::BCRYPT, NO_PAD)),
+];
+
+/// The Base64 custom alphabet decoder, call:
+/// `let base64_custom_decoder = Decoder::<Base64CustomDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = base64_custom_decoder.crack(input)` to decode a base64 string with a custom alphabet
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::base64_custom_decoder::Base64CustomDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_base64_custom = Decoder::<Base64CustomDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_base64_custom.crack("SXMgdGhpcyB0aGUgcmlnaHQgYWxwaGFiZXQ,Pz8gSSBob3BlIHNvIQ", &checker);
+/// assert_eq!(result.key.as_deref(), Some("IMAP"));
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "Is this the right alphabet??? I hope so!");
+/// ```
+pub struct Base64CustomDecoder;
+
+impl Crack for Decoder<Base64CustomDecoder> {
+    fn new() -> Decoder<Base64CustomDecoder> {
+        DecoderBuilder::new("Base64 Custom Alphabet")
+            .with_description("Base64 can be written with any 64 character alphabet. Well known alternatives include the modified alphabet used for IMAP mailbox names, which uses ',' instead of '/', and the alphabets used by crypt and bcrypt password hashes, which start with './'.")
+            .with_link("https://en.wikipedia.org/wiki/Base64#Variants_summary_table")
+            .with_tags(&["base64", "decoder", "base"])
+            .with_popularity(0.4)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Base64 custom alphabets with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        let candidates: Vec<(&str, String)> = decode_base64_custom_no_error_handling(text)
+            .into_iter()
+            .filter(|(_, decoded_text)| check_string_success(decoded_text, text))
+            .collect();
+        if candidates.is_empty() {
+            debug!("Failed to decode base64 custom alphabets because Base64CustomDecoder::decode_base64_custom_no_error_handling returned nothing");
+            return results;
+        }
+
+        let mut decoded_strings: Vec<String> = candidates
+            .iter()
+            .map(|(_, decoded_text)| decoded_text.clone())
+            .collect();
+        // The candidate the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            if results.success {
+                let alphabet_name = &candidates[index].0;
+                info!("Found a match with the {} base64 alphabet", alphabet_name);
+                results.set_key(alphabet_name.to_string());
+            }
+            decoded_strings[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(decoded_strings);
+        results
+    }
+    decoder_getters!();
+}
+
+/// helper function
+/// Returns the name of every alphabet which decoded the text to printable UTF-8, with what it decoded to.
+fn decode_base64_custom_no_error_handling(text: &str) -> Vec<(&'static str, String)> {
+    // Strip all padding
+    let text = text.replace('=', "");
+    BASE64_CUSTOM_ALPHABETS
+        .iter()
+        // Without a ',' the IMAP alphabet decodes exactly like the standard one
+        .filter(|(name, _)| *name != "IMAP" || text.contains(','))
+        .filter_map(|(name, engine)| {
+            let decoded_text = bytes_to_candidate_string(&engine.decode(text.as_bytes()).ok()?)?;
+            if decoded_text
+                .chars()
+                .any(|c| c.is_control() && !c.is_whitespace())
+            {
+                return None;
+            }
+            Some((*name, decoded_text))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base64CustomDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn base64_custom_decodes_imap_alphabet() {
+        let decoder = Decoder::<Base64CustomDecoder>::new();
+        let result = decoder.crack(
+            "SXMgdGhpcyB0aGUgcmlnaHQgYWxwaGFiZXQ,Pz8gSSBob3BlIHNvIQ==",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.key.as_deref(), Some("IMAP"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Is this the right alphabet??? I hope so!"
+        );
+    }
+
+    #[test]
+    fn base64_custom_decodes_crypt_alphabet() {
+        let decoder = Decoder::<Base64CustomDecoder>::new();
+        let result = decoder.crack(
+            "R4VZ64BmSL/o643gQ4VVMaJo65BoML7oQm/rOLFc642UN4xo",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.key.as_deref(), Some("crypt"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "the crypt alphabet starts with a dot"
+        );
+    }
+
+    #[test]
+    fn base64_custom_does_not_try_imap_on_standard_base64() {
+        let decoder = Decoder::<Base64CustomDecoder>::new();
+        let result = decoder.crack("aGVsbG8gd29ybGQ=", &get_athena_checker());
+        assert_ne!(result.key.as_deref(), Some("IMAP"));
+        if let Some(decoded_strings) = result.unencrypted_text {
+            assert!(!decoded_strings.contains(&"hello world".to_string()));
+        }
+    }
+
+    #[test]
+    fn base64_custom_handles_panics() {
+        let decoder = Decoder::<Base64CustomDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base64_custom_handles_panic_if_empty_string() {
+        let decoder = Decoder::<Base64CustomDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base64_custom_handles_panic_if_emoji() {
+        let decoder = Decoder::<Base64CustomDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -1,15 +1,19 @@
-///! Decode a base64 string
-///! Performs error handling and returns a string
-///! Call base64_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
+//! Decode a base64 string
+//! Performs error handling and returns a string
+//! Call base64_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! Lines which were padded on their own, as in email bodies and PEM files, are decoded one at a time.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_string_success, normalize_base_input,
+};
 use base64::{engine::general_purpose, Engine as _};
 
 use super::crack_results::CrackResult;
+use super::interface::only_uses_alphabet;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
@@ -35,30 +39,39 @@ pub struct Base64Decoder;
 
 impl Crack for Decoder<Base64Decoder> {
     fn new() -> Decoder<Base64Decoder> {
-        Decoder {
-            name: "Base64",
-            description: "Base64 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-64 representation.",
-            link: "https://en.wikipedia.org/wiki/Base64",
-            tags: vec!["base64", "decoder", "base"],
-            popularity: 1.0,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Base64")
+            .with_description("Base64 is a group of binary-to-text encoding schemes that represent binary data (more specifically, a sequence of 8-bit bytes) in an ASCII string format by translating the data into a radix-64 representation.")
+            .with_link("https://en.wikipedia.org/wiki/Base64")
+            .with_tags(&["base64", "decoder", "base"])
+            .with_popularity(1.0)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Base64 with text {:?}", text);
-        let decoded_text = decode_base64_no_error_handling(text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let decoded_bytes = decode_base64_no_error_handling(text);
+        let mut results = CrackResult::new(self);
 
-        if decoded_text.is_none() {
+        if decoded_bytes.is_none() {
             debug!("Failed to decode base64 because Base64Decoder::decode_base64_no_error_handling returned None");
             return results;
         }
 
-        let decoded_text = decoded_text.unwrap();
+        let decoded_bytes = decoded_bytes.unwrap();
+        let decoded_text = match bytes_to_candidate_string(&decoded_bytes) {
+            Some(decoded_text) => decoded_text,
+            None => {
+                // This isn't text, but it may be a file, or a decoder which works on bytes might make sense of it
+                debug!("Base64 decoded to binary, passing on the bytes");
+                let checker_result = checker.check_bytes(&decoded_bytes);
+                results = results.with_bytes_candidate(decoded_bytes);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
         if !check_string_success(&decoded_text, text) {
             info!(
                 "Failed to decode base64 because check_string_success returned false on string {}",
@@ -68,24 +81,66 @@ impl Crack for Decoder<Base64Decoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// Base64 is letters, digits, '+', '/' and padding.
+    /// PEM armor lines can have anything in them, so text with them is always attempted.
+    fn can_attempt(&self, text: &str) -> bool {
+        text.contains("-----")
+            || only_uses_alphabet(
+                text,
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=",
+            )
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes the text as padded base64
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(general_purpose::STANDARD.encode(text))
     }
 }
 
 /// helper function
-fn decode_base64_no_error_handling(text: &str) -> Option<String> {
+fn decode_base64_no_error_handling(text: &str) -> Option<Vec<u8>> {
+    let lines = base64_lines(text);
+    // Email bodies and PEM files can pad every line on its own,
+    // and padding in the middle of one blob would throw the bits out of line
+    let bytes = if lines
+        .split_last()
+        .is_some_and(|(_, first_lines)| first_lines.iter().any(|line| line.ends_with('=')))
+    {
+        lines
+            .iter()
+            .map(|line| decode_base64_bytes(line))
+            .collect::<Option<Vec<Vec<u8>>>>()?
+            .concat()
+    } else {
+        decode_base64_bytes(&lines.concat())?
+    };
+    Some(bytes)
+}
+
+/// The lines of the text with any whitespace and PEM armor lines such as
+/// `-----BEGIN CERTIFICATE-----` removed
+fn base64_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !is_pem_armor(line))
+        .map(normalize_base_input)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Whether the line is the `-----BEGIN ...-----` or `-----END ...-----` line around a PEM blob
+fn is_pem_armor(line: &str) -> bool {
+    (line.starts_with("-----BEGIN ") || line.starts_with("-----END ")) && line.ends_with("-----")
+}
+
+/// Decodes one run of base64, ignoring its padding
+fn decode_base64_bytes(text: &str) -> Option<Vec<u8>> {
     // Strip all padding
     let text = text.replace('=', "");
     // Runs the code to decode base64
@@ -93,7 +148,6 @@ fn decode_base64_no_error_handling(text: &str) -> Option<String> {
     general_purpose::STANDARD_NO_PAD
         .decode(text.as_bytes())
         .ok()
-        .map(|inner| String::from_utf8(inner).ok())?
 }
 
 #[cfg(test)]
@@ -114,6 +168,90 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn base64_encode_round_trips() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
+    #[test]
+    fn base64_decodes_wrapped_email_body() {
+        // MIME wraps base64 at 76 characters with CRLF line breaks
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack(
+            "SGkgdGVhbSwgcGxlYXNlIGZpbmQgYXR0YWNoZWQgdGhlIG1pbnV0ZXMgZnJvbSB0aGlzIG1vcm5p\r\n\
+             bmcgbWVldGluZy4gV2UgYWdyZWVkIHRvIHNoaXAgdGhlIG5ldyByZWxlYXNlIG9uIEZyaWRheSBh\r\n\
+             ZnRlciB0aGUgZmluYWwgcm91bmQgb2YgdGVzdGluZy4=\r\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Hi team, please find attached the minutes from this morning meeting. We agreed to ship the new release on Friday after the final round of testing."
+        );
+    }
+
+    #[test]
+    fn base64_decodes_lines_padded_separately() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack(
+            "Zmlyc3QgbGluZSBvZiB0aGUgbWVzc2FnZQ==\nLCBhbmQgdGhlIHNlY29uZCBvbmU=\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "first line of the message, and the second one"
+        );
+    }
+
+    #[test]
+    fn base64_decodes_pem_armored_blob() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack(
+            "-----BEGIN MESSAGE-----\n\
+             VGhpcyBQRU0gYmxvY2sgb25seSBob2xkcyBhIHNob3J0IHRleHQgbWVzc2FnZSBmb3Ig\n\
+             dGVzdGluZy4=\n\
+             -----END MESSAGE-----\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "This PEM block only holds a short text message for testing."
+        );
+    }
+
+    #[test]
+    fn base64_with_one_invalid_line_fails() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder
+            .crack(
+                "Zmlyc3QgbGluZSBvZiB0aGUgbWVzc2FnZQ==\nthis line is not base64!\nLCBhbmQgdGhlIHNlY29uZCBvbmU=",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn base64_single_line_with_padding_decodes_as_before() {
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let result = base64_decoder.crack(
+            "Zmlyc3QgbGluZSBvZiB0aGUgbWVzc2FnZQ==",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "first line of the message"
+        );
+    }
+
     #[test]
     fn successful_decoding() {
         let base64_decoder = Decoder::<Base64Decoder>::new();
@@ -192,4 +330,15 @@ mod tests {
             assert_eq!(true, true);
         }
     }
+
+    #[test]
+    fn base64_only_attempts_its_alphabet() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        assert!(!decoder.can_attempt("Hello, world! This isn't base64."));
+        assert!(decoder.can_attempt("aGVsbG8g\r\nd29ybGQ=\n"));
+        // The armor around a PEM blob is stripped before decoding
+        assert!(
+            decoder.can_attempt("-----BEGIN MESSAGE-----\naGVsbG8gd29ybGQ=\n-----END MESSAGE-----")
+        );
+    }
 }
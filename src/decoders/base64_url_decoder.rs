@@ -1,15 +1,18 @@
-///! Decode a base64_url string
-///! Performs error handling and returns a string
-///! Call base64_url_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
+//! Decode a base64_url string
+//! Performs error handling and returns a string
+//! Call base64_url_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_string_success, normalize_base_input,
+};
 use base64::{engine::general_purpose, Engine as _};
 
 use super::crack_results::CrackResult;
+use super::interface::only_uses_alphabet;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
@@ -35,23 +38,21 @@ pub struct Base64URLDecoder;
 
 impl Crack for Decoder<Base64URLDecoder> {
     fn new() -> Decoder<Base64URLDecoder> {
-        Decoder {
-            name: "Base64 URL",
-            description: "Modified Base64 for URL variants exist (such as base64url in RFC 4648), where the '+' and '/' characters of standard Base64 are respectively replaced by '-' and '_', so that using URL encoders/decoders is no longer necessary.",
-            link: "https://en.wikipedia.org/wiki/Base64#URL_applications",
-            tags: vec!["base64_url", "base64", "url", "decoder", "base"],
-            popularity: 0.9,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Base64 URL")
+            .with_description("Modified Base64 for URL variants exist (such as base64url in RFC 4648), where the '+' and '/' characters of standard Base64 are respectively replaced by '-' and '_', so that using URL encoders/decoders is no longer necessary.")
+            .with_link("https://en.wikipedia.org/wiki/Base64#URL_applications")
+            .with_tags(&["base64_url", "base64", "url", "decoder", "base"])
+            .with_popularity(0.9)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying base64_url with text {:?}", text);
         let decoded_text = decode_base64_url_no_error_handling(text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode base64_url because Base64URLDecoder::decode_base64_url_no_error_handling returned None");
@@ -68,32 +69,41 @@ impl Crack for Decoder<Base64URLDecoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// URL safe base64 is letters, digits, '-', '_' and padding, with at least one '-' or '_'
+    fn can_attempt(&self, text: &str) -> bool {
+        text.contains(['-', '_'])
+            && only_uses_alphabet(
+                text,
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_=",
+            )
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes the text as padded URL safe base64
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(general_purpose::URL_SAFE.encode(text))
     }
 }
 
 /// helper function
 fn decode_base64_url_no_error_handling(text: &str) -> Option<String> {
-    // Strip all padding
-    let text = text.replace('=', "");
+    // Without '-' or '_' this is standard base64, which the Base64 decoder already handles
+    if !text.contains(['-', '_']) {
+        return None;
+    }
+    // Strip all padding and any whitespace from wrapping
+    let text = normalize_base_input(text).replace('=', "");
     // Runs the code to decode base64_url
     // Doesn't perform error handling, call from_base64_url
     general_purpose::URL_SAFE_NO_PAD
         .decode(text.as_bytes())
         .ok()
-        .map(|inner| String::from_utf8(inner).ok())?
+        .and_then(|inner| bytes_to_candidate_string(&inner))
 }
 
 #[cfg(test)]
@@ -114,6 +124,16 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn base64_url_encode_round_trips() {
+        let decoder = Decoder::<Base64URLDecoder>::new();
+        for text in ["???>>>", "subjects?_d=1&x=~~~ >>>"] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn base64_url_decodes_successfully() {
         // This tests if Base64 URL can decode Base64 URL successfully
@@ -130,20 +150,32 @@ mod tests {
     }
 
     #[test]
-    fn base64_url_decodes_regular_base64_successfully() {
-        // This tests if Base64 URL can decode regular Base64 successfully
-        // Base64 URL can decode Base64 strings if they don't have "+" or "/" in them
+    fn base64_url_decodes_dashes_and_underscores() {
         let base64_url_decoder = Decoder::<Base64URLDecoder>::new();
         let result = base64_url_decoder.crack(
-            "VGhpcyBpcyBkZWNvZGFibGUgYnkgYm90aCBCYXNlNjQgYW5kIEJhc2U2NCBVUkw=",
+            "aGVsbG8gd29ybGQsIGRvIHlvdSBsaWtlIHN1YnNjcmlwdHM_IH5-fiA-Pj4",
             &get_athena_checker(),
         );
         assert_eq!(
             result.unencrypted_text.unwrap()[0],
-            "This is decodable by both Base64 and Base64 URL"
+            "hello world, do you like subscripts? ~~~ >>>"
         );
     }
 
+    #[test]
+    fn base64_url_leaves_regular_base64_to_base64_decoder() {
+        // Regular Base64 without "+" or "/" is valid Base64 URL too,
+        // but only the Base64 decoder should claim it
+        let base64_url_decoder = Decoder::<Base64URLDecoder>::new();
+        let result = base64_url_decoder
+            .crack(
+                "VGhpcyBpcyBkZWNvZGFibGUgYnkgYm90aCBCYXNlNjQgYW5kIEJhc2U2NCBVUkw=",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
     #[test]
     fn base64_url_handles_regular_base64_with_plus_signs() {
         // This tests if Base64 URL can handle regular Base64 with plus signs
@@ -209,4 +241,12 @@ mod tests {
             .unencrypted_text;
         assert!(result.is_none());
     }
+
+    #[test]
+    fn base64_url_only_attempts_its_alphabet() {
+        let decoder = Decoder::<Base64URLDecoder>::new();
+        assert!(!decoder.can_attempt("aGVsbG8gd29ybGQ="));
+        assert!(!decoder.can_attempt("well-known text, with punctuation"));
+        assert!(decoder.can_attempt("aGk_dGhlcmU-\nPz8="));
+    }
 }
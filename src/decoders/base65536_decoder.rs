@@ -1,14 +1,14 @@
-///! Decode a base65536 string
-///! Performs error handling and returns a string
-///! Call base65536_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
+//! Decode a base65536 string
+//! Performs error handling and returns a string
+//! Call base65536_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{bytes_to_candidate_string, check_string_success};
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
@@ -34,25 +34,23 @@ pub struct Base65536Decoder;
 
 impl Crack for Decoder<Base65536Decoder> {
     fn new() -> Decoder<Base65536Decoder> {
-        Decoder {
-            name: "Base65536",
-            description: "Base65536 is a binary encoding optimised for UTF-32-encoded text. Base65536 uses only \"safe\" Unicode code points - no unassigned code points, no whitespace, no control characters, etc.",
-            link: "https://github.com/qntm/base65536",
-            tags: vec!["base65536", "decoder", "base"],
-            popularity: 0.1,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Base65536")
+            .with_description("Base65536 is a binary encoding optimised for UTF-32-encoded text. Base65536 uses only \"safe\" Unicode code points - no unassigned code points, no whitespace, no control characters, etc.")
+            .with_link("https://github.com/qntm/base65536")
+            .with_tags(&["base65536", "decoder", "base"])
+            .with_popularity(0.1)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying base65536 with text {:?}", text);
         let decoded_text: Option<String> = decode_base65536_no_error_handling(text);
 
         trace!("Decoded text for base65536: {:?}", decoded_text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode base65536 because Base65536Decoder::decode_base65536_no_error_handling returned None");
@@ -69,19 +67,16 @@ impl Crack for Decoder<Base65536Decoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Encodes the text as base65536
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(base65536::encode(text.as_bytes(), None))
     }
 }
 
@@ -90,7 +85,7 @@ fn decode_base65536_no_error_handling(text: &str) -> Option<String> {
     // Runs the code to decode base65536
     // Doesn't perform error handling, call from_base65536
     if let Ok(decoded_text) = base65536::decode(text, false) {
-        return Some(String::from_utf8_lossy(&decoded_text).to_string());
+        return bytes_to_candidate_string(&decoded_text);
     }
     None
 }
@@ -113,6 +108,20 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn base65536_encode_round_trips() {
+        let decoder = Decoder::<Base65536Decoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn base65536_decodes_successfully() {
         // This tests if Base65536 can decode Base65536 successfully
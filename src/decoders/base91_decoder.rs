@@ -1,14 +1,14 @@
+//! Decodes a base91 string
+//! Performs error handling and returns a string
+//! Call base91_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{bytes_to_candidate_string, check_string_success};
 
 use super::crack_results::CrackResult;
-///! Decodes a base91 string
-///! Performs error handling and returns a string
-///! Call base91_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
@@ -34,23 +34,21 @@ pub struct Base91Decoder;
 
 impl Crack for Decoder<Base91Decoder> {
     fn new() -> Decoder<Base91Decoder> {
-        Decoder {
-            name: "Base91",
-            description: "basE91 is an advanced method for encoding binary data as ASCII characters. It is similar to UUencode or base64, but is more efficient.",
-            link: "https://base91.sourceforge.net/",
-            tags: vec!["base91", "decoder", "base"],
-            popularity: 0.3,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Base91")
+            .with_description("basE91 is an advanced method for encoding binary data as ASCII characters. It is similar to UUencode or base64, but is more efficient.")
+            .with_link("https://base91.sourceforge.net/")
+            .with_tags(&["base91", "decoder", "base"])
+            .with_popularity(0.3)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Base91 with text {:?}", text);
         let decoded_text = decode_base91_no_error_handling(text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode base91 because Base91Decoder::decode_base91_no_error_handling returned None");
@@ -67,19 +65,16 @@ impl Crack for Decoder<Base91Decoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Encodes the text as base91
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(String::from_utf8_lossy(&base91::slice_encode(text.as_bytes())).to_string())
     }
 }
 
@@ -88,7 +83,7 @@ fn decode_base91_no_error_handling(text: &str) -> Option<String> {
     // Runs the code to decode base91
     // Doesn't perform error handling, call from_base91
     let decoded_text = base91::slice_decode(text.as_bytes());
-    return Some(String::from_utf8_lossy(&decoded_text).to_string());
+    bytes_to_candidate_string(&decoded_text)
 }
 
 #[cfg(test)]
@@ -109,6 +104,20 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn base91_encode_round_trips() {
+        let decoder = Decoder::<Base91Decoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn successful_decoding() {
         let base91_decoder = Decoder::<Base91Decoder>::new();
@@ -0,0 +1,341 @@
+//! Decode a bech32 or bech32m string
+//! Performs error handling and returns a string
+//! Call bech32_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! A valid checksum is strong evidence on its own, so the result is marked as
+//! a success even when the payload isn't text the checkers recognise.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The 32 characters of the data part, in order of their value
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The checksum is 6 characters long
+const BECH32_CHECKSUM_LENGTH: usize = 6;
+
+/// The generator of the BCH code used for the checksum
+const BECH32_GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// What the checksum of a bech32 string works out as
+const BECH32_CONSTANT: u32 = 1;
+
+/// What the checksum of a bech32m string works out as
+const BECH32M_CONSTANT: u32 = 0x2bc830a3;
+
+/// Human readable parts of Bitcoin segwit addresses, whose first group is the witness version
+const SEGWIT_HRPS: [&str; 3] = ["bc", "tb", "bcrt"];
+
+/// The Bech32 decoder, call:
+/// `let bech32_decoder = Decoder::<Bech32Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = bech32_decoder.crack(input)` to decode a bech32 string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::bech32_decoder::Bech32Decoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_bech32 = Decoder::<Bech32Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_bech32.crack("ares1dpjkcmr0ypmk7unvvs0qfyy2", &checker);
+/// assert_eq!(result.key.as_deref(), Some("bech32m, hrp=ares"));
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct Bech32Decoder;
+
+impl Crack for Decoder<Bech32Decoder> {
+    fn new() -> Decoder<Bech32Decoder> {
+        DecoderBuilder::new("Bech32")
+            .with_description("Bech32 and its successor Bech32m encode data as a human readable part, the separator 1 and a data part written with 32 characters, ending in a 6 character BCH checksum. They are used for Bitcoin segwit addresses and Lightning invoices.")
+            .with_link("https://en.bitcoin.it/wiki/BIP_0173")
+            .with_tags(&["bech32", "bitcoin", "decoder", "base"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying bech32 with text {:?}", text);
+        let decoded = decode_bech32_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded.is_none() {
+            debug!("Failed to decode bech32 because Bech32Decoder::decode_bech32_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded = decoded.unwrap();
+        let decoded_text = decoded.to_text();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode bech32 because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.set_key(format!("{}, hrp={}", decoded.variant, decoded.hrp));
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+        if !results.success {
+            // The checksum already tells us this is bech32
+            results.success = true;
+            results.checker_name = "Bech32 checksum";
+            results.checker_description =
+                "The checksum of the bech32 string was valid, so it is almost certainly bech32";
+        }
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// A bech32 string with a valid checksum
+struct Bech32Data {
+    /// Either "bech32" or "bech32m", depending on the checksum constant
+    variant: &'static str,
+    /// The human readable part before the last 1
+    hrp: String,
+    /// The witness version of segwit addresses
+    witness_version: Option<u8>,
+    /// The data part converted back to bytes
+    payload: Vec<u8>,
+}
+
+impl Bech32Data {
+    /// The payload if it is printable ASCII, otherwise a summary of what was found
+    fn to_text(&self) -> String {
+        if !self.payload.is_empty()
+            && self
+                .payload
+                .iter()
+                .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+        {
+            return String::from_utf8_lossy(&self.payload).to_string();
+        }
+
+        let hex: String = self
+            .payload
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        match self.witness_version {
+            Some(version) => format!(
+                "{}, hrp={}, witness version {}, {}-byte payload {}",
+                self.variant,
+                self.hrp,
+                version,
+                self.payload.len(),
+                hex
+            ),
+            None => format!(
+                "{}, hrp={}, {}-byte payload {}",
+                self.variant,
+                self.hrp,
+                self.payload.len(),
+                hex
+            ),
+        }
+    }
+}
+
+/// helper function
+/// Returns None if the string isn't bech32 or its checksum is wrong.
+fn decode_bech32_no_error_handling(text: &str) -> Option<Bech32Data> {
+    let text = text.trim();
+    // Mixed case is not allowed
+    if text.chars().any(|c| c.is_ascii_lowercase()) && text.chars().any(|c| c.is_ascii_uppercase())
+    {
+        return None;
+    }
+    let text = text.to_ascii_lowercase();
+
+    let separator = text.rfind('1')?;
+    let (hrp, data) = (&text[..separator], &text[separator + 1..]);
+    if hrp.is_empty()
+        || !hrp.bytes().all(|byte| (33..=126).contains(&byte))
+        || data.len() < BECH32_CHECKSUM_LENGTH
+    {
+        return None;
+    }
+
+    let values = data
+        .bytes()
+        .map(|byte| {
+            BECH32_CHARSET
+                .iter()
+                .position(|&c| c == byte)
+                .map(|value| value as u8)
+        })
+        .collect::<Option<Vec<u8>>>()?;
+
+    let mut checked: Vec<u8> = hrp.bytes().map(|byte| byte >> 5).collect();
+    checked.push(0);
+    checked.extend(hrp.bytes().map(|byte| byte & 31));
+    checked.extend(&values);
+    let variant = match polymod(&checked) {
+        BECH32_CONSTANT => "bech32",
+        BECH32M_CONSTANT => "bech32m",
+        _ => return None,
+    };
+
+    let groups = &values[..values.len() - BECH32_CHECKSUM_LENGTH];
+    let (witness_version, groups) = match groups.split_first() {
+        Some((&version, program)) if SEGWIT_HRPS.contains(&hrp) => (Some(version), program),
+        _ => (None, groups),
+    };
+
+    Some(Bech32Data {
+        variant,
+        hrp: hrp.to_string(),
+        witness_version,
+        payload: five_bit_groups_to_bytes(groups),
+    })
+}
+
+/// The BCH checksum from BIP 173
+fn polymod(values: &[u8]) -> u32 {
+    values.iter().fold(1, |checksum, &value| {
+        let top = checksum >> 25;
+        let mut checksum = ((checksum & 0x1ffffff) << 5) ^ u32::from(value);
+        for (index, generator) in BECH32_GENERATOR.iter().enumerate() {
+            if (top >> index) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+        checksum
+    })
+}
+
+/// Joins the 5 bit groups back into bytes, dropping the padding bits at the end
+fn five_bit_groups_to_bytes(groups: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(groups.len() * 5 / 8);
+    let mut accumulator: u32 = 0;
+    let mut bits = 0;
+    for &group in groups {
+        accumulator = (accumulator << 5) | u32::from(group);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((accumulator >> bits) as u8);
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bech32Decoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn bech32_decodes_mainnet_address() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder.crack(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("bech32, hrp=bc"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "bech32, hrp=bc, witness version 0, 20-byte payload 751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+    }
+
+    #[test]
+    fn bech32_decodes_uppercase_address() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder.crack(
+            "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("bech32, hrp=bc"));
+    }
+
+    #[test]
+    fn bech32m_decodes_printable_payload() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder.crack("ares1dpjkcmr0ypmk7unvvs0qfyy2", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("bech32m, hrp=ares"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn bech32_corrupted_checksum_fails() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder
+            .crack(
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn bech32_mixed_case_fails() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder
+            .crack(
+                "bc1qW508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn bech32_handles_panics() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn bech32_handles_panic_if_empty_string() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn bech32_handles_panic_if_emoji() {
+        let decoder = Decoder::<Bech32Decoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,293 @@
+//! Measures how long each decoder really takes, for `ares benchmark`.
+//! The `expected_runtime` and `failure_runtime` every decoder is built with are guesses,
+//! and the search uses them to pick what to decode next, so this checks them.
+//! Each decoder runs on text it can decode, made with its own `encode` when it has one,
+//! and on random text, the way the search runs it: `can_attempt` first, then `crack`.
+
+use std::time::Duration;
+
+use super::{get_all_decoders, interface::Crack};
+use crate::checkers::CheckerTypes;
+use crate::timer::Instant;
+
+/// How long the texts each decoder runs on are, in characters
+const LENGTHS: [usize; 3] = [16, 128, 1024];
+
+/// What the texts a decoder can decode are made from
+const PLAINTEXT: &str = "the quick brown fox jumps over the lazy dog and ";
+
+/// Measured runtimes this many times larger or smaller than a decoder's are worth changing it to
+const SUGGEST_FACTOR: f64 = 2.0;
+
+/// How long a decoder took on one path, when it decoded the text or when it gave up
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Timing {
+    /// How many times it was timed, after warming up
+    pub samples: usize,
+    /// How many seconds it took on average
+    pub mean_seconds: f64,
+    /// How many seconds the slowest run took
+    pub slowest_seconds: f64,
+}
+
+/// How long a decoder took, next to what it's built with
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DecoderBenchmark {
+    /// The decoder's name, like "Base64"
+    pub name: String,
+    /// The decoder's `expected_runtime`, in seconds
+    pub expected_runtime: f32,
+    /// The decoder's `failure_runtime`, in seconds
+    pub failure_runtime: f32,
+    /// How long it took to decode text, or None if it decoded none of the texts
+    pub success: Option<Timing>,
+    /// How long it took to give up on text, or None if it decoded all of them
+    pub failure: Option<Timing>,
+}
+
+/// How long every decoder took, the slowest first
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BenchmarkReport {
+    /// How many times each decoder was timed on each text
+    pub iterations: usize,
+    /// Every decoder, the slowest first
+    pub decoders: Vec<DecoderBenchmark>,
+}
+
+/// A runtime a decoder is built with which is far from what was measured
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// The decoder's name
+    pub decoder: String,
+    /// The builder method which sets it, like "with_expected_runtime"
+    pub method: &'static str,
+    /// What the decoder is built with, in seconds
+    pub current: f32,
+    /// What was measured, in seconds
+    pub measured: f32,
+}
+
+impl DecoderBenchmark {
+    /// The slowest of how long it took to decode and to give up, in seconds
+    fn slowest_mean(&self) -> f64 {
+        [&self.success, &self.failure]
+            .into_iter()
+            .flatten()
+            .map(|timing| timing.mean_seconds)
+            .fold(0.0, f64::max)
+    }
+}
+
+impl BenchmarkReport {
+    /// The runtimes decoders are built with which are more than twice as large or small as was measured
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let mut suggestions = Vec::new();
+        for decoder in &self.decoders {
+            for (method, current, timing) in [
+                (
+                    "with_expected_runtime",
+                    decoder.expected_runtime,
+                    &decoder.success,
+                ),
+                (
+                    "with_failure_runtime",
+                    decoder.failure_runtime,
+                    &decoder.failure,
+                ),
+            ] {
+                let Some(timing) = timing else {
+                    continue;
+                };
+                let ratio = timing.mean_seconds / f64::from(current).max(f64::MIN_POSITIVE);
+                if !(1.0 / SUGGEST_FACTOR..=SUGGEST_FACTOR).contains(&ratio) {
+                    suggestions.push(Suggestion {
+                        decoder: decoder.name.clone(),
+                        method,
+                        current,
+                        measured: timing.mean_seconds as f32,
+                    });
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+/// Times every decoder `iterations` times on each text, after running it once on each to warm up.
+/// ```
+/// use ares::decoders::benchmark::benchmark_decoders;
+/// use ares::decoders::get_all_decoders;
+///
+/// let report = benchmark_decoders(1);
+/// assert_eq!(report.decoders.len(), get_all_decoders().len());
+/// ```
+pub fn benchmark_decoders(iterations: usize) -> BenchmarkReport {
+    // A checker which never identifies anything, so decoders are timed rather than the checkers
+    let checker = CheckerTypes::from_closure("Null Checker", |_| None);
+    let mut decoders: Vec<DecoderBenchmark> = get_all_decoders()
+        .iter()
+        .map(|decoder| benchmark_decoder(decoder.as_ref(), &checker, iterations))
+        .collect();
+    decoders.sort_by(|a, b| b.slowest_mean().total_cmp(&a.slowest_mean()));
+    BenchmarkReport {
+        iterations,
+        decoders,
+    }
+}
+
+/// Times the decoder on every text in its corpus, see `corpus`
+fn benchmark_decoder(
+    decoder: &(dyn Crack + Send + Sync),
+    checker: &CheckerTypes,
+    iterations: usize,
+) -> DecoderBenchmark {
+    let mut successes = Vec::new();
+    let mut failures = Vec::new();
+    for text in corpus(decoder) {
+        attempt(decoder, &text, checker);
+        for _ in 0..iterations {
+            let started = Instant::now();
+            let decoded = attempt(decoder, &text, checker);
+            let taken = started.elapsed();
+            if decoded {
+                successes.push(taken);
+            } else {
+                failures.push(taken);
+            }
+        }
+    }
+    DecoderBenchmark {
+        name: decoder.get_name().to_string(),
+        expected_runtime: decoder.get_expected_runtime(),
+        failure_runtime: decoder.get_failure_runtime(),
+        success: timing(&successes),
+        failure: timing(&failures),
+    }
+}
+
+/// Runs the decoder on the text like the search does, returning whether it decoded it
+fn attempt(decoder: &(dyn Crack + Send + Sync), text: &str, checker: &CheckerTypes) -> bool {
+    // The search never runs a decoder on text it can't attempt, so that's how long giving up takes
+    if !decoder.can_attempt(text) {
+        return false;
+    }
+    let result = decoder.crack(text, checker);
+    result
+        .unencrypted_text
+        .is_some_and(|texts| !texts.is_empty())
+        || result
+            .unencrypted_bytes
+            .is_some_and(|bytes| !bytes.is_empty())
+}
+
+/// The mean and slowest of the times, or None if there are none
+fn timing(times: &[Duration]) -> Option<Timing> {
+    let total: Duration = times.iter().sum();
+    let slowest = times.iter().max()?;
+    Some(Timing {
+        samples: times.len(),
+        mean_seconds: total.as_secs_f64() / times.len() as f64,
+        slowest_seconds: slowest.as_secs_f64(),
+    })
+}
+
+/// The texts the decoder is timed on: for each of `LENGTHS`, plaintext of that length,
+/// encoded by the decoder when it can encode, and random printable ASCII of that length,
+/// which is the wrong alphabet for most decoders.
+/// Decoders which can't encode, like the ciphers which need a key, get the plaintext as it is.
+fn corpus(decoder: &(dyn Crack + Send + Sync)) -> Vec<String> {
+    let mut random = Xorshift(0x5eed_a7e5);
+    let mut texts = Vec::new();
+    for length in LENGTHS {
+        let plaintext: String = PLAINTEXT.chars().cycle().take(length).collect();
+        texts.push(decoder.encode(&plaintext).unwrap_or(plaintext));
+        texts.push((0..length).map(|_| random.printable()).collect());
+    }
+    texts
+}
+
+/// Random numbers which are the same every run, so every decoder gets the same random text
+struct Xorshift(u64);
+
+impl Xorshift {
+    /// A random printable ASCII character, from ' ' to '~'
+    fn printable(&mut self) -> char {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        char::from(b' ' + (self.0 % 95) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{benchmark_decoders, BenchmarkReport, DecoderBenchmark, Timing};
+    use crate::decoders::get_all_decoders;
+
+    #[test]
+    fn every_decoder_is_timed() {
+        let report = benchmark_decoders(1);
+        assert_eq!(report.iterations, 1);
+        let mut names: Vec<String> = report
+            .decoders
+            .iter()
+            .map(|decoder| decoder.name.clone())
+            .collect();
+        let mut all: Vec<String> = get_all_decoders()
+            .iter()
+            .map(|decoder| decoder.get_name().to_string())
+            .collect();
+        names.sort_unstable();
+        all.sort_unstable();
+        assert_eq!(names, all);
+        // Six texts, three it can decode and three of the wrong alphabet
+        let base64 = report
+            .decoders
+            .iter()
+            .find(|decoder| decoder.name == "Base64")
+            .unwrap();
+        assert_eq!(base64.success.as_ref().unwrap().samples, 3);
+        assert_eq!(base64.failure.as_ref().unwrap().samples, 3);
+    }
+
+    #[test]
+    fn giving_up_on_the_wrong_alphabet_is_timed() {
+        let report = benchmark_decoders(2);
+        let hexadecimal = report
+            .decoders
+            .iter()
+            .find(|decoder| decoder.name == "Hexadecimal")
+            .unwrap();
+        let failure = hexadecimal.failure.as_ref().unwrap();
+        assert_eq!(failure.samples, 6);
+        assert!(failure.slowest_seconds >= failure.mean_seconds);
+    }
+
+    #[test]
+    fn runtimes_far_from_what_was_measured_are_suggested() {
+        let timing = |mean_seconds| {
+            Some(Timing {
+                samples: 1,
+                mean_seconds,
+                slowest_seconds: mean_seconds,
+            })
+        };
+        let report = BenchmarkReport {
+            iterations: 1,
+            decoders: vec![DecoderBenchmark {
+                name: "Base64".to_string(),
+                expected_runtime: 0.01,
+                failure_runtime: 0.01,
+                success: timing(0.000_01),
+                failure: timing(0.015),
+            }],
+        };
+        let suggestions = report.suggestions();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].method, "with_expected_runtime");
+        assert_eq!(suggestions[0].measured, 0.000_01);
+    }
+}
@@ -1,74 +1,136 @@
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{check_candidates, check_string_success};
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
-///! Binary Decoder
+/// Binary Decoder
 pub struct BinaryDecoder;
 
+/// The characters we allow between groups of bits
+const BINARY_SEPARATORS: [char; 6] = [' ', '\n', '\r', '\t', ',', ';'];
+
 impl Crack for Decoder<BinaryDecoder> {
     fn new() -> Decoder<BinaryDecoder> {
-        Decoder {
-            name: "Binary",
-            description: "A binary code represents text, computer processor instructions, or any other data using a two-symbol system. The two-symbol system used is often \"0\" and \"1\" from the binary number system. The binary code assigns a pattern of binary digits, also known as bits, to each character, instruction, etc.",
-            link: "https://en.wikipedia.org/wiki/Binary_code",
-            tags: vec!["binary", "base", "decoder"],
-            popularity: 1.0,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Binary")
+            .with_description("A binary code represents text, computer processor instructions, or any other data using a two-symbol system. The two-symbol system used is often \"0\" and \"1\" from the binary number system. The binary code assigns a pattern of binary digits, also known as bits, to each character, instruction, etc.")
+            .with_link("https://en.wikipedia.org/wiki/Binary_code")
+            .with_tags(&["binary", "base", "decoder"])
+            .with_popularity(1.0)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying binary with text {:?}", text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
         let mut decoded_strings = Vec::new();
+        // The bit length each of the decoded strings was decoded with
+        let mut shifts = Vec::new();
 
-        for shift in 1..25 {
-            let decoded_text = binary_to_string(text, shift);
+        let bit_lengths = match bit_lengths_to_try(text) {
+            Some(bit_lengths) => bit_lengths,
+            None => {
+                debug!("Failed to decode binary because the text is not a bit string");
+                return results;
+            }
+        };
 
-            let decoded_text = decoded_text;
-            decoded_strings.push(decoded_text);
-            let borrowed_decoded_text = &decoded_strings[decoded_strings.len() - 1];
-            if !check_string_success(borrowed_decoded_text, text) {
+        for shift in bit_lengths {
+            let decoded_text = binary_to_string(text, shift);
+            // Most byte lengths decode to garbage, so skip them rather than giving up
+            if !check_string_success(&decoded_text, text) {
                 debug!(
-                    "Failed to decode binary because binary returned false on string {}. This means the string is 'funny' as it wasn't modified.",
-                    borrowed_decoded_text
+                    "Skipping binary bit {} because check_string_success returned false on string {}",
+                    shift, decoded_text
                 );
-                return results;
+                continue;
             }
-            let checker_result = checker.check(borrowed_decoded_text);
+
+            shifts.push(shift);
+            decoded_strings.push(decoded_text);
+        }
+
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
             // If checkers return true, exit early with the correct result
-            if checker_result.is_identified {
-                info!("Found a match with binary bit {}", shift);
-                results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
-                results.update_checker(&checker_result);
-                return results;
+            if results.success {
+                info!("Found a match with binary bit {}", shifts[index]);
+                return results.with_candidate(decoded_strings.swap_remove(index));
             }
+            // Otherwise the one the checker came closest to identifying goes first
+            decoded_strings[..=index].rotate_right(1);
         }
-        results.unencrypted_text = Some(decoded_strings);
+        results = results.with_candidates(decoded_strings);
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// Binary is only 0s and 1s, and the separators between bytes
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars()
+            .all(|c| c == '0' || c == '1' || BINARY_SEPARATORS.contains(&c))
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes every byte of the text as 8 bits, separated by spaces
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(
+            text.bytes()
+                .map(|byte| format!("{byte:08b}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
     }
 }
 
+/// Returns the byte lengths worth trying for the given bit string, most likely first
+/// Returns None if the text contains anything other than 0, 1 and separators,
+/// or if there are not enough bits to make a single byte.
+fn bit_lengths_to_try(text: &str) -> Option<Vec<u8>> {
+    if !text
+        .chars()
+        .all(|c| c == '0' || c == '1' || BINARY_SEPARATORS.contains(&c))
+    {
+        return None;
+    }
+    let total_bits = text.chars().filter(|c| *c == '0' || *c == '1').count();
+    if total_bits < 8 {
+        return None;
+    }
+
+    let mut bit_lengths = Vec::new();
+    // If the user split the bits into equal groups, the group length is the byte length
+    let mut groups = text
+        .split(BINARY_SEPARATORS)
+        .filter(|group| !group.is_empty());
+    if let Some(first_group) = groups.next() {
+        let group_length = first_group.len();
+        if group_length < 25 && groups.all(|group| group.len() == group_length) {
+            bit_lengths.push(group_length as u8);
+        }
+    }
+    // 8-bit bytes are the most common, but 7-bit ASCII is used when the
+    // length is divisible by 7 and not by 8
+    if total_bits % 8 == 0 {
+        bit_lengths.push(8);
+    } else if total_bits % 7 == 0 {
+        bit_lengths.push(7);
+    }
+    for bit_length in 1..25 {
+        if !bit_lengths.contains(&bit_length) {
+            bit_lengths.push(bit_length);
+        }
+    }
+    Some(bit_lengths)
+}
+
 /// Decodes binary to string
 /// bit is the byte length
 fn binary_to_string(binary: &str, bit: u8) -> String {
-    let mut out = String::new();
+    let mut out = Vec::new();
     let mut iter = binary.as_bytes().iter().filter_map(|byte| match byte {
         b'0' => Some(0),
         b'1' => Some(1),
@@ -80,11 +142,13 @@ fn binary_to_string(binary: &str, bit: u8) -> String {
             .take(usize::from(bit))
             .reduce(|acc, elem| (acc << 1) | elem);
         match byte {
-            Some(byte) => out.push(char::from(byte)),
+            Some(byte) => out.push(byte),
             None => break,
         }
     }
-    out
+    // Multi-byte characters are kept together, anything else is read one byte per character
+    String::from_utf8(out)
+        .unwrap_or_else(|err| err.into_bytes().into_iter().map(char::from).collect())
 }
 
 #[cfg(test)]
@@ -105,6 +169,20 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn binary_encode_round_trips() {
+        let decoder = Decoder::<BinaryDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert!(result.unencrypted_text.unwrap().contains(&text.to_string()));
+        }
+    }
+
     #[test]
     fn binary_bit_7_decodes_successfully() {
         // This tests if Binary can decode Binary bit 7 successfully
@@ -136,11 +214,44 @@ mod tests {
     }
 
     #[test]
-    fn binary_bit_15_with_a_lot_of_delimiters_decodes_successfully() {
-        // This tests if Binary can decode Binary bit 15 with a lot of delimiters successfully
+    fn binary_bit_15_with_a_lot_of_delimiters_fails() {
+        // Only 0, 1 and common separators are accepted.
+        // Arbitrary delimiters and other digits mean this is not a bit string.
         let decoder = Decoder::<BinaryDecoder>::new();
         let result = decoder.crack(r"000+00\0001\010||100;00[000]00{}011'010'00;0'000:000:0110:10;01;0.00.000.001.11.00.11;000 ,000,000,1 00,000;0$00 0$000 0$1101$001;0!00 !00000!1 1100!1 1;000`000`000`100~000;00~000-00=0110_0011;00\\000\00\/011/0111/1 ;00?000<>000}110{11150;09008goodluck003005011h10110;00,00m00b0011f0s11f11;0h00j0r00c001t1011*00;00* 000%00011#101301;0070040 08001-1101=00;000_0 0.0001,100 .101;00090006 00113001 ~00;00d00-0 000-0101=110", &get_athena_checker());
-        assert_eq!(result.unencrypted_text.unwrap()[0], "This is convoluted.");
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn binary_spaced_bit_8_decodes_successfully() {
+        let decoder = Decoder::<BinaryDecoder>::new();
+        let result = decoder.crack(
+            "01101000 01100101 01101100 01101100 01101111",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn binary_with_stray_2_fails() {
+        // A decimal digit other than 0 or 1 means this is not binary
+        let decoder = Decoder::<BinaryDecoder>::new();
+        let result = decoder
+            .crack(
+                "01101000 01100101 01101120 01101100 01101111",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn binary_shorter_than_a_byte_fails() {
+        let decoder = Decoder::<BinaryDecoder>::new();
+        let result = decoder
+            .crack("0110", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
     }
 
     #[test]
@@ -178,4 +289,12 @@ mod tests {
             .unencrypted_text;
         assert!(result.is_none());
     }
+
+    #[test]
+    fn binary_only_attempts_bits() {
+        let decoder = Decoder::<BinaryDecoder>::new();
+        assert!(!decoder.can_attempt("01101000 2"));
+        assert!(!decoder.can_attempt("to be or not to be"));
+        assert!(decoder.can_attempt("01101000,01101001;\r\n\t01101010"));
+    }
 }
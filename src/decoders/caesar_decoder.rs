@@ -1,14 +1,14 @@
-///! Decode a caesar cipher string
-///! Performs error handling and returns a string
-///! Call caesar_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
+//! Decode a caesar cipher string
+//! Performs error handling and returns a string
+//! Call caesar_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{check_candidates, check_string_success};
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{info, trace};
 
@@ -28,78 +28,101 @@ use log::{info, trace};
 ///
 /// let result = decode_caesar.crack("uryyb guvf vf ybat grkg", &checker).unencrypted_text;
 /// assert!(result.is_some());
-/// // If it succeeds, the 0th element is the plaintext else it'll contain 25 elements
-/// // of unsuccessfully decoded text
-/// assert_eq!(result.unwrap()[0], "hello this is long text");
+/// // It always contains all 25 shifts, if one succeeds it is the 0th element
+/// let result = result.unwrap();
+/// assert_eq!(result.len(), 25);
+/// assert_eq!(result[0], "hello this is long text");
 /// ```
 pub struct CaesarDecoder;
 
 impl Crack for Decoder<CaesarDecoder> {
     fn new() -> Decoder<CaesarDecoder> {
-        Decoder {
-            name: "Caesar Cipher",
-            description: "Caesar cipher, also known as Caesar's cipher, the shift cipher, Caesar's code or Caesar shift, is one of the simplest and most widely known encryption techniques. It is a type of substitution cipher in which each letter in the plaintext is replaced by a letter some fixed number of positions down the alphabet.",
-            link: "https://en.wikipedia.org/wiki/Caesar_cipher",
-            tags: vec!["caesar", "decryption", "classic", "reciprocal"],
-            popularity: 1.0,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Caesar Cipher")
+            .with_description("Caesar cipher, also known as Caesar's cipher, the shift cipher, Caesar's code or Caesar shift, is one of the simplest and most widely known encryption techniques. It is a type of substitution cipher in which each letter in the plaintext is replaced by a letter some fixed number of positions down the alphabet.")
+            .with_link("https://en.wikipedia.org/wiki/Caesar_cipher")
+            .with_tags(&["caesar", "rot13", "decryption", "classic", "classical", "reciprocal"])
+            .with_popularity(1.0)
+            .with_expected_runtime(0.02)
+            .with_failure_runtime(0.05)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Caesar Cipher with text {:?}", text);
-        let mut results = CrackResult::new(self, text.to_string());
-        let mut decoded_strings = Vec::new();
-        for shift in 1..25 {
-            let decoded_text = caesar(text, shift);
-            decoded_strings.push(decoded_text);
-            let borrowed_decoded_text = &decoded_strings[decoded_strings.len() - 1];
-            if !check_string_success(borrowed_decoded_text, text) {
-                info!(
-                    "Failed to decode caesar because check_string_success returned false on string {}. This means the string is 'funny' as it wasn't modified.",
-                    borrowed_decoded_text
-                );
-                return results;
-            }
-            let checker_result = checker.check(borrowed_decoded_text);
-            // If checkers return true, exit early with the correct result
-            if checker_result.is_identified {
+        let mut results = CrackResult::new(self);
+
+        if !is_mostly_letters(text) {
+            info!(
+                "Not trying caesar on {} because less than half of it is letters",
+                text
+            );
+            return results;
+        }
+
+        // decoded_strings[i] is the text encrypted with a shift of i + 1
+        let mut decoded_strings: Vec<String> =
+            (1..26).map(|shift| caesar(text, 26 - shift)).collect();
+        if !decoded_strings
+            .iter()
+            .all(|decoded_text| check_string_success(decoded_text, text))
+        {
+            info!(
+                "Failed to decode caesar because check_string_success returned false on {}. This means the string is 'funny' as it wasn't modified.",
+                text
+            );
+            return results;
+        }
+
+        // The shift the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            if results.success {
+                let shift = index + 1;
                 trace!("Found a match with caesar shift {}", shift);
-                results.unencrypted_text = Some(vec![borrowed_decoded_text.to_string()]);
-                results.update_checker(&checker_result);
-                return results;
+                results.set_key(format!("shift {shift}"));
             }
+            decoded_strings[..=index].rotate_right(1);
         }
-        results.unencrypted_text = Some(decoded_strings);
+
+        results = results.with_candidates(decoded_strings);
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Encodes the text with ROT13, the shift people use most.
+    /// Cracking tries every shift, so it decodes whichever shift was used.
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(caesar(text, 13))
     }
 }
 
-///! Caesar cipher to rotate cipher text by shift and return an owned String.
-fn caesar(cipher: &str, shift: u8) -> String {
-    cipher
+/// Caesar is only worth trying if at least half of the non-whitespace characters are letters.
+/// Otherwise we'd make 25 pointless checker calls for things like numbers.
+fn is_mostly_letters(text: &str) -> bool {
+    let (letters, total) = text
         .chars()
-        .map(|c| {
-            if c.is_ascii_alphabetic() {
-                let first = if c.is_ascii_lowercase() { b'a' } else { b'A' };
-                // modulo the distance to keep character range
-                (first + (c as u8 + shift - first) % 26) as char
-            } else {
-                c
-            }
-        })
-        .collect()
+        .filter(|c| !c.is_whitespace())
+        .fold((0, 0), |(letters, total), c| {
+            (letters + usize::from(c.is_ascii_alphabetic()), total + 1)
+        });
+    total > 0 && letters * 2 >= total
+}
+
+/// Caesar cipher to rotate cipher text by shift and return an owned String.
+fn caesar(cipher: &str, shift: u8) -> String {
+    let mut shifted = String::with_capacity(cipher.len());
+    shifted.extend(cipher.chars().map(|c| {
+        if c.is_ascii_alphabetic() {
+            let first = if c.is_ascii_lowercase() { b'a' } else { b'A' };
+            // modulo the distance to keep character range
+            (first + (c as u8 + shift - first) % 26) as char
+        } else {
+            c
+        }
+    }));
+    shifted
 }
 
 #[cfg(test)]
@@ -147,6 +170,27 @@ mod tests {
         assert_eq!(decoded_str[0], "attack");
     }
 
+    #[test]
+    fn the_shift_closest_to_english_goes_first() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+
+        // ROT13 of "the gate at dawn" with noise after it, which isn't quite English
+        let result = caesar_decoder.crack(
+            "gur tngr ng qnja dkmi xjcw oaeg yzmd ijkx mmdg",
+            &get_athena_checker(),
+        );
+        assert!(!result.success);
+        assert!(result.checker_confidence > 0.5);
+        let decoded_str = &result
+            .unencrypted_text
+            .expect("No unencrypted text for caesar");
+        assert_eq!(
+            decoded_str[0],
+            "the gate at dawn qxzv kwpj bnrt lmzq vwxk zzqt"
+        );
+        assert_eq!(decoded_str.len(), 25);
+    }
+
     #[test]
     fn successful_decoding_longer_text() {
         let caesar_decoder = Decoder::<CaesarDecoder>::new();
@@ -169,6 +213,63 @@ mod tests {
         assert_eq!(decoded_str[0], "Hello! this is long text?");
     }
 
+    #[test]
+    fn caesar_encode_round_trips() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let encoded = caesar_decoder.encode("hello world, I am a test").unwrap();
+        assert_eq!(encoded, "uryyb jbeyq, V nz n grfg");
+        let result = caesar_decoder.crack(&encoded, &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world, I am a test"
+        );
+    }
+
+    #[test]
+    fn caesar_decodes_rot_13() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let result = caesar_decoder.crack("uryyb jbeyq, V nz n grfg", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("shift 13"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world, I am a test"
+        );
+    }
+
+    #[test]
+    fn caesar_decodes_shift_7() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let result = caesar_decoder.crack(
+            "Aol xbpjr iyvdu mve qbtwz vcly aol shgf kvn.",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.key.as_deref(), Some("shift 7"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "The quick brown fox jumps over the lazy dog."
+        );
+    }
+
+    #[test]
+    fn caesar_returns_all_25_shifts() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let result = caesar_decoder.crack("uryyb jbeyq", &get_athena_checker());
+        let decoded_strings = result.unencrypted_text.unwrap();
+        assert_eq!(decoded_strings.len(), 25);
+        for shift in 1..26 {
+            assert!(decoded_strings.contains(&caesar("uryyb jbeyq", 26 - shift)));
+        }
+    }
+
+    #[test]
+    fn caesar_skips_digits() {
+        let caesar_decoder = Decoder::<CaesarDecoder>::new();
+        let result = caesar_decoder
+            .crack("1234 5678 90ab", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
     #[test]
     fn caesar_decode_empty_string() {
         // caesar returns an empty string, this is a valid caesar string
@@ -186,13 +287,7 @@ mod tests {
         let result = caesar_decoder
             .crack("#", &get_athena_checker())
             .unencrypted_text;
-        if result.is_some() {
-            panic!("Decode_caesar did not return an option with Some<t>.")
-        } else {
-            // If we get here, the test passed
-            // Because the caesar_decoder.crack function returned None
-            // as it should do for the input
-            assert!(true)
-        }
+        // The caesar_decoder.crack function should return None for this input
+        assert!(result.is_none());
     }
 }
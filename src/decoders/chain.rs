@@ -0,0 +1,231 @@
+//! Chains of decoders used the other way round, to encode text for tests and examples,
+//! like `ares encode --chain base64,hex "hello world"`.
+//! A chain names its decoders like `--only` does, by name or by a tag only one decoder has,
+//! and every decoder in it has to be able to encode, see `Crack::encode`.
+
+use std::fmt;
+
+use super::{get_all_decoders, interface::Crack};
+use crate::checkers::{athena::Athena, checker_type::Check, checker_type::Checker, CheckerTypes};
+
+/// A decoder in a chain, which can encode
+type ChainDecoder = Box<dyn Crack + Send + Sync>;
+
+/// What decoders are asked to encode, to tell whether they can
+const SAMPLE: &str = "hello world";
+
+/// Why a chain couldn't be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    /// No decoder which can encode has this name, or this tag alone
+    Unknown {
+        /// The name as it was given
+        name: String,
+        /// The names of every decoder which can encode
+        encoders: Vec<String>,
+    },
+    /// The decoder can't encode, like one which needs a key
+    CannotEncode(String),
+    /// The decoder can encode, but not this text, like morse code with an emoji
+    EncodeFailed(String),
+    /// Checking the other way, the decoder couldn't decode what it was given
+    DecodeFailed(String),
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::Unknown { name, encoders } => write!(
+                f,
+                "no decoder which can encode is called \"{name}\", the ones which can are {}",
+                encoders.join(", ")
+            ),
+            ChainError::CannotEncode(name) => write!(f, "{name} can only decode, not encode"),
+            ChainError::EncodeFailed(name) => write!(f, "{name} couldn't encode the text"),
+            ChainError::DecodeFailed(name) => write!(f, "{name} couldn't decode the text"),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// Whether the decoder can encode
+fn can_encode(decoder: &ChainDecoder) -> bool {
+    decoder.encode(SAMPLE).is_some()
+}
+
+/// The decoders the names pick, in order. A name is a decoder's name, ignoring case,
+/// or a tag only one decoder has, like "hex" for Hexadecimal.
+/// # Errors
+/// Returns the first name which doesn't pick a decoder, or picks one which can't encode.
+pub fn resolve_chain(names: &[String]) -> Result<Vec<ChainDecoder>, ChainError> {
+    names
+        .iter()
+        .map(|name| {
+            let mut decoders = get_all_decoders();
+            let index = decoders
+                .iter()
+                .position(|decoder| decoder.get_name().eq_ignore_ascii_case(name))
+                .or_else(|| {
+                    let tagged: Vec<usize> = (0..decoders.len())
+                        .filter(|&index| {
+                            decoders[index]
+                                .get_tags()
+                                .iter()
+                                .any(|tag| tag.eq_ignore_ascii_case(name))
+                        })
+                        .collect();
+                    match tagged[..] {
+                        [index] => Some(index),
+                        _ => None,
+                    }
+                });
+            let Some(index) = index else {
+                return Err(ChainError::Unknown {
+                    name: name.clone(),
+                    encoders: encoders(&decoders),
+                });
+            };
+            let decoder = decoders.swap_remove(index);
+            if !can_encode(&decoder) {
+                return Err(ChainError::CannotEncode(decoder.get_name().to_string()));
+            }
+            Ok(decoder)
+        })
+        .collect()
+}
+
+/// The names of the decoders which can encode, sorted
+fn encoders(decoders: &[ChainDecoder]) -> Vec<String> {
+    let mut names: Vec<String> = decoders
+        .iter()
+        .filter(|decoder| can_encode(decoder))
+        .map(|decoder| decoder.get_name().to_string())
+        .collect();
+    names.sort_unstable_by_key(|name| name.to_lowercase());
+    names
+}
+
+/// Encodes the text with each of the chain's decoders in turn,
+/// so decoding with them the other way round gives it back.
+/// ```
+/// use ares::decoders::chain::encode_chain;
+///
+/// let chain = ["base64".to_string(), "hex".to_string()];
+/// assert_eq!(encode_chain(&chain, "hi").unwrap(), "61476b3d");
+/// ```
+/// # Errors
+/// Returns why a name isn't in the chain, or which decoder couldn't encode the text.
+pub fn encode_chain(names: &[String], text: &str) -> Result<String, ChainError> {
+    resolve_chain(names)?
+        .iter()
+        .try_fold(text.to_string(), |text, decoder| {
+            decoder
+                .encode(&text)
+                .ok_or_else(|| ChainError::EncodeFailed(decoder.get_name().to_string()))
+        })
+}
+
+/// Decodes the text with each of the chain's decoders, the last first,
+/// to check what `encode_chain` made decodes back. When a decoder decodes to several candidates,
+/// like Caesar with every shift, it's the one which encodes back to the text.
+/// Decoders tagged "reciprocal" are their own inverse, so they encode the text again
+/// when cracking it doesn't give it back, as Caesar won't try text which is mostly digits.
+/// # Errors
+/// Returns why a name isn't in the chain, or which decoder couldn't decode the text.
+pub fn decode_chain(names: &[String], text: &str) -> Result<String, ChainError> {
+    let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+    resolve_chain(names)?
+        .iter()
+        .rev()
+        .try_fold(text.to_string(), |text, decoder| {
+            let candidates = decoder
+                .crack(&text, &checker)
+                .unencrypted_text
+                .unwrap_or_default();
+            let encodes_back = candidates
+                .iter()
+                .position(|candidate| decoder.encode(candidate).as_deref() == Some(&text));
+            match encodes_back {
+                Some(index) => Some(candidates[index].clone()),
+                None if decoder.get_tags().contains(&"reciprocal") => decoder.encode(&text),
+                None => candidates.into_iter().next(),
+            }
+            .ok_or_else(|| ChainError::DecodeFailed(decoder.get_name().to_string()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_chain, encode_chain, resolve_chain, ChainError};
+
+    /// The names as a chain
+    fn chain(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn names_and_lone_tags_pick_decoders() {
+        let decoders = resolve_chain(&chain(&["BASE64", "hex", "rot13"])).unwrap();
+        let names: Vec<&str> = decoders.iter().map(|decoder| decoder.get_name()).collect();
+        assert_eq!(names, ["Base64", "Hexadecimal", "Caesar Cipher"]);
+    }
+
+    #[test]
+    fn encoding_then_decoding_gives_the_text_back() {
+        let names = chain(&["rot13", "reverse", "base64", "hex"]);
+        let encoded = encode_chain(&names, "the quick brown fox").unwrap();
+        assert_eq!(
+            decode_chain(&names, &encoded).unwrap(),
+            "the quick brown fox"
+        );
+    }
+
+    #[test]
+    fn reciprocal_decoders_decode_what_they_wont_crack() {
+        // Caesar doesn't crack text which is mostly digits, but ROT13 undoes itself
+        let names = chain(&["hex", "rot13"]);
+        let encoded = encode_chain(&names, "hi").unwrap();
+        assert_eq!(encoded, "6869");
+        assert_eq!(decode_chain(&names, &encoded).unwrap(), "hi");
+        let names = chain(&["base64", "hex", "rot13"]);
+        let encoded = encode_chain(&names, "hello world").unwrap();
+        assert_eq!(decode_chain(&names, &encoded).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn unknown_names_list_the_encoders() {
+        let Err(error) = resolve_chain(&chain(&["base64", "rot14"])) else {
+            panic!("rot14 isn't a decoder");
+        };
+        let ChainError::Unknown { name, encoders } = &error else {
+            panic!("{error:?}");
+        };
+        assert_eq!(name, "rot14");
+        assert!(encoders.iter().any(|encoder| encoder == "Base64"));
+        assert!(encoders.iter().any(|encoder| encoder == "Caesar Cipher"));
+        assert!(!encoders.iter().any(|encoder| encoder == "Vigenère"));
+        // "base" is a tag, but of many decoders
+        assert!(matches!(
+            resolve_chain(&chain(&["base"])),
+            Err(ChainError::Unknown { .. })
+        ));
+    }
+
+    #[test]
+    fn decoders_which_cant_encode_are_refused() {
+        let Err(error) = resolve_chain(&chain(&["base64", "vigenère"])) else {
+            panic!("Vigenère can't encode");
+        };
+        assert_eq!(error, ChainError::CannotEncode("Vigenère".to_string()));
+        assert_eq!(error.to_string(), "Vigenère can only decode, not encode");
+    }
+
+    #[test]
+    fn text_an_encoder_cant_encode_is_an_error() {
+        assert_eq!(
+            encode_chain(&chain(&["morse code"]), "✓"),
+            Err(ChainError::EncodeFailed("Morse Code".to_string()))
+        );
+    }
+}
@@ -3,46 +3,44 @@ use crate::decoders::interface::check_string_success;
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
-///! Citrix CTX1 Decoder
+/// Citrix CTX1 Decoder
 pub struct CitrixCTX1Decoder;
 
-///! Error enum
+/// Error enum
 #[derive(Debug)]
 enum Error {
-    ///! Error when the input is not divisible by 4
+    /// Error when the input is not divisible by 4
     InvalidLength,
-    ///! Error with left-hand side subtraction
+    /// Error with left-hand side subtraction
     LhsOverflow,
-    ///! Error with right-hand side subtraction
+    /// Error with right-hand side subtraction
     RhsOverflow,
-    ///! Error if the result isn't UTF-8
+    /// Error if the result isn't UTF-8
     InvalidUtf8,
 }
 
 impl Crack for Decoder<CitrixCTX1Decoder> {
     fn new() -> Decoder<CitrixCTX1Decoder> {
-        Decoder {
-            name: "Citrix Ctx1",
-            description: "Citrix CTX1 is a very old encoding that was used for encoding Citrix passwords.",
-            link: "https://www.remkoweijnen.nl/blog/2012/05/13/encoding-and-decoding-citrix-passwords/",
-            tags: vec!["citrix_ctx1", "citrix", "passwords", "decoder"],
-            popularity: 0.1,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Citrix Ctx1")
+            .with_description("Citrix CTX1 is a very old encoding that was used for encoding Citrix passwords.")
+            .with_link("https://www.remkoweijnen.nl/blog/2012/05/13/encoding-and-decoding-citrix-passwords/")
+            .with_tags(&["citrix_ctx1", "citrix", "passwords", "decoder"])
+            .with_popularity(0.1)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying citrix_ctx1 with text {:?}", text);
         let decoded_text: Result<String, Error> = decode_citrix_ctx1(text);
 
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_err() {
             debug!("Failed to decode citrix_ctx1: {:?}", decoded_text);
@@ -61,25 +59,22 @@ impl Crack for Decoder<CitrixCTX1Decoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Citrix CTX1 comes in groups of 4 characters, all from 'A' upwards
+    fn can_attempt(&self, text: &str) -> bool {
+        text.len().is_multiple_of(4) && text.bytes().all(|byte| byte >= b'A')
     }
 }
 
 /// Decodes Citrix CTX1
 fn decode_citrix_ctx1(text: &str) -> Result<String, Error> {
-    if text.len() % 4 != 0 {
+    if !text.len().is_multiple_of(4) {
         return Err(Error::InvalidLength);
     }
 
@@ -204,4 +199,13 @@ mod tests {
         let result = citrix_ctx1_decoder.crack("😂", &get_athena_checker());
         assert_eq!(result.unencrypted_text.unwrap()[0], "[*");
     }
+
+    #[test]
+    fn citrix_ctx1_only_attempts_its_alphabet() {
+        let decoder = Decoder::<CitrixCTX1Decoder>::new();
+        assert!(!decoder.can_attempt("has spaces!!"));
+        assert!(!decoder.can_attempt("MNGIKIANMEGBKIANMHGCOHECJADFPPFKINCIOBEEIFCA0"));
+        // Every byte from 'A' upwards is tolerated, not just the letters CTX1 writes
+        assert!(decoder.can_attempt("MNGIKIANmegb~~~~"));
+    }
 }
@@ -1,55 +1,336 @@
-use crate::checkers::checker_result::CheckResult;
-
-use super::interface::Decoder;
-
-///! This module contains CrackSuccess and CrackFailure
-///
-
-/// Every cracker returns this object which
-/// Either indicates success or failure among other things.
-#[derive(Debug, Clone)]
-pub struct CrackResult {
-    /// If our checkers return success, we change this bool to True
-    pub success: bool,
-    /// Encrypted text is the text _before_ we decrypt it.
-    pub encrypted_text: String,
-    /// Unencrypted text is what it looks like after.
-    /// if decoder failed, this will be None
-    pub unencrypted_text: Option<Vec<String>>,
-    /// Decoder is the function we used to decode the text
-    pub decoder: &'static str,
-    /// Checker which identified the text
-    pub checker_name: &'static str,
-    /// Description is a short description of the checker
-    pub checker_description: &'static str,
-    /// Key is optional as decoders do not use keys.
-    pub key: Option<&'static str>,
-    /// Description is a short description of the decoder
-    pub description: &'static str,
-    /// Link is a link to more info about the decoder
-    pub link: &'static str,
-}
-
-impl CrackResult {
-    /// This function returns a new CrackResult
-    pub fn new<T>(decoder_used: &Decoder<T>, text: String) -> Self {
-        CrackResult {
-            success: false,
-            encrypted_text: text,
-            unencrypted_text: None,
-            decoder: decoder_used.name,
-            checker_name: "",
-            checker_description: "",
-            key: None,
-            description: decoder_used.description,
-            link: decoder_used.link,
-        }
-    }
-
-    /// Updates the checker information
-    pub fn update_checker(&mut self, checker_result: &CheckResult) {
-        self.checker_name = checker_result.checker_name;
-        self.checker_description = checker_result.checker_description;
-        self.success = checker_result.is_identified;
-    }
-}
+//! This module contains CrackSuccess and CrackFailure
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::checkers::checker_result::CheckResult;
+use crate::searchers::priority::DecoderCosts;
+
+use super::interface::Decoder;
+
+/// Every cracker returns this object which
+/// Either indicates success or failure among other things.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CrackResult {
+    /// If our checkers return success, we change this bool to True
+    pub success: bool,
+    /// Encrypted text is the text _before_ we decrypt it.
+    /// It's only recorded when the decoder decoded it, see `Crack::crack`, and is empty otherwise.
+    pub encrypted_text: String,
+    /// Unencrypted text is what it looks like after.
+    /// Decoders can return several candidates, and the one the checker identified goes first.
+    /// if decoder failed, this will be None
+    pub unencrypted_text: Option<Vec<String>>,
+    /// Decoders which work on bytes put what they decoded here instead,
+    /// when it isn't text, so it can be passed on to the next decoder which works on bytes.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub unencrypted_bytes: Option<Vec<Vec<u8>>>,
+    /// Decoder is the function we used to decode the text
+    pub decoder: &'static str,
+    /// Checker which identified the text
+    pub checker_name: &'static str,
+    /// Description is a short description of the checker
+    pub checker_description: &'static str,
+    /// Why the checker decided the text is plaintext
+    pub checker_reason: String,
+    /// How sure the checker was that the text is plaintext, from 0 to 1,
+    /// so successful results can be ranked, and text which came close is decoded first
+    pub checker_confidence: f32,
+    /// Key is optional as decoders do not use keys.
+    /// Decoders which do use one record it here, e.g. "shift 13" for Caesar.
+    pub key: Option<String>,
+    /// Description is a short description of the decoder
+    pub description: &'static str,
+    /// Link is a link to more info about the decoder
+    pub link: &'static str,
+    /// The tags of the decoder, see `Decoder.tags`
+    pub tags: &'static [&'static str],
+    /// The popularity of the decoder, see `Decoder.popularity`, so searches can try likely paths first
+    pub popularity: f32,
+    /// How worth running the decoder is compared to a typical one, from 0 to 1,
+    /// so searches can try likely paths first without going down slow ones
+    pub priority: f32,
+    /// How long the decoder took, set by the filtration system when it runs the decoder
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub time_taken: Option<Duration>,
+}
+
+impl CrackResult {
+    /// A result for the decoder, which hasn't decoded anything yet.
+    /// It doesn't copy the text the decoder was given, as most attempts fail;
+    /// `Crack::crack` records it if the decoder decodes it, see `with_encrypted_text`.
+    pub fn new<T>(decoder_used: &Decoder<T>) -> Self {
+        CrackResult {
+            success: false,
+            encrypted_text: String::new(),
+            unencrypted_text: None,
+            unencrypted_bytes: None,
+            decoder: decoder_used.name,
+            checker_name: "",
+            checker_description: "",
+            checker_reason: String::new(),
+            checker_confidence: 0.0,
+            key: None,
+            description: decoder_used.description,
+            link: decoder_used.link,
+            tags: decoder_used.tags,
+            popularity: decoder_used.popularity,
+            priority: DecoderCosts {
+                popularity: decoder_used.popularity,
+                expected_success: decoder_used.expected_success,
+                expected_runtime: decoder_used.expected_runtime,
+                failure_runtime: decoder_used.failure_runtime,
+            }
+            .relative_priority(),
+            time_taken: None,
+        }
+    }
+
+    /// Sets the one candidate the decoder produced
+    /// ```
+    /// use ares::decoders::crack_results::CrackResult;
+    /// use ares::decoders::interface::{Crack, Decoder};
+    /// use ares::decoders::base64_decoder::Base64Decoder;
+    ///
+    /// let decoder = Decoder::<Base64Decoder>::new();
+    /// let result = CrackResult::new(&decoder).with_candidate("hello".to_string());
+    /// assert_eq!(result.unencrypted_text, Some(vec!["hello".to_string()]));
+    /// ```
+    pub fn with_candidate(self, text: String) -> Self {
+        self.with_candidates(vec![text])
+    }
+
+    /// Sets the text the decoder consumed, for results made outside `Crack::crack`
+    pub fn with_encrypted_text(mut self, text: String) -> Self {
+        self.encrypted_text = text;
+        self
+    }
+
+    /// Whether the decoder produced a candidate, as text or as bytes
+    pub fn decoded(&self) -> bool {
+        self.unencrypted_text.is_some() || self.unencrypted_bytes.is_some()
+    }
+
+    /// Sets every candidate the decoder produced, with the one the checker identified first
+    pub fn with_candidates(mut self, texts: Vec<String>) -> Self {
+        self.unencrypted_text = Some(texts);
+        self
+    }
+
+    /// Sets the bytes the decoder produced, when they aren't text
+    pub fn with_bytes_candidate(mut self, bytes: Vec<u8>) -> Self {
+        self.unencrypted_bytes = Some(vec![bytes]);
+        self
+    }
+
+    /// The candidate the checker identified, if it identified one
+    pub fn identified_text(&self) -> Option<&String> {
+        if !self.success {
+            return None;
+        }
+        self.unencrypted_text.as_ref()?.first()
+    }
+
+    /// Records the key or parameters the decoder used, such as "shift 13" for Caesar
+    pub fn set_key(&mut self, key: String) {
+        self.key = Some(key);
+    }
+
+    /// Serializes the result to JSON, with snake_case field names
+    /// # Errors
+    /// Returns the serde_json error if serializing fails.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Updates the checker information, whether or not it identified the text,
+    /// as how confident it was tells the search how close the decoding came
+    pub fn update_checker(&mut self, checker_result: &CheckResult) {
+        self.checker_name = checker_result.checker_name;
+        self.checker_description = checker_result.checker_description;
+        self.checker_reason = checker_result.reason.clone();
+        self.checker_confidence = checker_result.confidence;
+        self.success = checker_result.is_identified;
+    }
+}
+
+/// Shows the decoder's name, followed by the key it used if it has one
+impl fmt::Display for CrackResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{} ({})", self.decoder, key),
+            None => write!(f, "{}", self.decoder),
+        }
+    }
+}
+
+/// Adds up how long each decoder took over all of the results, slowest first,
+/// with one line per decoder such as "Base58 Ripple: 412ms over 97 calls".
+/// Results without a time are left out.
+pub fn timing_report(results: &[CrackResult]) -> String {
+    let mut totals: HashMap<&str, (Duration, usize)> = HashMap::new();
+    for result in results {
+        if let Some(time_taken) = result.time_taken {
+            let (total, calls) = totals.entry(result.decoder).or_default();
+            *total += time_taken;
+            *calls += 1;
+        }
+    }
+
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by(|(a_name, (a_total, _)), (b_name, (b_total, _))| {
+        b_total.cmp(a_total).then(a_name.cmp(b_name))
+    });
+    totals
+        .into_iter()
+        .map(|(name, (total, calls))| {
+            let plural = if calls == 1 { "" } else { "s" };
+            format!("{name}: {}ms over {calls} call{plural}", total.as_millis())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{timing_report, CrackResult};
+    use std::time::Duration;
+
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::{
+            base64_decoder::Base64Decoder,
+            caesar_decoder::CaesarDecoder,
+            interface::{Crack, Decoder},
+        },
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn single_candidate_decoder_returns_one_candidate() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let result = decoder.crack("aGVsbG8gd29ybGQ=", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text,
+            Some(vec!["hello world".to_string()])
+        );
+        assert_eq!(
+            result.identified_text().map(String::as_str),
+            Some("hello world")
+        );
+    }
+
+    #[test]
+    fn multi_candidate_decoder_puts_identified_candidate_first() {
+        let decoder = Decoder::<CaesarDecoder>::new();
+        let result = decoder.crack("uryyb guvf vf ybat grkg", &get_athena_checker());
+        let candidates = result.unencrypted_text.clone().unwrap();
+        assert_eq!(candidates.len(), 25);
+        assert_eq!(candidates[0], "hello this is long text");
+        assert_eq!(result.identified_text(), Some(&candidates[0]));
+    }
+
+    #[test]
+    fn unidentified_result_has_no_identified_text() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let result = decoder.crack("hello good day!", &get_athena_checker());
+        assert!(result.identified_text().is_none());
+    }
+
+    #[test]
+    fn keyed_decoder_sets_key_and_shows_it() {
+        let decoder = Decoder::<CaesarDecoder>::new();
+        let result = decoder.crack("uryyb guvf vf ybat grkg", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("shift 13"));
+        assert_eq!(result.to_string(), "Caesar Cipher (shift 13)");
+    }
+
+    #[test]
+    fn unkeyed_decoder_leaves_key_empty() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let result = decoder.crack("aGVsbG8gd29ybGQ=", &get_athena_checker());
+        assert!(result.key.is_none());
+        assert_eq!(result.to_string(), "Base64");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn crack_result_json_has_stable_field_names() {
+        let decoder = Decoder::<CaesarDecoder>::new();
+        let result = decoder.crack("uryyb guvf vf ybat grkg", &get_athena_checker());
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["decoder"], "Caesar Cipher");
+        assert_eq!(json["encrypted_text"], "uryyb guvf vf ybat grkg");
+        assert_eq!(json["unencrypted_text"][0], "hello this is long text");
+        assert_eq!(json["success"], true);
+        assert_eq!(json["key"], "shift 13");
+        assert_eq!(json["tags"][0], "caesar");
+        for field in [
+            "checker_name",
+            "checker_description",
+            "checker_reason",
+            "description",
+            "link",
+        ] {
+            assert!(json[field].is_string(), "{field} is missing");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn crack_result_json_keeps_non_ascii_text() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let result = decoder.crack("Y2Fmw6kgw7xiZXIgYWxsZXMg8J+YgA==", &get_athena_checker());
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["unencrypted_text"][0], "café über alles 😀");
+        assert!(json["key"].is_null());
+    }
+
+    #[test]
+    fn update_checker_keeps_which_checker_fired_and_why() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let result = decoder.crack(
+            "dGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw==",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.checker_name, "English Checker");
+        assert!(result.checker_reason.starts_with("matched "));
+        assert!(result.checker_confidence > 0.8);
+    }
+
+    #[test]
+    fn timing_report_groups_by_decoder() {
+        let base64 = Decoder::<Base64Decoder>::new();
+        let caesar = Decoder::<CaesarDecoder>::new();
+        let timed = |decoder_result: CrackResult, millis| CrackResult {
+            time_taken: Some(Duration::from_millis(millis)),
+            ..decoder_result
+        };
+        let results = vec![
+            timed(CrackResult::new(&base64), 100),
+            timed(CrackResult::new(&caesar), 300),
+            timed(CrackResult::new(&base64), 150),
+            // Results which weren't timed are left out
+            CrackResult::new(&caesar),
+        ];
+        assert_eq!(
+            timing_report(&results),
+            "Caesar Cipher: 300ms over 1 call\nBase64: 250ms over 2 calls"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Decode a list of decimal character codes
+//! Performs error handling and returns a string
+//! Call decimal_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The Decimal decoder, call:
+/// `let decimal_decoder = Decoder::<DecimalDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = decimal_decoder.crack(input)` to decode a list of decimal character codes
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::decimal_decoder::{DecimalDecoder};
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_decimal = Decoder::<DecimalDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_decimal.crack("104 101 108 108 111", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello");
+/// ```
+pub struct DecimalDecoder;
+
+impl Crack for Decoder<DecimalDecoder> {
+    fn new() -> Decoder<DecimalDecoder> {
+        DecoderBuilder::new("Decimal")
+            .with_description("Each character of the text is written as its decimal character code, such as 104 for h. Codes up to 255 are treated as bytes, larger codes are treated as Unicode code points.")
+            .with_link("https://en.wikipedia.org/wiki/ASCII#Character_set")
+            .with_tags(&["decimal", "ascii", "decoder", "base"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying decimal with text {:?}", text);
+        let decoded_text = decode_decimal_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode decimal because DecimalDecoder::decode_decimal_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode decimal because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+    /// Decimal is digits and the separators between them.
+    /// A '+' is allowed as the numbers are parsed with it.
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars()
+            .all(|c| c.is_ascii_digit() || c == '+' || DECIMAL_SEPARATORS.contains(&c))
+    }
+    /// Encodes every byte of the text as a decimal number, separated by spaces
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(
+            text.bytes()
+                .map(|byte| byte.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+/// The characters which can separate two character codes
+const DECIMAL_SEPARATORS: [char; 6] = [' ', '\n', '\r', '\t', ',', ';'];
+
+/// The fewest character codes we will decode.
+/// Anything shorter is more likely to be a date or a version number than text.
+const MIN_DECIMAL_TOKENS: usize = 3;
+
+/// helper function
+/// Codes between 0 and 255 are read as bytes (UTF-8, falling back to Latin-1).
+/// If any code is larger than 255 every code is read as a Unicode code point.
+fn decode_decimal_no_error_handling(text: &str) -> Option<String> {
+    let codes = text
+        .split(DECIMAL_SEPARATORS)
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+
+    if codes.len() < MIN_DECIMAL_TOKENS {
+        return None;
+    }
+
+    if codes.iter().all(|&code| code <= 255) {
+        let bytes: Vec<u8> = codes.iter().map(|&code| code as u8).collect();
+        return match String::from_utf8(bytes) {
+            Ok(decoded) => Some(decoded),
+            Err(err) => Some(err.into_bytes().into_iter().map(char::from).collect()),
+        };
+    }
+
+    codes.into_iter().map(char::from_u32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecimalDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn decimal_encode_round_trips() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
+    #[test]
+    fn decimal_decodes_successfully() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("104 101 108 108 111", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn decimal_decodes_comma_separated() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("104,101,108,108,111", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn decimal_decodes_unicode_code_points() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("72 101 108 108 111 128512", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "Hello😀");
+    }
+
+    #[test]
+    fn decimal_out_of_range_fails() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder
+            .crack("104 101 999999999999 108 111", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn decimal_does_not_decode_dates() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder
+            .crack("2023 10", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn decimal_handles_panics() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn decimal_handles_panic_if_empty_string() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn decimal_handles_panic_if_emoji() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn decimal_only_attempts_numbers() {
+        let decoder = Decoder::<DecimalDecoder>::new();
+        assert!(!decoder.can_attempt("104 101 108 108 111 world"));
+        assert!(!decoder.can_attempt("3.14 2.71 1.41"));
+        assert!(decoder.can_attempt("104,101;+108\r\n\t108 111"));
+    }
+}
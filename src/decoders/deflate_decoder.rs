@@ -0,0 +1,282 @@
+//! Decompress gzip, zlib or raw deflate data
+//! Performs error handling and returns a string
+//! Call deflate_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! Binary data from decoders like base64 and hexadecimal comes in through `crack_bytes`,
+//! and compressed text is read as Latin-1.
+
+use std::io::Read;
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use flate2::read::{DeflateDecoder as RawDeflateDecoder, GzDecoder, ZlibDecoder};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// We stop decompressing after this many bytes so zip bombs can't eat all the memory
+const MAX_DECOMPRESSED_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The share of characters which have to be printable for the output to be text
+const MIN_PRINTABLE_RATIO: f64 = 0.9;
+
+/// The Deflate decoder, call:
+/// `let deflate_decoder = Decoder::<DeflateDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = deflate_decoder.crack(input)` to decompress a string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::deflate_decoder::DeflateDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_deflate = Decoder::<DeflateDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // zlib compressed "hello world" as Latin-1
+/// let compressed = "\u{78}\u{9c}\u{cb}\u{48}\u{cd}\u{c9}\u{c9}\u{57}\u{28}\u{cf}\u{2f}\u{ca}\u{49}\u{01}\u{00}\u{1a}\u{0b}\u{04}\u{5d}";
+/// let result = decode_deflate.crack(compressed, &checker);
+/// assert_eq!(result.key.as_deref(), Some("zlib"));
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct DeflateDecoder;
+
+impl Crack for Decoder<DeflateDecoder> {
+    fn new() -> Decoder<DeflateDecoder> {
+        DecoderBuilder::new("Deflate")
+            .with_description("Deflate is the lossless compression algorithm used by gzip, zlib and zip files. Gzip and zlib wrap the compressed data with a header and a checksum, while raw deflate has neither.")
+            .with_link("https://en.wikipedia.org/wiki/Deflate")
+            .with_tags(&["deflate", "gzip", "zlib", "compression", "decoder"])
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying deflate with text {:?}", text);
+        let decoded =
+            latin1_to_bytes(text).and_then(|bytes| decode_deflate_no_error_handling(&bytes));
+        crack_decompressed(CrackResult::new(self), text, decoded, checker)
+    }
+    /// Compressed data is binary, so decompress the bytes other decoders produced directly
+    fn accepts_bytes(&self) -> bool {
+        true
+    }
+    /// Decompresses the bytes, rather than reading them from text
+    fn decode_bytes(&self, data: &[u8], checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying deflate with {} bytes", data.len());
+        let decoded = decode_deflate_no_error_handling(data);
+        let original = String::from_utf8_lossy(data);
+        crack_decompressed(CrackResult::new(self), &original, decoded, checker)
+    }
+    decoder_getters!();
+}
+
+/// Checks what `decode_deflate_no_error_handling` decompressed from `original`,
+/// whether it came from text or bytes
+fn crack_decompressed(
+    mut results: CrackResult,
+    original: &str,
+    decoded: Option<(&'static str, String)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    if decoded.is_none() {
+        debug!("Failed to decompress because DeflateDecoder::decode_deflate_no_error_handling returned None");
+        return results;
+    }
+
+    let (container, decoded_text) = decoded.unwrap();
+    if !check_string_success(&decoded_text, original) {
+        info!(
+            "Failed to decompress because check_string_success returned false on string {}",
+            decoded_text
+        );
+        return results;
+    }
+
+    let checker_result = checker.check(&decoded_text);
+    results.set_key(container.to_string());
+    results = results.with_candidate(decoded_text);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+/// helper function
+/// Returns the container which was found and the decompressed text.
+/// Gzip and zlib are picked by their headers, anything else is tried as raw deflate.
+fn decode_deflate_no_error_handling(bytes: &[u8]) -> Option<(&'static str, String)> {
+    let (container, decompressed) = if bytes.starts_with(&[0x1f, 0x8b]) {
+        ("gzip", decompress(GzDecoder::new(bytes))?)
+    } else if is_zlib_header(bytes) {
+        ("zlib", decompress(ZlibDecoder::new(bytes))?)
+    } else {
+        ("raw", decompress(RawDeflateDecoder::new(bytes))?)
+    };
+
+    let decoded_text = String::from_utf8(decompressed).ok()?;
+    if !is_mostly_printable(&decoded_text) {
+        return None;
+    }
+    Some((container, decoded_text))
+}
+
+/// Returns None if any character doesn't fit in a byte
+fn latin1_to_bytes(text: &str) -> Option<Vec<u8>> {
+    text.chars().map(|c| u8::try_from(c).ok()).collect()
+}
+
+/// The compression method is 8 (deflate) and the first two bytes are a multiple of 31
+fn is_zlib_header(bytes: &[u8]) -> bool {
+    match bytes {
+        [cmf, flg, ..] => {
+            cmf & 0x0f == 8 && cmf >> 4 <= 7 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0
+        }
+        _ => false,
+    }
+}
+
+/// Reads the whole stream, failing if it is corrupt, empty or bigger than MAX_DECOMPRESSED_BYTES
+fn decompress(reader: impl Read) -> Option<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut decompressed)
+        .ok()?;
+    if decompressed.is_empty() || decompressed.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return None;
+    }
+    Some(decompressed)
+}
+
+/// Random bytes often inflate to something, so make sure it's text
+fn is_mostly_printable(text: &str) -> bool {
+    let total = text.chars().count();
+    let printable = text
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .count();
+    printable as f64 >= total as f64 * MIN_PRINTABLE_RATIO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use std::io::Write;
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    // helper for tests
+    fn bytes_to_latin1(bytes: &[u8]) -> String {
+        bytes.iter().map(|&byte| char::from(byte)).collect()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn deflate_decodes_gzip() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let compressed = bytes_to_latin1(&gzip(b"hello world"));
+        let result = decoder.crack(&compressed, &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("gzip"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn deflate_decodes_zlib() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello world").unwrap();
+        let compressed = bytes_to_latin1(&encoder.finish().unwrap());
+        let result = decoder.crack(&compressed, &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("zlib"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn deflate_decodes_raw_deflate() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello world").unwrap();
+        let compressed = bytes_to_latin1(&encoder.finish().unwrap());
+        let result = decoder.crack(&compressed, &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("raw"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn deflate_truncated_stream_fails() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let compressed = gzip(b"hello world, this stream is going to be cut short");
+        let truncated = bytes_to_latin1(&compressed[..compressed.len() / 2]);
+        let result = decoder
+            .crack(&truncated, &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn deflate_stops_decompression_bombs() {
+        let bomb = gzip(&vec![b'a'; MAX_DECOMPRESSED_BYTES as usize + 1]);
+        assert!(decode_deflate_no_error_handling(&bomb).is_none());
+    }
+
+    #[test]
+    fn deflate_decompresses_bytes() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        assert!(decoder.accepts_bytes());
+        let result = decoder.crack_bytes(&gzip(b"hello world"), &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("gzip"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn deflate_handles_panics() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn deflate_handles_panic_if_empty_string() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn deflate_handles_panic_if_emoji() {
+        let decoder = Decoder::<DeflateDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,302 @@
+//! Decode the escape sequences in a programming language's string literal
+//! Performs error handling and returns a string
+//! Call escaped_string_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The escaped string decoder, call:
+/// `let escaped_string_decoder = Decoder::<EscapedStringDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = escaped_string_decoder.crack(input)` to unescape a string literal
+/// The struct generated by new() comes from interface.rs
+///
+/// It unescapes `\xHH` bytes, `\uHHHH` and `%uHHHH` UTF-16 units, surrogate pairs included,
+/// `\UHHHHHHHH` characters, and `\n`, `\r`, `\t`, `\0`, `\\`, `\"` and `\'`.
+/// Anything else after a backslash fails the whole decode, as do lone surrogates,
+/// and bytes which aren't UTF-8 once unescaped, rather than passing on half unescaped text.
+/// ```
+/// use ares::decoders::escaped_string_decoder::EscapedStringDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_escaped_string = Decoder::<EscapedStringDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_escaped_string.crack(r"\x68\x65\x6c\x6c\x6f world\n", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world\n");
+/// ```
+pub struct EscapedStringDecoder;
+
+impl Crack for Decoder<EscapedStringDecoder> {
+    fn new() -> Decoder<EscapedStringDecoder> {
+        DecoderBuilder::new("Escaped String")
+            .with_description("Programming languages write characters in string literals as escape sequences, like \\x68 or \\u0068 for h, and \\n for a new line. IIS and old JavaScript escape() write %u0068 for the same thing. Payloads are often hidden in them.")
+            .with_link("https://en.wikipedia.org/wiki/Escape_sequences_in_C")
+            .with_tags(&["escape", "string", "programming", "web", "decoder"])
+            .with_popularity(0.5)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying to unescape string with text {:?}", text);
+        let mut results = CrackResult::new(self);
+        let Some(decoded_text) = unescape(text) else {
+            debug!(
+                "Failed to unescape string because EscapedStringDecoder::unescape returned None"
+            );
+            return results;
+        };
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to unescape string because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+    /// Every escape starts with a backslash or "%u"
+    fn can_attempt(&self, text: &str) -> bool {
+        text.contains('\\') || text.contains("%u")
+    }
+}
+
+/// Unescapes the escape sequences in the text.
+/// Returns None if there aren't any, or if any of them is invalid.
+/// A "%" which isn't followed by "u" and 4 hex digits is left as it is, as text uses it for percentages.
+fn unescape(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    // The first half of a surrogate pair, waiting for the second
+    let mut high_surrogate: Option<u16> = None;
+    let mut escapes = 0;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let escape = match c {
+            '\\' => Some(parse_backslash_escape(&rest[1..])?),
+            '%' => parse_percent_u_escape(&rest[1..]),
+            _ => None,
+        };
+        let Some((escape, length)) = escape else {
+            if high_surrogate.is_some() {
+                return None;
+            }
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        escapes += 1;
+        rest = &rest[1 + length..];
+        // The second half of a surrogate pair has to follow the first, and nothing else can
+        if high_surrogate.is_some() != matches!(escape, Escape::Utf16(0xdc00..=0xdfff)) {
+            return None;
+        }
+        let c = match escape {
+            Escape::Byte(byte) => {
+                bytes.push(byte);
+                continue;
+            }
+            Escape::Char(c) => c,
+            Escape::Utf16(high @ 0xd800..=0xdbff) => {
+                high_surrogate = Some(high);
+                continue;
+            }
+            Escape::Utf16(unit) => match high_surrogate.take() {
+                Some(high) => char::decode_utf16([high, unit]).next()?.ok()?,
+                None => char::from_u32(u32::from(unit))?,
+            },
+        };
+        let mut buffer = [0; 4];
+        bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+    }
+    if escapes == 0 || high_surrogate.is_some() {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// What an escape sequence stands for
+enum Escape {
+    /// A byte, from `\xHH`, which may be part of a UTF-8 character
+    Byte(u8),
+    /// A UTF-16 unit, from `\uHHHH` or `%uHHHH`, which may be half of a surrogate pair
+    Utf16(u16),
+    /// A character
+    Char(char),
+}
+
+/// The escape after a backslash, and how many bytes of the text it takes up.
+/// Returns None if it isn't a valid escape, including when the text ends.
+fn parse_backslash_escape(text: &str) -> Option<(Escape, usize)> {
+    let escape = match text.chars().next()? {
+        'x' => {
+            return Some((
+                Escape::Byte(u8::from_str_radix(hex_digits(text, 2)?, 16).ok()?),
+                3,
+            ))
+        }
+        'u' => {
+            return Some((
+                Escape::Utf16(u16::from_str_radix(hex_digits(text, 4)?, 16).ok()?),
+                5,
+            ))
+        }
+        'U' => {
+            let code = u32::from_str_radix(hex_digits(text, 8)?, 16).ok()?;
+            return Some((Escape::Char(char::from_u32(code)?), 9));
+        }
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' => '\\',
+        '"' => '"',
+        '\'' => '\'',
+        _ => return None,
+    };
+    Some((Escape::Char(escape), 1))
+}
+
+/// The `%uHHHH` escape after a percent sign, and how many bytes of the text it takes up,
+/// or None if the percent sign isn't one
+fn parse_percent_u_escape(text: &str) -> Option<(Escape, usize)> {
+    if !text.starts_with('u') {
+        return None;
+    }
+    let unit = u16::from_str_radix(hex_digits(text, 4)?, 16).ok()?;
+    Some((Escape::Utf16(unit), 5))
+}
+
+/// The `count` hex digits after the escape's letter, which is the first character of the text
+fn hex_digits(text: &str, count: usize) -> Option<&str> {
+    let digits = text.get(1..=count)?;
+    digits
+        .chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then_some(digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EscapedStringDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn backslash_x_escapes_are_unescaped() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack(r"\x68\x65\x6c\x6c\x6f there", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello there");
+    }
+
+    #[test]
+    fn backslash_x_escapes_make_utf8() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack(r"caf\xc3\xa9", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "café");
+    }
+
+    #[test]
+    fn backslash_u_surrogate_pair_is_one_character() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack(r"hi \uD83D\uDE00 \U0001F600", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hi 😀 😀");
+    }
+
+    #[test]
+    fn percent_u_escapes_are_unescaped() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack("%u0068%u0065%u006c%u006c%u006f 100%", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello 100%");
+    }
+
+    #[test]
+    fn basic_escapes_are_unescaped() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack(r#"say \"hi\"\r\n\tC:\\ \'ok\'"#, &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "say \"hi\"\r\n\tC:\\ 'ok'"
+        );
+    }
+
+    #[test]
+    fn trailing_backslash_fails() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack(r"\x68\x69\", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn unknown_escape_fails() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack(r"\x68\x69 \q", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn invalid_escapes_fail() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        for text in [
+            r"\x6g",
+            r"\u00",
+            r"\uD83D",
+            r"\uD83D hi",
+            r"\uDE00",
+            "%uD83D",
+            r"\U00110000",
+            r"\xff\xfe",
+        ] {
+            let result = decoder.crack(text, &get_athena_checker());
+            assert!(result.unencrypted_text.is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn text_without_escapes_fails() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        assert!(!decoder.can_attempt("hello there"));
+        let result = decoder.crack("100% of %users", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn escaped_string_decode_empty_string() {
+        let decoder = Decoder::<EscapedStringDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
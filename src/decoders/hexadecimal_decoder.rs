@@ -1,53 +1,64 @@
 use crate::checkers::CheckerTypes;
-use crate::decoders::interface::check_string_success;
+use crate::decoders::interface::{
+    bytes_to_candidate_string, check_string_success, normalize_base_input,
+};
 
 use super::crack_results::CrackResult;
+use super::interface::only_uses_alphabet;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
-///! Hexadecimal Decoder
+/// Hexadecimal Decoder
 pub struct HexadecimalDecoder;
 
-///! Error enum
+/// Error enum
 #[derive(Debug)]
 enum Error {
-    ///! Error when the input is not divisible by 2
+    /// Error when the input is not divisible by 2
     InvalidLength,
-    ///! Error if the result isn't UTF-8
-    InvalidUtf8,
+    /// Error when the input contains a character which is not hex or a separator
+    InvalidCharacter,
 }
 
 impl Crack for Decoder<HexadecimalDecoder> {
     fn new() -> Decoder<HexadecimalDecoder> {
-        Decoder {
-            name: "Hexadecimal",
-            description: "Data is broken into 4-bit sequences, and each value (between 0 and 15 inclusively) is encoded using one of 16 symbols from the ASCII character set. Although any 16 symbols from the ASCII character set can be used, in practice the ASCII digits '0'–'9' and the letters 'A'–'F' (or the lowercase 'a'–'f') are always chosen in order to align with standard written notation for hexadecimal numbers.",
-            link: "https://en.wikipedia.org/wiki/Hexadecimal#Base16_(transfer_encoding)",
-            tags: vec!["hexadecimal", "hex", "base", "decoder"],
-            popularity: 1.0,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Hexadecimal")
+            .with_description("Data is broken into 4-bit sequences, and each value (between 0 and 15 inclusively) is encoded using one of 16 symbols from the ASCII character set. Although any 16 symbols from the ASCII character set can be used, in practice the ASCII digits '0'–'9' and the letters 'A'–'F' (or the lowercase 'a'–'f') are always chosen in order to align with standard written notation for hexadecimal numbers.")
+            .with_link("https://en.wikipedia.org/wiki/Hexadecimal#Base16_(transfer_encoding)")
+            .with_tags(&["hexadecimal", "hex", "base", "decoder"])
+            .with_popularity(1.0)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying hexadecimal with text {:?}", text);
-        let decoded_text: Result<String, Error> = hexadecimal_to_string(text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let decoded_bytes: Result<Vec<u8>, Error> = hexadecimal_to_bytes(text);
+        let mut results = CrackResult::new(self);
 
-        if decoded_text.is_err() {
-            debug!("Failed to decode hexadecimal: {:?}", decoded_text);
+        if decoded_bytes.is_err() {
+            debug!("Failed to decode hexadecimal: {:?}", decoded_bytes);
             return results;
         }
 
+        let decoded_bytes = decoded_bytes.unwrap();
+        let decoded_text = match bytes_to_candidate_string(&decoded_bytes) {
+            Some(decoded_text) => decoded_text,
+            None => {
+                // This isn't text, but it may be a file, or a decoder which works on bytes might make sense of it
+                debug!("Hexadecimal decoded to binary, passing on the bytes");
+                let checker_result = checker.check_bytes(&decoded_bytes);
+                results = results.with_bytes_candidate(decoded_bytes);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
         trace!("Decoded text for hexadecimal: {:?}", decoded_text);
 
-        let decoded_text = decoded_text.unwrap();
-
         if !check_string_success(&decoded_text, text) {
             info!(
                 "Failed to decode hexadecimal because check_string_success returned false on string {}",
@@ -57,28 +68,35 @@ impl Crack for Decoder<HexadecimalDecoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// Hexadecimal is hex digits, with any case, "0x" or "\x" prefixes and separators
+    fn can_attempt(&self, text: &str) -> bool {
+        only_uses_alphabet(text, "0123456789abcdefABCDEFxX\\,:;")
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes the text as lowercase hex, with no separators
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(text.bytes().map(|byte| format!("{byte:02x}")).collect())
     }
 }
 
-/// Decodes hexadecimal to string
-fn hexadecimal_to_string(hex: &str) -> Result<String, Error> {
-    // Remove "0x" delimiters
-    let hex = hex.replace("0x", "");
-    // Remove all non-hexadecimal characters from the string
-    let hex = hex.replace(|c: char| !c.is_ascii_hexdigit(), "");
+/// Decodes hexadecimal to bytes
+fn hexadecimal_to_bytes(hex: &str) -> Result<Vec<u8>, Error> {
+    // Remove "0x" and "\x" prefixes
+    let hex = hex.replace("0x", "").replace("0X", "").replace("\\x", "");
+    // Remove whitespace and the separators people commonly put between the bytes
+    let hex = normalize_base_input(&hex).replace([',', ':', ';'], "");
+
+    // Anything left over which isn't hex means this isn't hex,
+    // so we fail instead of silently dropping characters
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidCharacter);
+    }
 
     // Convert the hexadecimal string to a vector of bytes
     let bytes = hex.as_bytes();
@@ -88,15 +106,11 @@ fn hexadecimal_to_string(hex: &str) -> Result<String, Error> {
         return Err(Error::InvalidLength);
     }
 
-    // Iterate over the vector of bytes in pairs
-    let mut result = String::new();
-    for pair in bytes.chunks(2) {
-        // Parse the pair of bytes as a hexadecimal number and push the corresponding
-        // ASCII character to the result string
-        result.push(u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap() as char);
-    }
-
-    String::from_utf8(result.into()).map_err(|_| Error::InvalidUtf8)
+    // Parse every pair of hex digits as a byte
+    Ok(bytes
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect())
 }
 
 #[cfg(test)]
@@ -117,6 +131,39 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn hexadecimal_encode_round_trips() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
+    #[test]
+    fn hexadecimal_binary_is_kept_as_bytes() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("1f8b0800ff00fe", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+        assert_eq!(
+            result.unencrypted_bytes,
+            Some(vec![vec![0x1f, 0x8b, 0x08, 0x00, 0xff, 0x00, 0xfe]])
+        );
+    }
+
+    #[test]
+    fn hexadecimal_with_trailing_newline_decodes_successfully() {
+        // Piping `xxd -p` output in a shell leaves a trailing newline
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("68656c6c6f20776f726c64\n", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
     #[test]
     fn hexadecimal_with_no_spaces_decodes_successfully() {
         // This tests if Hexadecimal can decode Hexadecimal with no spaces successfully
@@ -202,8 +249,7 @@ mod tests {
     #[test]
     fn hexadecimal_handles_panics() {
         // This tests if Hexadecimal can handle panics
-        // It should return Some
-        // This is because Hexadecimal can technically decode it, but it will be gibberish
+        // It should return None as the string contains non-hex characters
         let hexadecimal_decoder = Decoder::<HexadecimalDecoder>::new();
         let result = hexadecimal_decoder
             .crack(
@@ -211,7 +257,61 @@ mod tests {
                 &get_athena_checker(),
             )
             .unencrypted_text;
-        assert!(result.is_some());
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexadecimal_hello_world_decodes_successfully() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("68656c6c6f20776f726c64", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn hexadecimal_space_separated_bytes_decode_successfully() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("68 65 6c 6c 6f", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn hexadecimal_0x_prefix_with_commas_decodes_successfully() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("0x68,0x65,0x6c", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hel");
+    }
+
+    #[test]
+    fn hexadecimal_backslash_x_prefix_decodes_successfully() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("\\x68\\x65\\x6c\\x6c\\x6f", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn hexadecimal_colon_and_newline_separated_decodes_successfully() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder.crack("68:65:6c\n6c:6f\n", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn hexadecimal_odd_length_fails() {
+        // Odd length hex should not be truncated
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder
+            .crack("68656c6c6f2", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexadecimal_with_g_fails() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        let result = decoder
+            .crack("68656c6c6g", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
     }
 
     #[test]
@@ -235,4 +335,12 @@ mod tests {
             .unencrypted_text;
         assert!(result.is_none());
     }
+
+    #[test]
+    fn hexadecimal_only_attempts_hex_digits() {
+        let decoder = Decoder::<HexadecimalDecoder>::new();
+        assert!(!decoder.can_attempt("hello there"));
+        assert!(!decoder.can_attempt("68656c6c6z"));
+        assert!(decoder.can_attempt("0x68, 0X65:\\x6C; 6c 6F\n"));
+    }
 }
@@ -0,0 +1,266 @@
+//! Decode the output of xxd or hexdump -C
+//! Performs error handling and returns a string
+//! Call hexdump_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{bytes_to_candidate_string, check_string_success};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// A single line of hex is left to the hexadecimal decoder
+const MIN_HEXDUMP_LINES: usize = 2;
+
+/// The Hexdump decoder, call:
+/// `let hexdump_decoder = Decoder::<HexdumpDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = hexdump_decoder.crack(input)` to decode a hexdump
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::hexdump_decoder::HexdumpDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_hexdump = Decoder::<HexdumpDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let dump = "00000000: 6865 6c6c 6f20 776f 726c 642c 2074 6869  hello world, thi\n00000010: 7320 6973 2061 2074 6573 7421            s is a test!";
+/// let result = decode_hexdump.crack(dump, &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world, this is a test!");
+/// ```
+pub struct HexdumpDecoder;
+
+impl Crack for Decoder<HexdumpDecoder> {
+    fn new() -> Decoder<HexdumpDecoder> {
+        DecoderBuilder::new("Hexdump")
+            .with_description("A hex dump shows data as rows of hexadecimal bytes, each starting with the offset of its first byte and usually followed by the printable characters. This decoder reads the output of xxd and hexdump -C.")
+            .with_link("https://en.wikipedia.org/wiki/Hex_dump")
+            .with_tags(&["hexdump", "xxd", "hexadecimal", "decoder", "base"])
+            .with_popularity(0.4)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying hexdump with text {:?}", text);
+        let decoded_bytes = decode_hexdump_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_bytes.is_none() {
+            debug!("Failed to decode hexdump because HexdumpDecoder::decode_hexdump_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_bytes = decoded_bytes.unwrap();
+        let decoded_text = match bytes_to_candidate_string(&decoded_bytes) {
+            Some(decoded_text) => decoded_text,
+            None => {
+                // This isn't text, but it may be a file, or a decoder which works on bytes might make sense of it
+                debug!("Hexdump decoded to binary, passing on the bytes");
+                let checker_result = checker.check_bytes(&decoded_bytes);
+                results = results.with_bytes_candidate(decoded_bytes);
+                results.update_checker(&checker_result);
+                return results;
+            }
+        };
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode hexdump because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// helper function
+/// Every line has to be part of the dump, apart from blank lines
+/// and the lone offset hexdump prints at the end.
+fn decode_hexdump_no_error_handling(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut dump_lines = 0;
+    for line in text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+    {
+        let offset_length = line.len()
+            - line
+                .trim_start_matches(|c: char| c.is_ascii_hexdigit())
+                .len();
+        if offset_length < 4 {
+            return None;
+        }
+        let rest = &line[offset_length..];
+        if rest.is_empty() {
+            continue;
+        }
+        bytes.extend(parse_hexdump_line(rest)?);
+        dump_lines += 1;
+    }
+
+    if dump_lines < MIN_HEXDUMP_LINES || bytes.is_empty() {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// Reads the bytes from a line after its offset.
+/// xxd puts a colon after the offset and two spaces before the text,
+/// while hexdump -C puts the text between bars.
+fn parse_hexdump_line(rest: &str) -> Option<Vec<u8>> {
+    let hex = if let Some(xxd_rest) = rest.strip_prefix(':') {
+        xxd_rest.trim_start().split("  ").next()?
+    } else if rest.starts_with("  ") {
+        rest.split('|').next()?
+    } else {
+        return None;
+    };
+
+    let mut bytes = Vec::new();
+    for group in hex.split_whitespace() {
+        if !group.len().is_multiple_of(2) || !group.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        for pair in group.as_bytes().chunks(2) {
+            bytes.push(u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?);
+        }
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexdumpDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn hexdump_decodes_xxd() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder.crack(
+            "00000000: 6865 6c6c 6f20 776f 726c 642c 2074 6869  hello world, thi\n\
+             00000010: 7320 6973 2061 2068 6578 6475 6d70 2074  s is a hexdump t\n\
+             00000020: 6573 7421                                est!\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world, this is a hexdump test!"
+        );
+    }
+
+    #[test]
+    fn hexdump_decodes_hexdump_c() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder.crack(
+            "00000000  68 65 6c 6c 6f 20 77 6f  72 6c 64 2c 20 74 68 69  |hello world, thi|\n\
+             00000010  73 20 69 73 20 61 20 68  65 78 64 75 6d 70 20 74  |s is a hexdump t|\n\
+             00000020  65 73 74 21                                       |est!|\n\
+             00000024\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world, this is a hexdump test!"
+        );
+    }
+
+    #[test]
+    fn hexdump_decodes_truncated_last_line() {
+        // The last line has been cut off part way through the hex
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder.crack(
+            "00000000: 6865 6c6c 6f20 776f 726c 642c 2074 6869  hello world, thi\n\
+             00000010: 7320 6973 2061",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world, this is a"
+        );
+    }
+
+    #[test]
+    fn hexdump_garbled_line_fails() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder
+            .crack(
+                "00000000: 6865 6c6c 6f20 776f 726c 642c 2074 6869  hello world, thi\n\
+                 this line is not part of the dump\n\
+                 00000020: 6573 7421                                est!\n",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexdump_leaves_single_line_to_hex_decoder() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder
+            .crack(
+                "00000000: 6865 6c6c 6f20 776f 726c 64    hello world",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexdump_handles_panics() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexdump_handles_panic_if_empty_string() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn hexdump_handles_panic_if_emoji() {
+        let decoder = Decoder::<HexdumpDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,261 @@
+//! Normalise homoglyphs, letters from other scripts which look like Latin ones
+//! Performs error handling and returns a string
+//! Call homoglyph_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{info, trace};
+
+/// Text where more of the letters than this are Cyrillic or Greek lookalikes is written in that script,
+/// like Russian prose, rather than Latin text with a few letters swapped
+const MAX_LOOKALIKE_RATIO: f64 = 0.3;
+
+/// The Homoglyph Normalizer, call:
+/// `let homoglyph_decoder = Decoder::<HomoglyphNormalizerDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = homoglyph_decoder.crack(input)` to turn the lookalikes in it back into ASCII
+/// The struct generated by new() comes from interface.rs
+///
+/// Challenges swap a letter or two for a Cyrillic or Greek one which looks the same, like "е" for "e",
+/// or use fullwidth letters, so the text looks right but every decoder fails on it.
+/// This turns them back into the ASCII they look like, so the next decoder can decode the text.
+/// ```
+/// use ares::decoders::homoglyph_decoder::HomoglyphNormalizerDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_homoglyphs = Decoder::<HomoglyphNormalizerDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // The "е" and "о" are Cyrillic
+/// let result = decode_homoglyphs.crack("hеllo wоrld", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct HomoglyphNormalizerDecoder;
+
+impl Crack for Decoder<HomoglyphNormalizerDecoder> {
+    fn new() -> Decoder<HomoglyphNormalizerDecoder> {
+        DecoderBuilder::new("Homoglyph Normalizer")
+            .with_description("Homoglyphs are characters which look the same as others, like the Cyrillic а and the Latin a. Swapping a few letters for homoglyphs, or writing them fullwidth, leaves text looking as it was while stopping it from decoding. This turns them back into the ASCII they look like.")
+            .with_link("https://en.wikipedia.org/wiki/Homoglyph")
+            .with_tags(&["homoglyph", "unicode", "normalisation", "decoder"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying to normalise homoglyphs in text {:?}", text);
+        let mut results = CrackResult::new(self);
+        let Some(decoded_text) = normalise_homoglyphs(text) else {
+            info!("Failed to normalise homoglyphs, as there are none or the text is in another script");
+            return results;
+        };
+
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to normalise homoglyphs because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+    /// ASCII has no homoglyphs to normalise
+    fn can_attempt(&self, text: &str) -> bool {
+        !text.is_ascii()
+    }
+}
+
+/// Turns the homoglyphs in the text into the ASCII they look like.
+/// Returns None if there are none, or if so many of the letters are Cyrillic or Greek lookalikes
+/// that the text is written in that script, so it's left alone.
+fn normalise_homoglyphs(text: &str) -> Option<String> {
+    let (mut letters, mut lookalikes, mut fullwidth) = (0, 0, 0);
+    let normalised: String = text
+        .chars()
+        .map(|c| {
+            if c.is_alphabetic() {
+                letters += 1;
+            }
+            if let Some(ascii) = lookalike(c) {
+                lookalikes += 1;
+                ascii
+            } else if let Some(ascii) = from_fullwidth(c) {
+                fullwidth += 1;
+                ascii
+            } else {
+                c
+            }
+        })
+        .collect();
+    if lookalikes + fullwidth == 0 || lookalikes as f64 > letters as f64 * MAX_LOOKALIKE_RATIO {
+        return None;
+    }
+    Some(normalised)
+}
+
+/// The ASCII letter a Cyrillic or Greek letter looks like, if it looks like one.
+/// This is a handful of the letters which look exactly the same, not every confusable character.
+fn lookalike(c: char) -> Option<char> {
+    let ascii = match c {
+        // Cyrillic
+        'а' => 'a',
+        'е' => 'e',
+        'о' => 'o',
+        'р' => 'p',
+        'с' => 'c',
+        'у' => 'y',
+        'х' => 'x',
+        'і' => 'i',
+        'ј' => 'j',
+        'ѕ' => 's',
+        'ԁ' => 'd',
+        'һ' => 'h',
+        'ԛ' => 'q',
+        'ԝ' => 'w',
+        'А' => 'A',
+        'В' => 'B',
+        'Е' => 'E',
+        'К' => 'K',
+        'М' => 'M',
+        'Н' => 'H',
+        'О' => 'O',
+        'Р' => 'P',
+        'С' => 'C',
+        'Т' => 'T',
+        'Х' => 'X',
+        'І' => 'I',
+        'Ј' => 'J',
+        'Ѕ' => 'S',
+        'Ү' => 'Y',
+        // Greek
+        'ο' => 'o',
+        'ν' => 'v',
+        'ρ' => 'p',
+        'ϲ' => 'c',
+        'ϳ' => 'j',
+        'Α' => 'A',
+        'Β' => 'B',
+        'Ε' => 'E',
+        'Ζ' => 'Z',
+        'Η' => 'H',
+        'Ι' => 'I',
+        'Κ' => 'K',
+        'Μ' => 'M',
+        'Ν' => 'N',
+        'Ο' => 'O',
+        'Ρ' => 'P',
+        'Τ' => 'T',
+        'Υ' => 'Y',
+        'Χ' => 'X',
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+/// The ASCII character a fullwidth one is, like "Ａ" for "A"
+fn from_fullwidth(c: char) -> Option<char> {
+    match c {
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0),
+        // The ideographic space
+        '\u{3000}' => Some(' '),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HomoglyphNormalizerDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn cyrillic_lookalikes_become_ascii() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        // The "а" and "с" are Cyrillic
+        let result = decoder.crack("аGVsbG8gdGhlсmUgZ2VuZXJhbA==", &get_athena_checker());
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="
+        );
+    }
+
+    #[test]
+    fn greek_lookalikes_become_ascii() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        let result = decoder.crack("the Ρython cοde", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "the Python code");
+    }
+
+    #[test]
+    fn fullwidth_characters_become_ascii() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        let result = decoder.crack("ｆｌａｇ｛ｗｉｄｅ｝", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "flag{wide}");
+    }
+
+    #[test]
+    fn russian_is_left_alone() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        for text in [
+            "Съешь же ещё этих мягких французских булок, да выпей чаю",
+            "Привет, как дела?",
+            "Москва",
+        ] {
+            let result = decoder.crack(text, &get_athena_checker());
+            assert!(result.unencrypted_text.is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn ascii_has_nothing_to_normalise() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        assert!(!decoder.can_attempt("hello world"));
+        let result = decoder.crack("hello world", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn text_without_homoglyphs_is_left_alone() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        let result = decoder.crack("naïve café 😀", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn homoglyph_decode_empty_string() {
+        let decoder = Decoder::<HomoglyphNormalizerDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
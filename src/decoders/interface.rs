@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
+use crate::checkers::checker_result::CheckResult;
 use crate::checkers::CheckerTypes;
+use crate::config::get_config;
 
 use super::crack_results::CrackResult;
 
-///! The Interface defines what the struct for each decoder looks like
+/// The Interface defines what the struct for each decoder looks like
 //TODO: rename this file
 pub struct Decoder<Type> {
     /// The English name of the decoder.
@@ -17,25 +21,172 @@ pub struct Decoder<Type> {
     /// For example, if you want to filter decoders that are "base64"
     /// you would use the tag "base64" or "base".
     /// You can also add tags like "online" to filter decoders that are online.
-    pub tags: Vec<&'static str>,
+    pub tags: &'static [&'static str],
     /// We get popularity by eye-balling it or using the API's data
     pub popularity: f32,
+    /// Roughly how many seconds one call to `crack` takes
+    pub expected_runtime: f32,
+    /// How likely one call to `crack` is to decode the text, from 0 to 1
+    pub expected_success: f32,
+    /// Roughly how many seconds one call to `crack` takes when it can't decode the text.
+    /// Decoders which try every key take much longer to give up than to succeed.
+    pub failure_runtime: f32,
+    /// The normalised entropy of text this decoder tends to decode
+    pub normalised_entropy: &'static [f32],
     /// we don't use the Type, so we use PhantomData to mark it!
     pub phantom: std::marker::PhantomData<Type>,
 }
 
+impl<Type> Decoder<Type> {
+    /// Overrides the popularity, so users can bias the search toward decoders they expect
+    pub fn set_popularity(&mut self, popularity: f32) {
+        self.popularity = popularity;
+    }
+
+    /// Applies the popularity override for this decoder, if the map has one for its name
+    pub fn apply_popularity_overrides(&mut self, overrides: &HashMap<String, f32>) {
+        if let Some(&popularity) = overrides.get(self.name) {
+            self.set_popularity(popularity);
+        }
+    }
+}
+
+/// How many seconds a decoder takes, when it doesn't say
+pub const DEFAULT_EXPECTED_RUNTIME: f32 = 0.01;
+/// How likely a decoder is to decode the text, when it doesn't say
+pub const DEFAULT_EXPECTED_SUCCESS: f32 = 0.5;
+
+/// Builds a `Decoder`, filling in anything a decoder doesn't set with a sensible default.
+/// Every decoder needs a name, description and link.
+/// ```
+/// use ares::decoders::base64_decoder::Base64Decoder;
+/// use ares::decoders::interface::{Decoder, DecoderBuilder};
+///
+/// let decoder: Decoder<Base64Decoder> = DecoderBuilder::new("Base64")
+///     .with_description("Base64 is a binary-to-text encoding.")
+///     .with_link("https://en.wikipedia.org/wiki/Base64")
+///     .with_tags(&["base64", "decoder", "base"])
+///     .build();
+/// assert_eq!(decoder.popularity, 0.5);
+/// ```
+pub struct DecoderBuilder {
+    /// The English name of the decoder
+    name: &'static str,
+    /// The description, which every decoder needs
+    description: Option<&'static str>,
+    /// The link, which every decoder needs
+    link: Option<&'static str>,
+    /// The tags, none by default
+    tags: &'static [&'static str],
+    /// The popularity, 0.5 by default
+    popularity: f32,
+    /// The expected runtime, 0.01 seconds by default
+    expected_runtime: f32,
+    /// The expected success, 0.5 by default
+    expected_success: f32,
+    /// The failure runtime, the expected runtime by default
+    failure_runtime: Option<f32>,
+    /// The normalised entropy, empty by default
+    normalised_entropy: &'static [f32],
+}
+
+impl DecoderBuilder {
+    /// Starts building a decoder with the given name
+    pub fn new(name: &'static str) -> Self {
+        DecoderBuilder {
+            name,
+            description: None,
+            link: None,
+            tags: &[],
+            popularity: 0.5,
+            expected_runtime: DEFAULT_EXPECTED_RUNTIME,
+            expected_success: DEFAULT_EXPECTED_SUCCESS,
+            failure_runtime: None,
+            normalised_entropy: &[],
+        }
+    }
+
+    /// Sets the description
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// Sets the link
+    pub fn with_link(mut self, link: &'static str) -> Self {
+        self.link = Some(link);
+        self
+    }
+
+    /// Sets the tags
+    pub fn with_tags(mut self, tags: &'static [&'static str]) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Sets the popularity
+    pub fn with_popularity(mut self, popularity: f32) -> Self {
+        self.popularity = popularity;
+        self
+    }
+
+    /// Sets the expected runtime in seconds
+    pub fn with_expected_runtime(mut self, expected_runtime: f32) -> Self {
+        self.expected_runtime = expected_runtime;
+        self
+    }
+
+    /// Sets how likely the decoder is to decode the text, from 0 to 1
+    pub fn with_expected_success(mut self, expected_success: f32) -> Self {
+        self.expected_success = expected_success;
+        self
+    }
+
+    /// Sets how many seconds the decoder takes to give up on text it can't decode
+    pub fn with_failure_runtime(mut self, failure_runtime: f32) -> Self {
+        self.failure_runtime = Some(failure_runtime);
+        self
+    }
+
+    /// Sets the normalised entropy
+    pub fn with_normalised_entropy(mut self, normalised_entropy: &'static [f32]) -> Self {
+        self.normalised_entropy = normalised_entropy;
+        self
+    }
+
+    /// Builds the decoder
+    /// # Panics
+    /// Panics if the description or link was never set.
+    pub fn build<Type>(self) -> Decoder<Type> {
+        Decoder {
+            name: self.name,
+            description: self
+                .description
+                .unwrap_or_else(|| panic!("decoder {} has no description", self.name)),
+            link: self
+                .link
+                .unwrap_or_else(|| panic!("decoder {} has no link", self.name)),
+            tags: self.tags,
+            popularity: self.popularity,
+            expected_runtime: self.expected_runtime,
+            expected_success: self.expected_success,
+            failure_runtime: self.failure_runtime.unwrap_or(self.expected_runtime),
+            normalised_entropy: self.normalised_entropy,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
 /// The default implementation for a decoder
 pub struct DefaultDecoder;
 impl Default for Decoder<DefaultDecoder> {
     fn default() -> Decoder<DefaultDecoder> {
-        Decoder {
-            name: "Default decoder",
-            description: "N/A",
-            link: "N/A",
-            tags: vec!["N/A"],
-            popularity: 0.0,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Default decoder")
+            .with_description("N/A")
+            .with_link("N/A")
+            .with_tags(&["N/A"])
+            .with_popularity(0.0)
+            .build()
     }
 }
 
@@ -48,22 +199,495 @@ pub trait Crack {
     fn new() -> Self
     where
         Self: Sized;
-    /// Crack is the function that actually does the decoding
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult;
+    /// Decode is the function that actually does the decoding, which each decoder implements.
+    /// Most attempts fail, so the result doesn't copy the text; `crack` records it if it decoded.
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult;
+    /// Decodes the text, recording it in the result's `encrypted_text` if the decoder decoded it
+    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        let mut result = self.decode(text, checker);
+        if result.decoded() {
+            result.encrypted_text = text.to_string();
+        }
+        result
+    }
     /// Get all tags for the current decoder
-    fn get_tags(&self) -> &Vec<&str>;
+    fn get_tags(&self) -> &'static [&'static str];
     /// Get the nam of the current decoder
     fn get_name(&self) -> &str;
+    /// Get the description of the current decoder
+    fn get_description(&self) -> &str;
+    /// Get the link to read more about the current decoder
+    fn get_link(&self) -> &str;
+    /// Get the popularity of the current decoder
+    fn get_popularity(&self) -> f32;
+    /// Get roughly how many seconds the current decoder takes
+    fn get_expected_runtime(&self) -> f32;
+    /// Get how likely the current decoder is to decode the text
+    fn get_expected_success(&self) -> f32;
+    /// Get roughly how many seconds the current decoder takes to give up
+    fn get_failure_runtime(&self) -> f32;
+    /// Whether the text could possibly be decoded, checked before `crack` is called
+    /// so decoders don't run on text they would fail on straight away, like hexadecimal on prose.
+    /// It must be cheap, and it must never be false for text `crack` could decode,
+    /// so decoders which can't tell quickly keep the default of attempting everything.
+    fn can_attempt(&self, _text: &str) -> bool {
+        true
+    }
+    /// Encodes the text, so that `crack` decodes it back again.
+    /// Decoders which can't encode, or need a key to, return None.
+    fn encode(&self, _text: &str) -> Option<String> {
+        None
+    }
+    /// Whether the decoder works on raw bytes in `crack_bytes`,
+    /// such as decompressing what base64 decoded to
+    fn accepts_bytes(&self) -> bool {
+        false
+    }
+    /// Decode is the function that actually does the decoding, for bytes which may not be UTF-8.
+    /// Decoders which only work on text don't need to implement this,
+    /// by default the bytes are converted lossily and passed to `decode`.
+    fn decode_bytes(&self, data: &[u8], checker: &CheckerTypes) -> CrackResult {
+        self.decode(&String::from_utf8_lossy(data), checker)
+    }
+    /// Decodes the bytes like `crack`, recording a lossy copy of them if the decoder decoded them
+    fn crack_bytes(&self, data: &[u8], checker: &CheckerTypes) -> CrackResult {
+        let mut result = self.decode_bytes(data, checker);
+        if result.decoded() {
+            result.encrypted_text = String::from_utf8_lossy(data).into_owned();
+        }
+        result
+    }
+}
+
+/// Implements the getters of `Crack` for a `Decoder`, which are the same for every decoder.
+/// Call it inside `impl Crack for Decoder<...>`.
+macro_rules! decoder_getters {
+    () => {
+        /// Gets all tags for this decoder
+        fn get_tags(&self) -> &'static [&'static str] {
+            self.tags
+        }
+        /// Gets the name for the current decoder
+        fn get_name(&self) -> &str {
+            self.name
+        }
+        /// Gets the description for the current decoder
+        fn get_description(&self) -> &str {
+            self.description
+        }
+        /// Gets the link for the current decoder
+        fn get_link(&self) -> &str {
+            self.link
+        }
+        /// Gets the popularity for the current decoder
+        fn get_popularity(&self) -> f32 {
+            self.popularity
+        }
+        /// Gets the expected runtime for the current decoder
+        fn get_expected_runtime(&self) -> f32 {
+            self.expected_runtime
+        }
+        /// Gets how likely the current decoder is to decode the text
+        fn get_expected_success(&self) -> f32 {
+            self.expected_success
+        }
+        /// Gets how many seconds the current decoder takes to give up
+        fn get_failure_runtime(&self) -> f32 {
+            self.failure_runtime
+        }
+    };
 }
+pub(crate) use decoder_getters;
 
 /// Returns a boolean of True if the string is successfully changed
 /// So empty strings fail, but non-empty strings succeed
 /// and only if the string is different from the original text.
+/// It also fails if the string is mostly unprintable, see `Config.min_printable_ratio`,
+/// or if it is 1 character but the original text wasn't that short.
 pub fn check_string_success(decoded_text: &str, original_text: &str) -> bool {
-    if decoded_text.is_empty() {
+    check_string_success_with_ratio(
+        decoded_text,
+        original_text,
+        get_config().min_printable_ratio,
+    )
+}
+
+/// `check_string_success` with the minimum share of printable characters given,
+/// rather than taken from the config
+fn check_string_success_with_ratio(
+    decoded_text: &str,
+    original_text: &str,
+    min_printable_ratio: f64,
+) -> bool {
+    if decoded_text.trim().is_empty() || decoded_text == original_text {
         return false;
-    } else if decoded_text != original_text {
-        return true;
     }
-    false
+
+    let total = decoded_text.chars().count();
+    if total < 2 && original_text.chars().count() >= 2 {
+        return false;
+    }
+
+    is_mostly_printable(decoded_text, min_printable_ratio)
+}
+
+/// Whether at least `min_printable_ratio` of the text's characters are printable
+fn is_mostly_printable(text: &str, min_printable_ratio: f64) -> bool {
+    let total = text.chars().count();
+    let printable = text.chars().filter(|&c| !is_garbage(c)).count();
+    printable as f64 >= total as f64 * min_printable_ratio
+}
+
+/// Checks a decoder's candidates in order, stopping at the first the checker identifies,
+/// and returns which candidate that was with the checker's verdict.
+/// When it identifies none, the candidate it came closest to identifying is returned instead,
+/// so the search knows how close the decoder came. None if no candidate was checked.
+///
+/// A candidate which is the same as an earlier one isn't checked again, and neither is one which
+/// is blank or mostly unprintable, see `Config.min_printable_ratio`, as it can't be plaintext.
+/// The decoder still returns every candidate, so the search can decode them further.
+/// Decoders which try many keys, like Caesar, use this so they all check their candidates the same way.
+pub fn check_candidates(
+    checker: &CheckerTypes,
+    candidates: &[String],
+) -> Option<(usize, CheckResult)> {
+    let min_printable_ratio = get_config().min_printable_ratio;
+    let mut closest: Option<(usize, CheckResult)> = None;
+    for (index, candidate) in candidates.iter().enumerate() {
+        // Linear rather than hashed, as decoders have few candidates and most differ in the first character
+        if candidate.trim().is_empty()
+            || !is_mostly_printable(candidate, min_printable_ratio)
+            || candidates[..index].contains(candidate)
+        {
+            continue;
+        }
+        let checker_result = checker.check(candidate);
+        if checker_result.is_identified {
+            return Some((index, checker_result));
+        }
+        if closest
+            .as_ref()
+            .is_none_or(|(_, closest)| checker_result.confidence > closest.confidence)
+        {
+            closest = Some((index, checker_result));
+        }
+    }
+    closest
+}
+
+/// Whether the character is a replacement character or unprintable
+fn is_garbage(c: char) -> bool {
+    c == char::REPLACEMENT_CHARACTER || (c.is_control() && !c.is_whitespace())
+}
+
+/// Removes the ASCII whitespace (spaces, tabs, carriage returns and line feeds)
+/// which base encoded text picks up when it is wrapped or pasted,
+/// such as MIME wrapping base64 at 76 characters.
+/// Anything else is left alone, so characters outside the alphabet still fail to decode.
+pub fn normalize_base_input(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, ' ' | '\t' | '\r' | '\n'))
+        .collect()
+}
+
+/// Every character the base58 alphabets use. Bitcoin, Flickr, Monero and Ripple
+/// all use these, in a different order.
+pub const BASE58_CHARACTERS: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Whether every character of the text is in the alphabet, or is whitespace.
+/// Decoders use it in `can_attempt`. Whitespace is always allowed, as decoders
+/// which strip it from wrapped text would otherwise be skipped.
+pub fn only_uses_alphabet(text: &str, alphabet: &str) -> bool {
+    text.chars()
+        .all(|c| alphabet.contains(c) || c.is_whitespace())
+}
+
+/// Above this share of replacement or unprintable characters, output which isn't UTF-8 is treated as binary
+const MAX_LOSSY_GARBAGE_RATIO: f64 = 0.1;
+
+/// Turns the bytes a decoder produced into a string worth checking.
+/// Valid UTF-8 is kept unless it's mostly unprintable, see `Config.min_printable_ratio`,
+/// like UTF-16 with a NUL after every letter, which a decoder of bytes can make sense of.
+/// Anything else is converted lossily, but only if a few characters are replacement
+/// or unprintable characters. Otherwise it is binary and we return None.
+pub fn bytes_to_candidate_string(bytes: &[u8]) -> Option<String> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return is_mostly_printable(text, get_config().min_printable_ratio)
+            .then(|| text.to_string());
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let total = text.chars().count();
+    let garbage = text.chars().filter(|&c| is_garbage(c)).count();
+    if garbage as f64 > total as f64 * MAX_LOSSY_GARBAGE_RATIO {
+        return None;
+    }
+    Some(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bytes_to_candidate_string, normalize_base_input, Crack, Decoder, DecoderBuilder};
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
+    };
+    use crate::decoders::base64_decoder::Base64Decoder;
+    use std::collections::HashMap;
+
+    #[test]
+    fn decoder_builder_fills_in_defaults() {
+        let decoder: Decoder<Base64Decoder> = DecoderBuilder::new("Test")
+            .with_description("A test decoder")
+            .with_link("https://example.com")
+            .build();
+        assert_eq!(decoder.name, "Test");
+        assert_eq!(decoder.description, "A test decoder");
+        assert_eq!(decoder.link, "https://example.com");
+        assert!(decoder.tags.is_empty());
+        assert_eq!(decoder.popularity, 0.5);
+        assert_eq!(decoder.expected_runtime, 0.01);
+        assert_eq!(decoder.expected_success, 0.5);
+        // Giving up takes as long as decoding, unless the decoder says otherwise
+        assert_eq!(decoder.failure_runtime, 0.01);
+        assert!(decoder.normalised_entropy.is_empty());
+    }
+
+    #[test]
+    fn decoder_builder_overrides_defaults() {
+        let decoder: Decoder<Base64Decoder> = DecoderBuilder::new("Test")
+            .with_description("A test decoder")
+            .with_link("https://example.com")
+            .with_tags(&["test"])
+            .with_popularity(0.9)
+            .with_expected_runtime(0.5)
+            .with_expected_success(0.2)
+            .with_failure_runtime(2.0)
+            .with_normalised_entropy(&[0.75])
+            .build();
+        assert_eq!(decoder.tags, vec!["test"]);
+        assert_eq!(decoder.popularity, 0.9);
+        assert_eq!(decoder.expected_runtime, 0.5);
+        assert_eq!(decoder.expected_success, 0.2);
+        assert_eq!(decoder.failure_runtime, 2.0);
+        assert_eq!(decoder.normalised_entropy, vec![0.75]);
+    }
+
+    #[test]
+    #[should_panic(expected = "decoder Test has no link")]
+    fn decoder_builder_panics_without_link() {
+        let _decoder: Decoder<Base64Decoder> = DecoderBuilder::new("Test")
+            .with_description("A test decoder")
+            .build();
+    }
+
+    #[test]
+    fn popularity_overrides_only_apply_to_their_decoder() {
+        let overrides = HashMap::from([
+            ("Base64".to_string(), 0.1),
+            ("Caesar Cipher".to_string(), 0.9),
+        ]);
+        let mut decoder = Decoder::<Base64Decoder>::new();
+        decoder.apply_popularity_overrides(&overrides);
+        assert_eq!(decoder.popularity, 0.1);
+
+        let mut decoder = Decoder::<Base64Decoder>::new();
+        decoder.apply_popularity_overrides(&HashMap::new());
+        assert_eq!(decoder.popularity, 1.0);
+
+        decoder.set_popularity(0.3);
+        assert_eq!(decoder.popularity, 0.3);
+    }
+
+    #[test]
+    fn getters_work_through_a_trait_object() {
+        let decoder: Box<dyn Crack> = Box::new(Decoder::<Base64Decoder>::new());
+        assert_eq!(decoder.get_name(), "Base64");
+        assert!(decoder.get_description().starts_with("Base64 is"));
+        assert_eq!(decoder.get_link(), "https://en.wikipedia.org/wiki/Base64");
+        assert_eq!(decoder.get_popularity(), 1.0);
+        assert_eq!(decoder.get_expected_runtime(), 0.01);
+        assert!(decoder.get_tags().contains(&"base64"));
+
+        // The result keeps the link, so the output can cite it without the decoder
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        let result = decoder.crack("aGVsbG8gd29ybGQ=", &checker);
+        assert_eq!(result.description, decoder.get_description());
+        assert_eq!(result.link, decoder.get_link());
+    }
+
+    #[test]
+    fn text_decoders_crack_bytes_like_text() {
+        let decoder = Decoder::<Base64Decoder>::new();
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        assert!(!decoder.accepts_bytes());
+
+        let from_bytes = decoder.crack_bytes(b"aGVsbG8gd29ybGQ=", &checker);
+        let from_text = decoder.crack("aGVsbG8gd29ybGQ=", &checker);
+        assert_eq!(from_bytes.unencrypted_text, from_text.unencrypted_text);
+        assert_eq!(from_bytes.success, from_text.success);
+    }
+
+    #[test]
+    fn normalize_base_input_removes_line_breaks() {
+        assert_eq!(
+            normalize_base_input("aGVsbG8g\r\nd29y\nbGQ=\n"),
+            "aGVsbG8gd29ybGQ="
+        );
+    }
+
+    #[test]
+    fn normalize_base_input_removes_spaces_and_tabs() {
+        assert_eq!(normalize_base_input(" 68 65\t6c 6c 6f "), "68656c6c6f");
+    }
+
+    #[test]
+    fn normalize_base_input_keeps_other_characters() {
+        assert_eq!(normalize_base_input("ab!c d"), "ab!cd");
+    }
+
+    #[test]
+    fn bytes_to_candidate_string_keeps_ascii() {
+        assert_eq!(
+            bytes_to_candidate_string(b"hello world").as_deref(),
+            Some("hello world")
+        );
+    }
+
+    #[test]
+    fn bytes_to_candidate_string_keeps_multibyte_utf8() {
+        assert_eq!(
+            bytes_to_candidate_string("naïve café 😂".as_bytes()).as_deref(),
+            Some("naïve café 😂")
+        );
+    }
+
+    #[test]
+    fn bytes_to_candidate_string_rejects_binary() {
+        let bytes = [
+            0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff, 0xfe, 0x01,
+        ];
+        assert!(bytes_to_candidate_string(&bytes).is_none());
+    }
+
+    #[test]
+    fn bytes_to_candidate_string_rejects_utf8_which_is_mostly_nuls() {
+        assert!(bytes_to_candidate_string(b"h\0e\0l\0l\0o\0").is_none());
+    }
+
+    #[test]
+    fn bytes_to_candidate_string_accepts_one_stray_byte() {
+        let mut bytes = b"this text has a single stray byte in it ".to_vec();
+        bytes.push(0xff);
+        assert_eq!(
+            bytes_to_candidate_string(&bytes).as_deref(),
+            Some("this text has a single stray byte in it \u{fffd}")
+        );
+    }
+}
+
+#[cfg(test)]
+mod check_string_success_tests {
+    use super::{check_string_success, check_string_success_with_ratio};
+
+    #[test]
+    fn changed_text_succeeds() {
+        assert!(check_string_success("hello world", "aGVsbG8gd29ybGQ="));
+    }
+
+    #[test]
+    fn text_identical_to_the_input_fails() {
+        assert!(!check_string_success("hello world", "hello world"));
+    }
+
+    #[test]
+    fn empty_or_whitespace_text_fails() {
+        assert!(!check_string_success("", "aGVsbG8="));
+        assert!(!check_string_success(" \t\r\n", "aGVsbG8="));
+    }
+
+    #[test]
+    fn mostly_unprintable_text_fails() {
+        assert!(!check_string_success(
+            "\u{1}\u{2}\u{3}\u{fffd}\u{fffd}ab",
+            "abcdefg"
+        ));
+    }
+
+    #[test]
+    fn a_single_character_from_longer_text_fails() {
+        assert!(!check_string_success("h", "aA=="));
+        // Unless the input was that short too
+        assert!(check_string_success("b", "a"));
+    }
+
+    #[test]
+    fn printable_ratio_at_the_threshold_succeeds() {
+        // 7 of the 10 characters are printable
+        let text = "abcdefg\u{1}\u{2}\u{fffd}";
+        assert!(check_string_success_with_ratio(text, "input", 0.7));
+        assert!(!check_string_success_with_ratio(text, "input", 0.71));
+    }
+}
+
+#[cfg(test)]
+mod check_candidates_tests {
+    use super::{check_candidates, Crack, Decoder};
+    use crate::checkers::CheckerTypes;
+    use crate::decoders::caesar_decoder::CaesarDecoder;
+    use std::sync::{Arc, Mutex};
+
+    /// A checker which identifies `wanted`, and every text it was asked to check, in order
+    fn recording_checker(wanted: &'static str) -> (CheckerTypes, Arc<Mutex<Vec<String>>>) {
+        let checked = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&checked);
+        let checker = CheckerTypes::from_closure("Recording Checker", move |text| {
+            record.lock().unwrap().push(text.to_string());
+            (text == wanted).then(|| "it's the one".to_string())
+        });
+        (checker, checked)
+    }
+
+    #[test]
+    fn duplicate_candidates_are_checked_once() {
+        let (checker, checked) = recording_checker("nothing");
+        let candidates = ["uryyb", "hello", "uryyb", "hello"].map(String::from);
+        let (index, _) = check_candidates(&checker, &candidates).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(*checked.lock().unwrap(), ["uryyb", "hello"]);
+    }
+
+    #[test]
+    fn checking_stops_at_the_first_candidate_identified() {
+        let (checker, checked) = recording_checker("hello");
+        let candidates = ["uryyb", "hello", "jgnnq"].map(String::from);
+        let (index, result) = check_candidates(&checker, &candidates).unwrap();
+        assert_eq!(index, 1);
+        assert!(result.is_identified);
+        assert_eq!(result.checker_name, "Recording Checker");
+        assert_eq!(*checked.lock().unwrap(), ["uryyb", "hello"]);
+    }
+
+    #[test]
+    fn blank_and_unprintable_candidates_are_not_checked() {
+        let (checker, checked) = recording_checker("hello");
+        let candidates = [" \t", "\u{1}\u{2}\u{3}\u{fffd}ab", "hello"].map(String::from);
+        assert_eq!(check_candidates(&checker, &candidates).unwrap().0, 2);
+        assert_eq!(*checked.lock().unwrap(), ["hello"]);
+        // Nothing was checked, so there's no verdict
+        assert!(check_candidates(&checker, &candidates[..2]).is_none());
+    }
+
+    #[test]
+    fn every_candidate_is_kept_when_one_is_identified() {
+        // ROT13 of "hello there", so the checker is asked about 12 shifts before identifying it
+        let (checker, checked) = recording_checker("hello there");
+        let result = Decoder::<CaesarDecoder>::new().crack("uryyb gurer", &checker);
+        assert!(result.success);
+        assert_eq!(checked.lock().unwrap().len(), 13);
+        let candidates = result.unencrypted_text.unwrap();
+        assert_eq!(candidates.len(), 25);
+        assert_eq!(candidates[0], "hello there");
+    }
 }
@@ -0,0 +1,264 @@
+//! Decode text which was typed with the hands shifted on a QWERTY keyboard
+//! Performs error handling and returns a string
+//! Call keyboard_shift_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{check_candidates, check_string_success};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The QWERTY rows without shift held down
+const UNSHIFTED_ROWS: [&str; 4] = [
+    "`1234567890-=",
+    "qwertyuiop[]\\",
+    "asdfghjkl;'",
+    "zxcvbnm,./",
+];
+
+/// The QWERTY rows with shift held down, lined up with `UNSHIFTED_ROWS`
+const SHIFTED_ROWS: [&str; 4] = [
+    "~!@#$%^&*()_+",
+    "QWERTYUIOP{}|",
+    "ASDFGHJKL:\"",
+    "ZXCVBNM<>?",
+];
+
+/// How the text could have been shifted when it was typed, and how far we move each key to undo it.
+/// Moving down a row means a larger row index.
+const KEYBOARD_SHIFTS: [(&str, isize, isize); 6] = [
+    ("right 1", -1, 0),
+    ("left 1", 1, 0),
+    ("right 2", -2, 0),
+    ("left 2", 2, 0),
+    ("up 1", 0, 1),
+    ("down 1", 0, -1),
+];
+
+/// The keyboard shift decoder, call:
+/// `let keyboard_shift_decoder = Decoder::<KeyboardShiftDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = keyboard_shift_decoder.crack(input)` to decode a keyboard shifted string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::keyboard_shift_decoder::KeyboardShiftDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_keyboard_shift = Decoder::<KeyboardShiftDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_keyboard_shift.crack("jr;;p", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert!(result.unwrap().contains(&"hello".to_string()));
+/// ```
+pub struct KeyboardShiftDecoder;
+
+impl Crack for Decoder<KeyboardShiftDecoder> {
+    fn new() -> Decoder<KeyboardShiftDecoder> {
+        DecoderBuilder::new("Keyboard Shift")
+            .with_description("A keyboard shift cipher is text typed with the hands moved one or more keys along, or one row up or down, on a QWERTY keyboard. For example hello typed one key to the right becomes jr;;p.")
+            .with_link("https://www.dcode.fr/keyboard-shift-cipher")
+            .with_tags(&["keyboard", "substitution", "decoder"])
+            .with_popularity(0.3)
+            .with_expected_runtime(0.03)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying keyboard shift with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        if is_mostly_digits_or_whitespace(text) {
+            debug!(
+                "Not trying keyboard shift on {} because it is mostly digits or whitespace",
+                text
+            );
+            return results;
+        }
+
+        let mut candidates: Vec<(&str, String)> = Vec::new();
+        for (direction, columns, rows) in KEYBOARD_SHIFTS {
+            let decoded_text = keyboard_shift(text, columns, rows);
+            if check_string_success(&decoded_text, text)
+                && !candidates.iter().any(|(_, seen)| *seen == decoded_text)
+            {
+                candidates.push((direction, decoded_text));
+            }
+        }
+        if candidates.is_empty() {
+            debug!("Failed to decode keyboard shift as every shift left the text unchanged");
+            return results;
+        }
+
+        let mut decoded_strings: Vec<String> = candidates
+            .iter()
+            .map(|(_, decoded_text)| decoded_text.clone())
+            .collect();
+        // The candidate the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            if results.success {
+                let direction = &candidates[index].0;
+                info!("Found a match with the keyboard shifted {}", direction);
+                results.set_key(direction.to_string());
+            }
+            decoded_strings[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(decoded_strings);
+        results
+    }
+    decoder_getters!();
+}
+
+/// Numbers and blank text aren't worth shifting.
+fn is_mostly_digits_or_whitespace(text: &str) -> bool {
+    let total = text.chars().count();
+    let digits_or_whitespace = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || c.is_whitespace())
+        .count();
+    total == 0 || digits_or_whitespace * 2 > total
+}
+
+/// Moves every key by the given number of columns and rows.
+/// Columns wrap around within a row, while keys with no row above or below are left alone.
+/// Characters which aren't on the keyboard are kept as they are.
+fn keyboard_shift(text: &str, columns: isize, rows: isize) -> String {
+    text.chars()
+        .map(|c| {
+            for layer in [UNSHIFTED_ROWS, SHIFTED_ROWS] {
+                for (row_index, row) in layer.iter().enumerate() {
+                    if let Some(column) = row.chars().position(|key| key == c) {
+                        return shifted_key(&layer, row_index, column, columns, rows).unwrap_or(c);
+                    }
+                }
+            }
+            c
+        })
+        .collect()
+}
+
+/// Finds the key moved from the given position.
+/// Vertical moves line the rows up from their first letter, so q sits under 1 and a under q.
+fn shifted_key(
+    layer: &[&str; 4],
+    row_index: usize,
+    column: usize,
+    columns: isize,
+    rows: isize,
+) -> Option<char> {
+    if rows == 0 {
+        let row = layer[row_index];
+        let new_column = (column as isize + columns).rem_euclid(row.chars().count() as isize);
+        return row.chars().nth(new_column as usize);
+    }
+
+    // The number row has an extra key before 1, so skip it when lining the rows up
+    let aligned_column = column.checked_sub(usize::from(row_index == 0))?;
+    let new_row = row_index.checked_add_signed(rows)?;
+    layer
+        .get(new_row)?
+        .chars()
+        .nth(aligned_column + usize::from(new_row == 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn keyboard_shift_decodes_right_shift() {
+        let decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = decoder.crack(
+            "yjr wiovl ntpem gpc ki,[d pbrt yjr ;sxu fph",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("right 1"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "the quick brown fox jumps over the lazy dog"
+        );
+    }
+
+    #[test]
+    fn keyboard_shift_decodes_left_shift_with_symbols() {
+        let decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = decoder.crack("Gwkkim Qieks~ Rgw qiesa 'ew w'at,", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("left 1"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Hello, World! The words are easy."
+        );
+    }
+
+    #[test]
+    fn keyboard_shift_decodes_shifted_symbols() {
+        // With shift held down L is typed as : when moving one key right
+        assert_eq!(keyboard_shift("JR::P", -1, 0), "HELLO");
+        assert_eq!(keyboard_shift("HELLO", 1, 0), "JR::P");
+    }
+
+    #[test]
+    fn keyboard_shift_wraps_around_row_edges() {
+        assert_eq!(keyboard_shift("\\", 1, 0), "q");
+        assert_eq!(keyboard_shift("q", -1, 0), "\\");
+        assert_eq!(keyboard_shift("a", -2, 0), ";");
+    }
+
+    #[test]
+    fn keyboard_shift_moves_between_rows() {
+        assert_eq!(keyboard_shift("1qaz", 0, 1), "qazz");
+        assert_eq!(keyboard_shift("qaz", 0, -1), "1qa");
+        // The backtick has no key below it in the lined up rows
+        assert_eq!(keyboard_shift("`", 0, 1), "`");
+    }
+
+    #[test]
+    fn keyboard_shift_skips_numbers() {
+        let decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = decoder
+            .crack("1234 5678 9012", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn keyboard_shift_handles_panic_if_empty_string() {
+        let decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn keyboard_shift_handles_panic_if_emoji() {
+        let decoder = Decoder::<KeyboardShiftDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
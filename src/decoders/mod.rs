@@ -1,12 +1,17 @@
 //! This module contains all the code for decoders
 //! Think of a decoder as a decryption method that doesn't require a key
 //! The `interface.rs` defines what each decoder looks like.
-//! Once you have made a decoder you need to add it to the filtration system's
-//! mod.rs file
-//! you will also need to make it a public module in this file.
+//! Once you have made a decoder you need to add it to `get_all_decoders`
+//! and make it a public module in this file.
 
+/// The a1z26_decoder module decodes A1Z26
+pub mod a1z26_decoder;
 /// The atbash_decoder module decodes atbash
 pub mod atbash_decoder;
+/// The baconian_decoder module decodes Bacon's cipher
+pub mod baconian_decoder;
+/// The base32_crockford_decoder module decodes Crockford base32
+pub mod base32_crockford_decoder;
 /// The base32_decoder module decodes base32
 pub mod base32_decoder;
 /// The base58_bitcoin_decoder module decodes base58 bitcoin
@@ -15,8 +20,20 @@ pub mod base58_bitcoin_decoder;
 pub mod base58_monero_decoder;
 /// The binary_decoder module decodes binary
 pub mod binary_decoder;
+/// The decimal_decoder module decodes decimal character codes
+pub mod decimal_decoder;
+/// The escaped_string_decoder module unescapes string literals, like \x68 and \u0068
+pub mod escaped_string_decoder;
 /// The hexadecimal_decoder module decodes hexadecimal
 pub mod hexadecimal_decoder;
+/// The hexdump_decoder module decodes xxd and hexdump -C output
+pub mod hexdump_decoder;
+/// The homoglyph_decoder module turns lookalike Cyrillic, Greek and fullwidth letters into ASCII
+pub mod homoglyph_decoder;
+/// The jwt_decoder module decodes the header and payload of JSON Web Tokens
+pub mod jwt_decoder;
+/// The octal_decoder module decodes octal
+pub mod octal_decoder;
 
 /// The base58_ripple_decoder module decodes base58 ripple
 pub mod base58_ripple_decoder;
@@ -24,6 +41,8 @@ pub mod base58_ripple_decoder;
 /// The base58_flickr decoder module decodes base58 flickr
 pub mod base58_flickr_decoder;
 
+/// The base64_custom_decoder module decodes base64 with alternative alphabets
+pub mod base64_custom_decoder;
 /// The base64_decoder module decodes base64
 /// It is public as we use it in some tests.
 pub mod base64_decoder;
@@ -33,13 +52,25 @@ pub mod base64_url_decoder;
 pub mod base65536_decoder;
 /// The base91_decoder module decodes base91
 pub mod base91_decoder;
+/// The bech32_decoder module decodes bech32 and bech32m
+pub mod bech32_decoder;
+/// The benchmark module times every decoder, for `ares benchmark`
+pub mod benchmark;
+/// The chain module encodes with a chain of decoders, for `ares encode`
+pub mod chain;
 /// The citrix_ctx1_decoder module decodes citrix ctx1
 pub mod citrix_ctx1_decoder;
 /// The crack_results module defines the CrackResult
 /// Each and every decoder return same CrackResult
 pub mod crack_results;
+/// The deflate_decoder module decompresses gzip, zlib and raw deflate
+pub mod deflate_decoder;
+/// The punycode_decoder module decodes punycode domain names
+pub mod punycode_decoder;
 /// The url_decoder module decodes url
 pub mod url_decoder;
+/// The uuencode_decoder module decodes uuencoding
+pub mod uuencode_decoder;
 
 /// The interface module defines the interface for decoders
 /// Each and every decoder has the same struct & traits
@@ -54,5 +85,470 @@ pub mod reverse_decoder;
 /// It is public as we use it in some tests.
 pub mod morse_code;
 
+/// The nato_phonetic_decoder module decodes the NATO phonetic alphabet
+pub mod nato_phonetic_decoder;
+
+/// The tap_code_decoder module decodes tap code
+pub mod tap_code_decoder;
+
+/// The multi_tap_decoder module decodes multi-tap phone keypad presses
+pub mod multi_tap_decoder;
+
+/// The keyboard_shift_decoder module decodes QWERTY keyboard shifts
+pub mod keyboard_shift_decoder;
+
 /// For the caesar cipher decoder
 pub mod caesar_decoder;
+
+/// The rot18_decoder module decodes ROT18 and ROT5, which rotate digits as well as letters
+pub mod rot18_decoder;
+
+/// For the vigenere cipher decoder
+pub mod vigenere_decoder;
+
+/// For the rail fence cipher decoder
+pub mod railfence_decoder;
+
+/// The substitution_solver_decoder module solves simple substitution ciphers
+pub mod substitution_solver_decoder;
+
+/// The wide_char_decoder module decodes UTF-16 and UTF-32
+pub mod wide_char_decoder;
+
+/// For the single byte XOR decoder
+pub mod xor_single_byte_decoder;
+
+/// For the repeating key XOR decoder
+pub mod xor_repeating_key_decoder;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::config::get_config;
+use a1z26_decoder::A1Z26Decoder;
+use atbash_decoder::AtbashDecoder;
+use baconian_decoder::BaconianDecoder;
+use base32_crockford_decoder::Base32CrockfordDecoder;
+use base32_decoder::Base32Decoder;
+use base58_bitcoin_decoder::Base58BitcoinDecoder;
+use base58_flickr_decoder::Base58FlickrDecoder;
+use base58_monero_decoder::Base58MoneroDecoder;
+use base58_ripple_decoder::Base58RippleDecoder;
+use base64_custom_decoder::Base64CustomDecoder;
+use base64_decoder::Base64Decoder;
+use base64_url_decoder::Base64URLDecoder;
+use base65536_decoder::Base65536Decoder;
+use base91_decoder::Base91Decoder;
+use bech32_decoder::Bech32Decoder;
+use binary_decoder::BinaryDecoder;
+use caesar_decoder::CaesarDecoder;
+use citrix_ctx1_decoder::CitrixCTX1Decoder;
+use decimal_decoder::DecimalDecoder;
+use deflate_decoder::DeflateDecoder;
+use escaped_string_decoder::EscapedStringDecoder;
+use hexadecimal_decoder::HexadecimalDecoder;
+use hexdump_decoder::HexdumpDecoder;
+use homoglyph_decoder::HomoglyphNormalizerDecoder;
+use interface::{Crack, Decoder};
+use jwt_decoder::JwtDecoder;
+use keyboard_shift_decoder::KeyboardShiftDecoder;
+use morse_code::MorseCodeDecoder;
+use multi_tap_decoder::MultiTapDecoder;
+use nato_phonetic_decoder::NatoPhoneticDecoder;
+use octal_decoder::OctalDecoder;
+use punycode_decoder::PunycodeDecoder;
+use railfence_decoder::RailFenceDecoder;
+use reverse_decoder::ReverseDecoder;
+use rot18_decoder::Rot18Decoder;
+use substitution_solver_decoder::SubstitutionSolverDecoder;
+use tap_code_decoder::TapCodeDecoder;
+use url_decoder::URLDecoder;
+use uuencode_decoder::UuencodeDecoder;
+use vigenere_decoder::VigenereDecoder;
+use wide_char_decoder::WideCharDecoder;
+use xor_repeating_key_decoder::XorRepeatingKeyDecoder;
+use xor_single_byte_decoder::XorSingleByteDecoder;
+
+/// Builds the decoder, applying the popularity override if there is one
+fn build_decoder<Type: 'static>(popularity: &HashMap<String, f32>) -> Box<dyn Crack + Send + Sync>
+where
+    Decoder<Type>: Crack + Send + Sync,
+{
+    let mut decoder = Decoder::<Type>::new();
+    decoder.apply_popularity_overrides(popularity);
+    Box::new(decoder)
+}
+
+/// Returns one of every decoder, with the popularity overrides from the config.
+pub fn get_all_decoders() -> Vec<Box<dyn Crack + Send + Sync>> {
+    decoders_with_popularity(&get_config().decoder_popularity)
+}
+
+#[cfg(test)]
+thread_local! {
+    /// How many times this thread built the decoders, so tests can check a search only builds them once
+    pub(crate) static DECODERS_BUILT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Returns one of every decoder, with the given popularity overrides.
+/// This is the only list of decoders, so new decoders only need adding here.
+pub(crate) fn decoders_with_popularity(
+    popularity: &HashMap<String, f32>,
+) -> Vec<Box<dyn Crack + Send + Sync>> {
+    #[cfg(test)]
+    DECODERS_BUILT.with(|built| built.set(built.get() + 1));
+    vec![
+        build_decoder::<ReverseDecoder>(popularity),
+        build_decoder::<Base64Decoder>(popularity),
+        build_decoder::<Base58BitcoinDecoder>(popularity),
+        build_decoder::<Base58MoneroDecoder>(popularity),
+        build_decoder::<Base58RippleDecoder>(popularity),
+        build_decoder::<Base58FlickrDecoder>(popularity),
+        build_decoder::<Base91Decoder>(popularity),
+        build_decoder::<Bech32Decoder>(popularity),
+        build_decoder::<UuencodeDecoder>(popularity),
+        build_decoder::<DeflateDecoder>(popularity),
+        build_decoder::<WideCharDecoder>(popularity),
+        build_decoder::<Base65536Decoder>(popularity),
+        build_decoder::<BinaryDecoder>(popularity),
+        build_decoder::<HexadecimalDecoder>(popularity),
+        build_decoder::<HexdumpDecoder>(popularity),
+        build_decoder::<OctalDecoder>(popularity),
+        build_decoder::<DecimalDecoder>(popularity),
+        build_decoder::<Base32Decoder>(popularity),
+        build_decoder::<Base32CrockfordDecoder>(popularity),
+        build_decoder::<MorseCodeDecoder>(popularity),
+        build_decoder::<NatoPhoneticDecoder>(popularity),
+        build_decoder::<TapCodeDecoder>(popularity),
+        build_decoder::<MultiTapDecoder>(popularity),
+        build_decoder::<KeyboardShiftDecoder>(popularity),
+        build_decoder::<AtbashDecoder>(popularity),
+        build_decoder::<A1Z26Decoder>(popularity),
+        build_decoder::<BaconianDecoder>(popularity),
+        build_decoder::<CaesarDecoder>(popularity),
+        build_decoder::<Rot18Decoder>(popularity),
+        build_decoder::<VigenereDecoder>(popularity),
+        build_decoder::<RailFenceDecoder>(popularity),
+        build_decoder::<SubstitutionSolverDecoder>(popularity),
+        build_decoder::<XorSingleByteDecoder>(popularity),
+        build_decoder::<XorRepeatingKeyDecoder>(popularity),
+        build_decoder::<CitrixCTX1Decoder>(popularity),
+        build_decoder::<URLDecoder>(popularity),
+        build_decoder::<EscapedStringDecoder>(popularity),
+        build_decoder::<PunycodeDecoder>(popularity),
+        build_decoder::<HomoglyphNormalizerDecoder>(popularity),
+        build_decoder::<JwtDecoder>(popularity),
+        build_decoder::<Base64URLDecoder>(popularity),
+        build_decoder::<Base64CustomDecoder>(popularity),
+    ]
+}
+
+/// Returns every decoder with the given tag, such as "base"
+pub fn get_decoders_by_tag(tag: &str) -> Vec<Box<dyn Crack + Send + Sync>> {
+    get_all_decoders()
+        .into_iter()
+        .filter(|decoder| decoder.get_tags().contains(&tag))
+        .collect()
+}
+
+/// Returns the decoder with the given name, such as "Base64"
+pub fn get_decoder_by_name(name: &str) -> Option<Box<dyn Crack + Send + Sync>> {
+    get_all_decoders()
+        .into_iter()
+        .find(|decoder| decoder.get_name() == name)
+}
+
+/// What a decoder is, for listing every decoder, like `ares --list-decoders`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DecoderInfo {
+    /// The decoder's name, like "Base64"
+    pub name: String,
+    /// The tags it can be picked by, like "base"
+    pub tags: Vec<String>,
+    /// How popular it is, from 0 to 1. The more popular, the sooner the search tries it.
+    pub popularity: f32,
+    /// Where to read about it
+    pub link: String,
+    /// What it decodes
+    pub description: String,
+}
+
+/// Every decoder the search uses, the most popular first.
+/// They come from `get_all_decoders`, so the list is never out of date.
+/// ```
+/// use ares::decoders::{get_all_decoders, list_decoders};
+///
+/// let decoders = list_decoders();
+/// assert_eq!(decoders.len(), get_all_decoders().len());
+/// assert!(decoders.iter().any(|decoder| decoder.name == "Base64"));
+/// ```
+pub fn list_decoders() -> Vec<DecoderInfo> {
+    let mut decoders: Vec<DecoderInfo> = get_all_decoders()
+        .iter()
+        .map(|decoder| DecoderInfo {
+            name: decoder.get_name().to_string(),
+            tags: decoder
+                .get_tags()
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            popularity: decoder.get_popularity(),
+            link: decoder.get_link().to_string(),
+            description: decoder.get_description().to_string(),
+        })
+        .collect();
+    // Stable, so decoders as popular as each other stay in the order the search has them
+    decoders.sort_by(|a, b| b.popularity.total_cmp(&a.popularity));
+    decoders
+}
+
+/// Which of the decoders are one of `names`. A name is a decoder's name, ignoring case,
+/// or if no decoder has that name, a tag, so "base64" is the Base64 decoder alone,
+/// while "base" is every decoder tagged "base".
+pub fn decoders_matching(decoders: &[Box<dyn Crack + Send + Sync>], names: &[String]) -> Vec<bool> {
+    let mut matching = vec![false; decoders.len()];
+    for name in names {
+        let by_name: Vec<usize> = (0..decoders.len())
+            .filter(|&index| decoders[index].get_name().eq_ignore_ascii_case(name))
+            .collect();
+        if !by_name.is_empty() {
+            by_name.into_iter().for_each(|index| matching[index] = true);
+            continue;
+        }
+        for (index, decoder) in decoders.iter().enumerate() {
+            if decoder
+                .get_tags()
+                .iter()
+                .any(|tag| tag.eq_ignore_ascii_case(name))
+            {
+                matching[index] = true;
+            }
+        }
+    }
+    matching
+}
+
+/// A name given to pick decoders which is neither a decoder's name nor a tag,
+/// with the names and tags it may have meant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownDecoder {
+    /// The name as it was given
+    pub name: String,
+    /// The closest decoder names and tags, the closest first
+    pub near_matches: Vec<String>,
+}
+
+impl fmt::Display for UnknownDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no decoder is called or tagged \"{}\"", self.name)?;
+        if !self.near_matches.is_empty() {
+            let quoted: Vec<String> = self
+                .near_matches
+                .iter()
+                .map(|near| format!("\"{near}\""))
+                .collect();
+            write!(f, ", did you mean {}?", quoted.join(" or "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownDecoder {}
+
+/// The most near matches an `UnknownDecoder` suggests
+const NEAR_MATCHES: usize = 3;
+
+/// Checks every one of `names` is a decoder's name or tag, ignoring case, see `decoders_matching`.
+/// This doesn't read the config, so it can be checked before the config is set.
+/// # Errors
+/// Returns the first name which isn't, with the names and tags closest to it.
+pub fn check_decoder_names(names: &[String]) -> Result<(), UnknownDecoder> {
+    let decoders = decoders_with_popularity(&HashMap::new());
+    let mut known: Vec<&str> = Vec::new();
+    for decoder in &decoders {
+        known.push(decoder.get_name());
+        known.extend(decoder.get_tags().iter().copied());
+    }
+    // Kept in order when they only differ in case, so the decoder's name comes before the tag
+    known.sort_by_key(|known| known.to_lowercase());
+    // A decoder's name is often a tag too, like "Base64" and "base64"
+    known.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    let Some(unknown) = names
+        .iter()
+        .find(|name| !known.iter().any(|known| known.eq_ignore_ascii_case(name)))
+    else {
+        return Ok(());
+    };
+    let lowercase = unknown.to_lowercase();
+    // Close enough to be a typo, allowing more for longer names
+    let most_edits = (lowercase.chars().count() / 3).max(2);
+    let mut near: Vec<(usize, &str)> = known
+        .into_iter()
+        .map(|known| (edit_distance(&lowercase, &known.to_lowercase()), known))
+        .filter(|&(distance, _)| distance <= most_edits)
+        .collect();
+    near.sort_by_key(|&(distance, _)| distance);
+    Err(UnknownDecoder {
+        name: unknown.clone(),
+        near_matches: near
+            .into_iter()
+            .take(NEAR_MATCHES)
+            .map(|(_, known)| known.to_string())
+            .collect(),
+    })
+}
+
+/// How many characters have to be added, removed, changed or swapped with the next
+/// to turn one text into the other, the optimal string alignment distance.
+/// Swaps count as one, as "base46" is a typo of "base64" more than of "base91".
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance from the first i characters of `a` to the first j of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let changed = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = changed
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_decoder_names, decoders_matching, edit_distance, get_all_decoders,
+        get_decoder_by_name, get_decoders_by_tag, list_decoders,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_decoder_has_a_unique_name_and_a_tag() {
+        let decoders = get_all_decoders();
+        let mut names = HashSet::new();
+        for decoder in &decoders {
+            let name = decoder.get_name();
+            assert!(!name.is_empty());
+            assert!(
+                names.insert(name),
+                "{name} is used by more than one decoder"
+            );
+            assert!(!decoder.get_tags().is_empty(), "{name} has no tags");
+        }
+    }
+
+    #[test]
+    fn decoders_can_be_found_by_tag() {
+        let base_decoders = get_decoders_by_tag("base");
+        assert!(base_decoders
+            .iter()
+            .any(|decoder| decoder.get_name() == "Base64"));
+        assert!(base_decoders
+            .iter()
+            .all(|decoder| decoder.get_tags().contains(&"base")));
+        assert!(get_decoders_by_tag("not a tag").is_empty());
+    }
+
+    #[test]
+    fn decoders_can_be_found_by_name() {
+        let decoder = get_decoder_by_name("Caesar Cipher").unwrap();
+        assert_eq!(decoder.get_name(), "Caesar Cipher");
+        assert!(get_decoder_by_name("Not A Decoder").is_none());
+    }
+
+    #[test]
+    fn every_decoder_is_listed_once_the_most_popular_first() {
+        let listed = list_decoders();
+        let names: HashSet<&str> = listed.iter().map(|decoder| decoder.name.as_str()).collect();
+        assert_eq!(names.len(), listed.len());
+        for decoder in get_all_decoders() {
+            assert!(names.contains(decoder.get_name()), "{}", decoder.get_name());
+        }
+        assert!(listed
+            .windows(2)
+            .all(|pair| pair[0].popularity >= pair[1].popularity));
+    }
+
+    /// The names of the decoders matching `names`
+    fn matching(names: &[&str]) -> Vec<String> {
+        let decoders = get_all_decoders();
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        decoders
+            .iter()
+            .zip(decoders_matching(&decoders, &names))
+            .filter(|(_, matching)| *matching)
+            .map(|(decoder, _)| decoder.get_name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn a_decoder_name_picks_that_decoder_alone() {
+        // Base64 URL and Base64 Custom are tagged "base64" too
+        assert_eq!(matching(&["base64"]), ["Base64"]);
+        assert_eq!(matching(&["CAESAR CIPHER"]), ["Caesar Cipher"]);
+    }
+
+    #[test]
+    fn a_tag_picks_every_decoder_with_it() {
+        let base = matching(&["base"]);
+        assert!(base.contains(&"Base64".to_string()));
+        assert!(base.contains(&"Hexadecimal".to_string()));
+        assert!(!base.contains(&"Morse Code".to_string()));
+        assert_eq!(matching(&["xor"]).len(), 2);
+        let classical = matching(&["classical"]);
+        for name in ["Caesar Cipher", "ROT18", "Vigenère", "A1Z26"] {
+            assert!(classical.contains(&name.to_string()), "{name}");
+        }
+        assert!(matching(&["not a tag"]).is_empty());
+    }
+
+    #[test]
+    fn unknown_names_suggest_near_matches() {
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            check_decoder_names(&names(&["base", "Base64", "xor"])),
+            Ok(())
+        );
+        let unknown = check_decoder_names(&names(&["base", "base46"])).unwrap_err();
+        assert_eq!(unknown.name, "base46");
+        assert_eq!(unknown.near_matches[0], "Base64");
+        assert!(
+            unknown.to_string().contains("did you mean \"Base64\" or"),
+            "{unknown}"
+        );
+        let unknown = check_decoder_names(&names(&["quantum"])).unwrap_err();
+        assert!(unknown.near_matches.is_empty());
+        assert_eq!(
+            unknown.to_string(),
+            "no decoder is called or tagged \"quantum\""
+        );
+    }
+
+    #[test]
+    fn edit_distance_counts_changes() {
+        assert_eq!(edit_distance("base64", "base64"), 0);
+        assert_eq!(edit_distance("base46", "base64"), 1);
+        assert_eq!(edit_distance("base46", "base91"), 2);
+        assert_eq!(edit_distance("caesr", "caesar"), 1);
+        assert_eq!(edit_distance("", "xor"), 3);
+    }
+}
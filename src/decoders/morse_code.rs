@@ -3,38 +3,35 @@ use crate::decoders::interface::check_string_success;
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
-///! Morse Code Decoder
-///! Does not support decoding of morse code with / instead of a space
-///! or new lines for new words.
+/// Morse Code Decoder
+/// Does not support decoding of morse code with / instead of a space
+/// or new lines for new words.
 pub struct MorseCodeDecoder;
 
 impl Crack for Decoder<MorseCodeDecoder> {
     fn new() -> Decoder<MorseCodeDecoder> {
-        Decoder {
-            name: "Morse Code",
-            description: "Morse code is a method used in telecommunication to encode text characters as standardized sequences of two different signal durations, called dots and dashes, or dits and dahs.",
-            link: "https://en.wikipedia.org/wiki/Morse_code",
-            tags: vec!["morseCode", "decoder", "signals"],
-            popularity: 0.5,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("Morse Code")
+            .with_description("Morse code is a method used in telecommunication to encode text characters as standardized sequences of two different signal durations, called dots and dashes, or dits and dahs.")
+            .with_link("https://en.wikipedia.org/wiki/Morse_code")
+            .with_tags(&["morseCode", "decoder", "signals"])
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying Morse Code with text {:?}", text);
         // TODO support new line and slash morse code
         let text = normalise_morse_string(text);
         let decoded_text: Option<String> = text.split(' ').map(morse_to_alphanumeric).collect();
 
         trace!("Decoded text for morse code: {:?}", decoded_text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode Morse Code because a character was not in the dictionary");
@@ -51,19 +48,33 @@ impl Crack for Decoder<MorseCodeDecoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
+    decoder_getters!();
+    /// Morse code is dots, dashes, and slashes and spaces between letters and words.
+    /// Line breaks, written as characters or as "\n" and "\r", are removed before decoding.
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars().all(|c| {
+            matches!(
+                c,
+                '.' | '-' | '/' | ' ' | '\n' | '\r' | '\\' | 'n' | 'r' | 'N' | 'R'
+            )
+        })
     }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    /// Encodes the text as morse code, with '/' between words.
+    /// Returns None if a character has no morse code.
+    fn encode(&self, text: &str) -> Option<String> {
+        text.chars()
+            .map(|c| match c {
+                ' ' => Some("/"),
+                c => alphanumeric_to_morse(c.to_ascii_uppercase()),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|codes| codes.join(" "))
     }
 }
 
@@ -77,6 +88,65 @@ fn normalise_morse_string(text: &str) -> String {
         .replace('\\', "")
 }
 
+/// Maps an uppercase character to its morse code, returns None if it has none
+fn alphanumeric_to_morse(character: char) -> Option<&'static str> {
+    let result = match character {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        '0' => "-----",
+        '&' => ".-...",
+        '@' => ".--.-.",
+        ':' => "---...",
+        ',' => "--..--",
+        '.' => ".-.-.-",
+        '\'' => ".----.",
+        '"' => ".-..-.",
+        '?' => "..--..",
+        '/' => "-..-.",
+        '=' => "-...-",
+        '+' => ".-.-.",
+        '-' => "-....-",
+        '(' => "-.--.",
+        ')' => "-.--.-",
+        '!' => "-.-.--",
+        _ => return None,
+    };
+    Some(result)
+}
+
 /// Maps morse code to its alphanumeric character, returns None for invalid morse-code
 fn morse_to_alphanumeric(text: &str) -> Option<&str> {
     trace!("Starting to map morse code to alphanumeric");
@@ -161,6 +231,16 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn morse_code_encode_round_trips() {
+        let decoder = Decoder::<MorseCodeDecoder>::new();
+        for text in ["HELLO WORLD", "SOS 123!"] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn test_morse_code() {
         let decoder = Decoder::<MorseCodeDecoder>::new();
@@ -258,4 +338,12 @@ mod tests {
         );
         assert_eq!(result.unencrypted_text.unwrap()[0], "hello world!");
     }
+
+    #[test]
+    fn morse_only_attempts_dots_and_dashes() {
+        let decoder = Decoder::<MorseCodeDecoder>::new();
+        assert!(!decoder.can_attempt("... --- ... is morse for SOS"));
+        assert!(!decoder.can_attempt(".... .. \t .... .."));
+        assert!(decoder.can_attempt(".... .. / -- --- .-. ... .\\n.-.-.-\r\n"));
+    }
 }
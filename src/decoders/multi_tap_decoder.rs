@@ -0,0 +1,246 @@
+//! Decode a multi-tap phone keypad string
+//! Performs error handling and returns a string
+//! Call multi_tap_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// What each key types, 0 is a space and 1 types nothing
+const PHONE_KEYPAD: [&str; 10] = [
+    " ", "", "ABC", "DEF", "GHI", "JKL", "MNO", "PQRS", "TUV", "WXYZ",
+];
+
+/// The fewest letters a message can have
+const MIN_MULTI_TAP_LETTERS: usize = 3;
+
+/// Without separators, plain numbers are only decoded if the digits repeat this much on average
+const MIN_PRESSES_PER_RUN: f64 = 1.5;
+
+/// The multi-tap decoder, call:
+/// `let multi_tap_decoder = Decoder::<MultiTapDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = multi_tap_decoder.crack(input)` to decode a multi-tap string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::multi_tap_decoder::MultiTapDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_multi_tap = Decoder::<MultiTapDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_multi_tap.crack("44 33 555 555 666", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "HELLO");
+/// ```
+pub struct MultiTapDecoder;
+
+impl Crack for Decoder<MultiTapDecoder> {
+    fn new() -> Decoder<MultiTapDecoder> {
+        DecoderBuilder::new("Multi-tap")
+            .with_description("Multi-tap is how text was typed on old mobile phone keypads. Each key has several letters and is pressed once for the first letter, twice for the second and so on, so 44 is H and 555 is L. 0 types a space.")
+            .with_link("https://en.wikipedia.org/wiki/Multi-tap")
+            .with_tags(&["multi_tap", "t9", "phone", "decoder"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying multi-tap with text {:?}", text);
+        let decoded_text = decode_multi_tap_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode multi-tap because MultiTapDecoder::decode_multi_tap_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode multi-tap because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// helper function
+/// With whitespace every group is one letter,
+/// otherwise runs of the same digit are split greedily into letters.
+fn decode_multi_tap_no_error_handling(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty()
+        || !text
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_whitespace())
+    {
+        return None;
+    }
+
+    let presses: Vec<(usize, usize)> = if text.contains(char::is_whitespace) {
+        text.split_whitespace()
+            .map(|group| {
+                let key = group.as_bytes()[0];
+                if group.bytes().all(|digit| digit == key) {
+                    Some((usize::from(key - b'0'), group.len()))
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        greedy_presses(text)?
+    };
+
+    let decoded = presses
+        .iter()
+        .map(|&(key, count)| PHONE_KEYPAD[key].chars().nth(count - 1))
+        .collect::<Option<String>>()?;
+    if decoded.trim().len() < MIN_MULTI_TAP_LETTERS {
+        return None;
+    }
+    Some(decoded)
+}
+
+/// Splits unseparated digits into runs, and runs longer than the key into as many full presses as fit.
+fn greedy_presses(digits: &str) -> Option<Vec<(usize, usize)>> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for digit in digits.bytes() {
+        let key = usize::from(digit - b'0');
+        match runs.last_mut() {
+            Some((last_key, count)) if *last_key == key => *count += 1,
+            _ => runs.push((key, 1)),
+        }
+    }
+    if (digits.len() as f64) < runs.len() as f64 * MIN_PRESSES_PER_RUN {
+        return None;
+    }
+
+    let mut presses = Vec::new();
+    for (key, mut count) in runs {
+        let letters = PHONE_KEYPAD[key].len();
+        if letters == 0 {
+            return None;
+        }
+        while count > letters {
+            presses.push((key, letters));
+            count -= letters;
+        }
+        presses.push((key, count));
+    }
+    Some(presses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiTapDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn multi_tap_decodes_separated() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder.crack("44 33 555 555 666", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO");
+    }
+
+    #[test]
+    fn multi_tap_decodes_unseparated() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder.crack("4433555555666", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO");
+    }
+
+    #[test]
+    fn multi_tap_decodes_zero_as_space() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder.crack("44 33 555 555 666 0 9 666 777 555 3", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD");
+    }
+
+    #[test]
+    fn multi_tap_too_many_presses_fails() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder
+            .crack("44 33 5555 555 666", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn multi_tap_one_key_fails() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder
+            .crack("44 33 11 555 666", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn multi_tap_phone_number_fails() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder
+            .crack("+44 7700 900 123", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn multi_tap_handles_panics() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn multi_tap_handles_panic_if_empty_string() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn multi_tap_handles_panic_if_emoji() {
+        let decoder = Decoder::<MultiTapDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
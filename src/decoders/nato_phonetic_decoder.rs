@@ -0,0 +1,276 @@
+//! Decode a message spelt with the NATO phonetic alphabet
+//! Performs error handling and returns a string
+//! Call nato_phonetic_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The share of tokens which have to be NATO words (or separators) before we decode
+const MIN_RECOGNISED_RATIO: f64 = 0.8;
+
+/// The fewest code words a message can have, so a lone "Hotel" isn't decoded
+const MIN_NATO_WORDS: usize = 2;
+
+/// The NATO phonetic alphabet decoder, call:
+/// `let nato_phonetic_decoder = Decoder::<NatoPhoneticDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = nato_phonetic_decoder.crack(input)` to decode a NATO phonetic string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::nato_phonetic_decoder::NatoPhoneticDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_nato = Decoder::<NatoPhoneticDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_nato.crack("Hotel Echo Lima Lima Oscar", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "HELLO");
+/// ```
+pub struct NatoPhoneticDecoder;
+
+impl Crack for Decoder<NatoPhoneticDecoder> {
+    fn new() -> Decoder<NatoPhoneticDecoder> {
+        DecoderBuilder::new("NATO Phonetic")
+            .with_description("The NATO phonetic alphabet assigns a code word to each letter and digit, such as Alfa for A and Niner for 9, so they can be spelt out clearly over the radio. Words are usually separated with Stop or a slash.")
+            .with_link("https://en.wikipedia.org/wiki/NATO_phonetic_alphabet")
+            .with_tags(&["nato", "phonetic", "decoder"])
+            .with_popularity(0.4)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying NATO phonetic with text {:?}", text);
+        let decoded_text = decode_nato_phonetic_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode NATO phonetic because NatoPhoneticDecoder::decode_nato_phonetic_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode NATO phonetic because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// One piece of a NATO message
+enum NatoToken<'a> {
+    /// A code word for a letter or digit
+    Symbol(char),
+    /// "Stop" or "/" between words
+    WordBreak,
+    /// Anything we don't recognise, which is kept as it is
+    Unknown(&'a str),
+}
+
+/// helper function
+/// Returns None unless most of the tokens are NATO code words.
+fn decode_nato_phonetic_no_error_handling(text: &str) -> Option<String> {
+    // X-ray is the only code word with a hyphen, so join it up before splitting on hyphens
+    let text = text.replace("X-ray", "Xray").replace("x-ray", "xray");
+    let tokens: Vec<NatoToken> = text
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            if token == "/" || token.eq_ignore_ascii_case("stop") {
+                NatoToken::WordBreak
+            } else if let Some(symbol) = nato_word_to_char(token) {
+                NatoToken::Symbol(symbol)
+            } else {
+                NatoToken::Unknown(token)
+            }
+        })
+        .collect();
+
+    let symbols = tokens
+        .iter()
+        .filter(|token| matches!(token, NatoToken::Symbol(_)))
+        .count();
+    let unknown = tokens
+        .iter()
+        .filter(|token| matches!(token, NatoToken::Unknown(_)))
+        .count();
+    if symbols < MIN_NATO_WORDS
+        || ((tokens.len() - unknown) as f64) < tokens.len() as f64 * MIN_RECOGNISED_RATIO
+    {
+        return None;
+    }
+
+    let mut decoded = String::new();
+    for token in tokens {
+        match token {
+            NatoToken::Symbol(symbol) => decoded.push(symbol),
+            NatoToken::WordBreak => decoded.push(' '),
+            NatoToken::Unknown(token) => decoded.push_str(token),
+        }
+    }
+    Some(decoded.trim().to_string())
+}
+
+/// Maps a code word, ignoring case, to its letter or digit
+fn nato_word_to_char(word: &str) -> Option<char> {
+    let symbol = match word.to_ascii_lowercase().as_str() {
+        "alfa" | "alpha" => 'A',
+        "bravo" => 'B',
+        "charlie" => 'C',
+        "delta" => 'D',
+        "echo" => 'E',
+        "foxtrot" => 'F',
+        "golf" => 'G',
+        "hotel" => 'H',
+        "india" => 'I',
+        "juliett" | "juliet" => 'J',
+        "kilo" => 'K',
+        "lima" => 'L',
+        "mike" => 'M',
+        "november" => 'N',
+        "oscar" => 'O',
+        "papa" => 'P',
+        "quebec" => 'Q',
+        "romeo" => 'R',
+        "sierra" => 'S',
+        "tango" => 'T',
+        "uniform" => 'U',
+        "victor" => 'V',
+        "whiskey" | "whisky" => 'W',
+        "xray" => 'X',
+        "yankee" => 'Y',
+        "zulu" => 'Z',
+        "zero" => '0',
+        "one" | "wun" => '1',
+        "two" | "too" => '2',
+        "three" | "tree" => '3',
+        "four" | "fower" => '4',
+        "five" | "fife" => '5',
+        "six" => '6',
+        "seven" => '7',
+        "eight" | "ait" => '8',
+        "nine" | "niner" => '9',
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NatoPhoneticDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn nato_decodes_message() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder.crack(
+            "Hotel Echo Lima Lima Oscar Stop Whiskey Oscar Romeo Lima Delta",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO WORLD");
+    }
+
+    #[test]
+    fn nato_decodes_variants_and_hyphens() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder.crack(
+            "alpha-juliet-x-ray / ALFA-JULIETT-XRAY",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "AJX AJX");
+    }
+
+    #[test]
+    fn nato_decodes_digits() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder.crack(
+            "Charlie Alfa Romeo / Niner Two Fower",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "CAR 924");
+    }
+
+    #[test]
+    fn nato_passes_unknown_token_through() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder.crack(
+            "Hotel Echo Lima Lima Oscar Stop ??? Stop Whiskey Oscar Romeo Lima Delta",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO ??? WORLD");
+    }
+
+    #[test]
+    fn nato_english_sentence_fails() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder
+            .crack(
+                "We stayed at a hotel in Lima with one friend from India",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn nato_handles_panics() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn nato_handles_panic_if_empty_string() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn nato_handles_panic_if_emoji() {
+        let decoder = Decoder::<NatoPhoneticDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
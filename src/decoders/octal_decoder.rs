@@ -0,0 +1,218 @@
+//! Decode an octal string
+//! Performs error handling and returns a string
+//! Call octal_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The Octal decoder, call:
+/// `let octal_decoder = Decoder::<OctalDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = octal_decoder.crack(input)` to decode an octal string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::octal_decoder::{OctalDecoder};
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_octal = Decoder::<OctalDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_octal.crack("150 145 154 154 157", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello");
+/// ```
+pub struct OctalDecoder;
+
+impl Crack for Decoder<OctalDecoder> {
+    fn new() -> Decoder<OctalDecoder> {
+        DecoderBuilder::new("Octal")
+            .with_description("The octal numeral system, or oct for short, is the base-8 number system, and uses the digits 0 to 7. Each byte of the text is written as an octal number, either separated by spaces or as backslash escapes like the ones used in shells.")
+            .with_link("https://en.wikipedia.org/wiki/Octal")
+            .with_tags(&["octal", "decoder", "base"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying octal with text {:?}", text);
+        let decoded_text = decode_octal_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode octal because OctalDecoder::decode_octal_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode octal because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+    /// Octal is the digits 0 to 7, separated by whitespace or backslashes
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars()
+            .all(|c| ('0'..='7').contains(&c) || c == '\\' || c.is_whitespace())
+    }
+    /// Encodes every byte of the text as 3 octal digits, separated by spaces
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(
+            text.bytes()
+                .map(|byte| format!("{byte:03o}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+/// helper function
+/// Supports space separated octal (`150 145`) and backslash escaped octal (`\150\145`).
+/// The whole string has to be octal, so sentences which contain numbers are left alone.
+fn decode_octal_no_error_handling(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let groups: Vec<&str> = if text.starts_with('\\') {
+        // The first item is the empty string before the first backslash
+        text.split('\\').skip(1).map(str::trim).collect()
+    } else {
+        text.split_whitespace().collect()
+    };
+
+    let bytes = groups
+        .into_iter()
+        .map(octal_group_to_byte)
+        .collect::<Option<Vec<u8>>>()?;
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Converts a group of 1 to 3 octal digits into a byte
+/// Returns None if the group is not octal or is larger than 0o377
+fn octal_group_to_byte(group: &str) -> Option<u8> {
+    if group.is_empty() || group.len() > 3 || !group.chars().all(|c| ('0'..='7').contains(&c)) {
+        return None;
+    }
+    u8::try_from(u32::from_str_radix(group, 8).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OctalDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn octal_encode_round_trips() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
+    #[test]
+    fn octal_space_separated_decodes_successfully() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("150 145 154 154 157", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn octal_backslash_escaped_decodes_successfully() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("\\150\\145\\154\\154\\157", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello");
+    }
+
+    #[test]
+    fn octal_with_8_or_9_fails() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder
+            .crack("150 145 158 154 197", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn octal_larger_than_a_byte_fails() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder
+            .crack("150 145 400 154 157", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn octal_does_not_decode_english_sentence() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder
+            .crack("I bought 150 apples and 12 pears", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn octal_handles_panic_if_empty_string() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn octal_handles_panic_if_emoji() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn octal_only_attempts_octal_digits() {
+        let decoder = Decoder::<OctalDecoder>::new();
+        assert!(!decoder.can_attempt("150 145 158"));
+        assert!(!decoder.can_attempt("the year 1984"));
+        assert!(decoder.can_attempt("\\150\\145 \\154"));
+    }
+}
@@ -0,0 +1,287 @@
+//! Decode a punycode string
+//! Performs error handling and returns a string
+//! Call punycode_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The prefix which marks a punycode label in a domain name
+const ACE_PREFIX: &str = "xn--";
+
+// Parameters for punycode from RFC 3492 section 5
+/// The number of digits
+const BASE: u32 = 36;
+/// The smallest threshold
+const T_MIN: u32 = 1;
+/// The largest threshold
+const T_MAX: u32 = 26;
+/// Used when adapting the bias
+const SKEW: u32 = 38;
+/// Used when adapting the bias after the first code point
+const DAMP: u32 = 700;
+/// The bias we start with
+const INITIAL_BIAS: u32 = 72;
+/// The first non-ASCII code point
+const INITIAL_N: u32 = 128;
+
+/// The Punycode decoder, call:
+/// `let punycode_decoder = Decoder::<PunycodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = punycode_decoder.crack(input)` to decode a punycode string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::punycode_decoder::PunycodeDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_punycode = Decoder::<PunycodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_punycode.crack("xn--mnchen-3ya.de", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "münchen.de");
+/// ```
+pub struct PunycodeDecoder;
+
+impl Crack for Decoder<PunycodeDecoder> {
+    fn new() -> Decoder<PunycodeDecoder> {
+        DecoderBuilder::new("Punycode")
+            .with_description("Punycode is a representation of Unicode with the limited ASCII character subset used for Internet hostnames. Internationalised domain names have each non-ASCII label encoded with Punycode and prefixed with xn--, which is often abused in phishing domains.")
+            .with_link("https://en.wikipedia.org/wiki/Punycode")
+            .with_tags(&["punycode", "idna", "web", "decoder"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying punycode with text {:?}", text);
+        let decoded_text = decode_punycode_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode punycode because PunycodeDecoder::decode_punycode_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode punycode because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// helper function
+/// Decodes every `xn--` label of a hostname and leaves the other labels alone.
+/// If there are no `xn--` labels, a single bare punycode string is decoded instead.
+/// Returns None if nothing was decoded.
+fn decode_punycode_no_error_handling(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || !text.is_ascii() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let has_ace_label = text
+        .split('.')
+        .any(|label| label.to_ascii_lowercase().starts_with(ACE_PREFIX));
+    if has_ace_label {
+        let labels = text
+            .split('.')
+            .map(|label| {
+                if label.to_ascii_lowercase().starts_with(ACE_PREFIX) {
+                    punycode_decode(&label[ACE_PREFIX.len()..])
+                } else {
+                    Some(label.to_string())
+                }
+            })
+            .collect::<Option<Vec<String>>>()?;
+        return Some(labels.join("."));
+    }
+
+    // Without the prefix anything with a hyphen decodes to something,
+    // so we only accept it if it produced some non-ASCII characters.
+    if text.contains('.') || !text.contains('-') {
+        return None;
+    }
+    punycode_decode(text).filter(|decoded| !decoded.is_ascii())
+}
+
+/// Decodes a single punycode label as described in RFC 3492 section 6.2
+fn punycode_decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(position) => (&input[..position], &input[position + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.bytes();
+
+    while digits.len() > 0 {
+        let old_i = i;
+        let mut weight: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = digit_value(digits.next()?)?;
+            i = i.checked_add(digit.checked_mul(weight)?)?;
+            let threshold = if k <= bias {
+                T_MIN
+            } else if k >= bias + T_MAX {
+                T_MAX
+            } else {
+                k - bias
+            };
+            if digit < threshold {
+                break;
+            }
+            weight = weight.checked_mul(BASE - threshold)?;
+            k += BASE;
+        }
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+/// The bias adaptation function from RFC 3492 section 6.1
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+/// a-z are 0 to 25 and 0-9 are 26 to 35
+fn digit_value(byte: u8) -> Option<u32> {
+    match byte {
+        b'a'..=b'z' => Some(u32::from(byte - b'a')),
+        b'A'..=b'Z' => Some(u32::from(byte - b'A')),
+        b'0'..=b'9' => Some(u32::from(byte - b'0') + 26),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PunycodeDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn punycode_decodes_domain() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder.crack("xn--mnchen-3ya.de", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "münchen.de");
+    }
+
+    #[test]
+    fn punycode_decodes_multi_label_hostname() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder.crack("www.xn--bcher-kva.xn--p1ai", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "www.bücher.рф");
+    }
+
+    #[test]
+    fn punycode_decodes_without_prefix() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder.crack("mnchen-3ya", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "münchen");
+    }
+
+    #[test]
+    fn punycode_decodes_only_non_ascii_characters() {
+        // The Japanese example from RFC 3492
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder.crack(
+            "xn--n8jok5ay5dzabd5bym9f0cm5685rrjetr6pdxa",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "なぜみんな日本語を話してくれないのか"
+        );
+    }
+
+    #[test]
+    fn punycode_ascii_domain_fails() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder
+            .crack("www.example.com", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn punycode_handles_panics() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn punycode_handles_panic_if_empty_string() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn punycode_handles_panic_if_emoji() {
+        let decoder = Decoder::<PunycodeDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
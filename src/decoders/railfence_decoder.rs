@@ -0,0 +1,245 @@
+//! Decode a rail fence cipher string
+//! Performs error handling and returns a string
+//! Call railfence_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{check_candidates, check_string_success};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{info, trace};
+
+/// The most rails we will try
+const MAX_RAILS: usize = 10;
+
+/// Around 38% of the letters in English are vowels, but only about 20% in base encodings
+const MIN_VOWEL_RATIO: f64 = 0.3;
+
+/// The rail fence decoder, call:
+/// `let railfence_decoder = Decoder::<RailFenceDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = railfence_decoder.crack(input)` to decode a rail fence string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::railfence_decoder::RailFenceDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_railfence = Decoder::<RailFenceDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_railfence.crack("wrivdlaneaedsoee,fe toc  cr e e", &checker);
+/// assert_eq!(result.key.as_deref(), Some("rails=3 offset=0"));
+/// // If it succeeds, the 0th element is the plaintext followed by the other candidates
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "we are discovered, flee at once");
+/// ```
+pub struct RailFenceDecoder;
+
+impl Crack for Decoder<RailFenceDecoder> {
+    fn new() -> Decoder<RailFenceDecoder> {
+        DecoderBuilder::new("Rail Fence")
+            .with_description("The rail fence cipher is a transposition cipher which writes the plaintext in a zig-zag across a number of rails and then reads each rail in turn. The key is the number of rails, and optionally where in the zig-zag it starts.")
+            .with_link("https://en.wikipedia.org/wiki/Rail_fence_cipher")
+            .with_tags(&["railfence", "transposition", "classical", "decryption"])
+            .with_popularity(0.4)
+            .with_expected_runtime(0.03)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying rail fence with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        if !has_english_vowel_ratio(text) {
+            info!(
+                "Not trying rail fence on {} as its letters don't look like English",
+                text
+            );
+            return results;
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        let max_rails = MAX_RAILS.min(chars.len().saturating_sub(1));
+        let mut keys = Vec::new();
+        let mut decoded_strings: Vec<String> = Vec::new();
+        for rails in 2..=max_rails {
+            for offset in 0..rails {
+                let decoded_text = railfence_decrypt(&chars, rails, offset);
+                if check_string_success(&decoded_text, text)
+                    && !decoded_strings.contains(&decoded_text)
+                {
+                    keys.push(format!("rails={rails} offset={offset}"));
+                    decoded_strings.push(decoded_text);
+                }
+            }
+        }
+
+        if decoded_strings.is_empty() {
+            info!(
+                "Failed to decode rail fence because no rail count changed the text {}",
+                text
+            );
+            return results;
+        }
+
+        // The rail count the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            if results.success {
+                trace!("Found a match with rail fence {}", keys[index]);
+                results.set_key(keys.swap_remove(index));
+            }
+            decoded_strings[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(decoded_strings);
+        results
+    }
+    decoder_getters!();
+}
+
+/// Rail fence only moves characters around, so the ciphertext keeps the letters of the plaintext.
+/// Checking for a normal share of vowels stops us trying 50 rail counts on things like base64.
+fn has_english_vowel_ratio(text: &str) -> bool {
+    let (vowels, letters) =
+        text.chars()
+            .filter(char::is_ascii_alphabetic)
+            .fold((0, 0), |(vowels, letters), c| {
+                let is_vowel = matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u');
+                (vowels + usize::from(is_vowel), letters + 1)
+            });
+    letters > 0 && vowels as f64 / letters as f64 >= MIN_VOWEL_RATIO
+}
+
+/// Which rail the character at `position` was written on.
+/// The offset is how far into the zig-zag the plaintext started.
+fn rail_for_position(position: usize, rails: usize, offset: usize) -> usize {
+    let cycle = 2 * (rails - 1);
+    let step = (position + offset) % cycle;
+    if step < rails {
+        step
+    } else {
+        cycle - step
+    }
+}
+
+/// Splits the ciphertext back into rails and reads them in zig-zag order.
+/// Every character, including whitespace and punctuation, sits on the fence.
+fn railfence_decrypt(chars: &[char], rails: usize, offset: usize) -> String {
+    // Where each rail starts in the ciphertext, then where its next character is.
+    // There are at most MAX_RAILS, so it's on the stack rather than allocated for every key tried.
+    let mut next = [0; MAX_RAILS];
+    for position in 0..chars.len() {
+        next[rail_for_position(position, rails, offset)] += 1;
+    }
+    let mut start = 0;
+    for next in &mut next[..rails] {
+        (start, *next) = (start + *next, start);
+    }
+
+    let mut decrypted = String::with_capacity(chars.len());
+    for position in 0..chars.len() {
+        let rail = rail_for_position(position, rails, offset);
+        decrypted.push(chars[next[rail]]);
+        next[rail] += 1;
+    }
+    decrypted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn railfence_decrypts_3_rails() {
+        let chars: Vec<char> = "WECRLTEERDSOEEFEAOCAIVDEN".chars().collect();
+        assert_eq!(railfence_decrypt(&chars, 3, 0), "WEAREDISCOVEREDFLEEATONCE");
+    }
+
+    #[test]
+    fn railfence_decodes_3_rails() {
+        let decoder = Decoder::<RailFenceDecoder>::new();
+        let result = decoder.crack("wrivdlaneaedsoee,fe toc  cr e e", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("rails=3 offset=0"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "we are discovered, flee at once"
+        );
+    }
+
+    #[test]
+    fn railfence_decodes_5_rails_with_spaces() {
+        let decoder = Decoder::<RailFenceDecoder>::new();
+        let result = decoder.crack(
+            "hr seeeolsieth fnlwdist tr cl  h  o aleotafi",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.key.as_deref(), Some("rails=5 offset=0"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world this is a test of the rail fence"
+        );
+    }
+
+    #[test]
+    fn railfence_decrypts_with_offset() {
+        let chars: Vec<char> = "od tfrel l si eo  afnhlwrtisas tei ceoh thle"
+            .chars()
+            .collect();
+        assert_eq!(
+            railfence_decrypt(&chars, 4, 2),
+            "hello world this is a test of the rail fence"
+        );
+    }
+
+    #[test]
+    fn railfence_skips_base64() {
+        let decoder = Decoder::<RailFenceDecoder>::new();
+        let result = decoder
+            .crack("aGVsbG8gd29ybGQgaG93IGFyZSB5b3U=", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn railfence_two_characters_fails() {
+        let decoder = Decoder::<RailFenceDecoder>::new();
+        let result = decoder.crack("ae", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn railfence_handles_panic_if_empty_string() {
+        let decoder = Decoder::<RailFenceDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn railfence_handles_panic_if_emoji() {
+        let decoder = Decoder::<RailFenceDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -1,16 +1,22 @@
+//! Reverses the input string
+//! Performs error handling and returns a string
+//! Call reverse_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
 
 use super::crack_results::CrackResult;
-///! Reverses the input string
-///! Performs error handling and returns a string
-///! Call reverse_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{info, trace};
+use unicode_segmentation::UnicodeSegmentation;
 
-use log::trace;
 /// The Reverse decoder is a decoder that reverses the input string.
+/// It reverses graphemes rather than bytes or chars, so emoji and accents survive.
+/// Reversing twice gets you back where you started, so it is tagged as "reciprocal"
+/// and the filtration system won't run it straight after itself.
 /// ```rust
 /// use ares::decoders::reverse_decoder::ReverseDecoder;
 /// use ares::decoders::interface::{Crack, Decoder};
@@ -29,41 +35,41 @@ pub struct ReverseDecoder;
 
 impl Crack for Decoder<ReverseDecoder> {
     fn new() -> Decoder<ReverseDecoder> {
-        Decoder {
-            name: "Reverse",
-            description: "Reverses a string. stac -> cats",
-            link: "http://string-functions.com/reverse.aspx",
-            tags: vec!["reverse", "decoder", "reciprocal"],
+        DecoderBuilder::new("Reverse")
+            .with_description("Reverses a string. stac -> cats")
+            .with_link("http://string-functions.com/reverse.aspx")
+            .with_tags(&["reverse", "decoder", "reciprocal"])
             // I have never seen a reversed string in a CTF
             // or otherwise
-            popularity: 0.2,
-            phantom: std::marker::PhantomData,
-        }
+            .with_popularity(0.2)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Running reverse string");
-        let mut result = CrackResult::new(self, text.to_string());
-        if text.is_empty() {
+        let mut result = CrackResult::new(self);
+        let rev_str: String = text.graphemes(true).rev().collect();
+        // Palindromes and single characters reverse to themselves
+        if !check_string_success(&rev_str, text) {
+            info!(
+                "Failed to reverse because check_string_success returned false on string {}",
+                rev_str
+            );
             return result;
         }
-        let rev_str: String = text.chars().rev().collect();
         let checker_res = checker.check(&rev_str);
 
-        result.unencrypted_text = Some(vec![rev_str]);
+        result = result.with_candidate(rev_str);
         result.update_checker(&checker_res);
         result
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Reversing is its own inverse
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(text.graphemes(true).rev().collect())
     }
 }
 
@@ -75,7 +81,7 @@ mod tests {
             athena::Athena,
             checker_type::{Check, Checker},
         },
-        decoders::interface::Crack,
+        decoders::{base64_decoder::Base64Decoder, interface::Crack},
     };
 
     // helper for tests
@@ -84,6 +90,20 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn reverse_encode_round_trips() {
+        let decoder = Decoder::<ReverseDecoder>::new();
+        for text in [
+            "hello world",
+            "Sphinx of black quartz, judge my vow!",
+            "naïve café 😀",
+        ] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn returns_success() {
         let reverse_decoder = Decoder::<ReverseDecoder>::new();
@@ -102,4 +122,58 @@ mod tests {
             .unencrypted_text;
         assert!(result.is_none());
     }
+
+    #[test]
+    fn reverses_sentence() {
+        let reverse_decoder = Decoder::<ReverseDecoder>::new();
+        let result = reverse_decoder.crack("dlrow olleh", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn reversed_base64_decodes_after_reversing() {
+        let reverse_decoder = Decoder::<ReverseDecoder>::new();
+        let base64_decoder = Decoder::<Base64Decoder>::new();
+        let reversed = reverse_decoder
+            .crack("=QGby92dg8GbsVGa", &get_athena_checker())
+            .unencrypted_text
+            .unwrap();
+        assert_eq!(reversed[0], "aGVsbG8gd29ybGQ=");
+        let result = base64_decoder.crack(&reversed[0], &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn keeps_emoji_and_combining_characters_intact() {
+        let text = "👨\u{200d}👩\u{200d}👧 cafe\u{301} 🇬🇧";
+        let reverse_decoder = Decoder::<ReverseDecoder>::new();
+        let reversed = reverse_decoder
+            .crack(text, &get_athena_checker())
+            .unencrypted_text
+            .unwrap();
+        assert_eq!(reversed[0], "🇬🇧 e\u{301}fac 👨\u{200d}👩\u{200d}👧");
+        let round_trip = reverse_decoder
+            .crack(&reversed[0], &get_athena_checker())
+            .unencrypted_text
+            .unwrap();
+        assert_eq!(round_trip[0], text);
+    }
+
+    #[test]
+    fn palindrome_fails() {
+        let reverse_decoder = Decoder::<ReverseDecoder>::new();
+        let result = reverse_decoder
+            .crack("racecar", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn single_character_fails() {
+        let reverse_decoder = Decoder::<ReverseDecoder>::new();
+        let result = reverse_decoder
+            .crack("👍🏽", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
 }
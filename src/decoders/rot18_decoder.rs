@@ -0,0 +1,208 @@
+//! Decode ROT18, which is ROT13 on letters and ROT5 on digits, and ROT5 on its own
+//! Performs error handling and returns a string
+//! Call rot18_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_candidates;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{info, trace};
+
+/// The ROT18 decoder, call:
+/// `let rot18_decoder = Decoder::<Rot18Decoder>::new()` to create a new instance
+/// And then call:
+/// `result = rot18_decoder.crack(input)` to decode a ROT18 string
+/// The struct generated by new() comes from interface.rs
+///
+/// Caesar fixes the letters but leaves the digits, so this rotates digits by 5 as well.
+/// It returns two candidates, ROT18 (letters by 13 and digits by 5) and ROT5 (only the digits),
+/// with the one the checker identified first, and that one's name as the key.
+/// ```
+/// use ares::decoders::rot18_decoder::Rot18Decoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_rot18 = Decoder::<Rot18Decoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_rot18.crack("Gur zrrgvat vf ng 6585", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "The meeting is at 1030");
+/// ```
+pub struct Rot18Decoder;
+
+impl Crack for Decoder<Rot18Decoder> {
+    fn new() -> Decoder<Rot18Decoder> {
+        DecoderBuilder::new("ROT18")
+            .with_description("ROT18 rotates letters by 13, like ROT13, and digits by 5, which is called ROT5. Both are their own inverse. Puzzles use ROT5 on the digits, with or without ROT13 on the letters, which a Caesar cipher won't undo.")
+            .with_link("https://en.wikipedia.org/wiki/ROT13#Variants")
+            .with_tags(&["rot18", "rot5", "decryption", "classical", "reciprocal"])
+            .with_popularity(0.2)
+            // ROT13 text with digits in it reads as English after ROT18 too, with the digits wrong,
+            // so Caesar's shifts are decoded first
+            .with_expected_success(0.05)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying ROT18 with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        if !has_digits(text) {
+            info!(
+                "Not trying ROT18 on {} because it has no digits, so it is ROT13, which Caesar decodes",
+                text
+            );
+            return results;
+        }
+
+        // Rotating the digits always changes the text, so neither candidate is the text
+        let mut variants = [("ROT18", rot18(text)), ("ROT5", rot5(text))];
+        let candidates: Vec<String> = variants.iter().map(|(_, text)| text.clone()).collect();
+
+        // The variant the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &candidates) {
+            results.update_checker(&checker_result);
+            if results.success {
+                trace!("Found a match with {}", variants[index].0);
+                results.set_key(variants[index].0.to_string());
+            }
+            variants[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(variants.into_iter().map(|(_, text)| text).collect());
+        results
+    }
+    decoder_getters!();
+    /// ROT18 is its own inverse, so encoding is decoding
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(rot18(text))
+    }
+    /// Without digits, ROT18 is ROT13 and ROT5 does nothing
+    fn can_attempt(&self, text: &str) -> bool {
+        has_digits(text)
+    }
+}
+
+/// Whether the text has any ASCII digits in it
+fn has_digits(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Rotates letters by 13 and digits by 5
+fn rot18(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' => (b'a' + (c as u8 - b'a' + 13) % 26) as char,
+            'A'..='Z' => (b'A' + (c as u8 - b'A' + 13) % 26) as char,
+            _ => rotate_digit(c),
+        })
+        .collect()
+}
+
+/// Rotates digits by 5, leaving everything else
+fn rot5(text: &str) -> String {
+    text.chars().map(rotate_digit).collect()
+}
+
+/// The digit 5 on from this one, wrapping 9 round to 4
+fn rotate_digit(c: char) -> char {
+    match c {
+        '0'..='9' => (b'0' + (c as u8 - b'0' + 5) % 10) as char,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rot18, Rot18Decoder};
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn rot18_message_is_decoded() {
+        let decoder = Decoder::<Rot18Decoder>::new();
+        let result = decoder.crack(
+            "Gur zrrgvat vf ng 6585 va ebbz 97 ba gur 8eq sybbe",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("ROT18"));
+        assert_eq!(
+            result.unencrypted_text.unwrap(),
+            [
+                "The meeting is at 1030 in room 42 on the 3rd floor",
+                "Gur zrrgvat vf ng 1030 va ebbz 42 ba gur 3eq sybbe",
+            ]
+        );
+    }
+
+    #[test]
+    fn rot5_message_is_decoded() {
+        let decoder = Decoder::<Rot18Decoder>::new();
+        let result = decoder.crack(
+            "The meeting is at 6585 in room 97 on the 8rd floor",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("ROT5"));
+        assert_eq!(
+            result.unencrypted_text.unwrap(),
+            [
+                "The meeting is at 1030 in room 42 on the 3rd floor",
+                "Gur zrrgvat vf ng 1030 va ebbz 42 ba gur 3eq sybbe",
+            ]
+        );
+    }
+
+    #[test]
+    fn punctuation_is_left_alone() {
+        assert_eq!(
+            rot18("Uryyb, jbeyq! 5 + 5 = 0? (naïve café 😀)"),
+            "Hello, world! 0 + 0 = 5? (anïir pnsé 😀)"
+        );
+    }
+
+    #[test]
+    fn rot18_encode_round_trips() {
+        let decoder = Decoder::<Rot18Decoder>::new();
+        let encoded = decoder.encode("Agent 007, report at 0900!").unwrap();
+        assert_eq!(encoded, "Ntrag 552, ercbeg ng 5455!");
+        assert_eq!(rot18(&encoded), "Agent 007, report at 0900!");
+    }
+
+    #[test]
+    fn text_without_digits_is_left_to_caesar() {
+        let decoder = Decoder::<Rot18Decoder>::new();
+        assert!(!decoder.can_attempt("uryyb jbeyq"));
+        let result = decoder.crack("uryyb jbeyq", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn rot18_decode_empty_string() {
+        let decoder = Decoder::<Rot18Decoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
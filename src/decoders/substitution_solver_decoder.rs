@@ -0,0 +1,385 @@
+//! Solve a simple substitution cipher
+//! Performs error handling and returns a string
+//! Call substitution_solver_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! The key can't be brute forced, so we hill climb towards the key whose plaintext
+//! has the best English quadgram score, restarting a few times from the best key with some letters swapped.
+
+use std::time::Duration;
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+use crate::storage::quadgrams::{average_quadgram_score, quadgram_score};
+use crate::timer::Instant;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+use super::vigenere_decoder::index_of_coincidence;
+
+use log::{debug, info, trace};
+
+/// Below this many letters there isn't enough text for the quadgram score to find the key
+const MIN_SUBSTITUTION_LETTERS: usize = 150;
+
+/// The share of non-whitespace characters which have to be letters
+const MIN_LETTER_RATIO: f64 = 0.7;
+
+/// Ciphertext normally keeps its spaces, so without any this is probably something like base64
+const MAX_AVERAGE_WORD_LENGTH: f64 = 12.0;
+
+/// Text which already scores like this is English, so there's nothing to solve
+const ENGLISH_AVERAGE_QUADGRAM_SCORE: f64 = -5.5;
+
+/// Swapping letters doesn't change how often they repeat, so a substitution cipher keeps
+/// English's index of coincidence of about 0.067, while random text and polyalphabetic ciphers are nearer 0.038
+const MIN_INDEX_OF_COINCIDENCE: f64 = 0.055;
+
+/// The longest we spend solving one text, so the search isn't stalled
+const SOLVER_TIME_BUDGET: Duration = Duration::from_millis(500);
+
+/// How many times we start climbing from a new key
+const MAX_RESTARTS: usize = 20;
+
+/// How many random swaps are made to the best key before climbing again
+const SWAPS_PER_RESTART: usize = 4;
+
+/// We stop restarting once this many climbs in a row end up at the best key again
+const RESTARTS_TO_CONFIRM: usize = 3;
+
+/// Letters from most to least common in English, used to make the first guess
+const ENGLISH_LETTERS_BY_FREQUENCY: &[u8; 26] = b"ETAOINSHRDLCUMWFGYPBVKJXQZ";
+
+/// The substitution solver, call:
+/// `let substitution_solver_decoder = Decoder::<SubstitutionSolverDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = substitution_solver_decoder.crack(input)` to solve a substitution cipher
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::substitution_solver_decoder::SubstitutionSolverDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let solve_substitution = Decoder::<SubstitutionSolverDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // Too short to solve, so it isn't tried
+/// let result = solve_substitution.crack("itp qxovw ygsbf", &checker).unencrypted_text;
+/// assert!(result.is_none());
+/// ```
+pub struct SubstitutionSolverDecoder;
+
+impl Crack for Decoder<SubstitutionSolverDecoder> {
+    fn new() -> Decoder<SubstitutionSolverDecoder> {
+        DecoderBuilder::new("Substitution Solver")
+            .with_description("A simple substitution cipher replaces every letter with another letter using a shuffled alphabet as the key. There are too many keys to try them all, so the key is found by hill climbing on how English the plaintext looks.")
+            .with_link("https://en.wikipedia.org/wiki/Substitution_cipher#Simple_substitution")
+            .with_tags(&["substitution", "classical", "decryption"])
+            .with_popularity(0.3)
+            .with_expected_runtime(0.5)
+            .with_expected_success(0.05)
+            .with_failure_runtime(1.0)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying the substitution solver with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        if !looks_like_substitution_ciphertext(text) {
+            debug!("Not trying the substitution solver because the text is too short, isn't mostly words, has letters which don't repeat like English's or is already English");
+            return results;
+        }
+
+        let key = solve_substitution(text, SOLVER_TIME_BUDGET);
+        let decoded_text = apply_key(text, &key);
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to solve substitution because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results.set_key(format!("key={}", encryption_alphabet(&key)));
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// The solver takes a while, so only run it on long texts made of words which aren't English yet,
+/// with letters repeating as often as they do in English.
+fn looks_like_substitution_ciphertext(text: &str) -> bool {
+    let letters: Vec<u8> = text
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|byte| byte.to_ascii_uppercase() - b'A')
+        .collect();
+    let non_whitespace = text.chars().filter(|c| !c.is_whitespace()).count();
+    let words = text.split_whitespace().count();
+    if letters.len() < MIN_SUBSTITUTION_LETTERS
+        || (letters.len() as f64) < non_whitespace as f64 * MIN_LETTER_RATIO
+        || letters.len() as f64 > words as f64 * MAX_AVERAGE_WORD_LENGTH
+        || index_of_coincidence(&letters) < MIN_INDEX_OF_COINCIDENCE
+    {
+        return false;
+    }
+    average_quadgram_score(text).is_some_and(|score| score < ENGLISH_AVERAGE_QUADGRAM_SCORE)
+}
+
+/// Finds the key which turns the text into the most English looking plaintext.
+/// `key[c]` is the plaintext letter for ciphertext letter `c`, both 0 to 25.
+fn solve_substitution(text: &str, time_budget: Duration) -> [u8; 26] {
+    let started = Instant::now();
+    let ciphertext: Vec<u8> = text
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|byte| byte.to_ascii_uppercase() - b'A')
+        .collect();
+    let mut random = XorShift(0x2545_f491_4f6c_dd1d);
+
+    let mut best_key = frequency_key(&ciphertext);
+    let mut best_score = f64::NEG_INFINITY;
+    let mut times_best_found = 0;
+    let mut plaintext = vec![0; ciphertext.len()];
+
+    for restart in 0..MAX_RESTARTS {
+        // The first climb starts from the frequency guess,
+        // the rest from the best key so far with a few letters swapped
+        let mut key = best_key;
+        if restart > 0 {
+            for _ in 0..SWAPS_PER_RESTART {
+                key.swap(random.below(26), random.below(26));
+            }
+        }
+        let mut score = score_key_into(&ciphertext, &key, &mut plaintext);
+
+        // Try swapping every pair of letters, keeping the swaps which help,
+        // until a whole pass doesn't improve the key or the time is up
+        let mut improved = true;
+        'climb: while improved {
+            improved = false;
+            for a in 0..26 {
+                for b in a + 1..26 {
+                    if started.elapsed() >= time_budget {
+                        break 'climb;
+                    }
+                    key.swap(a, b);
+                    let new_score = score_key_into(&ciphertext, &key, &mut plaintext);
+                    if new_score > score {
+                        score = new_score;
+                        improved = true;
+                    } else {
+                        key.swap(a, b);
+                    }
+                }
+            }
+        }
+
+        // Letters missing from the text can map anywhere, so compare scores rather than keys
+        if (score - best_score).abs() < f64::EPSILON * score.abs() {
+            times_best_found += 1;
+        } else if score > best_score {
+            best_key = key;
+            best_score = score;
+            times_best_found = 1;
+        }
+        if times_best_found >= RESTARTS_TO_CONFIRM {
+            break;
+        }
+        if started.elapsed() >= time_budget {
+            debug!(
+                "The substitution solver ran out of time after {} climbs",
+                restart + 1
+            );
+            break;
+        }
+    }
+    best_key
+}
+
+/// Guesses the key by matching the most common ciphertext letters with the most common English letters
+fn frequency_key(ciphertext: &[u8]) -> [u8; 26] {
+    let mut counts = [0usize; 26];
+    for &letter in ciphertext {
+        counts[usize::from(letter)] += 1;
+    }
+    let mut by_frequency: Vec<usize> = (0..26).collect();
+    by_frequency.sort_by(|&a, &b| counts[b].cmp(&counts[a]));
+
+    let mut key = [0u8; 26];
+    for (cipher_letter, &plain_letter) in by_frequency.iter().zip(ENGLISH_LETTERS_BY_FREQUENCY) {
+        key[*cipher_letter] = plain_letter - b'A';
+    }
+    key
+}
+
+/// The quadgram score of the ciphertext decrypted with the key.
+/// The plaintext buffer is reused so the climb doesn't allocate.
+fn score_key_into(ciphertext: &[u8], key: &[u8; 26], plaintext: &mut [u8]) -> f64 {
+    for (plain, &cipher) in plaintext.iter_mut().zip(ciphertext) {
+        *plain = key[usize::from(cipher)];
+    }
+    quadgram_score(plaintext)
+}
+
+/// Decrypts the text, keeping the case of each letter and everything which isn't a letter
+fn apply_key(text: &str, key: &[u8; 26]) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_uppercase() {
+                char::from(key[usize::from(c as u8 - b'A')] + b'A')
+            } else if c.is_ascii_lowercase() {
+                char::from(key[usize::from(c as u8 - b'a')] + b'a')
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// The key written the usual way, as the ciphertext letter for each plaintext letter A to Z.
+/// Plaintext letters which never appeared are shown as '?'.
+fn encryption_alphabet(key: &[u8; 26]) -> String {
+    let mut alphabet = ['?'; 26];
+    for (cipher_letter, &plain_letter) in key.iter().enumerate() {
+        alphabet[usize::from(plain_letter)] = char::from(b'A' + cipher_letter as u8);
+    }
+    alphabet.iter().collect()
+}
+
+/// A small deterministic random number generator, so the solver gives the same answer every time
+struct XorShift(u64);
+
+impl XorShift {
+    /// A random number from 0 up to but not including `bound`
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    const PLAINTEXT: &str = "it was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, it was the season of light, it was the season of darkness, it was the spring of hope, it was the winter of despair, we had everything before us, we had nothing before us";
+
+    // helper for tests
+    fn encrypt(text: &str, alphabet: &[u8; 26]) -> String {
+        text.chars()
+            .map(|c| {
+                if c.is_ascii_lowercase() {
+                    char::from(alphabet[usize::from(c as u8 - b'a')]).to_ascii_lowercase()
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn substitution_solver_recovers_long_ciphertext() {
+        let decoder = Decoder::<SubstitutionSolverDecoder>::new();
+        let ciphertext = encrypt(PLAINTEXT, b"QWERTYUIOPASDFGHJKLZXCVBNM");
+        let result = decoder.crack(&ciphertext, &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], PLAINTEXT);
+        let key = result.key.unwrap();
+        // Only the letters in the plaintext can be recovered
+        assert!(key.starts_with("key=QWERTYUI"));
+    }
+
+    #[test]
+    fn substitution_solver_skips_short_ciphertext() {
+        let decoder = Decoder::<SubstitutionSolverDecoder>::new();
+        let ciphertext = encrypt("it was the best of times", b"QWERTYUIOPASDFGHJKLZXCVBNM");
+        let result = decoder
+            .crack(&ciphertext, &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn substitution_solver_skips_english() {
+        let decoder = Decoder::<SubstitutionSolverDecoder>::new();
+        let result = decoder
+            .crack(PLAINTEXT, &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    // helper for tests
+    fn random_words() -> String {
+        let mut random = XorShift(42);
+        (0..2000)
+            .map(|index| {
+                if index % 6 == 5 {
+                    ' '
+                } else {
+                    char::from(b'a' + random.below(26) as u8)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn substitution_solver_skips_letters_which_repeat_unlike_english() {
+        let decoder = Decoder::<SubstitutionSolverDecoder>::new();
+        let started = Instant::now();
+        let result = decoder
+            .crack(&random_words(), &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+        // It's turned away before the solver runs
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn substitution_solver_stays_within_time_budget() {
+        let random_text = random_words();
+        let budget = Duration::from_millis(200);
+        let started = Instant::now();
+        solve_substitution(&random_text, budget);
+        // A little slack for the swap which was running when the budget ran out
+        assert!(started.elapsed() < budget + Duration::from_millis(100));
+    }
+
+    #[test]
+    fn substitution_solver_handles_panic_if_empty_string() {
+        let decoder = Decoder::<SubstitutionSolverDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn substitution_solver_handles_panic_if_emoji() {
+        let decoder = Decoder::<SubstitutionSolverDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,235 @@
+//! Decode a tap code string
+//! Performs error handling and returns a string
+//! Call tap_code_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The 5x5 grid used by tap code, K is tapped as C
+const TAP_CODE_GRID: &[u8; 25] = b"ABCDEFGHIJLMNOPQRSTUVWXYZ";
+
+/// The fewest letters a message can have, so two small numbers aren't decoded
+const MIN_TAP_CODE_LETTERS: usize = 2;
+
+/// The tap code decoder, call:
+/// `let tap_code_decoder = Decoder::<TapCodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = tap_code_decoder.crack(input)` to decode a tap code string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::tap_code_decoder::TapCodeDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_tap_code = Decoder::<TapCodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_tap_code.crack("23 15 31 31 34", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "HELLO");
+/// ```
+pub struct TapCodeDecoder;
+
+impl Crack for Decoder<TapCodeDecoder> {
+    fn new() -> Decoder<TapCodeDecoder> {
+        DecoderBuilder::new("Tap Code")
+            .with_description("Tap code encodes letters as pairs of taps giving the row and column in a 5x5 Polybius square, with C and K sharing a square. It was famously used by prisoners of war to communicate through cell walls.")
+            .with_link("https://en.wikipedia.org/wiki/Tap_code")
+            .with_tags(&["tap_code", "polybius", "substitution", "decoder"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying tap code with text {:?}", text);
+        let decoded_text = decode_tap_code_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode tap code because TapCodeDecoder::decode_tap_code_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode tap code because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+    /// Tap code is dots, stars or digits, with whitespace and slashes between them
+    fn can_attempt(&self, text: &str) -> bool {
+        text.chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '*' | '/') || c.is_whitespace())
+    }
+}
+
+/// helper function
+/// Groups are separated by whitespace and letters may be separated by '/'.
+/// Every letter has to be a whole pair of groups.
+fn decode_tap_code_no_error_handling(text: &str) -> Option<String> {
+    let mut decoded = String::new();
+    for letters in text.split('/') {
+        let counts = letters
+            .split_whitespace()
+            .map(tap_group_counts)
+            .collect::<Option<Vec<Vec<usize>>>>()?
+            .concat();
+        if !counts.len().is_multiple_of(2) {
+            return None;
+        }
+        for pair in counts.chunks(2) {
+            decoded.push(char::from(TAP_CODE_GRID[(pair[0] - 1) * 5 + pair[1] - 1]));
+        }
+    }
+
+    if decoded.len() < MIN_TAP_CODE_LETTERS {
+        return None;
+    }
+    Some(decoded)
+}
+
+/// Turns one group into its tap counts.
+/// A group of '.' or '*' is one count, a single digit is one count
+/// and two digits are a whole row and column pair.
+fn tap_group_counts(group: &str) -> Option<Vec<usize>> {
+    let counts: Vec<usize> = if group.chars().all(|c| c == '.') || group.chars().all(|c| c == '*') {
+        vec![group.len()]
+    } else if group.len() <= 2 && group.chars().all(|c| c.is_ascii_digit()) {
+        group
+            .bytes()
+            .map(|digit| usize::from(digit - b'0'))
+            .collect()
+    } else {
+        return None;
+    };
+
+    if counts.iter().all(|count| (1..=5).contains(count)) {
+        Some(counts)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TapCodeDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn tap_code_decodes_dots() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder.crack(
+            ".. ... / . ..... / ... . / ... . / ... ....",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO");
+    }
+
+    #[test]
+    fn tap_code_decodes_stars_without_letter_separators() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder.crack("** *** * ***** *** * *** * *** ****", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO");
+    }
+
+    #[test]
+    fn tap_code_decodes_digit_pairs() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder.crack("23 15 31 31 34", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "HELLO");
+    }
+
+    #[test]
+    fn tap_code_decodes_k_as_c() {
+        // KICK is tapped the same as CICC
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder.crack("13 24 13 13", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "CICC");
+    }
+
+    #[test]
+    fn tap_code_odd_number_of_groups_fails() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder
+            .crack(".. ... . ..... ...", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tap_code_count_above_five_fails() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder
+            .crack("23 15 36 31 34", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tap_code_prose_fails() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tap_code_handles_panic_if_empty_string() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tap_code_handles_panic_if_emoji() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn tap_code_only_attempts_taps() {
+        let decoder = Decoder::<TapCodeDecoder>::new();
+        assert!(!decoder.can_attempt(".. ... tap tap"));
+        assert!(decoder.can_attempt("** ***/23 15\n. ....."));
+    }
+}
@@ -1,14 +1,14 @@
-///! Decode a url encoded string
-///! Performs error handling and returns a string
-///! Call url_decoder.crack to use. It returns option<String> and check with
-///! `result.is_some()` to see if it returned okay.
-///
+//! Decode a url encoded string
+//! Performs error handling and returns a string
+//! Call url_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
 use crate::checkers::CheckerTypes;
 use crate::decoders::interface::check_string_success;
 
 use super::crack_results::CrackResult;
 use super::interface::Crack;
-use super::interface::Decoder;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
 
 use log::{debug, info, trace};
 
@@ -34,25 +34,23 @@ pub struct URLDecoder;
 
 impl Crack for Decoder<URLDecoder> {
     fn new() -> Decoder<URLDecoder> {
-        Decoder {
-            name: "URL",
-            description: "URL encoding, officially known as percent-encoding, is a method to encode arbitrary data in a Uniform Resource Identifier (URI) using only the limited US-ASCII characters legal within a URI.",
-            link: "https://en.wikipedia.org/wiki/URL_encoding",
-            tags: vec!["url", "web", "decoder", "base"],
-            popularity: 0.6,
-            phantom: std::marker::PhantomData,
-        }
+        DecoderBuilder::new("URL")
+            .with_description("URL encoding, officially known as percent-encoding, is a method to encode arbitrary data in a Uniform Resource Identifier (URI) using only the limited US-ASCII characters legal within a URI.")
+            .with_link("https://en.wikipedia.org/wiki/URL_encoding")
+            .with_tags(&["url", "web", "decoder", "base"])
+            .with_popularity(0.6)
+            .build()
     }
 
     /// This function does the actual decoding
     /// It returns an Option<string> if it was successful
     /// Else the Option returns nothing and the error is logged in Trace
-    fn crack(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
         trace!("Trying url with text {:?}", text);
         let decoded_text: Option<String> = decode_url_no_error_handling(text);
 
         trace!("Decoded text for url: {:?}", decoded_text);
-        let mut results = CrackResult::new(self, text.to_string());
+        let mut results = CrackResult::new(self);
 
         if decoded_text.is_none() {
             debug!("Failed to decode url because URLDecoder::decode_url_no_error_handling returned None");
@@ -69,30 +67,72 @@ impl Crack for Decoder<URLDecoder> {
         }
 
         let checker_result = checker.check(&decoded_text);
-        results.unencrypted_text = Some(vec![decoded_text]);
+        results = results.with_candidate(decoded_text);
 
         results.update_checker(&checker_result);
 
         results
     }
-    /// Gets all tags for this decoder
-    fn get_tags(&self) -> &Vec<&str> {
-        &self.tags
-    }
-    /// Gets the name for the current decoder
-    fn get_name(&self) -> &str {
-        self.name
+    decoder_getters!();
+    /// Percent encodes everything apart from the unreserved characters
+    fn encode(&self, text: &str) -> Option<String> {
+        Some(
+            text.bytes()
+                .map(|byte| match byte {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                        char::from(byte).to_string()
+                    }
+                    _ => format!("%{byte:02X}"),
+                })
+                .collect(),
+        )
     }
 }
 
 /// helper function
+/// Percent-decodes the text, and turns `+` into a space if the text looks like a query string.
+/// Malformed sequences such as `%ZZ` or a trailing `%` are kept as literal text like browsers do.
+/// Returns None if nothing was decoded.
 fn decode_url_no_error_handling(text: &str) -> Option<String> {
-    // Runs the code to decode url
-    // Doesn't perform error handling, call from_url
-    if let Ok(decoded_text) = urlencoding::decode(text) {
-        return Some(decoded_text.into_owned());
+    let decode_plus = looks_like_query_string(text);
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut replaced = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex_pair = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|pair| std::str::from_utf8(pair).ok())
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok());
+                if let Some(byte) = hex_pair {
+                    decoded.push(byte);
+                    replaced = true;
+                    i += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            b'+' if decode_plus => {
+                decoded.push(b' ');
+                replaced = true;
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    if !replaced {
+        return None;
     }
-    None
+    String::from_utf8(decoded).ok()
+}
+
+/// A query string has no literal spaces and contains a `key=value` pair.
+/// Trailing `=` are ignored so base64 padding isn't mistaken for a query string.
+fn looks_like_query_string(text: &str) -> bool {
+    !text.contains(char::is_whitespace) && text.trim_end_matches('=').contains('=')
 }
 
 #[cfg(test)]
@@ -113,6 +153,16 @@ mod tests {
         CheckerTypes::CheckAthena(athena_checker)
     }
 
+    #[test]
+    fn url_encode_round_trips() {
+        let decoder = Decoder::<URLDecoder>::new();
+        for text in ["hello world & goodbye?", "naïve café 😀"] {
+            let encoded = decoder.encode(text).unwrap();
+            let result = decoder.crack(&encoded, &get_athena_checker());
+            assert_eq!(result.unencrypted_text.unwrap()[0], text);
+        }
+    }
+
     #[test]
     fn url_decodes_successfully() {
         // This tests if URL can decode URL successfully
@@ -127,6 +177,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn url_decodes_fully_encoded_string() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack("%48%65%6c%6c%6f", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "Hello");
+    }
+
+    #[test]
+    fn url_decodes_mixed_string() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack("hello%20world%21", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world!");
+    }
+
+    #[test]
+    fn url_decodes_plus_in_query_string() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack("q=hello+world&lang=en", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "q=hello world&lang=en");
+    }
+
+    #[test]
+    fn url_keeps_plus_outside_query_string() {
+        // No key=value pair, so + is not a space
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack("1+1%3D2", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "1+1=2");
+    }
+
+    #[test]
+    fn url_keeps_invalid_sequences_as_literal_text() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder.crack("100%G1%20done%", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "100%G1 done%");
+    }
+
+    #[test]
+    fn url_fails_if_nothing_was_decoded() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder
+            .crack("100%G1", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn url_does_not_treat_base64_padding_as_query_string() {
+        let url_decoder = Decoder::<URLDecoder>::new();
+        let result = url_decoder
+            .crack("aGk+b2s=", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
     #[test]
     fn url_handles_panics() {
         // This tests if URL can handle panics
@@ -0,0 +1,227 @@
+//! Decode a uuencoded string
+//! Performs error handling and returns a string
+//! Call uuencode_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// The UUencode decoder, call:
+/// `let uuencode_decoder = Decoder::<UuencodeDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = uuencode_decoder.crack(input)` to decode a uuencoded string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::uuencode_decoder::UuencodeDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_uuencode = Decoder::<UuencodeDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_uuencode.crack("begin 644 hello.txt\n+:&5L;&\\@=V]R;&0`\n`\nend", &checker).unencrypted_text;
+/// assert!(result.is_some());
+/// assert_eq!(result.unwrap()[0], "hello world");
+/// ```
+pub struct UuencodeDecoder;
+
+impl Crack for Decoder<UuencodeDecoder> {
+    fn new() -> Decoder<UuencodeDecoder> {
+        DecoderBuilder::new("UUencode")
+            .with_description("Uuencoding is a form of binary-to-text encoding that originated in the Unix program uuencode. Each line starts with a character giving its length, followed by groups of 4 characters which each encode 3 bytes. Files are framed by begin and end lines.")
+            .with_link("https://en.wikipedia.org/wiki/Uuencoding")
+            .with_tags(&["uuencode", "decoder", "base"])
+            .with_popularity(0.3)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying UUencode with text {:?}", text);
+        let decoded_text = decode_uuencode_no_error_handling(text);
+        let mut results = CrackResult::new(self);
+
+        if decoded_text.is_none() {
+            debug!("Failed to decode UUencode because UuencodeDecoder::decode_uuencode_no_error_handling returned None");
+            return results;
+        }
+
+        let decoded_text = decoded_text.unwrap();
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode UUencode because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// helper function
+/// Decodes either a full `begin ... end` block or bare uuencoded lines.
+fn decode_uuencode_no_error_handling(text: &str) -> Option<String> {
+    let mut lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if lines.first()?.starts_with("begin ") {
+        lines.remove(0);
+        if lines.pop()? != "end" {
+            return None;
+        }
+    }
+
+    let mut bytes = Vec::new();
+    for line in lines {
+        let line = line.as_bytes();
+        let length = usize::from(uu_value(line[0])?);
+        if length == 0 {
+            // A zero length line marks the end of the data
+            break;
+        }
+        let encoded = &line[1..];
+        let needed = length.div_ceil(3) * 4;
+        // Some encoders add a checksum character at the end of each line
+        if encoded.len() != needed && encoded.len() != needed + 1 {
+            return None;
+        }
+
+        let mut line_bytes = Vec::with_capacity(needed / 4 * 3);
+        for group in encoded[..needed].chunks(4) {
+            let values = group
+                .iter()
+                .map(|&c| uu_value(c))
+                .collect::<Option<Vec<u8>>>()?;
+            line_bytes.push((values[0] << 2) | (values[1] >> 4));
+            line_bytes.push((values[1] << 4) | (values[2] >> 2));
+            line_bytes.push((values[2] << 6) | values[3]);
+        }
+        line_bytes.truncate(length);
+        bytes.extend(line_bytes);
+    }
+
+    if bytes.is_empty() {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Each character stores 6 bits as its value minus 0x20.
+/// A backtick is used instead of a space for zero.
+fn uu_value(c: u8) -> Option<u8> {
+    match c {
+        0x20..=0x60 => Some((c - 0x20) & 0x3F),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UuencodeDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn uuencode_decodes_framed_input() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder.crack(
+            "begin 644 hello.txt\n+:&5L;&\\@=V]R;&0`\n`\nend\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn uuencode_decodes_bare_line() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder.crack("+:&5L;&\\@=V]R;&0`", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn uuencode_decodes_space_as_zero() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder.crack("+:&5L;&\\@=V]R;&0 ", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn uuencode_decodes_truncated_final_line() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder.crack(
+            "M5&AE('%U:6-K(&)R;W=N(&9O>\"!J=6UP<R!O=F5R('1H92!L87IY(&1O9R!A\n9;F0@:V5E<',@<G5N;FEN9R!F87(@87=A>0``\n`\n",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "The quick brown fox jumps over the lazy dog and keeps running far away"
+        );
+    }
+
+    #[test]
+    fn uuencode_wrong_length_fails() {
+        // The length character says 15 bytes but only 12 are encoded
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder
+            .crack("/:&5L;&\\@=V]R;&0`", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn uuencode_sentence_fails() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder
+            .crack(
+                "hello my name is panicky mc panic face!",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn uuencode_handles_panic_if_empty_string() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn uuencode_handles_panic_if_emoji() {
+        let decoder = Decoder::<UuencodeDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
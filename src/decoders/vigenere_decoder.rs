@@ -0,0 +1,332 @@
+//! Decode a Vigenère cipher string
+//! Performs error handling and returns a string
+//! Call vigenere_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! We first try a list of commonly used keys, and for longer texts
+//! we recover the key with the index of coincidence and frequency analysis.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// Keys which are commonly used in CTFs and puzzles.
+/// These are tried first as they work on texts of any length.
+const COMMON_KEYS: [&str; 32] = [
+    "KEY", "LEMON", "SECRET", "PASSWORD", "CIPHER", "VIGENERE", "CRYPTO", "FLAG", "CTF", "ARES",
+    "CIPHEY", "HELLO", "ABC", "ABCD", "TEST", "ADMIN", "ALPHA", "CODE", "HIDDEN", "MAGIC",
+    "ORANGE", "APPLE", "BANANA", "DRAGON", "MONKEY", "SHADOW", "MASTER", "QWERTY", "LETMEIN",
+    "KING", "QUEEN", "PUZZLE",
+];
+
+/// Below this many letters the statistics are too noisy to recover a key
+const MIN_LETTERS_FOR_FREQUENCY_ANALYSIS: usize = 100;
+
+/// The longest key we will try to recover with frequency analysis
+const MAX_KEY_LENGTH: usize = 20;
+
+/// English text has an index of coincidence of about 0.067 and random text about 0.038.
+/// Columns scoring above this were probably all shifted by the same key letter.
+const ENGLISH_INDEX_OF_COINCIDENCE_THRESHOLD: f64 = 0.06;
+
+/// How often each letter appears in English text, A to Z
+pub(crate) const ENGLISH_LETTER_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// The Vigenère decoder, call:
+/// `let vigenere_decoder = Decoder::<VigenereDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = vigenere_decoder.crack(input)` to decode a Vigenère string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::vigenere_decoder::VigenereDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_vigenere = Decoder::<VigenereDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_vigenere.crack("sxvrgd llg tellpg rx wsap", &checker);
+/// assert_eq!(result.key.as_deref(), Some("SECRET"));
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "attack the castle at dawn");
+/// ```
+pub struct VigenereDecoder;
+
+impl Crack for Decoder<VigenereDecoder> {
+    fn new() -> Decoder<VigenereDecoder> {
+        DecoderBuilder::new("Vigenère")
+            .with_description("The Vigenère cipher is a polyalphabetic substitution cipher which shifts each letter by the corresponding letter of a repeating key. It was known as le chiffrage indéchiffrable for three centuries until Kasiski and Babbage broke it.")
+            .with_link("https://en.wikipedia.org/wiki/Vigen%C3%A8re_cipher")
+            .with_tags(&["vigenere", "substitution", "classical", "decryption"])
+            .with_popularity(0.6)
+            .with_expected_runtime(0.1)
+            .with_expected_success(0.2)
+            .with_failure_runtime(0.2)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying Vigenère with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        let letter_count = text.chars().filter(char::is_ascii_alphabetic).count();
+        if letter_count == 0 {
+            debug!("Not trying Vigenère as the text has no letters");
+            return results;
+        }
+
+        for key in COMMON_KEYS {
+            let decoded_text = vigenere_decrypt(text, key);
+            if !check_string_success(&decoded_text, text) {
+                continue;
+            }
+            let checker_result = checker.check(&decoded_text);
+            if checker_result.is_identified {
+                trace!("Found a match with Vigenère key {}", key);
+                results = results.with_candidate(decoded_text);
+                results.set_key(key.to_string());
+                results.update_checker(&checker_result);
+                return results;
+            }
+        }
+
+        if letter_count < MIN_LETTERS_FOR_FREQUENCY_ANALYSIS {
+            info!(
+                "Failed to decode Vigenère as no common key worked and {} letters is too short for frequency analysis",
+                letter_count
+            );
+            return results;
+        }
+
+        let key = match recover_key(text) {
+            Some(key) => key,
+            None => return results,
+        };
+        let decoded_text = vigenere_decrypt(text, &key);
+        if !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode Vigenère because check_string_success returned false on string {}",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+        results.set_key(key);
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// Decrypts the text with the key, preserving case and leaving non-letters alone.
+/// The key only advances on letters, which is how the cipher is normally used.
+fn vigenere_decrypt(text: &str, key: &str) -> String {
+    let key = key.as_bytes();
+    let mut key_index = 0;
+    let mut decrypted = String::with_capacity(text.len());
+    decrypted.extend(text.chars().map(|c| {
+        if !c.is_ascii_alphabetic() {
+            return c;
+        }
+        let first = if c.is_ascii_lowercase() { b'a' } else { b'A' };
+        let shift = key[key_index % key.len()].to_ascii_uppercase() - b'A';
+        key_index += 1;
+        (first + (c as u8 - first + 26 - shift) % 26) as char
+    }));
+    decrypted
+}
+
+/// Estimates the key length with the index of coincidence,
+/// then finds each letter of the key with a chi-squared test against English.
+fn recover_key(text: &str) -> Option<String> {
+    let letters: Vec<u8> = text
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase() - b'A')
+        .collect();
+
+    let key_length = estimate_key_length(&letters)?;
+    debug!("Vigenère key length is probably {}", key_length);
+
+    (0..key_length)
+        .map(|column| {
+            let column: Vec<u8> = letters
+                .iter()
+                .skip(column)
+                .step_by(key_length)
+                .copied()
+                .collect();
+            let shift = (0..26u8)
+                .min_by(|&a, &b| chi_squared(&column, a).total_cmp(&chi_squared(&column, b)))?;
+            Some((b'A' + shift) as char)
+        })
+        .collect()
+}
+
+/// Multiples of the real key length score as well as the key length itself,
+/// and short columns score higher by chance, so we take the shortest length
+/// which looks like English rather than the highest scoring one.
+fn estimate_key_length(letters: &[u8]) -> Option<usize> {
+    let scores: Vec<(usize, f64)> = (1..=MAX_KEY_LENGTH.min(letters.len() / 2))
+        .map(|length| {
+            let average = (0..length)
+                .map(|column| {
+                    let column: Vec<u8> = letters
+                        .iter()
+                        .skip(column)
+                        .step_by(length)
+                        .copied()
+                        .collect();
+                    index_of_coincidence(&column)
+                })
+                .sum::<f64>()
+                / length as f64;
+            (length, average)
+        })
+        .collect();
+
+    scores
+        .iter()
+        .find(|&&(_, score)| score >= ENGLISH_INDEX_OF_COINCIDENCE_THRESHOLD)
+        .or_else(|| scores.iter().max_by(|a, b| a.1.total_cmp(&b.1)))
+        .map(|&(length, _)| length)
+}
+
+/// The chance that two letters picked at random from the text are the same.
+/// English is about 0.067, random text is about 0.038.
+pub(crate) fn index_of_coincidence(letters: &[u8]) -> f64 {
+    let length = letters.len() as f64;
+    if length < 2.0 {
+        return 0.0;
+    }
+    let mut counts = [0usize; 26];
+    for &letter in letters {
+        counts[usize::from(letter)] += 1;
+    }
+    let matches: f64 = counts
+        .iter()
+        .map(|&count| (count * count.saturating_sub(1)) as f64)
+        .sum();
+    matches / (length * (length - 1.0))
+}
+
+/// How far the letters shifted back by `shift` are from English letter frequencies.
+/// Lower is more English.
+fn chi_squared(letters: &[u8], shift: u8) -> f64 {
+    let mut counts = [0usize; 26];
+    for &letter in letters {
+        counts[usize::from((letter + 26 - shift) % 26)] += 1;
+    }
+    let length = letters.len() as f64;
+    counts
+        .iter()
+        .zip(ENGLISH_LETTER_FREQUENCIES)
+        .map(|(&count, frequency)| {
+            let expected = frequency * length;
+            (count as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    // "It was the best of times..." from A Tale of Two Cities, encrypted with LEMON
+    const LEMON_CIPHERTEXT: &str = "Tx iof elq prdx at gtqqg, ve amg gsi icedx at gtqqg, ve amg gsi mur zj iwfosy, wg hee hup ess bq jacytwtbrdw, uh jlw fvr ptaqu zj nsytir, wg hee hup ibcps sr wanvqrhwmfm, ve amg gsi esndsz cs wmsvg, tx iof elq grlwab bq hmfxyieg.";
+
+    #[test]
+    fn vigenere_decrypts() {
+        assert_eq!(
+            vigenere_decrypt("LXFOPV EF RNHR", "LEMON"),
+            "ATTACK AT DAWN"
+        );
+    }
+
+    #[test]
+    fn vigenere_decodes_long_text_with_lemon() {
+        let decoder = Decoder::<VigenereDecoder>::new();
+        let result = decoder.crack(LEMON_CIPHERTEXT, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("LEMON"));
+        assert!(result.unencrypted_text.unwrap()[0]
+            .starts_with("It was the best of times, it was the worst of times"));
+    }
+
+    #[test]
+    fn vigenere_recovers_lemon_with_frequency_analysis() {
+        assert_eq!(recover_key(LEMON_CIPHERTEXT).as_deref(), Some("LEMON"));
+    }
+
+    #[test]
+    fn vigenere_decodes_short_text_with_dictionary_key() {
+        let decoder = Decoder::<VigenereDecoder>::new();
+        let result = decoder.crack("sxvrgd llg tellpg rx wsap", &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("SECRET"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "attack the castle at dawn"
+        );
+    }
+
+    #[test]
+    fn vigenere_does_not_crack_random_letters() {
+        let decoder = Decoder::<VigenereDecoder>::new();
+        let result = decoder.crack(
+            "qzxvkjwpmfbgtlhycrdnoseuaiqzxvkjwpmfbgtlhycrdnoseuai",
+            &get_athena_checker(),
+        );
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn vigenere_skips_text_without_letters() {
+        let decoder = Decoder::<VigenereDecoder>::new();
+        let result = decoder
+            .crack("1234 5678 !?", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn vigenere_handles_panic_if_empty_string() {
+        let decoder = Decoder::<VigenereDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn vigenere_handles_panic_if_emoji() {
+        let decoder = Decoder::<VigenereDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
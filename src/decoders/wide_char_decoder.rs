@@ -0,0 +1,370 @@
+//! Decode UTF-16 and UTF-32 text, like what Windows and PowerShell's `-EncodedCommand` use
+//! Performs error handling and returns a string
+//! Call wide_char_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! Binary data from decoders like base64 and hexadecimal comes in through `crack_bytes`,
+//! and text is read as Latin-1, with a replacement character standing for a byte which was lost.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+
+use log::{debug, info, trace};
+
+/// Text shorter than this many bytes is too short to tell wide characters from NULs by chance
+const MIN_BYTES: usize = 8;
+
+/// Without a byte order mark, at least this share of the characters have to look like
+/// wide ASCII, such as "h\0" in UTF-16LE, so text with the odd NUL in it isn't mangled
+const MIN_PATTERN_RATIO: f64 = 0.9;
+
+/// The Wide Characters decoder, call:
+/// `let wide_char_decoder = Decoder::<WideCharDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = wide_char_decoder.crack(input)` to decode UTF-16 or UTF-32
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::wide_char_decoder::WideCharDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_wide_chars = Decoder::<WideCharDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // "hello world" in UTF-16LE
+/// let result = decode_wide_chars.crack_bytes(b"h\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0", &checker);
+/// assert_eq!(result.key.as_deref(), Some("UTF-16LE"));
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+/// ```
+pub struct WideCharDecoder;
+
+impl Crack for Decoder<WideCharDecoder> {
+    fn new() -> Decoder<WideCharDecoder> {
+        DecoderBuilder::new("Wide Characters")
+            .with_description("UTF-16 and UTF-32 store each character in 2 or 4 bytes, so ASCII text has NUL bytes between its letters. Windows stores text as UTF-16LE, and PowerShell's -EncodedCommand is base64 of it.")
+            .with_link("https://en.wikipedia.org/wiki/UTF-16")
+            .with_tags(&["utf16", "utf32", "unicode", "windows", "decoder"])
+            .with_popularity(0.4)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying wide characters with text {:?}", text);
+        let decoded = latin1_to_bytes(text).and_then(|bytes| decode_wide_no_error_handling(&bytes));
+        crack_wide(CrackResult::new(self), text, decoded, checker)
+    }
+    /// UTF-16 and UTF-32 aren't UTF-8, so decode the bytes other decoders produced directly
+    fn accepts_bytes(&self) -> bool {
+        true
+    }
+    /// Decodes the bytes, rather than reading them from text
+    fn decode_bytes(&self, data: &[u8], checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying wide characters with {} bytes", data.len());
+        let bytes: Vec<Option<u8>> = data.iter().copied().map(Some).collect();
+        let decoded = decode_wide_no_error_handling(&bytes);
+        let original = String::from_utf8_lossy(data);
+        crack_wide(CrackResult::new(self), &original, decoded, checker)
+    }
+    decoder_getters!();
+    /// Wide ASCII has NULs between its letters, otherwise it starts with a byte order mark
+    fn can_attempt(&self, text: &str) -> bool {
+        text.contains('\0') || text.starts_with("\u{ff}\u{fe}") || text.starts_with("\u{fe}\u{ff}")
+    }
+}
+
+/// Checks what `decode_wide_no_error_handling` decoded from `original`,
+/// whether it came from text or bytes
+fn crack_wide(
+    mut results: CrackResult,
+    original: &str,
+    decoded: Option<(Encoding, String)>,
+    checker: &CheckerTypes,
+) -> CrackResult {
+    let Some((encoding, decoded_text)) = decoded else {
+        debug!("Failed to decode wide characters because WideCharDecoder::decode_wide_no_error_handling returned None");
+        return results;
+    };
+
+    if !check_string_success(&decoded_text, original) {
+        info!(
+            "Failed to decode wide characters because check_string_success returned false on string {}",
+            decoded_text
+        );
+        return results;
+    }
+
+    let checker_result = checker.check(&decoded_text);
+    results.set_key(encoding.name().to_string());
+    results = results.with_candidate(decoded_text);
+
+    results.update_checker(&checker_result);
+
+    results
+}
+
+/// The encodings of wide characters we decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// 4 bytes a character, the lowest first
+    Utf32Le,
+    /// 2 bytes a character, the lowest first, as Windows uses
+    Utf16Le,
+    /// 2 bytes a character, the highest first
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Every encoding, UTF-32 first, as wide ASCII in it has the NULs UTF-16 has and more
+    const ALL: [Encoding; 3] = [Encoding::Utf32Le, Encoding::Utf16Le, Encoding::Utf16Be];
+
+    /// The encoding's name, which is the decoder's key
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf32Le => "UTF-32LE",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+        }
+    }
+
+    /// How many bytes each character takes
+    fn unit_size(self) -> usize {
+        match self {
+            Encoding::Utf32Le => 4,
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+        }
+    }
+
+    /// The bytes which are NUL when the character is ASCII
+    fn zero_bytes(self) -> &'static [usize] {
+        match self {
+            Encoding::Utf32Le => &[1, 2, 3],
+            Encoding::Utf16Le => &[1],
+            Encoding::Utf16Be => &[0],
+        }
+    }
+
+    /// The byte order mark text in the encoding starts with
+    fn byte_order_mark(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf32Le => &[0xff, 0xfe, 0, 0],
+            Encoding::Utf16Le => &[0xff, 0xfe],
+            Encoding::Utf16Be => &[0xfe, 0xff],
+        }
+    }
+
+    /// The character the bytes of one unit are, or a replacement character if they aren't one.
+    /// A lost byte is None, and the character it was in is lost with it.
+    fn code_point(self, unit: &[Option<u8>]) -> u32 {
+        if unit.contains(&None) {
+            return u32::from(char::REPLACEMENT_CHARACTER);
+        }
+        let byte = |index: usize| unit[index].unwrap_or_default();
+        match self {
+            Encoding::Utf32Le => u32::from_le_bytes([byte(0), byte(1), byte(2), byte(3)]),
+            Encoding::Utf16Le => u32::from(u16::from_le_bytes([byte(0), byte(1)])),
+            Encoding::Utf16Be => u32::from(u16::from_be_bytes([byte(0), byte(1)])),
+        }
+    }
+
+    /// Whether the unit is an ASCII character other than NUL, like "h\0" in UTF-16LE
+    fn is_wide_ascii(self, unit: &[Option<u8>]) -> bool {
+        unit.iter().enumerate().all(|(index, byte)| {
+            if self.zero_bytes().contains(&index) {
+                *byte == Some(0)
+            } else {
+                byte.is_some_and(|byte| byte != 0 && byte.is_ascii())
+            }
+        })
+    }
+}
+
+/// helper function
+/// Works out the encoding from the byte order mark, or from the NULs wide ASCII has,
+/// and decodes the bytes with it. A None byte is one which was lost.
+/// Returns None if they aren't wide characters.
+fn decode_wide_no_error_handling(bytes: &[Option<u8>]) -> Option<(Encoding, String)> {
+    if bytes.len() < MIN_BYTES {
+        return None;
+    }
+    let (encoding, start) = Encoding::ALL
+        .into_iter()
+        .find(|encoding| {
+            let mark = encoding.byte_order_mark();
+            bytes.len() >= mark.len()
+                && bytes
+                    .iter()
+                    .zip(mark)
+                    .all(|(byte, mark)| *byte == Some(*mark))
+        })
+        .map(|encoding| (encoding, encoding.byte_order_mark().len()))
+        .or_else(|| {
+            Encoding::ALL
+                .into_iter()
+                .find(|&encoding| looks_like(bytes, encoding))
+                .map(|encoding| (encoding, 0))
+        })?;
+
+    let units = bytes[start..].chunks_exact(encoding.unit_size());
+    let decoded = match encoding {
+        Encoding::Utf32Le => units
+            .map(|unit| {
+                char::from_u32(encoding.code_point(unit)).unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect(),
+        // Each unit fits in a u16, and characters outside the BMP take two of them
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            char::decode_utf16(units.map(|unit| encoding.code_point(unit) as u16))
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+    };
+    Some((encoding, decoded))
+}
+
+/// Whether the bytes are wide ASCII in the encoding, with at most the odd character which isn't
+fn looks_like(bytes: &[Option<u8>], encoding: Encoding) -> bool {
+    let unit_size = encoding.unit_size();
+    if !bytes.len().is_multiple_of(unit_size) {
+        return false;
+    }
+    let units = bytes.len() / unit_size;
+    let wide_ascii = bytes
+        .chunks_exact(unit_size)
+        .filter(|unit| encoding.is_wide_ascii(unit))
+        .count();
+    wide_ascii as f64 >= units as f64 * MIN_PATTERN_RATIO
+}
+
+/// The bytes of text read as Latin-1, with None for a replacement character, as its byte was lost.
+/// Returns None if any other character doesn't fit in a byte, as then the text was never wide characters.
+fn latin1_to_bytes(text: &str) -> Option<Vec<Option<u8>>> {
+    text.chars()
+        .map(|c| match u8::try_from(c) {
+            Ok(byte) => Some(Some(byte)),
+            Err(_) if c == char::REPLACEMENT_CHARACTER => Some(None),
+            Err(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WideCharDecoder;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn utf16le_bytes_are_decoded() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let result = decoder.crack_bytes(&utf16le("hello there general"), &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("UTF-16LE"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello there general");
+    }
+
+    #[test]
+    fn utf16be_with_a_byte_order_mark_is_decoded() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        // The byte order mark says which way round the bytes are, so it needn't be ASCII
+        let mut bytes = vec![0xfe, 0xff];
+        bytes.extend(
+            "naïve café, ça va"
+                .encode_utf16()
+                .flat_map(u16::to_be_bytes),
+        );
+        let result = decoder.crack_bytes(&bytes, &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("UTF-16BE"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "naïve café, ça va");
+    }
+
+    #[test]
+    fn utf32le_is_decoded() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let bytes: Vec<u8> = "hello world"
+            .chars()
+            .flat_map(|c| u32::from(c).to_le_bytes())
+            .collect();
+        let result = decoder.crack_bytes(&bytes, &get_athena_checker());
+        assert_eq!(result.key.as_deref(), Some("UTF-32LE"));
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn text_with_nuls_between_the_letters_is_decoded() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let result = decoder.crack("h\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0", &get_athena_checker());
+        assert_eq!(result.unencrypted_text.unwrap()[0], "hello world");
+    }
+
+    #[test]
+    fn a_lost_byte_is_a_replacement_character() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        // "é" in UTF-16LE is E9 00, which isn't UTF-8, so a lossy copy lost the E9
+        let result = decoder.crack(
+            "c\0a\0f\0\u{fffd}\0 \0o\0p\0e\0n\0 \0t\0o\0d\0a\0y\0",
+            &get_athena_checker(),
+        );
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "caf\u{fffd} open today"
+        );
+    }
+
+    #[test]
+    fn ascii_is_not_wide() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        assert!(!decoder.can_attempt("hello world"));
+        let result = decoder.crack_bytes(b"hello world", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn the_odd_nul_in_text_is_left_alone() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let result = decoder.crack("hello\0 world, how are you\0", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn short_text_is_left_alone() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let result = decoder.crack_bytes(b"h\0i\0", &get_athena_checker());
+        assert!(result.unencrypted_text.is_none());
+    }
+
+    #[test]
+    fn wide_char_handles_panic_if_empty_string() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn wide_char_handles_panic_if_emoji() {
+        let decoder = Decoder::<WideCharDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,312 @@
+//! Decode a string XOR'd with a repeating key
+//! Performs error handling and returns a string
+//! Call xor_repeating_key_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! The key length is estimated with the Hamming distance between blocks,
+//! then every byte of the key is solved as a single byte XOR.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::check_string_success;
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+use super::xor_single_byte_decoder::{english_score, hex_to_bytes};
+
+use base64::{engine::general_purpose, Engine as _};
+use log::{debug, info, trace};
+
+/// Below this many bytes the statistics fall apart
+const MIN_XOR_BYTES: usize = 80;
+
+/// The shortest and longest keys we try
+const KEY_LENGTHS: std::ops::RangeInclusive<usize> = 2..=40;
+
+/// How many of the most likely key lengths we try to solve
+const KEY_LENGTHS_TO_TRY: usize = 3;
+
+/// The repeating key XOR decoder, call:
+/// `let xor_repeating_key_decoder = Decoder::<XorRepeatingKeyDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = xor_repeating_key_decoder.crack(input)` to decode a repeating key XOR string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::xor_repeating_key_decoder::XorRepeatingKeyDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_xor = Decoder::<XorRepeatingKeyDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// // Text which is too short to have its key recovered is skipped
+/// let result = decode_xor.crack("0b3637272a69652a2e6b", &checker).unencrypted_text;
+/// assert!(result.is_none());
+/// ```
+pub struct XorRepeatingKeyDecoder;
+
+impl Crack for Decoder<XorRepeatingKeyDecoder> {
+    fn new() -> Decoder<XorRepeatingKeyDecoder> {
+        DecoderBuilder::new("XOR Repeating Key")
+            .with_description("The bytes of the text are XOR'd with a key which repeats, making it a Vigenère cipher over bytes. The key length can be found from the Hamming distance between blocks of the ciphertext, after which each byte of the key is a single byte XOR.")
+            .with_link("https://en.wikipedia.org/wiki/XOR_cipher")
+            .with_tags(&["xor", "decryption", "bitwise"])
+            .with_popularity(0.4)
+            .with_expected_runtime(0.05)
+            .with_expected_success(0.1)
+            .with_failure_runtime(0.1)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying XOR repeating key with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        let ciphertext = text_to_bytes(text);
+        if ciphertext.len() < MIN_XOR_BYTES {
+            debug!(
+                "Not trying XOR repeating key as {} bytes is too short",
+                ciphertext.len()
+            );
+            return results;
+        }
+
+        let (key, decoded_text) = match break_repeating_key_xor(&ciphertext) {
+            Some(solution) => solution,
+            None => {
+                debug!("Failed to decode XOR repeating key as no key length produced English-looking text");
+                return results;
+            }
+        };
+
+        // Plaintext input solves to nonsense which is less English than the input
+        let input_score = english_score(text.as_bytes()).unwrap_or(0.0);
+        let decoded_score = english_score(decoded_text.as_bytes()).unwrap_or(0.0);
+        if decoded_score <= input_score || !check_string_success(&decoded_text, text) {
+            info!(
+                "Failed to decode XOR repeating key because {} looks less like English than the input",
+                decoded_text
+            );
+            return results;
+        }
+
+        let checker_result = checker.check(&decoded_text);
+        results = results.with_candidate(decoded_text);
+        results.set_key(describe_key(&key));
+        results.update_checker(&checker_result);
+
+        results
+    }
+    decoder_getters!();
+}
+
+/// The ciphertext is usually given as hex or base64, otherwise we use the raw bytes.
+/// XOR'ing text with a text key gives mostly unprintable bytes,
+/// so if the bytes are readable already they were never XOR'd.
+fn text_to_bytes(text: &str) -> Vec<u8> {
+    let without_whitespace: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = hex_to_bytes(text)
+        .or_else(|| general_purpose::STANDARD.decode(without_whitespace).ok())
+        .unwrap_or_else(|| text.as_bytes().to_vec());
+    if english_score(&bytes).is_some() {
+        return Vec::new();
+    }
+    bytes
+}
+
+/// Tries the most likely key lengths and returns the key and plaintext which look most like English
+fn break_repeating_key_xor(ciphertext: &[u8]) -> Option<(Vec<u8>, String)> {
+    likely_key_lengths(ciphertext)
+        .into_iter()
+        .filter_map(|key_length| {
+            let key = shortest_repeating_unit(solve_key(ciphertext, key_length)?);
+            let plaintext = String::from_utf8(xor_with_repeating_key(ciphertext, &key)).ok()?;
+            let score = english_score(plaintext.as_bytes())?;
+            Some((score, key, plaintext))
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, key, plaintext)| (key, plaintext))
+}
+
+/// Blocks XOR'd with the same key have a smaller Hamming distance than random blocks,
+/// so the key lengths with the smallest average distance per bit are the most likely.
+fn likely_key_lengths(ciphertext: &[u8]) -> Vec<usize> {
+    let mut scores: Vec<(f64, usize)> = KEY_LENGTHS
+        .filter(|&key_length| ciphertext.len() >= key_length * 2)
+        .map(|key_length| {
+            let blocks: Vec<&[u8]> = ciphertext.chunks_exact(key_length).collect();
+            let pairs = blocks.len() - 1;
+            let total: usize = blocks
+                .windows(2)
+                .map(|pair| hamming_distance(pair[0], pair[1]))
+                .sum();
+            let normalised = total as f64 / pairs as f64 / key_length as f64;
+            (normalised, key_length)
+        })
+        .collect();
+    scores.sort_by(|a, b| a.0.total_cmp(&b.0));
+    scores
+        .into_iter()
+        .take(KEY_LENGTHS_TO_TRY)
+        .map(|(_, key_length)| key_length)
+        .collect()
+}
+
+/// Each column of the ciphertext was XOR'd with the same key byte,
+/// so we pick the byte which makes the column look most like English.
+fn solve_key(ciphertext: &[u8], key_length: usize) -> Option<Vec<u8>> {
+    (0..key_length)
+        .map(|column| {
+            let column: Vec<u8> = ciphertext
+                .iter()
+                .skip(column)
+                .step_by(key_length)
+                .copied()
+                .collect();
+            (0..=u8::MAX)
+                .filter_map(|key| {
+                    let decoded: Vec<u8> = column.iter().map(|byte| byte ^ key).collect();
+                    Some((english_score(&decoded)?, key))
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, key)| key)
+        })
+        .collect()
+}
+
+/// A multiple of the real key length solves to the key repeated, e.g. "ICEICE".
+/// This returns the shortest key which repeats to the same thing.
+fn shortest_repeating_unit(mut key: Vec<u8>) -> Vec<u8> {
+    if let Some(length) = (1..key.len()).find(|&length| {
+        key.len().is_multiple_of(length) && key.chunks(length).all(|chunk| chunk == &key[..length])
+    }) {
+        key.truncate(length);
+    }
+    key
+}
+
+/// XORs the bytes with the key, repeating the key as needed
+fn xor_with_repeating_key(bytes: &[u8], key: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect()
+}
+
+/// The number of bits which differ between the two slices
+fn hamming_distance(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x ^ y).count_ones() as usize)
+        .sum()
+}
+
+/// Shows the key as hex, and as text too if it is printable
+fn describe_key(key: &[u8]) -> String {
+    let hex: String = key.iter().map(|byte| format!("{byte:02x}")).collect();
+    if key
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        format!("key=0x{hex} ({})", String::from_utf8_lossy(key))
+    } else {
+        format!("key=0x{hex}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    // A paragraph about a lighthouse keeper XOR'd with "Ares secret" and base64 encoded
+    const BASE64_CIPHERTEXT: &str = "FRoAU0waAgsGDRs0AQBTSxYAExcXVCIeDB5CFgFDBg0RYQEVGlISCUMBERUoABZTRQUAEQtFETcXCxpOFEUBFwMbMxdFB0gWRRAHC1Q2FwsHABcKFBxLVAkXRQdSGggOFwFUNRoAU1caBgheRQQuHgwASBYBQwYNEWEVFxZBB0UPFwsHYRMLFwAEChYcAVQ1GgBTQx8KABkSGzMZRQdIEhFDBhAGLxcBU1QbAEMeDBMpBktTcxsMEwFFBCABFhpOFEUXGgBUMx0GGFlTFQwbCwBhExFTThoCCwZFAy4HCRcAAAAGUhEcJFIHFkEeRRAFABExUgQQUhwWEFIRHCRSEhJUFhdDEwsQYRkLHFdTEQsTEVQ1GgAKAAQAERdFFy0dFhYABwpDBg0RYRoEAUIcEBFcRT0vUhEbRVMSChwRETNSERtFUxYXHRcZMlISFlIWRRAdRRguBwFTVBsEF1INEWERCgZMF0UNHRFUKRcEAQAbDA4BABgnUhEbSR0OT1IHATVSERtFUwkKFQ0AYRwABUUBRQwcBhFhBQAdVFMKFgZFAykbCRYAGwBDBQQHYQYNFlIWRRcdRRguHQ5TQRURBgBFHTVc";
+
+    #[test]
+    fn hamming_distance_is_correct() {
+        assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    }
+
+    #[test]
+    fn repeated_key_is_shortened() {
+        assert_eq!(shortest_repeating_unit(b"ICEICEICE".to_vec()), b"ICE");
+        assert_eq!(shortest_repeating_unit(b"ICEICX".to_vec()), b"ICEICX");
+    }
+
+    #[test]
+    fn xor_repeating_key_decodes_base64() {
+        let decoder = Decoder::<XorRepeatingKeyDecoder>::new();
+        let result = decoder.crack(BASE64_CIPHERTEXT, &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(
+            result.key.as_deref(),
+            Some("key=0x4172657320736563726574 (Ares secret)")
+        );
+        assert!(result.unencrypted_text.unwrap()[0]
+            .starts_with("The lighthouse keeper climbed the spiral stairs"));
+    }
+
+    #[test]
+    fn xor_repeating_key_skips_short_input() {
+        let decoder = Decoder::<XorRepeatingKeyDecoder>::new();
+        let result = decoder
+            .crack("0b3637272a69652a2e6b", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_repeating_key_does_not_crack_plaintext() {
+        let decoder = Decoder::<XorRepeatingKeyDecoder>::new();
+        let result = decoder
+            .crack(
+                "The lighthouse keeper climbed the spiral stairs every evening before the sun went down.",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_repeating_key_does_not_crack_plain_base64() {
+        let decoder = Decoder::<XorRepeatingKeyDecoder>::new();
+        let result = decoder
+            .crack(
+                "VGhlIGxpZ2h0aG91c2Uga2VlcGVyIGNsaW1iZWQgdGhlIHNwaXJhbCBzdGFpcnMgZXZlcnkgZXZlbmluZyBiZWZvcmUgdGhlIHN1biB3ZW50IGRvd24u",
+                &get_athena_checker(),
+            )
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_repeating_key_handles_panic_if_empty_string() {
+        let decoder = Decoder::<XorRepeatingKeyDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_repeating_key_handles_panic_if_emoji() {
+        let decoder = Decoder::<XorRepeatingKeyDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
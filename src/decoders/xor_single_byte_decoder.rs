@@ -0,0 +1,250 @@
+//! Decode a string XOR'd with a single byte
+//! Performs error handling and returns a string
+//! Call xor_single_byte_decoder.crack to use. It returns option<String> and check with
+//! `result.is_some()` to see if it returned okay.
+//! All 255 keys are tried, but only the few most English-looking results are sent to the checker.
+
+use crate::checkers::CheckerTypes;
+use crate::decoders::interface::{check_candidates, check_string_success};
+
+use super::crack_results::CrackResult;
+use super::interface::Crack;
+use super::interface::{decoder_getters, Decoder, DecoderBuilder};
+use super::vigenere_decoder::ENGLISH_LETTER_FREQUENCIES;
+
+use log::{debug, info, trace};
+
+/// How many of the best scoring keys are sent to the checker
+const CANDIDATES_TO_CHECK: usize = 3;
+
+/// Below this many bytes lots of keys give printable text, so the score means nothing
+const MIN_XOR_BYTES: usize = 8;
+
+/// The share of characters which have to be printable for a key to be considered
+const MIN_PRINTABLE_RATIO: f64 = 0.95;
+
+/// The single byte XOR decoder, call:
+/// `let xor_single_byte_decoder = Decoder::<XorSingleByteDecoder>::new()` to create a new instance
+/// And then call:
+/// `result = xor_single_byte_decoder.crack(input)` to decode a single byte XOR string
+/// The struct generated by new() comes from interface.rs
+/// ```
+/// use ares::decoders::xor_single_byte_decoder::XorSingleByteDecoder;
+/// use ares::decoders::interface::{Crack, Decoder};
+/// use ares::checkers::{athena::Athena, CheckerTypes, checker_type::{Check, Checker}};
+///
+/// let decode_xor = Decoder::<XorSingleByteDecoder>::new();
+/// let athena_checker = Checker::<Athena>::new();
+/// let checker = CheckerTypes::CheckAthena(athena_checker);
+///
+/// let result = decode_xor.crack("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736", &checker);
+/// assert_eq!(result.key.as_deref(), Some("key=0x58"));
+/// assert_eq!(result.unencrypted_text.unwrap()[0], "Cooking MC's like a pound of bacon");
+/// ```
+pub struct XorSingleByteDecoder;
+
+impl Crack for Decoder<XorSingleByteDecoder> {
+    fn new() -> Decoder<XorSingleByteDecoder> {
+        DecoderBuilder::new("XOR Single Byte")
+            .with_description("Every byte of the text is XOR'd with the same single byte key. It is often used to hide strings and configuration in malware, and is a staple of cryptography challenges.")
+            .with_link("https://en.wikipedia.org/wiki/XOR_cipher")
+            .with_tags(&["xor", "decryption", "bitwise"])
+            .with_expected_runtime(0.05)
+            .with_expected_success(0.2)
+            .build()
+    }
+
+    /// This function does the actual decoding
+    /// It returns an Option<string> if it was successful
+    /// Else the Option returns nothing and the error is logged in Trace
+    fn decode(&self, text: &str, checker: &CheckerTypes) -> CrackResult {
+        trace!("Trying XOR single byte with text {:?}", text);
+        let mut results = CrackResult::new(self);
+
+        let candidates: Vec<(u8, String)> = best_single_byte_xor_candidates(text)
+            .into_iter()
+            .filter(|(_, decoded_text)| check_string_success(decoded_text, text))
+            .collect();
+        if candidates.is_empty() {
+            debug!("Failed to decode XOR single byte as no key produced English-looking text");
+            return results;
+        }
+
+        let mut decoded_strings: Vec<String> = candidates
+            .iter()
+            .map(|(_, decoded_text)| decoded_text.clone())
+            .collect();
+        // The candidate the checker identified goes first, or the one it came closest to identifying
+        if let Some((index, checker_result)) = check_candidates(checker, &decoded_strings) {
+            results.update_checker(&checker_result);
+            if results.success {
+                let key = &candidates[index].0;
+                info!("Found a match with XOR key {:#04x}", key);
+                results.set_key(format!("key={key:#04x}"));
+            }
+            decoded_strings[..=index].rotate_right(1);
+        }
+
+        results = results.with_candidates(decoded_strings);
+        results
+    }
+    decoder_getters!();
+}
+
+/// Tries every key on the raw bytes of the text, and on the hex decoded bytes if it looks like hex.
+/// Returns the best scoring keys which are more English-looking than the input, best first.
+fn best_single_byte_xor_candidates(text: &str) -> Vec<(u8, String)> {
+    let input_score = english_score(text.as_bytes()).unwrap_or(0.0);
+    let mut sources = vec![text.as_bytes().to_vec()];
+    if let Some(bytes) = hex_to_bytes(text) {
+        sources.push(bytes);
+    }
+
+    let mut scored: Vec<(f64, u8, Vec<u8>)> = Vec::new();
+    for source in sources
+        .iter()
+        .filter(|source| source.len() >= MIN_XOR_BYTES)
+    {
+        for key in 1..=u8::MAX {
+            let decoded = xor_with_key(source, key);
+            if let Some(score) = english_score(&decoded) {
+                if score > input_score {
+                    scored.push((score, key, decoded));
+                }
+            }
+        }
+    }
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .filter_map(|(_, key, decoded)| Some((key, String::from_utf8(decoded).ok()?)))
+        .take(CANDIDATES_TO_CHECK)
+        .collect()
+}
+
+/// XORs every byte with the key
+fn xor_with_key(bytes: &[u8], key: u8) -> Vec<u8> {
+    bytes.iter().map(|byte| byte ^ key).collect()
+}
+
+/// Decodes the text as hex, ignoring whitespace.
+/// Returns None if it isn't hex.
+pub(crate) fn hex_to_bytes(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if digits.is_empty()
+        || !digits.len().is_multiple_of(2)
+        || !digits.iter().all(u8::is_ascii_hexdigit)
+    {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+/// A cheap score of how English the bytes look, higher is better.
+/// Returns None if too many of the bytes aren't printable ASCII.
+pub(crate) fn english_score(bytes: &[u8]) -> Option<f64> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let printable = bytes
+        .iter()
+        .filter(|b| b.is_ascii_graphic() || matches!(b, b' ' | b'\n' | b'\r' | b'\t'))
+        .count();
+    if (printable as f64) < bytes.len() as f64 * MIN_PRINTABLE_RATIO {
+        return None;
+    }
+    let score: f64 = bytes
+        .iter()
+        .map(|&byte| match byte {
+            b' ' => 0.13,
+            b'a'..=b'z' => ENGLISH_LETTER_FREQUENCIES[usize::from(byte - b'a')],
+            b'A'..=b'Z' => ENGLISH_LETTER_FREQUENCIES[usize::from(byte - b'A')],
+            _ => 0.0,
+        })
+        .sum();
+    Some(score / bytes.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{
+            athena::Athena,
+            checker_type::{Check, Checker},
+            CheckerTypes,
+        },
+        decoders::interface::{Crack, Decoder},
+    };
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn xor_single_byte_decodes_hex() {
+        let decoder = Decoder::<XorSingleByteDecoder>::new();
+        let result = decoder.crack(
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+            &get_athena_checker(),
+        );
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("key=0x58"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "Cooking MC's like a pound of bacon"
+        );
+    }
+
+    #[test]
+    fn xor_single_byte_decodes_raw_text() {
+        let decoder = Decoder::<XorSingleByteDecoder>::new();
+        let result = decoder.crack("m`iij%rjwia%qmlv%lv%d%q`vq", &get_athena_checker());
+        assert!(result.success);
+        assert_eq!(result.key.as_deref(), Some("key=0x05"));
+        assert_eq!(
+            result.unencrypted_text.unwrap()[0],
+            "hello world this is a test"
+        );
+    }
+
+    #[test]
+    fn xor_single_byte_random_bytes_fail() {
+        let decoder = Decoder::<XorSingleByteDecoder>::new();
+        let result = decoder.crack(
+            "4411e0895768d06db327eea62f3edb02938f1320a292fb38",
+            &get_athena_checker(),
+        );
+        assert!(!result.success);
+        assert!(result.key.is_none());
+    }
+
+    #[test]
+    fn xor_single_byte_does_not_decode_english() {
+        let decoder = Decoder::<XorSingleByteDecoder>::new();
+        let result = decoder
+            .crack("hello world this is a test", &get_athena_checker())
+            .unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_single_byte_handles_panic_if_empty_string() {
+        let decoder = Decoder::<XorSingleByteDecoder>::new();
+        let result = decoder.crack("", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn xor_single_byte_handles_panic_if_emoji() {
+        let decoder = Decoder::<XorSingleByteDecoder>::new();
+        let result = decoder.crack("😂", &get_athena_checker()).unencrypted_text;
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,339 @@
+//! Ares for C and C++, so tools which aren't written in Rust can crack in-process.
+//! Build the library with the `ffi` feature and the `release-ffi` profile, which puts it in `target/release-ffi`,
+//! and make the header with cbindgen:
+//! ```sh
+//! cargo build --profile release-ffi --features ffi
+//! cbindgen --config cbindgen.toml --output ares.h
+//! ```
+//! ```c
+//! AresResult *result = ares_crack("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", NULL);
+//! if (ares_result_status(result) == ARES_STATUS_FOUND) {
+//!     printf("%s\n", ares_result_plaintext(result));
+//!     for (size_t i = 0; i < ares_result_path_len(result); i++) {
+//!         printf("%s\n", ares_result_path_step(result, i));
+//!     }
+//! }
+//! ares_result_free(result);
+//! ```
+//! When the whole text can't be cracked but some of its lines can, the status is `ARES_STATUS_LINES`,
+//! and each line's plaintext is read with `ares_result_line_plaintext` rather than `ares_result_plaintext`.
+//!
+//! Every string is UTF-8 and ends with a NUL. Strings Ares gives back belong to the result,
+//! so they're only valid until it's freed, and mustn't be freed on their own.
+//!
+//! A result is a handle rather than the address of its memory, so Ares can tell the results
+//! it gave out from anything else. Using one which was freed, or which Ares never gave out, is harmless:
+//! the functions return NULL or 0, and freeing it again does nothing.
+//!
+//! Panics are caught and become `ARES_STATUS_PANICKED`. The `release-ffi` profile unwinds for that,
+//! where Ares' own release profile aborts, so a library built with `--release` takes the program down instead.
+
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::config::Config;
+use crate::decoders::interface::Decoder;
+use crate::{perform_cracking, CrackOutcome};
+
+/// How to crack. Zeroes and NULLs mean Ares' defaults, so a zeroed `AresOptions` is the same as none.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct AresOptions {
+    /// How many milliseconds to crack for, or 0 for 5 seconds
+    pub timeout_ms: u64,
+    /// How many decoders deep to search, or 0 for Ares' default
+    pub max_depth: u32,
+    /// A regex the plaintext matches, or NULL to find English instead.
+    /// It's copied, so it can be freed once `ares_crack` returns.
+    pub regex: *const c_char,
+}
+
+/// How `ares_crack` went
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AresStatus {
+    /// It found the plaintext
+    Found = 0,
+    /// Everything that could be decoded was, and none of it was plaintext
+    NotFound = 1,
+    /// It ran out of time before it found the plaintext
+    TimedOut = 2,
+    /// The text was NULL
+    NullArgument = 3,
+    /// The text or the regex wasn't UTF-8, the regex wasn't a regex, or the text was too big to crack
+    InvalidArgument = 4,
+    /// Ares panicked, which is a bug in Ares
+    Panicked = 5,
+    /// The result was freed, or isn't one Ares gave out
+    InvalidResult = 6,
+    /// The whole text wasn't cracked, but some of its lines were, see `ares_result_lines_len`
+    Lines = 7,
+}
+
+/// What `ares_crack` found. It's opaque, so it's only used through the `ares_result_` functions,
+/// and freed with `ares_result_free`.
+pub struct AresResult {
+    /// Never made, as results are handles to `CrackResultData`
+    _private: [u8; 0],
+}
+
+/// What a result holds
+struct CrackResultData {
+    /// How the crack went
+    status: AresStatus,
+    /// Why it failed, if it did
+    error: Option<CString>,
+    /// The plaintext, if it was found
+    plaintext: Option<CString>,
+    /// The names of the decoders from the input to the plaintext, in order
+    path: Vec<CString>,
+    /// The lines cracked on their own, if the status is `Lines`
+    lines: Vec<CrackedLine>,
+}
+
+/// A line of the text, cracked on its own
+struct CrackedLine {
+    /// Which line of the text it is, starting from 1
+    number: usize,
+    /// The line's plaintext, if it was found
+    plaintext: Option<CString>,
+}
+
+/// The results given out and not yet freed, by handle
+static RESULTS: Mutex<Option<HashMap<usize, CrackResultData>>> = Mutex::new(None);
+/// The handle of the next result. Handles are never used twice, so a freed one stays freed.
+static NEXT_HANDLE: AtomicUsize = AtomicUsize::new(1);
+
+/// Cracks `text`, with `opts` or with Ares' defaults if it's NULL.
+/// Returns a result, never NULL, even when cracking failed; see `ares_result_status`.
+/// The result belongs to the caller, who frees it with `ares_result_free`.
+/// # Safety
+/// `text` and `opts->regex` must be NULL or point to NUL-terminated strings,
+/// and `opts` must be NULL or point to an `AresOptions`.
+#[no_mangle]
+pub unsafe extern "C" fn ares_crack(
+    text: *const c_char,
+    opts: *const AresOptions,
+) -> *mut AresResult {
+    let data = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: the caller promises the pointers are NULL or valid
+        unsafe { crack(text, opts) }
+    }))
+    .unwrap_or_else(|_| CrackResultData::failed(AresStatus::Panicked, "Ares panicked"));
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    RESULTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(HashMap::new)
+        .insert(handle, data);
+    handle as *mut AresResult
+}
+
+/// How the crack went, or `ARES_STATUS_INVALID_RESULT` if `result` was freed or is NULL
+#[no_mangle]
+pub extern "C" fn ares_result_status(result: *const AresResult) -> AresStatus {
+    with_result(result, |data| data.status).unwrap_or(AresStatus::InvalidResult)
+}
+
+/// Why the crack failed, or NULL if it didn't. The string belongs to the result.
+#[no_mangle]
+pub extern "C" fn ares_result_error(result: *const AresResult) -> *const c_char {
+    with_result(result, |data| as_ptr(data.error.as_ref())).unwrap_or(ptr::null())
+}
+
+/// The plaintext, or NULL if none was found. The string belongs to the result.
+/// A plaintext with NUL characters in it ends at the first, as C strings do.
+#[no_mangle]
+pub extern "C" fn ares_result_plaintext(result: *const AresResult) -> *const c_char {
+    with_result(result, |data| as_ptr(data.plaintext.as_ref())).unwrap_or(ptr::null())
+}
+
+/// How many decoders there are from the input to the plaintext, or 0 if none was found
+#[no_mangle]
+pub extern "C" fn ares_result_path_len(result: *const AresResult) -> usize {
+    with_result(result, |data| data.path.len()).unwrap_or(0)
+}
+
+/// The name of the decoder at `index` on the way to the plaintext, starting from 0 at the input,
+/// or NULL if there isn't one there. The string belongs to the result.
+#[no_mangle]
+pub extern "C" fn ares_result_path_step(result: *const AresResult, index: usize) -> *const c_char {
+    with_result(result, |data| as_ptr(data.path.get(index))).unwrap_or(ptr::null())
+}
+
+/// How many lines of the text were cracked on their own, or 0 unless the status is `ARES_STATUS_LINES`.
+/// Blank lines and comments aren't cracked, so they aren't counted.
+#[no_mangle]
+pub extern "C" fn ares_result_lines_len(result: *const AresResult) -> usize {
+    with_result(result, |data| data.lines.len()).unwrap_or(0)
+}
+
+/// Which line of the text the line at `index` is, starting from 1, or 0 if there isn't one there
+#[no_mangle]
+pub extern "C" fn ares_result_line_number(result: *const AresResult, index: usize) -> usize {
+    with_result(result, |data| {
+        data.lines.get(index).map_or(0, |line| line.number)
+    })
+    .unwrap_or(0)
+}
+
+/// The plaintext of the line at `index`, or NULL if it wasn't cracked or there isn't one there.
+/// The string belongs to the result.
+#[no_mangle]
+pub extern "C" fn ares_result_line_plaintext(
+    result: *const AresResult,
+    index: usize,
+) -> *const c_char {
+    with_result(result, |data| {
+        as_ptr(
+            data.lines
+                .get(index)
+                .and_then(|line| line.plaintext.as_ref()),
+        )
+    })
+    .unwrap_or(ptr::null())
+}
+
+/// Frees the result and its strings. Freeing NULL, or a result which was already freed, does nothing.
+#[no_mangle]
+pub extern "C" fn ares_result_free(result: *mut AresResult) {
+    let freed = RESULTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .and_then(|results| results.remove(&(result as usize)));
+    // Dropped outside the lock
+    drop(freed);
+}
+
+/// Calls `f` with what the result holds, or returns None if it isn't a result Ares gave out
+fn with_result<T>(result: *const AresResult, f: impl FnOnce(&CrackResultData) -> T) -> Option<T> {
+    let results = RESULTS.lock().unwrap_or_else(PoisonError::into_inner);
+    results.as_ref()?.get(&(result as usize)).map(f)
+}
+
+/// The string's pointer, or NULL if there isn't one
+fn as_ptr(string: Option<&CString>) -> *const c_char {
+    string.map_or(ptr::null(), |string| string.as_ptr())
+}
+
+impl CrackResultData {
+    /// A result for a crack which couldn't be done
+    fn failed(status: AresStatus, error: &str) -> Self {
+        CrackResultData {
+            status,
+            error: Some(c_string(error)),
+            plaintext: None,
+            path: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+}
+
+/// The string as a C string, ending at the first NUL if there is one
+fn c_string(string: &str) -> CString {
+    let until_nul = string.split('\0').next().unwrap_or_default();
+    CString::new(until_nul).expect("there are no NULs left")
+}
+
+/// Reads a C string as UTF-8, or None if it's NULL
+/// # Safety
+/// `string` must be NULL or point to a NUL-terminated string.
+unsafe fn read_str<'a>(string: *const c_char) -> Option<Result<&'a str, std::str::Utf8Error>> {
+    // SAFETY: the caller promises it's NULL or NUL-terminated
+    (!string.is_null()).then(|| unsafe { CStr::from_ptr(string) }.to_str())
+}
+
+/// Cracks the text, see `ares_crack`
+/// # Safety
+/// As for `ares_crack`.
+unsafe fn crack(text: *const c_char, opts: *const AresOptions) -> CrackResultData {
+    // SAFETY: the caller promises the pointers are NULL or valid
+    let Some(text) = (unsafe { read_str(text) }) else {
+        return CrackResultData::failed(AresStatus::NullArgument, "the text is NULL");
+    };
+    let Ok(text) = text else {
+        return CrackResultData::failed(AresStatus::InvalidArgument, "the text isn't UTF-8");
+    };
+    // SAFETY: the caller promises it's NULL or points to an AresOptions
+    let opts = unsafe { opts.as_ref() }.copied().unwrap_or(AresOptions {
+        timeout_ms: 0,
+        max_depth: 0,
+        regex: ptr::null(),
+    });
+    // SAFETY: the caller promises it's NULL or NUL-terminated
+    let regex = match unsafe { read_str(opts.regex) } {
+        None => None,
+        Some(Ok(regex)) => Some(regex.to_string()),
+        Some(Err(_)) => {
+            return CrackResultData::failed(AresStatus::InvalidArgument, "the regex isn't UTF-8")
+        }
+    };
+    if let Some(Err(error)) = regex.as_deref().map(Regex::new) {
+        return CrackResultData::failed(AresStatus::InvalidArgument, &error.to_string());
+    }
+
+    let mut config = Config::default();
+    if opts.timeout_ms > 0 {
+        config.timeout = Duration::from_millis(opts.timeout_ms);
+    }
+    if opts.max_depth > 0 {
+        config.max_depth = opts.max_depth;
+    }
+    config.regex = regex;
+    let outcome = perform_cracking(text, config);
+    if let CrackOutcome::TooLarge(too_large) = &outcome {
+        return CrackResultData::failed(AresStatus::InvalidArgument, &too_large.to_string());
+    }
+
+    if let CrackOutcome::Lines(lines) = outcome {
+        return CrackResultData {
+            status: AresStatus::Lines,
+            error: None,
+            plaintext: None,
+            path: Vec::new(),
+            lines: lines
+                .into_iter()
+                .map(|line| CrackedLine {
+                    number: line.index + 1,
+                    plaintext: line.outcome.found().map(|found| c_string(&found.plaintext)),
+                })
+                .collect(),
+        };
+    }
+
+    let status = match &outcome {
+        CrackOutcome::Found(_) => AresStatus::Found,
+        CrackOutcome::TimedOut(_) => AresStatus::TimedOut,
+        _ => AresStatus::NotFound,
+    };
+    let Some(found) = outcome.found() else {
+        return CrackResultData {
+            status,
+            error: None,
+            plaintext: None,
+            path: Vec::new(),
+            lines: Vec::new(),
+        };
+    };
+    CrackResultData {
+        status,
+        error: None,
+        plaintext: Some(c_string(&found.plaintext)),
+        // The input itself being the plaintext is no decoders deep
+        path: found
+            .path
+            .iter()
+            .filter(|step| step.decoder != Decoder::default().name)
+            .map(|step| c_string(step.decoder))
+            .collect(),
+        lines: Vec::new(),
+    }
+}
@@ -0,0 +1,208 @@
+//! Cleans the input before the search. Text copied from web pages often has zero-width spaces,
+//! byte order marks, bidi controls and non-breaking spaces in it, which nobody can see
+//! but which stop every base decoder from decoding it.
+//! The zero-width characters can also be a message of their own, hidden in plain sight.
+
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::{Decoder, DecoderBuilder};
+use log::debug;
+
+/// Zero width space, a 0 when it hides a message
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+/// Zero width non-joiner, a 1 when it hides a message
+const ZERO_WIDTH_NON_JOINER: char = '\u{200C}';
+
+/// Characters which take up no space, and are removed
+const ZERO_WIDTH: &[char] = &[
+    ZERO_WIDTH_SPACE,
+    ZERO_WIDTH_NON_JOINER,
+    '\u{200D}', // zero width joiner
+    '\u{2060}', // word joiner
+    '\u{180E}', // Mongolian vowel separator
+    '\u{FEFF}', // byte order mark, or zero width no-break space
+];
+
+/// Characters which change the direction text is shown in, and are removed
+const BIDI_CONTROLS: &[char] = &[
+    '\u{061C}', '\u{200E}', '\u{200F}', '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}',
+];
+
+/// Spaces which look like a plain space, and are replaced with one
+const UNUSUAL_SPACES: &[char] = &[
+    '\u{00A0}', '\u{1680}', '\u{2000}', '\u{2001}', '\u{2002}', '\u{2003}', '\u{2004}', '\u{2005}',
+    '\u{2006}', '\u{2007}', '\u{2008}', '\u{2009}', '\u{200A}', '\u{202F}', '\u{205F}', '\u{3000}',
+];
+
+/// Reads a message hidden in zero-width characters. It isn't run by the search like
+/// other decoders, as the search never sees the characters, so it is only used to describe
+/// how the hidden message was found.
+pub struct ZeroWidthSteganography;
+
+/// The decoder which found the hidden message, for the path to it
+fn zero_width_steganography() -> Decoder<ZeroWidthSteganography> {
+    DecoderBuilder::new("Zero-width Steganography")
+        .with_description("Hides a message in characters which take up no space, a zero width space for each 0 bit and a zero width non-joiner for each 1 bit.")
+        .with_link("https://en.wikipedia.org/wiki/Zero-width_space")
+        .with_tags(&["steganography", "invisible", "unicode"])
+        .build()
+}
+
+/// The input once it's been cleaned, and what was taken out of it
+#[derive(Debug, Clone)]
+pub struct CleanedInput {
+    /// The input as it was given
+    pub original: String,
+    /// The input without invisible characters, and with unusual spaces replaced by plain ones.
+    /// This is what the search decodes.
+    pub text: String,
+    /// Every invisible character removed, in the order they were in the input
+    pub removed: Vec<char>,
+    /// How many unusual spaces were replaced with plain spaces
+    pub spaces_replaced: usize,
+    /// The message hidden in the zero-width characters, as decoding the input,
+    /// so the search can start from it too
+    pub hidden_message: Option<CrackResult>,
+}
+
+impl CleanedInput {
+    /// How many of the removed characters were zero-width
+    pub fn zero_width_removed(&self) -> usize {
+        self.removed
+            .iter()
+            .filter(|removed| ZERO_WIDTH.contains(removed))
+            .count()
+    }
+
+    /// How many of the removed characters were bidi controls
+    pub fn bidi_controls_removed(&self) -> usize {
+        self.removed.len() - self.zero_width_removed()
+    }
+
+    /// Whether cleaning changed the input
+    pub fn changed(&self) -> bool {
+        self.text != self.original
+    }
+}
+
+/// Removes invisible characters from the input, replaces unusual spaces with plain ones,
+/// and reads any message the zero-width characters hide
+pub fn clean_input(input: &str) -> CleanedInput {
+    let mut text = String::with_capacity(input.len());
+    let mut removed = Vec::new();
+    let mut spaces_replaced = 0;
+    for c in input.chars() {
+        if ZERO_WIDTH.contains(&c) || BIDI_CONTROLS.contains(&c) {
+            removed.push(c);
+        } else if UNUSUAL_SPACES.contains(&c) {
+            text.push(' ');
+            spaces_replaced += 1;
+        } else {
+            text.push(c);
+        }
+    }
+    let hidden_message = read_hidden_message(&removed).map(|message| {
+        debug!("The zero-width characters hide {:?}", message);
+        CrackResult::new(&zero_width_steganography())
+            .with_encrypted_text(input.to_string())
+            .with_candidate(message)
+    });
+    CleanedInput {
+        original: input.to_string(),
+        text,
+        removed,
+        spaces_replaced,
+        hidden_message,
+    }
+}
+
+/// Reads zero width spaces as 0 bits and zero width non-joiners as 1 bits, most significant first.
+/// It's only a message if there are whole bytes of them, and they are printable UTF-8.
+fn read_hidden_message(removed: &[char]) -> Option<String> {
+    let bits: Vec<u8> = removed
+        .iter()
+        .filter_map(|&c| match c {
+            ZERO_WIDTH_SPACE => Some(0),
+            ZERO_WIDTH_NON_JOINER => Some(1),
+            _ => None,
+        })
+        .collect();
+    if bits.is_empty() || !bits.len().is_multiple_of(8) {
+        return None;
+    }
+    let bytes: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, bit| (acc << 1) | bit))
+        .collect();
+    let message = String::from_utf8(bytes).ok()?;
+    let printable = message
+        .chars()
+        .all(|c| !c.is_control() || c.is_ascii_whitespace());
+    (printable && !message.trim().is_empty()).then_some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clean_input;
+
+    /// Hides the message in zero-width characters, one for each bit
+    fn hide(message: &str) -> String {
+        message
+            .bytes()
+            .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1))
+            .map(|bit| if bit == 0 { '\u{200B}' } else { '\u{200C}' })
+            .collect()
+    }
+
+    #[test]
+    fn invisible_characters_are_removed() {
+        let cleaned = clean_input("\u{FEFF}aGVs\u{200B}bG8=\u{202E}");
+        assert_eq!(cleaned.text, "aGVsbG8=");
+        assert_eq!(cleaned.removed, ['\u{FEFF}', '\u{200B}', '\u{202E}']);
+        assert_eq!(cleaned.zero_width_removed(), 2);
+        assert_eq!(cleaned.bidi_controls_removed(), 1);
+        assert!(cleaned.hidden_message.is_none());
+    }
+
+    #[test]
+    fn unusual_spaces_become_plain_spaces() {
+        let cleaned = clean_input("hello\u{00A0}there\u{3000}general");
+        assert_eq!(cleaned.text, "hello there general");
+        assert_eq!(cleaned.spaces_replaced, 2);
+        assert!(cleaned.removed.is_empty());
+    }
+
+    #[test]
+    fn clean_input_is_untouched() {
+        let input = "aGVsbG8gdGhlcmUgZ2VuZXJhbA==";
+        let cleaned = clean_input(input);
+        assert_eq!(cleaned.text, input);
+        assert!(!cleaned.changed());
+        assert!(cleaned.removed.is_empty());
+        assert_eq!(cleaned.spaces_replaced, 0);
+        assert!(cleaned.hidden_message.is_none());
+    }
+
+    #[test]
+    fn zero_width_binary_is_read_as_a_hidden_message() {
+        let hidden = hide("meet at noon");
+        let (first, rest) = hidden.split_at(hidden.len() / 2);
+        let cleaned = clean_input(&format!("nothing {first}to see{rest} here"));
+        assert_eq!(cleaned.text, "nothing to see here");
+        assert_eq!(cleaned.zero_width_removed(), 96);
+
+        let message = cleaned.hidden_message.unwrap();
+        assert_eq!(message.decoder, "Zero-width Steganography");
+        assert_eq!(
+            message.unencrypted_text,
+            Some(vec!["meet at noon".to_string()])
+        );
+    }
+
+    #[test]
+    fn stray_zero_width_characters_hide_nothing() {
+        // Seven bits isn't a byte, and a NUL byte isn't a message
+        assert!(clean_input(&hide("a")[3..]).hidden_message.is_none());
+        assert!(clean_input(&"\u{200B}".repeat(8)).hidden_message.is_none());
+    }
+}
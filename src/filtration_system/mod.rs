@@ -1,35 +1,28 @@
-use std::sync::mpsc::channel;
+//! Proposal: https://broadleaf-angora-7db.notion.site/Filtration-System-7143b36a42f1466faea3077bfc7e859e
+//! Given a filter object, return an array of decoders/crackers which have been filtered
+//! It also cleans the input before the search, see `invisible_characters`.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
 
 use crate::checkers::CheckerTypes;
-use crate::decoders::atbash_decoder::AtbashDecoder;
-use crate::decoders::base32_decoder::Base32Decoder;
-use crate::decoders::base58_bitcoin_decoder::Base58BitcoinDecoder;
-use crate::decoders::base58_monero_decoder::Base58MoneroDecoder;
-use crate::decoders::binary_decoder::BinaryDecoder;
-use crate::decoders::hexadecimal_decoder::HexadecimalDecoder;
-use crate::DecoderResult;
-
-use crate::decoders::base58_flickr_decoder::Base58FlickrDecoder;
-use crate::decoders::base58_ripple_decoder::Base58RippleDecoder;
-
-///! Proposal: https://broadleaf-angora-7db.notion.site/Filtration-System-7143b36a42f1466faea3077bfc7e859e
-///! Given a filter object, return an array of decoders/crackers which have been filtered
-///
-use crate::decoders::base64_decoder::Base64Decoder;
-use crate::decoders::base64_url_decoder::Base64URLDecoder;
-use crate::decoders::base65536_decoder::Base65536Decoder;
-use crate::decoders::base91_decoder::Base91Decoder;
-use crate::decoders::caesar_decoder::CaesarDecoder;
-use crate::decoders::citrix_ctx1_decoder::CitrixCTX1Decoder;
-use crate::decoders::crack_results::CrackResult;
-use crate::decoders::interface::{Crack, Decoder};
-use crate::decoders::morse_code::MorseCodeDecoder;
-use crate::decoders::reverse_decoder::ReverseDecoder;
-use crate::decoders::url_decoder::URLDecoder;
+use crate::config::Config;
+use crate::decoders::crack_results::{timing_report, CrackResult};
+use crate::decoders::interface::Crack;
+use crate::decoders::{decoders_matching, decoders_with_popularity};
+use crate::searchers::priority::DecoderCosts;
+use crate::searchers::SearchStats;
+use crate::timer::Instant;
 
 use log::trace;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+/// Removes characters nobody can see from the input, and reads what they hide
+pub mod invisible_characters;
+
 /// The struct which contains all of the decoders
 /// Where decoders is crackers, decryptors, etc.
 /// This contains a public attribute Components
@@ -38,7 +31,7 @@ use rayon::prelude::*;
 /// Relevant docs: https://doc.rust-lang.org/book/ch17-02-trait-objects.html
 pub struct Decoders {
     /// Components is a vector of decoders.
-    pub components: Vec<Box<dyn Crack + Sync>>,
+    pub components: Vec<Box<dyn Crack + Send + Sync>>,
 }
 
 impl Decoders {
@@ -49,44 +42,130 @@ impl Decoders {
     /// https://doc.rust-lang.org/book/ch17-02-trait-objects.html
     /// Which allows us to have multiple different structs in the same vector
     /// But each struct shares the same `.crack()` method, so it's fine.
-    pub fn run(&self, text: &str, checker: CheckerTypes) -> MyResults {
+    /// When the text came from binary, the bytes are only given to decoders which work on bytes.
+    /// Decoders tagged "reciprocal" undo themselves, so they are skipped
+    /// if they were the last decoder used to get this text, `last_decoder`.
+    /// Each decoder run is counted in `stats`.
+    ///
+    /// With the `parallel` feature the decoders run at the same time, but the answer is always
+    /// the first decoder in `components` to succeed, so the same text always decodes the same way.
+    pub fn run(
+        &self,
+        text: &str,
+        bytes: Option<&[u8]>,
+        last_decoder: Option<&str>,
+        checker: &CheckerTypes,
+        stats: &SearchStats,
+    ) -> MyResults {
         trace!("Running .crack() on all decoders");
-        let (sender, receiver) = channel();
-        self.components
-            .into_par_iter()
-            .try_for_each_with(sender, |s, i| {
-                let results = i.crack(text, &checker);
-                if results.success {
-                    s.send(results).expect("expected no send error!");
-                    // returning None short-circuits the iterator
-                    // we don't process any further as we got success
-                    return None;
-                }
-                s.send(results).expect("expected no send error!");
-                // return Some(()) to indicate that continue processing
-                Some(())
-            });
+        let mut all_results = self.crack_all(text, bytes, last_decoder, checker, stats, true);
 
-        let mut all_results: Vec<CrackResult> = Vec::new();
-
-        while let Ok(result) = receiver.recv() {
-            // if we recv success, break.
-            if result.success {
-                return MyResults::Break(result);
-            }
-            all_results.push(result)
+        // Results are in the order of the decoders, so this is the first decoder to succeed
+        if let Some(index) = all_results.iter().position(|result| result.success) {
+            return MyResults::Break(Box::new(all_results.swap_remove(index)));
         }
 
+        trace!("Decoder timings:\n{}", timing_report(&all_results));
         MyResults::Continue(all_results)
     }
+
+    /// Runs every decoder like `run`, but carries on after one succeeds,
+    /// so the search can look for more than one plaintext.
+    /// The results are in the order of `components`, successes included.
+    pub fn run_all(
+        &self,
+        text: &str,
+        bytes: Option<&[u8]>,
+        last_decoder: Option<&str>,
+        checker: &CheckerTypes,
+        stats: &SearchStats,
+    ) -> Vec<CrackResult> {
+        trace!("Running .crack() on all decoders, whatever succeeds");
+        let all_results = self.crack_all(text, bytes, last_decoder, checker, stats, false);
+        trace!("Decoder timings:\n{}", timing_report(&all_results));
+        all_results
+    }
+
+    /// Runs the decoders, returning their results in the order of `components`.
+    /// When `stop_at_success` is set, decoders after one which succeeded may be skipped.
+    fn crack_all(
+        &self,
+        text: &str,
+        bytes: Option<&[u8]>,
+        last_decoder: Option<&str>,
+        checker: &CheckerTypes,
+        stats: &SearchStats,
+        stop_at_success: bool,
+    ) -> Vec<CrackResult> {
+        // The index of the first decoder to succeed so far
+        let first_success = AtomicUsize::new(usize::MAX);
+        let crack = |index: usize, i: &(dyn Crack + Send + Sync)| {
+            // An earlier decoder succeeded, so this one can't be the answer
+            if stop_at_success && index > first_success.load(Ordering::Relaxed) {
+                return None;
+            }
+            // Running it again would give back the text it decoded
+            if last_decoder == Some(i.get_name()) && i.get_tags().contains(&"reciprocal") {
+                return None;
+            }
+            let started = Instant::now();
+            let mut results = match bytes {
+                Some(bytes) if i.accepts_bytes() => i.crack_bytes(bytes, checker),
+                // Binary is just noise to decoders which only work on text
+                Some(_) => return None,
+                // The text can't be this decoder's, so there's no need to run it
+                None if !i.can_attempt(text) => {
+                    stats.count_decoder_pruned();
+                    return None;
+                }
+                None => i.crack(text, checker),
+            };
+            stats.count_decoder_invocation();
+            results.time_taken = Some(started.elapsed());
+            if results.success {
+                first_success.fetch_min(index, Ordering::Relaxed);
+            }
+            Some(results)
+        };
+
+        // Rayon's threads don't have this crack's config, so each decoder is run with it
+        #[cfg(feature = "parallel")]
+        let config = crate::config::get_config();
+        #[cfg(feature = "parallel")]
+        let all_results = self
+            .components
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, i)| {
+                crate::config::with_config(Arc::clone(&config), || crack(index, i.as_ref()))
+            })
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let all_results = self
+            .components
+            .iter()
+            .enumerate()
+            .filter_map(|(index, i)| crack(index, i.as_ref()))
+            .collect();
+        all_results
+    }
+}
+
+/// The names of the decoders, in the order they run
+impl fmt::Debug for Decoders {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.components.iter().map(|decoder| decoder.get_name()))
+            .finish()
+    }
 }
 
 /// [`Enum`] for our custom results.
 /// if our checker succeed, we return `Break` variant contining [`CrackResult`]
 /// else we return `Continue` with the decoded results.
 pub enum MyResults {
-    /// Variant containing successful [`CrackResult`]
-    Break(CrackResult),
+    /// Variant containing successful [`CrackResult`], boxed as it is much bigger than a `Vec`
+    Break(Box<CrackResult>),
     /// Contains [`Vec`] of [`CrackResult`] for further processing
     Continue(Vec<CrackResult>),
 }
@@ -96,82 +175,226 @@ impl MyResults {
     /// as we aren't using it, it's just used in tests
     pub fn _break_value(self) -> Option<CrackResult> {
         match self {
-            MyResults::Break(val) => Some(val),
+            MyResults::Break(val) => Some(*val),
             MyResults::Continue(_) => None,
         }
     }
 }
 
-/// Currently takes no args as this is just a spike to get all the basic functionality working
-pub fn filter_and_get_decoders(_text_struct: &DecoderResult) -> Decoders {
+/// Returns all of the decoders the search runs, the most worth running first,
+/// see `DecoderCosts::priority`, with the config's popularity overrides.
+/// Only the decoders the config includes, and doesn't exclude, are returned,
+/// see `Config::include_decoders`.
+/// Nothing here depends on the text being decoded, so a search builds them once and runs them on every text.
+pub fn filter_and_get_decoders(config: &Config) -> Decoders {
     trace!("Filtering and getting all decoders");
-    let binary = Decoder::<BinaryDecoder>::new();
-    let hexadecimal = Decoder::<HexadecimalDecoder>::new();
-    let base58_bitcoin = Decoder::<Base58BitcoinDecoder>::new();
-    let base58_monero = Decoder::<Base58MoneroDecoder>::new();
-    let base58_ripple = Decoder::<Base58RippleDecoder>::new();
-    let base58_flickr = Decoder::<Base58FlickrDecoder>::new();
-    let base64 = Decoder::<Base64Decoder>::new();
-    let base91 = Decoder::<Base91Decoder>::new();
-    let base64_url = Decoder::<Base64URLDecoder>::new();
-    let base65536 = Decoder::<Base65536Decoder>::new();
-    let citrix_ctx1 = Decoder::<CitrixCTX1Decoder>::new();
-    let url = Decoder::<URLDecoder>::new();
-    let base32 = Decoder::<Base32Decoder>::new();
-    let reversedecoder = Decoder::<ReverseDecoder>::new();
-    let morsecodedecoder = Decoder::<MorseCodeDecoder>::new();
-    let atbashdecoder = Decoder::<AtbashDecoder>::new();
-    let caesardecoder = Decoder::<CaesarDecoder>::new();
-    Decoders {
-        components: vec![
-            Box::new(reversedecoder),
-            Box::new(base64),
-            Box::new(base58_bitcoin),
-            Box::new(base58_monero),
-            Box::new(base58_ripple),
-            Box::new(base58_flickr),
-            Box::new(base91),
-            Box::new(base65536),
-            Box::new(binary),
-            Box::new(hexadecimal),
-            Box::new(base32),
-            Box::new(morsecodedecoder),
-            Box::new(atbashdecoder),
-            Box::new(caesardecoder),
-            Box::new(citrix_ctx1),
-            Box::new(url),
-            Box::new(base64_url),
-        ],
+    let mut components = decoders_with_popularity(&config.decoder_popularity);
+
+    if !config.include_decoders.is_empty() || !config.exclude_decoders.is_empty() {
+        let included = decoders_matching(&components, &config.include_decoders);
+        let excluded = decoders_matching(&components, &config.exclude_decoders);
+        components = components
+            .into_iter()
+            .zip(included.into_iter().zip(excluded))
+            .filter(|(_, (included, excluded))| {
+                (config.include_decoders.is_empty() || *included) && !excluded
+            })
+            .map(|(decoder, _)| decoder)
+            .collect();
     }
+
+    // Popular, quick decoders which often work are started first
+    let priority = |decoder: &(dyn Crack + Send + Sync)| DecoderCosts::of(decoder).priority();
+    components.sort_by(|a, b| priority(b.as_ref()).total_cmp(&priority(a.as_ref())));
+
+    Decoders { components }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        checkers::{
-            athena::Athena,
-            checker_type::{Check, Checker},
-            CheckerTypes,
-        },
-        DecoderResult,
+    use crate::checkers::{
+        athena::Athena,
+        checker_type::{Check, Checker},
+        CheckerTypes,
     };
 
     // TODO: when we add a proper filtration system
     // We need to test that.
-    use super::filter_and_get_decoders;
+    use super::{filter_and_get_decoders, MyResults};
+    use crate::config::Config;
+    use crate::decoders::{
+        interface::{Crack, Decoder},
+        reverse_decoder::ReverseDecoder,
+    };
+    use crate::searchers::SearchStats;
+    use std::time::Duration;
+    #[cfg(feature = "parallel")]
+    use {
+        super::Decoders,
+        crate::decoders::crack_results::CrackResult,
+        crate::decoders::interface::{decoder_getters, DecoderBuilder},
+        std::{thread::sleep, time::Instant},
+    };
+
+    /// A decoder which takes a long time to decode nothing
+    #[cfg(feature = "parallel")]
+    struct SlowDecoder;
+
+    #[cfg(feature = "parallel")]
+    impl Crack for Decoder<SlowDecoder> {
+        fn new() -> Self {
+            DecoderBuilder::new("Slow")
+                .with_description("Sleeps, then gives up")
+                .with_link("N/A")
+                .build()
+        }
+
+        fn decode(&self, _text: &str, _checker: &CheckerTypes) -> CrackResult {
+            sleep(Duration::from_millis(100));
+            CrackResult::new(self)
+        }
+        decoder_getters!();
+    }
 
     #[test]
     fn it_works() {
-        let _decoders = filter_and_get_decoders(&DecoderResult::default());
+        let _decoders = filter_and_get_decoders(&Config::default());
         assert_eq!(2 + 2, 4);
     }
 
+    #[test]
+    fn every_decoder_builds() {
+        // Building a decoder without a description or link panics
+        let decoders = filter_and_get_decoders(&Config::default());
+        assert!(decoders.components.len() > 30);
+        assert!(decoders
+            .components
+            .iter()
+            .all(|decoder| !decoder.get_name().is_empty()));
+    }
+
     #[test]
     fn decoders_can_call_dot_run() {
-        let decoders = filter_and_get_decoders(&DecoderResult::default());
+        let decoders = filter_and_get_decoders(&Config::default());
         let athena_checker = Checker::<Athena>::new();
         let checker = CheckerTypes::CheckAthena(athena_checker);
-        decoders.run("TXIgUm9ib3QgaXMgZ3JlYXQ=", checker);
+        decoders.run(
+            "TXIgUm9ib3QgaXMgZ3JlYXQ=",
+            None,
+            None,
+            &checker,
+            &SearchStats::default(),
+        );
         assert_eq!(true, true);
     }
+
+    #[test]
+    fn reciprocal_decoder_is_not_run_after_itself() {
+        let reverse = Decoder::<ReverseDecoder>::new();
+        let decoders = filter_and_get_decoders(&Config::default());
+        let checker = CheckerTypes::from_closure("Never", |_| None);
+        let decoded_by = |last_decoder| {
+            decoders
+                .run_all(
+                    "olleh",
+                    None,
+                    last_decoder,
+                    &checker,
+                    &SearchStats::default(),
+                )
+                .into_iter()
+                .map(|result| result.decoder)
+                .collect::<Vec<_>>()
+        };
+        assert!(!decoded_by(Some(reverse.name)).contains(&"Reverse"));
+        assert!(decoded_by(None).contains(&"Reverse"));
+    }
+
+    #[test]
+    fn base64_is_attempted_before_slow_exotic_decoders() {
+        let decoders = filter_and_get_decoders(&Config::default());
+        let position = |name: &str| {
+            decoders
+                .components
+                .iter()
+                .position(|decoder| decoder.get_name() == name)
+                .unwrap_or_else(|| panic!("there is no {name} decoder"))
+        };
+        for slow in ["Substitution Solver", "Vigenère", "XOR Repeating Key"] {
+            assert!(position("Base64") < position(slow), "{slow}");
+        }
+        // Caesar is as popular as base64, but has 25 shifts to check
+        assert!(position("Base64") < position("Caesar Cipher"));
+    }
+
+    #[test]
+    fn run_records_how_long_each_decoder_took() {
+        let decoders = filter_and_get_decoders(&Config::default());
+        let athena_checker = Checker::<Athena>::new();
+        let checker = CheckerTypes::CheckAthena(athena_checker);
+        let results = match decoders.run(
+            "TXIgUm9ib3QgaXMgZ3JlYXQ=",
+            None,
+            None,
+            &checker,
+            &SearchStats::default(),
+        ) {
+            MyResults::Break(result) => vec![*result],
+            MyResults::Continue(results) => results,
+        };
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .all(|result| result.time_taken.is_some_and(|t| t > Duration::ZERO)));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn slow_decoders_run_at_the_same_time() {
+        let decoders = Decoders {
+            components: (0..8)
+                .map(|_| Box::new(Decoder::<SlowDecoder>::new()) as Box<dyn Crack + Send + Sync>)
+                .collect(),
+        };
+        let checker = CheckerTypes::CheckAthena(Checker::<Athena>::new());
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        let started = Instant::now();
+        let results =
+            pool.install(|| decoders.run("hello", None, None, &checker, &SearchStats::default()));
+        // One at a time, the 8 decoders take 800ms
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert!(matches!(results, MyResults::Continue(results) if results.len() == 8));
+    }
+
+    #[test]
+    fn first_decoder_to_succeed_is_always_the_answer() {
+        let input = "aGVsbG8gd29ybGQ=";
+        // Anything a decoder changes is accepted, so most decoders succeed
+        let checker = CheckerTypes::from_closure("Changed", move |text| {
+            (text != input).then(|| "it changed".to_string())
+        });
+        let decoders = filter_and_get_decoders(&Config::default());
+        let answer = |decoders: &super::Decoders| {
+            decoders
+                .run(input, None, None, &checker, &SearchStats::default())
+                ._break_value()
+                .expect("a decoder succeeds")
+                .decoder
+        };
+        let first = answer(&decoders);
+        for _ in 0..20 {
+            assert_eq!(answer(&decoders), first);
+        }
+    }
+
+    #[test]
+    fn decoders_and_checkers_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<super::Decoders>();
+        assert_send_sync::<CheckerTypes>();
+    }
 }
@@ -0,0 +1,240 @@
+//! The outcome as one JSON object, so scripts can read what Ares found,
+//! like `ares --output-format json -t ... | jq .plaintext`
+
+use serde::Serialize;
+
+use crate::decoders::crack_results::CrackResult;
+use crate::{CrackOutcome, CrackSuccess, PartialCandidate, RunStats};
+
+/// How cracking ended, with the best plaintext at the top so scripts needn't dig for it
+#[derive(Serialize)]
+struct JsonOutcome<'a> {
+    /// "found", "exhausted", "timed_out", "depth_limited", "frontier_limited", "seen_limited", "cancelled", "lines" or "too_large"
+    status: &'static str,
+    /// The best plaintext, or null if there isn't one
+    plaintext: Option<&'a str>,
+    /// How sure the checker was about the best plaintext, from 0 to 1, or null if there isn't one
+    confidence: Option<f32>,
+    /// The decoders from the input to the best plaintext, empty if there isn't one
+    path: Vec<JsonStep<'a>>,
+    /// Every plaintext found, the best first
+    results: Vec<JsonResult<'a>>,
+    /// When the input was cracked line by line, how each line went
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    lines: Vec<JsonLine<'a>>,
+    /// When the search was cancelled, what it was about to decode, the most promising first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    candidates: Vec<JsonResult<'a>>,
+    /// When the input wasn't cracked as it was too big, why
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// What the search did
+    stats: JsonStats,
+}
+
+/// A plaintext, or text a cancelled search was about to decode, and how Ares got to it
+#[derive(Serialize)]
+struct JsonResult<'a> {
+    /// The text
+    plaintext: &'a str,
+    /// How sure the checker was that it's plaintext, from 0 to 1
+    confidence: f32,
+    /// The checker which identified it, empty if none did
+    checker: &'static str,
+    /// The decoders from the input to it
+    path: Vec<JsonStep<'a>>,
+}
+
+/// One decoder on the way to the plaintext
+#[derive(Serialize)]
+struct JsonStep<'a> {
+    /// The decoder's name
+    decoder: &'static str,
+    /// The key it used, like "shift 13", or null if it doesn't use one
+    key: Option<&'a str>,
+    /// Where to read about the decoder
+    link: &'static str,
+    /// What it decoded the text to, or null if that isn't text
+    output: Option<&'a str>,
+}
+
+/// How one line of the input went
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    /// Which line of the input it is, starting from 0
+    index: usize,
+    /// The line
+    line: &'a str,
+    /// How cracking it ended
+    outcome: JsonOutcome<'a>,
+}
+
+/// What the search did, with the time in seconds as scripts expect
+#[derive(Serialize)]
+struct JsonStats {
+    /// How long cracking took
+    elapsed_seconds: f64,
+    /// How many texts the search decoded
+    nodes_expanded: usize,
+    /// How many times a decoder was run
+    decoder_invocations: usize,
+    /// How many texts the search dropped, as too many were waiting to be decoded
+    nodes_evicted: usize,
+}
+
+impl CrackOutcome {
+    /// The outcome as one JSON object, with the status, the best plaintext,
+    /// how sure the checker was about it, the decoders that got there and what the search did.
+    /// When there's no plaintext it's still an object, with a null plaintext.
+    /// ```rust
+    /// use ares::{perform_cracking, config::Config};
+    ///
+    /// let outcome = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", Config::default());
+    /// let json: serde_json::Value = serde_json::from_str(&outcome.to_json().unwrap()).unwrap();
+    /// assert_eq!(json["status"], "found");
+    /// assert_eq!(json["plaintext"], "hello there general");
+    /// assert_eq!(json["path"][0]["decoder"], "Base64");
+    /// ```
+    /// # Errors
+    /// Returns the serde_json error if serializing fails.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&JsonOutcome::from(self))
+    }
+}
+
+impl<'a> From<&'a CrackOutcome> for JsonOutcome<'a> {
+    fn from(outcome: &'a CrackOutcome) -> Self {
+        let mut json = JsonOutcome {
+            status: outcome.status(),
+            plaintext: None,
+            confidence: None,
+            path: Vec::new(),
+            results: Vec::new(),
+            lines: Vec::new(),
+            candidates: Vec::new(),
+            error: None,
+            stats: JsonStats::from(&outcome.stats()),
+        };
+        match outcome {
+            CrackOutcome::Found(results) => {
+                json.results = results.iter().map(JsonResult::from).collect();
+                if let Some(best) = results.first() {
+                    json.plaintext = Some(&best.plaintext);
+                    json.confidence = best.path.last().map(|step| step.checker_confidence);
+                    json.path = steps(&best.path);
+                }
+            }
+            CrackOutcome::Lines(lines) => {
+                json.lines = lines
+                    .iter()
+                    .map(|line| JsonLine {
+                        index: line.index,
+                        line: &line.line,
+                        outcome: JsonOutcome::from(&line.outcome),
+                    })
+                    .collect();
+            }
+            CrackOutcome::Cancelled(report) => {
+                json.candidates = report.candidates.iter().map(JsonResult::from).collect();
+            }
+            CrackOutcome::TooLarge(too_large) => json.error = Some(too_large.to_string()),
+            CrackOutcome::Exhausted(_)
+            | CrackOutcome::TimedOut(_)
+            | CrackOutcome::DepthLimited(_)
+            | CrackOutcome::FrontierLimited(_)
+            | CrackOutcome::SeenLimited(_) => {}
+        }
+        json
+    }
+}
+
+/// The decoders in the path, in order
+fn steps(path: &[CrackResult]) -> Vec<JsonStep<'_>> {
+    path.iter()
+        .map(|step| JsonStep {
+            decoder: step.decoder,
+            key: step.key.as_deref(),
+            link: step.link,
+            output: step
+                .unencrypted_text
+                .as_ref()
+                .and_then(|texts| texts.first())
+                .map(String::as_str),
+        })
+        .collect()
+}
+
+impl<'a> From<&'a CrackSuccess> for JsonResult<'a> {
+    fn from(success: &'a CrackSuccess) -> Self {
+        let last = success.path.last();
+        JsonResult {
+            plaintext: &success.plaintext,
+            confidence: last.map_or(0.0, |step| step.checker_confidence),
+            checker: last.map_or("", |step| step.checker_name),
+            path: steps(&success.path),
+        }
+    }
+}
+
+impl<'a> From<&'a PartialCandidate> for JsonResult<'a> {
+    fn from(candidate: &'a PartialCandidate) -> Self {
+        JsonResult {
+            plaintext: &candidate.text,
+            confidence: candidate.checker_confidence,
+            checker: "",
+            path: steps(&candidate.path),
+        }
+    }
+}
+
+impl From<&RunStats> for JsonStats {
+    fn from(stats: &RunStats) -> Self {
+        JsonStats {
+            elapsed_seconds: stats.elapsed.as_secs_f64(),
+            nodes_expanded: stats.nodes_expanded,
+            decoder_invocations: stats.decoder_invocations,
+            nodes_evicted: stats.nodes_evicted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::Decoder;
+    use crate::{CrackOutcome, CrackSuccess, RunStats};
+
+    #[test]
+    fn newlines_and_non_ascii_are_escaped() {
+        let mut step =
+            CrackResult::new(&Decoder::default()).with_encrypted_text("aGk=".to_string());
+        step.unencrypted_text = Some(vec!["first line\n\"naïve\" ✓".to_string()]);
+        step.checker_confidence = 0.75;
+        let outcome = CrackOutcome::Found(vec![CrackSuccess {
+            plaintext: "first line\n\"naïve\" ✓".to_string(),
+            bytes: None,
+            path: vec![step],
+            stats: RunStats::default(),
+        }]);
+        let json = outcome.to_json().unwrap();
+        // One line, so it can be read line by line
+        assert!(!json.contains('\n'), "{json}");
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["plaintext"], "first line\n\"naïve\" ✓");
+        assert_eq!(value["confidence"], 0.75);
+        assert_eq!(value["path"][0]["output"], "first line\n\"naïve\" ✓");
+        assert_eq!(value["results"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn no_plaintext_is_still_an_object() {
+        let json = CrackOutcome::TimedOut(RunStats::default())
+            .to_json()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["status"], "timed_out");
+        assert!(value["plaintext"].is_null());
+        assert!(value["path"].as_array().unwrap().is_empty());
+        assert_eq!(value["stats"]["nodes_expanded"], 0);
+    }
+}
@@ -9,10 +9,15 @@
 
 /// The main crate for the Ares project.
 /// This provides the library API interface for Ares.
+// Not wired up to the CLI yet, see `cli_input_parser`.
+#[allow(dead_code)]
 mod api_library_input_struct;
+/// Stops a search early, keeping what it found so far
+pub mod cancellation;
 /// Checkers is a module that contains the functions that check if the input is plaintext
 pub mod checkers;
 /// CLI Arg Parsing library
+#[cfg(not(target_arch = "wasm32"))]
 pub mod cli;
 /// CLI Input Parser parses the input from the CLI and returns a struct.
 mod cli_input_parser;
@@ -23,135 +28,982 @@ pub mod cli_pretty_printing;
 pub mod config;
 /// Decoders are the functions that actually perform the decodings.
 pub mod decoders;
+/// `ares_crack` for C and C++, see `ffi`
+#[cfg(feature = "ffi")]
+pub mod ffi;
 /// The filtration system builds what decoders to use at runtime
 /// By default it will use them all.
+/// It also cleans invisible characters out of the input before the search.
 mod filtration_system;
+/// The outcome as JSON, for scripts
+#[cfg(feature = "serde")]
+mod json_output;
+/// Renders how Ares got from the input to the plaintext, for people to read
+pub mod output;
+/// Tells a program using Ares how a long search is going
+pub mod progress;
+/// The `ares` Python module, see `python`
+#[cfg(feature = "python")]
+mod python;
 /// The searcher is the thing which searches for the plaintext
 /// It is the core of the program.
 mod searchers;
 /// The storage module contains all the dictionaries and provides
 /// storage of data to our decoderrs and checkers.
 mod storage;
+/// Fixtures for the unit tests
+#[cfg(test)]
+mod test_helpers;
 /// Timer for internal use
 mod timer;
+/// `crack` for JavaScript, taking its options as JSON and giving back the outcome as JSON
+#[cfg(feature = "serde")]
+pub mod wasm;
 
-use checkers::{
-    athena::Athena,
-    checker_result::CheckResult,
-    checker_type::{Check, Checker},
-};
+// The browser's clock comes from js-sys
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("build for wasm32 with `--no-default-features --features wasm`");
+
+use checkers::{athena::Athena, checker_type::Checker, CheckerTypes};
 use log::debug;
+use std::fmt;
+use std::sync::Arc;
 
-use crate::{config::Config, decoders::interface::Decoder};
+use crate::{
+    cancellation::CancellationToken,
+    config::Config,
+    decoders::interface::Decoder,
+    progress::{Progress, ProgressCallback},
+    timer::Timer,
+};
 
 use self::decoders::crack_results::CrackResult;
+use self::filtration_system::invisible_characters::clean_input;
 /// The main function to call which performs the cracking.
+/// The config decides what counts as plaintext, with Athena unless there's a regex or wordlist,
+/// how long and how deep to search and how, which decoders to use, and how many plaintexts to look for.
+/// The CLI is a thin wrapper around this, so they crack the same way.
 /// ```rust
 /// use ares::perform_cracking;
 /// use ares::config::Config;
+/// use std::time::Duration;
 /// let mut config = Config::default();
 /// // You can set the config to your liking using the Config struct
 /// // Just edit the data like below if you want:
-/// config.timeout = 5;
+/// config.timeout = Duration::from_secs(5);
+/// config.max_depth = 10;
 /// config.human_checker_on = false;
 /// config.verbose = 0;
 /// let result = perform_cracking("VGhlIG1haW4gZnVuY3Rpb24gdG8gY2FsbCB3aGljaCBwZXJmb3JtcyB0aGUgY3JhY2tpbmcu", config);
 /// assert!(true);
-/// // The result is an Option<DecoderResult> so we need to unwrap it
-/// // The DecoderResult contains the text and the path
-/// // The path is a vector of CrackResults which contains the decoder used and the keys used
-/// // The text is a vector of strings because some decoders return more than 1 text (Caesar)
-/// // Becuase the program has returned True, the first result is the plaintext (and it will only have 1 result).
-/// // This is some tech debt we need to clean up https://github.com/bee-san/Ares/issues/130
-/// assert!(result.unwrap().text[0] == "The main function to call which performs the cracking.");
+/// // The result is a CrackOutcome, which is Found with a CrackSuccess when we found the plaintext
+/// // The CrackSuccess contains the plaintext and the path to it
+/// // The path is a vector of CrackResults, one per decoder from the input to the plaintext,
+/// // with the text each decoder consumed, the candidate it produced, and any key it used
+/// // However it ended, the outcome says what the search did
+/// let stats = result.stats();
+/// assert!(stats.decoder_invocations >= 1);
+/// let success = result.found().unwrap();
+/// assert_eq!(success.plaintext, "The main function to call which performs the cracking.");
+/// assert_eq!(success.path[0].decoder, "Base64");
 /// ```
 /// The human checker defaults to off in the config, but it returns the first thing it finds currently.
 /// We have an issue for that here https://github.com/bee-san/Ares/issues/129
 /// ```rust
-/// use ares::perform_cracking;
+/// use ares::{perform_cracking, CrackOutcome};
 /// use ares::config::Config;
+/// use std::time::Duration;
 /// let mut config = Config::default();
 /// // You can set the config to your liking using the Config struct
 /// // Just edit the data like below if you want:
-/// config.timeout = 0;
+/// config.timeout = Duration::ZERO;
 /// let result = perform_cracking("VGhlIG1haW4gZnVuY3Rpb24gdG8gY2FsbCB3aGljaCBwZXJmb3JtcyB0aGUgY3JhY2tpbmcu", config);
 /// assert!(true);
-/// // If the program times out, or it cannot decode the text it will return None.
-/// assert!(result.is_none());
+/// // If the program times out, or it cannot decode the text, the outcome says why.
+/// assert!(matches!(result, CrackOutcome::TimedOut(_)));
 /// ```
-pub fn perform_cracking(text: &str, config: Config) -> Option<DecoderResult> {
-    config::set_global_config(config);
-    let initial_check_for_plaintext = check_if_input_text_is_plaintext(text);
-    if initial_check_for_plaintext.is_identified {
-        debug!(
-            "The input text provided to the program {} is the plaintext. Returning early.",
-            text
-        );
-        cli_pretty_printing::return_early_because_input_text_is_plaintext();
+/// If you know something about the plaintext, a regex finds it where English detection wouldn't.
+/// ```rust
+/// use ares::perform_cracking;
+/// use ares::config::Config;
+/// let config = Config {
+///     regex: Some(r"^flag\{\w+\}$".to_string()),
+///     ..Config::default()
+/// };
+/// // "flag{zqxv_9}" in base64, then hexadecimal
+/// let success = perform_cracking("5a6d78685a33743663586832587a6c39", config)
+///     .found()
+///     .unwrap();
+/// assert_eq!(success.plaintext, "flag{zqxv_9}");
+/// assert_eq!(success.path.len(), 2);
+/// assert_eq!(success.path[1].checker_name, "Regex Checker");
+/// ```
+pub fn perform_cracking(text: &str, config: Config) -> CrackOutcome {
+    perform_cracking_with_cancellation(text, config, CancellationToken::new())
+}
+
+/// Performs the cracking like `perform_cracking`, but stops early when `cancel` is cancelled,
+/// such as when the user presses Ctrl-C. The search stops before it decodes the next text,
+/// and says what it was about to decode in `CrackOutcome::Cancelled`.
+/// ```rust
+/// use ares::cancellation::CancellationToken;
+/// use ares::config::Config;
+/// use ares::{perform_cracking_with_cancellation, CrackOutcome};
+///
+/// let cancel = CancellationToken::new();
+/// cancel.cancel();
+/// let result = perform_cracking_with_cancellation("Uryyb, jbeyq! Ubj ner lbh gbqnl?", Config::default(), cancel);
+/// let CrackOutcome::Cancelled(report) = result else {
+///     panic!("the search was cancelled before it started");
+/// };
+/// assert_eq!(report.stats.nodes_expanded, 0);
+/// assert_eq!(report.candidates[0].text, "Uryyb, jbeyq! Ubj ner lbh gbqnl?");
+/// ```
+pub fn perform_cracking_with_cancellation(
+    text: &str,
+    config: Config,
+    cancel: CancellationToken,
+) -> CrackOutcome {
+    perform_cracking_with_progress(text, config, cancel, None)
+}
+
+/// Performs the cracking like `perform_cracking_with_cancellation`, calling `progress`
+/// with how the search is going, such as to draw a status line.
+/// It's called at most every 100ms or every 100 texts decoded, so it costs the search
+/// next to nothing, and once more when each search ends, before this returns.
+/// ```rust
+/// use ares::cancellation::CancellationToken;
+/// use ares::config::Config;
+/// use ares::perform_cracking_with_progress;
+/// use std::sync::mpsc;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let result = perform_cracking_with_progress(
+///     "aGVsbG8gdGhlcmUgZ2VuZXJhbA==",
+///     Config::default(),
+///     CancellationToken::new(),
+///     Some(Box::new(move |event| sender.send(event).unwrap())),
+/// );
+/// assert_eq!(result.found().unwrap().plaintext, "hello there general");
+/// let last = receiver.iter().last().unwrap();
+/// assert!(last.nodes_expanded >= 1);
+/// ```
+pub fn perform_cracking_with_progress(
+    text: &str,
+    config: Config,
+    cancel: CancellationToken,
+    progress: Option<ProgressCallback>,
+) -> CrackOutcome {
+    let athena = CheckerTypes::CheckAthena(Checker::<Athena>::from_config(&config));
+    let progress = progress.map(Progress::new);
+    search_with_checker(
+        text,
+        athena,
+        Arc::new(config),
+        &cancel,
+        progress.as_ref(),
+        true,
+    )
+}
+
+/// Performs the cracking like `perform_cracking`, but the plaintext is whatever the checker identifies,
+/// so programs using Ares can bring their own checker, such as one made with `CheckerTypes::from_closure`.
+/// The checker is used as it is, so make Athena with `Checker::<Athena>::from_config` to give it
+/// this config's regex, wordlist and thresholds.
+/// ```rust
+/// use ares::perform_cracking_with_checker;
+/// use ares::checkers::CheckerTypes;
+/// use ares::config::Config;
+///
+/// let checker = CheckerTypes::from_closure("Flag Checker", |text| {
+///     text.starts_with("FLAG-").then(|| "starts with FLAG-".to_string())
+/// });
+/// // "FLAG-zqxv" in base64
+/// let result = perform_cracking_with_checker("RkxBRy16cXh2", Config::default(), checker)
+///     .found()
+///     .unwrap();
+/// assert_eq!(result.plaintext, "FLAG-zqxv");
+/// assert_eq!(result.path.last().unwrap().checker_name, "Flag Checker");
+/// ```
+pub fn perform_cracking_with_checker(
+    text: &str,
+    config: Config,
+    checker: CheckerTypes,
+) -> CrackOutcome {
+    search_with_checker(
+        text,
+        checker,
+        Arc::new(config),
+        &CancellationToken::new(),
+        None,
+        true,
+    )
+}
 
-        let mut crack_result = CrackResult::new(&Decoder::default(), (&text).to_string());
-        crack_result.checker_name = initial_check_for_plaintext.checker_name;
+/// Carries on cracking a plaintext Ares found, for when the checker accepted text which was still encoded,
+/// a layer too early. The search starts from the plaintext without checking it again,
+/// with the config given, usually the first search's, and the plaintexts it finds have the path from the original input.
+/// Otherwise it's cracked like `perform_cracking_with_progress` cracks its input, down to its size limit and its lines.
+/// ```rust
+/// use ares::cancellation::CancellationToken;
+/// use ares::checkers::CheckerTypes;
+/// use ares::config::Config;
+/// use ares::{continue_cracking, perform_cracking_with_checker};
+///
+/// // A checker which accepts base64 of "hello" as the plaintext
+/// let checker = CheckerTypes::from_closure("Too Early Checker", |text| {
+///     text.starts_with("aGVsbG8").then(|| "is hello in base64".to_string())
+/// });
+/// // "hello there general" in base64, then base64 again
+/// let input = "YUdWc2JHOGdkR2hsY21VZ1oyVnVaWEpoYkE9PQ==";
+/// let too_early = perform_cracking_with_checker(input, Config::default(), checker)
+///     .found()
+///     .unwrap();
+/// assert_eq!(too_early.plaintext, "aGVsbG8gdGhlcmUgZ2VuZXJhbA==");
+///
+/// let found = continue_cracking(&too_early, Config::default(), CancellationToken::new(), None)
+///     .found()
+///     .unwrap();
+/// assert_eq!(found.plaintext, "hello there general");
+/// let decoders: Vec<&str> = found.path.iter().map(|step| step.decoder).collect();
+/// assert_eq!(decoders, ["Base64", "Base64"]);
+/// ```
+pub fn continue_cracking(
+    found: &CrackSuccess,
+    config: Config,
+    cancel: CancellationToken,
+    progress: Option<ProgressCallback>,
+) -> CrackOutcome {
+    let athena = CheckerTypes::CheckAthena(Checker::<Athena>::from_config(&config));
+    let progress = progress.map(Progress::new);
+    let outcome = search_with_checker(
+        &found.plaintext,
+        athena,
+        Arc::new(config),
+        &cancel,
+        progress.as_ref(),
+        false,
+    );
+    // The input itself being the plaintext isn't a step on the way
+    let before: Vec<&CrackResult> = found
+        .path
+        .iter()
+        .filter(|step| step.decoder != Decoder::default().name)
+        .collect();
+    with_path_before(outcome, &before)
+}
+
+/// Puts the steps before the path of every plaintext in the outcome, including those of its lines
+fn with_path_before(outcome: CrackOutcome, before: &[&CrackResult]) -> CrackOutcome {
+    match outcome {
+        CrackOutcome::Found(mut results) => {
+            for result in &mut results {
+                result.path = before
+                    .iter()
+                    .map(|&step| step.clone())
+                    .chain(result.path.drain(..))
+                    .collect();
+            }
+            CrackOutcome::Found(results)
+        }
+        CrackOutcome::Lines(lines) => CrackOutcome::Lines(
+            lines
+                .into_iter()
+                .map(|line| LineOutcome {
+                    outcome: with_path_before(line.outcome, before),
+                    ..line
+                })
+                .collect(),
+        ),
+        outcome => outcome,
+    }
+}
 
-        let output = DecoderResult {
-            text: vec![(&text).to_string()],
-            path: vec![crack_result],
+/// Cracks the input with the config, which is the one `config::get_config` returns while it does,
+/// so everything the crack reads, down to the decoders, is from this config rather than the global one.
+/// If the whole input can't be cracked and it has enough lines, each line is cracked on its own,
+/// see `Config::split_lines`. The whole input always goes first, so the lines of one wrapped
+/// encoding, like a long base64 blob, aren't cracked as if they were separate.
+/// The whole input has half of `Config::timeout` when it could be split, and the lines share what's left,
+/// so splitting never makes cracking take longer.
+/// Unless `check_input` is set, the whole input isn't checked before it's searched, as when it's already a plaintext.
+fn search_with_checker(
+    text: &str,
+    checker: CheckerTypes,
+    config: Arc<Config>,
+    cancel: &CancellationToken,
+    progress: Option<&Progress>,
+    check_input: bool,
+) -> CrackOutcome {
+    config::with_config(Arc::clone(&config), || {
+        search_with_config(text, checker, &config, cancel, progress, check_input)
+    })
+}
+
+/// Cracks the input like `search_with_checker`, once the config is the crack's
+fn search_with_config(
+    text: &str,
+    checker: CheckerTypes,
+    config: &Config,
+    cancel: &CancellationToken,
+    progress: Option<&Progress>,
+    check_input: bool,
+) -> CrackOutcome {
+    if text.len() > config.max_input_size {
+        return CrackOutcome::TooLarge(InputTooLarge {
+            size: text.len(),
+            limit: config.max_input_size,
+        });
+    }
+    let timer = Timer::start(config.timeout);
+    // One checker for the whole run, so the human checker never asks about the same text twice,
+    // and text which different decoders agree on is only checked once
+    let checker = CheckerTypes::human_confirmed(CheckerTypes::cached(CheckerTypes::previewed(
+        checker,
+        config.preview_size,
+    )));
+    let lines = lines_to_crack(text);
+    let fewest_lines = if config.split_lines {
+        2
+    } else {
+        AUTO_SPLIT_LINES
+    };
+    let can_split = lines.len() >= fewest_lines;
+    // When it can be split, the whole input has half the time, and its lines the rest
+    let outcome = if can_split {
+        let whole = Config {
+            timeout: config.timeout / 2,
+            ..config.clone()
         };
+        search_input(text, &checker, &whole, cancel, progress, check_input)
+    } else {
+        search_input(text, &checker, config, cancel, progress, check_input)
+    };
+    if outcome.is_found() || matches!(outcome, CrackOutcome::Cancelled(_)) || !can_split {
+        return outcome;
+    }
+    debug!(
+        "The whole input couldn't be cracked, so cracking its {} lines one by one",
+        lines.len()
+    );
+    let lines: Vec<LineOutcome> = lines
+        .into_iter()
+        .map(|(index, line)| {
+            let time_left = config.timeout.saturating_sub(timer.elapsed());
+            // Lines left once the time is up aren't searched at all
+            let outcome = if time_left.is_zero() {
+                CrackOutcome::TimedOut(RunStats::default())
+            } else {
+                let config = Config {
+                    timeout: time_left,
+                    ..config.clone()
+                };
+                search_input(line, &checker, &config, cancel, progress, true)
+            };
+            LineOutcome {
+                index,
+                line: line.to_string(),
+                outcome,
+            }
+        })
+        .collect();
+    // If no line cracks either, why the whole input failed says more
+    if !lines.iter().any(|line| line.outcome.is_found()) {
+        return outcome;
+    }
+    CrackOutcome::Lines(lines)
+}
+
+/// The biggest input Ares cracks by default, see `Config::max_input_size`
+pub const DEFAULT_MAX_INPUT_SIZE: usize = 1024 * 1024;
 
-        return Some(output);
+/// Inputs with at least this many lines are cracked line by line when the whole input can't be
+const AUTO_SPLIT_LINES: usize = 3;
+
+/// The lines worth cracking on their own, and where they are in the input, starting from 0.
+/// Blank lines and comments, starting with "#" or "//", are left out.
+fn lines_to_crack(text: &str) -> Vec<(usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+        .collect()
+}
+
+/// Checks the text, if `check_input` is set, and searches for its plaintext
+fn search_input(
+    text: &str,
+    checker: &CheckerTypes,
+    config: &Config,
+    cancel: &CancellationToken,
+    progress: Option<&Progress>,
+    check_input: bool,
+) -> CrackOutcome {
+    let input = clean_input(text);
+    cli_pretty_printing::cleaned_input(&input);
+    if !check_input {
+        return searchers::search_for_plaintext(&input, checker, config, cancel, progress);
     }
 
-    // Build a new search tree
-    // This starts us with a node with no parents
-    // let search_tree = searchers::Tree::new(text.to_string());
-    // Perform the search algorithm
-    // It will either return a failure or success.
-    searchers::search_for_plaintext(text)
+    // A message hidden in the input is what the input is hiding,
+    // so the text hiding it isn't the answer by itself
+    let early_plaintext = match &input.hidden_message {
+        Some(hidden) => check_if_hidden_message_is_plaintext(hidden, checker),
+        None => check_if_input_text_is_plaintext(&input.text, checker),
+    };
+    let Some(output) = early_plaintext else {
+        // Build a new search tree
+        // This starts us with a node with no parents
+        // let search_tree = searchers::Tree::new(text.to_string());
+        // Perform the search algorithm
+        // It will either return a failure or success.
+        return searchers::search_for_plaintext(&input, checker, config, cancel, progress);
+    };
+
+    if config.results_wanted <= 1 {
+        return CrackOutcome::Found(vec![output]);
+    }
+    // Something decoded from the input may be plaintext too, so look for the rest
+    let searched = searchers::search_for_plaintext(&input, checker, config, cancel, progress);
+    let stats = searched.stats();
+    let mut found = vec![output];
+    found.extend(searched.found_all());
+    // They all came from the one search
+    for result in &mut found {
+        result.stats = stats.clone();
+    }
+    searchers::rank_plaintexts(&mut found);
+    found.truncate(config.results_wanted);
+    CrackOutcome::Found(found)
 }
 
 /// Checks if the given input is plaintext or not
 /// Used at the start of the program to not waste CPU cycles
-fn check_if_input_text_is_plaintext(text: &str) -> CheckResult {
-    let athena_checker = Checker::<Athena>::new();
-    athena_checker.check(text)
+fn check_if_input_text_is_plaintext(text: &str, checker: &CheckerTypes) -> Option<CrackSuccess> {
+    let initial_check_for_plaintext = checker.check(text);
+    if !initial_check_for_plaintext.is_identified {
+        return None;
+    }
+    debug!(
+        "The input text provided to the program {} is the plaintext. Returning early.",
+        text
+    );
+    cli_pretty_printing::return_early_because_input_text_is_plaintext();
+
+    let mut crack_result =
+        CrackResult::new(&Decoder::default()).with_encrypted_text(text.to_string());
+    crack_result.checker_name = initial_check_for_plaintext.checker_name;
+    crack_result.checker_reason = initial_check_for_plaintext.reason;
+    crack_result.checker_confidence = initial_check_for_plaintext.confidence;
+
+    Some(CrackSuccess {
+        plaintext: text.to_string(),
+        bytes: None,
+        path: vec![crack_result],
+        stats: RunStats::default(),
+    })
 }
 
-/// DecoderResult is the result of decoders
+/// Checks if the message hidden in the input's zero-width characters is the plaintext,
+/// which the search would otherwise only decode
+fn check_if_hidden_message_is_plaintext(
+    hidden: &CrackResult,
+    checker: &CheckerTypes,
+) -> Option<CrackSuccess> {
+    let message = hidden.unencrypted_text.as_ref()?.first()?;
+    let check_result = checker.check(message);
+    if !check_result.is_identified {
+        return None;
+    }
+    debug!(
+        "The message hidden in the input, {}, is the plaintext",
+        message
+    );
+    let mut crack_result = hidden.clone();
+    crack_result.update_checker(&check_result);
+    Some(CrackSuccess {
+        plaintext: message.clone(),
+        bytes: None,
+        path: vec![crack_result],
+        stats: RunStats::default(),
+    })
+}
+
+/// How cracking ended: with the plaintext, or with why there isn't one
 #[derive(Debug)]
-pub struct DecoderResult {
-    /// The text we have from the decoder, as a vector
-    /// because the decoder might return more than 1 text (caesar)
-    pub text: Vec<String>,
-    /// The list of decoders we have so far
-    /// The CrackResult contains more than just each decoder, such as the keys used
-    /// or the checkers used.
-    pub path: Vec<CrackResult>,
+pub enum CrackOutcome {
+    /// The plaintexts and how we got to them, the best first.
+    /// There is one unless `Config::results_wanted` asked for more, and never none.
+    Found(Vec<CrackSuccess>),
+    /// Everything that could be decoded was, and none of it was plaintext
+    Exhausted(RunStats),
+    /// The search ran out of time, see `Config::timeout`
+    TimedOut(RunStats),
+    /// Everything within `Config::max_depth` decoders of the input was decoded and none of it was plaintext,
+    /// but there was more to decode deeper down
+    DepthLimited(RunStats),
+    /// Everything the search kept was decoded and none of it was plaintext, but it dropped
+    /// the least promising text when more than `Config::max_frontier` texts were waiting,
+    /// so the plaintext may have been among what it dropped
+    FrontierLimited(RunStats),
+    /// Everything the search queued was decoded and none of it was plaintext, but it had seen
+    /// as many texts as it remembers, 2 million, so it stopped queueing new ones,
+    /// and the plaintext may have been among them
+    SeenLimited(RunStats),
+    /// The search was cancelled before it found the plaintext, see `perform_cracking_with_cancellation`.
+    /// The report has what it was about to decode.
+    Cancelled(PartialReport),
+    /// The whole input couldn't be cracked, but some of its lines could, see `Config::split_lines`.
+    /// There is one for each line which wasn't blank or a comment, in the order they're in the input.
+    Lines(Vec<LineOutcome>),
+    /// The input was bigger than `Config::max_input_size`, so it wasn't searched at all
+    TooLarge(InputTooLarge),
 }
 
-/// Creates a default DecoderResult with Default as the text / path
-impl Default for DecoderResult {
-    fn default() -> Self {
-        DecoderResult {
-            text: vec!["Default".to_string()],
-            path: vec![CrackResult::new(&Decoder::default(), "Default".to_string())],
-        }
+/// Why an input wasn't cracked: it's bigger than `Config::max_input_size`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputTooLarge {
+    /// How big the input is, in bytes
+    pub size: usize,
+    /// The biggest input Ares cracks, in bytes
+    pub limit: usize,
+}
+
+impl fmt::Display for InputTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the input is {} bytes, but Ares only cracks inputs of up to {} bytes. Crack just the part of it which is encoded, or raise the limit",
+            self.size, self.limit
+        )
     }
 }
 
-/// Lets us create a new decoderResult with given text
-impl DecoderResult {
-    /// It's only used in tests so it thinks its dead code
-    fn _new(text: &str) -> Self {
-        DecoderResult {
-            text: vec![text.to_string()],
-            path: vec![CrackResult::new(&Decoder::default(), "Default".to_string())],
+impl std::error::Error for InputTooLarge {}
+
+/// How cracking one line of the input ended
+#[derive(Debug)]
+pub struct LineOutcome {
+    /// Which line of the input it is, starting from 0, counting blank lines and comments
+    pub index: usize,
+    /// The line, without the whitespace around it
+    pub line: String,
+    /// How cracking the line ended
+    pub outcome: CrackOutcome,
+}
+
+impl CrackOutcome {
+    /// The best plaintext and how we got to it, if we found one.
+    /// When the input was cracked line by line there isn't one plaintext, see `CrackOutcome::Lines`.
+    pub fn found(self) -> Option<CrackSuccess> {
+        self.found_all().into_iter().next()
+    }
+
+    /// Every plaintext we found and how we got to it, the best first
+    pub fn found_all(self) -> Vec<CrackSuccess> {
+        match self {
+            CrackOutcome::Found(results) => results,
+            _ => Vec::new(),
         }
     }
+
+    /// Whether we found the plaintext
+    pub fn is_found(&self) -> bool {
+        matches!(self, CrackOutcome::Found(_))
+    }
+
+    /// What a script matches on to tell how cracking ended, like "found" or "timed_out"
+    pub fn status(&self) -> &'static str {
+        match self {
+            CrackOutcome::Found(_) => "found",
+            CrackOutcome::Exhausted(_) => "exhausted",
+            CrackOutcome::TimedOut(_) => "timed_out",
+            CrackOutcome::DepthLimited(_) => "depth_limited",
+            CrackOutcome::FrontierLimited(_) => "frontier_limited",
+            CrackOutcome::SeenLimited(_) => "seen_limited",
+            CrackOutcome::Cancelled(_) => "cancelled",
+            CrackOutcome::Lines(_) => "lines",
+            CrackOutcome::TooLarge(_) => "too_large",
+        }
+    }
+
+    /// What the search did, however it ended.
+    /// When the input was cracked line by line, it's what the searches of every line did together.
+    pub fn stats(&self) -> RunStats {
+        match self {
+            CrackOutcome::Found(results) => results
+                .first()
+                .map(|result| result.stats.clone())
+                .unwrap_or_default(),
+            CrackOutcome::Exhausted(stats)
+            | CrackOutcome::TimedOut(stats)
+            | CrackOutcome::DepthLimited(stats)
+            | CrackOutcome::FrontierLimited(stats)
+            | CrackOutcome::SeenLimited(stats) => stats.clone(),
+            CrackOutcome::Cancelled(report) => report.stats.clone(),
+            // It wasn't searched
+            CrackOutcome::TooLarge(_) => RunStats::default(),
+            CrackOutcome::Lines(lines) => lines.iter().map(|line| line.outcome.stats()).fold(
+                RunStats::default(),
+                |total, line| RunStats {
+                    nodes_expanded: total.nodes_expanded + line.nodes_expanded,
+                    decoder_invocations: total.decoder_invocations + line.decoder_invocations,
+                    decoders_pruned: total.decoders_pruned + line.decoders_pruned,
+                    nodes_evicted: total.nodes_evicted + line.nodes_evicted,
+                    largest_frontier: total.largest_frontier.max(line.largest_frontier),
+                    elapsed: total.elapsed + line.elapsed,
+                },
+            ),
+        }
+    }
+}
+
+/// What a search did, so runs with different configs can be compared
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RunStats {
+    /// How many texts the search decoded
+    pub nodes_expanded: usize,
+    /// How many times a decoder was run
+    pub decoder_invocations: usize,
+    /// How many times a decoder wasn't run, as it couldn't decode the text
+    pub decoders_pruned: usize,
+    /// How many texts the search dropped, as too many were waiting to be decoded
+    pub nodes_evicted: usize,
+    /// The most texts waiting to be decoded at once
+    pub largest_frontier: usize,
+    /// How long the search ran for
+    pub elapsed: std::time::Duration,
+}
+
+/// What a cancelled search had done, and the text it was about to decode
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PartialReport {
+    /// The most promising text the search hadn't decoded yet, the best first.
+    /// The text the checker was most confident about comes first,
+    /// and of those, the text which looks least random.
+    pub candidates: Vec<PartialCandidate>,
+    /// What the search did before it was cancelled
+    pub stats: RunStats,
+}
+
+/// Text a cancelled search was about to decode, and how it got to it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PartialCandidate {
+    /// The text. When it's binary, this is a lossy copy of `bytes`.
+    pub text: String,
+    /// When the text is binary, these are its bytes
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub bytes: Option<Vec<u8>>,
+    /// How sure the checker was that the text is plaintext, from 0 to 1
+    pub checker_confidence: f32,
+    /// The entropy of the text as a share of the most possible, from 0 to 1.
+    /// The lower it is, the less random the text looks.
+    pub entropy: f64,
+    /// Every decoder from the input to the text, like `CrackSuccess::path`
+    pub path: Vec<CrackResult>,
+}
+
+/// The plaintext, and how Ares got to it from the input
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CrackSuccess {
+    /// The plaintext. When it's a file rather than text, this is a lossy copy of `bytes`.
+    pub plaintext: String,
+    /// When the plaintext is a file rather than text, these are its bytes
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub bytes: Option<Vec<u8>>,
+    /// Every decoder from the input to the plaintext, in the order they were used.
+    /// Each CrackResult has the text the decoder consumed, the one candidate it produced
+    /// which the search followed, and the key it used, if any.
+    pub path: Vec<CrackResult>,
+    /// What the search did before it stopped. When it looked for more than one plaintext,
+    /// every plaintext has the same stats, for the whole search.
+    /// When the input itself is the plaintext there was no search, so they are all zero.
+    pub stats: RunStats,
+}
+
+impl CrackSuccess {
+    /// Serializes the path, from the ciphertext to the plaintext, as a JSON array of CrackResults
+    /// # Errors
+    /// Returns the serde_json error if serializing fails.
+    #[cfg(feature = "serde")]
+    pub fn path_to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.path)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::perform_cracking;
+    use super::{
+        continue_cracking, perform_cracking, perform_cracking_with_checker,
+        perform_cracking_with_progress, CrackOutcome, InputTooLarge,
+    };
+    use crate::cancellation::CancellationToken;
+    use crate::checkers::CheckerTypes;
     use crate::config::Config;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_decode_path_serializes_as_json_array() {
+        let config = Config::default();
+        // "Hello, World!" in base64, in base64
+        let result = perform_cracking("U0dWc2JHOHNJRmR2Y214a0lRPT0=", config)
+            .found()
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&result.path_to_json().unwrap()).unwrap();
+
+        let path = json.as_array().unwrap();
+        assert_eq!(path.len(), result.path.len());
+        assert_eq!(path[0]["encrypted_text"], "U0dWc2JHOHNJRmR2Y214a0lRPT0=");
+        assert_eq!(path.last().unwrap()["unencrypted_text"][0], "Hello, World!");
+    }
+
+    #[test]
+    fn test_perform_cracking_with_a_custom_checker() {
+        let secret = || {
+            CheckerTypes::from_closure("Secret Checker", |text| {
+                text.contains("secret")
+                    .then(|| "mentions a secret".to_string())
+            })
+        };
+        // "zqxv secret wpkt" in base64, which Athena wouldn't call plaintext
+        let result =
+            perform_cracking_with_checker("enF4diBzZWNyZXQgd3BrdA==", Config::default(), secret())
+                .found()
+                .unwrap();
+        assert_eq!(result.plaintext, "zqxv secret wpkt");
+        let last = result.path.last().unwrap();
+        assert_eq!(last.decoder, "Base64");
+        assert_eq!(last.checker_name, "Secret Checker");
+        assert_eq!(last.checker_reason, "mentions a secret");
+
+        // It works inside a composite too
+        let flag = CheckerTypes::from_closure("Flag Checker", |text| {
+            text.starts_with("FLAG-")
+                .then(|| "starts with FLAG-".to_string())
+        });
+        let result = perform_cracking_with_checker(
+            "enF4diBzZWNyZXQgd3BrdA==",
+            Config::default(),
+            CheckerTypes::any_of(vec![flag, secret()]),
+        )
+        .found()
+        .unwrap();
+        assert_eq!(result.path.last().unwrap().checker_name, "Secret Checker");
+    }
+
+    #[test]
+    fn test_invisible_characters_do_not_stop_decoding() {
+        // "hello there general" in base64, copied from a web page with a zero width space and a BOM in it
+        let result = perform_cracking(
+            "\u{FEFF}aGVsbG8gdGhl\u{200B}cmUgZ2VuZXJhbA==",
+            Config::default(),
+        )
+        .found()
+        .unwrap();
+        assert_eq!(result.plaintext, "hello there general");
+        assert_eq!(result.path.len(), 1);
+        assert_eq!(result.path[0].decoder, "Base64");
+    }
+
+    #[test]
+    fn test_message_hidden_in_zero_width_characters_is_decoded() {
+        // "the secret is in the vault" in base64, as zero width spaces and non-joiners
+        let hidden: String = "dGhlIHNlY3JldCBpcyBpbiB0aGUgdmF1bHQ="
+            .bytes()
+            .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1))
+            .map(|bit| if bit == 0 { '\u{200B}' } else { '\u{200C}' })
+            .collect();
+        let input = format!("nothing to see here{hidden}");
+        let result = perform_cracking(&input, Config::default()).found().unwrap();
+        assert_eq!(result.plaintext, "the secret is in the vault");
+        let decoders: Vec<&str> = result.path.iter().map(|step| step.decoder).collect();
+        assert_eq!(decoders, ["Zero-width Steganography", "Base64"]);
+        assert_eq!(result.path[0].encrypted_text, input);
+    }
+
+    #[test]
+    fn test_each_line_is_cracked_when_the_whole_input_cannot_be() {
+        let input = "dGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw==\n\
+                     6d656574206d652061742074686520757375616c20706c6163652061742074656e\n\
+                     OJXXIYLUMUQHI2DFEBVWK6LTEBSXMZLSPEQHG2LOM5WGKIDXMVSWW===";
+        let CrackOutcome::Lines(lines) = perform_cracking(input, Config::default()) else {
+            panic!("the input wasn't cracked line by line");
+        };
+        let cracked: Vec<(usize, String, &str)> = lines
+            .into_iter()
+            .map(|line| {
+                let result = line.outcome.found().unwrap();
+                (line.index, result.plaintext, result.path[0].decoder)
+            })
+            .collect();
+        assert_eq!(
+            cracked,
+            [
+                (
+                    0,
+                    "the quick brown fox jumps over the lazy dog".to_string(),
+                    "Base64"
+                ),
+                (
+                    1,
+                    "meet me at the usual place at ten".to_string(),
+                    "Hexadecimal"
+                ),
+                (2, "rotate the keys every single week".to_string(), "Base32"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_the_lines_share_the_timeout() {
+        // Nothing decodes any of these lines to plaintext, so each search runs until it's out of time
+        let input = "q8ZkR2vW0pLx7NcT4bYh9JmE1sGa6FuD3oKi5VzQ\n\
+                     Xw3Lp9QzR7vT2mK8nB4cY6hJ1sD5fG0aE3uI7oPq\n\
+                     Tz6Hb2Nq8Rw4Lm1Vk9Xc3Pj7Gd5Fs0Ay2Ue6Io4W\n\
+                     Mk5Qr1Wz7Tb3Yh9Nc2Vx8Lp4Gj6Fd0Sa3Ei7Uo1K\n\
+                     Pv2Jx8Kd4Rm6Tq0Wb3Ny7Ls1Hf5Gc9Za2Ee8Oi6Ut\n\
+                     Bn7Cy3Fw9Hk1Lq5Mr0Sv4Td8Jx2Gp6Wz3Ea7Io1Ku";
+        let config = Config {
+            timeout: Duration::from_secs(1),
+            ..Config::default()
+        };
+        let started = Instant::now();
+        let outcome = perform_cracking(input, config);
+        assert!(!outcome.is_found());
+        // Six lines and the whole input with a second each would take several
+        assert!(
+            started.elapsed() < Duration::from_millis(2500),
+            "{:?}",
+            started.elapsed()
+        );
+    }
+
+    #[test]
+    fn test_continuing_cracks_each_line_after_the_path() {
+        // A checker which accepts the lines while they're still encoded, in base64 together
+        let checker = CheckerTypes::from_closure("Too Early Checker", |text| {
+            text.starts_with("dGhlIHF1aWNr")
+                .then(|| "is the encoded lines".to_string())
+        });
+        let input = "ZEdobElIRjFhV05ySUdKeWIzZHVJR1p2ZUNCcWRXMXdjeUJ2ZG1WeUlIUm9aU0JzWVhwNUlHUnZadz09Cj\
+                     ZkNjU2NTc0MjA2ZDY1MjA2MTc0MjA3NDY4NjUyMDc1NzM3NTYxNmMyMDcwNmM2MTYzNjUyMDYxNzQyMDc0\
+                     NjU2ZQpPSlhYSVlMVU1VUUhJMkRGRUJWV0s2TFRFQlNYTVpMU1BFUUhHMkxPTTVXR0tJRFhNVlNXVz09PQ==";
+        let too_early = perform_cracking_with_checker(input, Config::default(), checker)
+            .found()
+            .unwrap();
+        let CrackOutcome::Lines(lines) = continue_cracking(
+            &too_early,
+            Config::default(),
+            CancellationToken::new(),
+            None,
+        ) else {
+            panic!("the plaintext wasn't cracked line by line");
+        };
+        let decoders: Vec<Vec<&str>> = lines
+            .into_iter()
+            .map(|line| {
+                let result = line.outcome.found().unwrap();
+                result.path.iter().map(|step| step.decoder).collect()
+            })
+            .collect();
+        assert_eq!(
+            decoders,
+            [
+                ["Base64", "Base64"],
+                ["Base64", "Hexadecimal"],
+                ["Base64", "Base32"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_continuing_keeps_to_the_size_limit() {
+        let found = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", Config::default())
+            .found()
+            .unwrap();
+        let config = Config {
+            max_input_size: 8,
+            ..Config::default()
+        };
+        let outcome = continue_cracking(&found, config, CancellationToken::new(), None);
+        assert!(matches!(
+            outcome,
+            CrackOutcome::TooLarge(InputTooLarge { size: 19, limit: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_wrapped_base64_is_cracked_as_one() {
+        // Every line of this decodes on its own too, but they are one message
+        let input =
+            "TXVsdGlwbGUgbGluZXMgb2Ygb25lIG1lc3NhZ2UgYXJlIHdyYXBwZWQgYXQgc2V2ZW50eSBzaXgg\n\
+                     Y2hhcmFjdGVycywgbGlrZSBNSU1FIGRvZXMgaXQsIGFuZCBtdXN0IGJlIGRlY29kZWQgdG9nZXRo\n\
+                     ZXIgYXMgb25lIHVuaXQu";
+        let result = perform_cracking(input, Config::default()).found().unwrap();
+        assert_eq!(
+            result.plaintext,
+            "Multiple lines of one message are wrapped at seventy six characters, \
+             like MIME does it, and must be decoded together as one unit."
+        );
+    }
+
+    #[test]
+    fn test_blank_and_comment_lines_are_skipped() {
+        let input = "# dumped from the server\n\n\
+                     dGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw==\n   \n\
+                     // base32\n\
+                     OJXXIYLUMUQHI2DFEBVWK6LTEBSXMZLSPEQHG2LOM5WGKIDXMVSWW===\n\
+                     6d656574206d652061742074686520757375616c20706c6163652061742074656e\n";
+        let CrackOutcome::Lines(lines) = perform_cracking(input, Config::default()) else {
+            panic!("the input wasn't cracked line by line");
+        };
+        let indexes: Vec<usize> = lines.iter().map(|line| line.index).collect();
+        assert_eq!(indexes, [2, 5, 6]);
+        assert!(lines.into_iter().all(|line| line.outcome.is_found()));
+    }
+
+    #[test]
+    fn test_progress_is_reported_before_the_result() {
+        // ROT13, then hexadecimal, then base64
+        let input = "Njc3NTcyMjA2NjcyNzA2NTcyNjcyMDc2NjYyMDc2NjEyMDY3NzU3MjIwNjk2ZTY4Nzk2Nw==";
+        let (sender, receiver) = mpsc::channel();
+        let progress_sender = sender.clone();
+        let result = perform_cracking_with_progress(
+            input,
+            Config::default(),
+            CancellationToken::new(),
+            Some(Box::new(move |event| {
+                progress_sender.send(Some(event)).unwrap();
+            })),
+        );
+        // Marks where the result came back
+        sender.send(None).unwrap();
+        assert_eq!(
+            result.found().unwrap().plaintext,
+            "the secret is in the vault"
+        );
+
+        let received: Vec<_> = receiver.try_iter().collect();
+        let (result_marker, events) = received.split_last().unwrap();
+        assert!(result_marker.is_none());
+        let events: Vec<_> = events.iter().map(|event| event.clone().unwrap()).collect();
+        assert!(events.len() >= 2, "{events:?}");
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].nodes_expanded <= pair[1].nodes_expanded
+                && pair[0].elapsed <= pair[1].elapsed));
+        // The input is decoded first, and is too long to be shown whole
+        assert!(input.starts_with(events[0].current_text.trim_end_matches('…')));
+        assert_eq!(events[0].depth, 0);
+        assert!(events.last().unwrap().nodes_expanded > events[0].nodes_expanded);
+    }
 
     #[test]
     fn test_perform_cracking_returns() {
@@ -163,26 +1015,26 @@ mod tests {
     fn test_perform_cracking_returns_successful() {
         // this will work after english checker can identify "CANARY: hello"
         // let result = perform_cracking("Q0FOQVJZOiBoZWxsbw==");
-        // assert!(result.is_some());
-        // assert!(result.unwrap() == "CANARY: hello")
+        // assert!(result.is_found());
+        // assert!(result.found().unwrap() == "CANARY: hello")
         let config = Config::default();
         let result = perform_cracking("b2xsZWg=", config);
-        assert!(result.is_some());
-        assert!(result.unwrap().text[0] == "hello");
+        assert!(result.is_found());
+        assert!(result.found().unwrap().plaintext == "hello");
     }
     #[test]
     fn test_perform_cracking_returns_failure() {
         let config = Config::default();
         let result = perform_cracking("", config);
-        assert!(result.is_none());
+        assert!(!result.is_found());
     }
 
     #[test]
     fn test_perform_cracking_returns_successful_base64_reverse() {
         let config = Config::default();
         let result = perform_cracking("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", config);
-        assert!(result.is_some());
-        assert!(result.unwrap().text[0] == "hello there general")
+        assert!(result.is_found());
+        assert!(result.found().unwrap().plaintext == "hello there general")
     }
 
     #[test]
@@ -190,7 +1042,7 @@ mod tests {
         let config = Config::default();
         let result = perform_cracking("192.168.0.1", config);
         // Since we are exiting early the path should be of length 1, which is 1 check (the Athena check)
-        assert!(result.unwrap().path.len() == 1);
+        assert!(result.found().unwrap().path.len() == 1);
     }
     #[test]
     // Previously this would decode to `Fchohs as 13 dzoqsg!` because the English checker wasn't that good
@@ -199,7 +1051,7 @@ mod tests {
         let config = Config::default();
         let result = perform_cracking("Ebgngr zr 13 cynprf!", config);
         // We return None since the input is the plaintext
-        assert!(result.unwrap().text[0] == "Rotate me 13 places!");
+        assert!(result.found().unwrap().plaintext == "Rotate me 13 places!");
     }
 
     #[test]
@@ -207,8 +1059,8 @@ mod tests {
         let config = Config::default();
         let result = perform_cracking("Hello, World!", config);
         // We return None since the input is the plaintext
-        let res_unwrapped = result.unwrap();
-        assert!(&res_unwrapped.text[0] == "Hello, World!");
+        let res_unwrapped = result.found().unwrap();
+        assert!(&res_unwrapped.plaintext == "Hello, World!");
         // Since our input is the plaintext we did not decode it
         // Therefore we return with the default decoder
         assert!(res_unwrapped.path[0].decoder == "Default decoder");
@@ -1,17 +1,131 @@
-use ares::cli::parse_cli_args;
-use ares::cli_pretty_printing::program_exiting_successful_decoding;
-use ares::perform_cracking;
+use ares::cancellation::CancellationToken;
+use ares::cli::interactive::Picker;
+use ares::cli::{parse_cli_args, write_output};
+use ares::cli_pretty_printing::{
+    clear_search_progress, cracked_lines, failed_to_decode, failed_to_decode_within_limits,
+    program_exiting_successful_decoding, search_progress, wrote_lines, wrote_plaintext,
+};
+use ares::config::{set_global_config, OutputFormat};
+use ares::progress::ProgressCallback;
+use ares::{continue_cracking, perform_cracking_with_progress, CrackOutcome};
+use std::io::IsTerminal;
+use std::path::Path;
 
 fn main() {
     // Turn CLI arguments into a library object
     let (text, config) = parse_cli_args();
-    let result = perform_cracking(&text, config);
+    // The search is given its config, and what's printed once it's over reads this one
+    set_global_config(config.clone());
+    let cancel = CancellationToken::new();
+    let handler_cancel = cancel.clone();
+    // The first Ctrl-C stops the search and prints what it found so far, a second one quits
+    ctrlc::set_handler(move || {
+        if handler_cancel.is_cancelled() {
+            std::process::exit(130);
+        }
+        handler_cancel.cancel();
+    })
+    .expect("the Ctrl-C handler is only set once");
+    // A status line on stderr, unless it would get in the way of the logs or whoever reads the output
+    let show_progress = !config.api_mode
+        && !config.output_format.is_quiet()
+        && config.verbose == 0
+        && std::io::stderr().is_terminal();
+    let progress = || -> Option<ProgressCallback> {
+        show_progress.then(|| Box::new(|event| search_progress(&event)) as ProgressCallback)
+    };
+    let output_format = config.output_format;
+    let output_file = config.output_file.clone();
+    let interactive = config.interactive && !config.api_mode;
+    // Cracking further from a plaintext picked uses the same config
+    let further_config = interactive.then(|| config.clone());
+    let mut result = perform_cracking_with_progress(&text, config, cancel.clone(), progress());
+    clear_search_progress();
+    if let Some(further_config) = further_config {
+        result = Picker::for_terminal().choose(result, |found| {
+            let further =
+                continue_cracking(found, further_config.clone(), cancel.clone(), progress());
+            clear_search_progress();
+            further
+        });
+    }
+    let exit_code = exit_code(&result, output_format);
+    if let Some(path) = &output_file {
+        if let Err(error) = write_output(&result, path) {
+            eprintln!("couldn't write {}: {error}", path.display());
+            std::process::exit(1);
+        }
+    }
+    match output_format {
+        OutputFormat::Text => print_outcome(result, output_file.as_deref()),
+        #[cfg(feature = "serde")]
+        OutputFormat::Json => println!(
+            "{}",
+            result
+                .to_json()
+                .expect("the outcome only has strings and numbers")
+        ),
+        // The plaintext is in the file, and nothing else is printed
+        OutputFormat::Plain if output_file.is_some() => {}
+        OutputFormat::Plain => print_plaintexts(result),
+    }
+    // So scripts can tell whether Ares found the plaintext
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+}
+
+/// 0 if Ares found the plaintext, otherwise 1, or with `--quiet`, 2 if it ran out of time
+fn exit_code(result: &CrackOutcome, output_format: OutputFormat) -> i32 {
+    match result {
+        // Lines are only cracked one by one if at least one of them could be
+        CrackOutcome::Found(_) | CrackOutcome::Lines(_) => 0,
+        CrackOutcome::TimedOut(_) if output_format.is_quiet() => 2,
+        _ => 1,
+    }
+}
+
+/// Prints only the plaintexts, one per line, for `--quiet`.
+/// When the input was cracked line by line, a line which couldn't be cracked is left empty,
+/// so each plaintext is on the same line as its ciphertext was.
+fn print_plaintexts(result: CrackOutcome) {
     match result {
+        CrackOutcome::Found(results) => {
+            for result in results {
+                println!("{}", result.plaintext);
+            }
+        }
+        CrackOutcome::Lines(lines) => {
+            for line in lines {
+                match line.outcome.found() {
+                    Some(result) => println!("{}", result.plaintext),
+                    None => println!(),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Prints the outcome for people to read.
+/// When the plaintext was written to `output_file`, only how it was decoded is printed.
+fn print_outcome(result: CrackOutcome, output_file: Option<&Path>) {
+    match (result, output_file) {
+        (CrackOutcome::Found(results), Some(path)) => {
+            if let Some(result) = results.first() {
+                wrote_plaintext(result, path);
+            }
+        }
+        (CrackOutcome::Lines(lines), Some(path)) => wrote_lines(&lines, path),
         // TODO: As result have array of CrackResult used,
         // we can print in better way with more info
-        Some(result) => {
-            program_exiting_successful_decoding(result);
+        (CrackOutcome::Found(results), None) => {
+            for result in results {
+                program_exiting_successful_decoding(result);
+            }
         }
-        None => ares::cli_pretty_printing::failed_to_decode(),
+        (CrackOutcome::Lines(lines), None) => cracked_lines(lines),
+        (CrackOutcome::Exhausted(_), _) => failed_to_decode(),
+        (limited, _) => failed_to_decode_within_limits(&limited),
     }
 }
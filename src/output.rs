@@ -0,0 +1,278 @@
+//! Renders the decoders Ares used to get from the input to the plaintext, one a line,
+//! with what each of them decoded the text to, like
+//! ```text
+//! input "NzQ2ODY1MjA2MzYxNzQ…zQ2ODY1MjA2ZDYxNzQ=" (60 chars)
+//! ├─ Base64 → "7468652063617420736…f6e20746865206d6174" (44 chars)
+//! └─ Hexadecimal → "the cat sat on the mat"
+//!    ✓ English Checker identified it because it ...
+//! ```
+
+use std::io::IsTerminal;
+
+use ansi_term::{Colour, Style};
+
+use crate::decoders::crack_results::CrackResult;
+
+/// Texts longer than this many characters are cut short in the middle
+pub const PREVIEW_CHARS: usize = 40;
+/// Bytes longer than this are cut short in the middle
+const PREVIEW_BYTES: usize = 16;
+
+/// Renders the path from the input to the plaintext, with or without colours
+pub struct PathRenderer {
+    /// Whether to colour the text with ANSI escape codes
+    colour: bool,
+}
+
+impl PathRenderer {
+    /// A renderer which colours the text if `colour` is true
+    pub fn new(colour: bool) -> Self {
+        PathRenderer { colour }
+    }
+
+    /// A renderer for stdout, which only colours the text if stdout is a terminal
+    /// and `NO_COLOR` isn't set, see https://no-color.org
+    pub fn for_stdout() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        PathRenderer::new(std::io::stdout().is_terminal() && !no_color)
+    }
+
+    /// The input, then each decoder on its own line with the key it used and a preview
+    /// of what it decoded the text to, then the checker which identified the plaintext.
+    /// There's no newline at the end.
+    /// ```
+    /// use ares::decoders::base64_decoder::Base64Decoder;
+    /// use ares::decoders::crack_results::CrackResult;
+    /// use ares::decoders::interface::{Crack, Decoder};
+    /// use ares::output::PathRenderer;
+    ///
+    /// let step = CrackResult::new(&Decoder::<Base64Decoder>::new())
+    ///     .with_encrypted_text("aGkK".to_string())
+    ///     .with_candidate("hi\n".to_string());
+    /// assert_eq!(
+    ///     PathRenderer::new(false).render(&[step]),
+    ///     "input \"aGkK\"\n└─ Base64 → \"hi\\n\""
+    /// );
+    /// ```
+    pub fn render(&self, path: &[CrackResult]) -> String {
+        let Some(first) = path.first() else {
+            return String::new();
+        };
+        let mut lines = vec![format!(
+            "input {}",
+            self.paint(Colour::Yellow.normal(), &preview(&first.encrypted_text))
+        )];
+        for (index, step) in path.iter().enumerate() {
+            let branch = if index + 1 == path.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            let mut line = format!(
+                "{} {}",
+                self.paint(Style::new().dimmed(), branch),
+                self.paint(Style::new().bold(), step.decoder)
+            );
+            if let Some(key) = &step.key {
+                line.push_str(&format!(" ({})", self.paint(Colour::Cyan.normal(), key)));
+            }
+            if let Some(output) = step_preview(step) {
+                line.push_str(&format!(
+                    " → {}",
+                    self.paint(Colour::Yellow.normal(), &output)
+                ));
+            }
+            lines.push(line);
+        }
+        if let Some(last) = path.last().filter(|last| !last.checker_name.is_empty()) {
+            let because = if last.checker_reason.is_empty() {
+                String::new()
+            } else {
+                format!(" because it {}", last.checker_reason)
+            };
+            lines.push(format!(
+                "   {} {} identified it{because}",
+                self.paint(Colour::Green.bold(), "✓"),
+                self.paint(Colour::Green.normal(), last.checker_name)
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// The text in the style, if we're colouring
+    fn paint(&self, style: Style, text: &str) -> String {
+        if self.colour {
+            style.paint(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// What the step decoded the text to: a hex preview when it's bytes or mostly unprintable,
+/// otherwise a text preview. None if it didn't decode to anything.
+fn step_preview(step: &CrackResult) -> Option<String> {
+    if let Some(bytes) = step
+        .unencrypted_bytes
+        .as_ref()
+        .and_then(|bytes| bytes.first())
+    {
+        return Some(hex_preview(bytes));
+    }
+    let text = step.unencrypted_text.as_ref()?.first()?;
+    let total = text.chars().count();
+    let printable = text
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .count();
+    if printable * 2 < total {
+        return Some(hex_preview(text.as_bytes()));
+    }
+    Some(preview(text))
+}
+
+/// The text in quotes with control characters escaped, like `"hi\n"`.
+/// Longer than `PREVIEW_CHARS` characters it's cut short in the middle,
+/// like `"the start…the end" (120 chars)`.
+pub fn preview(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= PREVIEW_CHARS {
+        return format!("\"{}\"", escape(&chars));
+    }
+    let half = (PREVIEW_CHARS - 1) / 2;
+    format!(
+        "\"{}…{}\" ({} chars)",
+        escape(&chars[..half]),
+        escape(&chars[chars.len() - half..]),
+        chars.len()
+    )
+}
+
+/// The bytes in hex, like `89 50 4e 47 (4 bytes)`.
+/// Longer than `PREVIEW_BYTES` they're cut short in the middle.
+fn hex_preview(bytes: &[u8]) -> String {
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    if bytes.len() <= PREVIEW_BYTES {
+        return format!("{} ({} bytes)", hex(bytes), bytes.len());
+    }
+    let half = PREVIEW_BYTES / 2;
+    format!(
+        "{} … {} ({} bytes)",
+        hex(&bytes[..half]),
+        hex(&bytes[bytes.len() - half..]),
+        bytes.len()
+    )
+}
+
+/// The characters with control characters escaped like Rust would, so `\n` rather than a new line,
+/// and everything else, like accents and emoji, left as it is
+fn escape(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|&c| {
+            if c.is_control() {
+                c.escape_default().to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preview, PathRenderer};
+    use crate::checkers::checker_result::CheckResult;
+    use crate::decoders::caesar_decoder::CaesarDecoder;
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::{Crack, Decoder};
+    use crate::decoders::{
+        base64_decoder::Base64Decoder, deflate_decoder::DeflateDecoder,
+        hexadecimal_decoder::HexadecimalDecoder,
+    };
+
+    /// Hexadecimal, then base64 to gzip, then decompressed and shifted back 13 letters
+    fn four_steps() -> Vec<CrackResult> {
+        let hex = CrackResult::new(&Decoder::<HexadecimalDecoder>::new())
+            .with_encrypted_text("4834734941".to_string())
+            .with_candidate("H4sIA".to_string());
+        let base64 = CrackResult::new(&Decoder::<Base64Decoder>::new())
+            .with_encrypted_text("H4sIA".to_string())
+            .with_bytes_candidate(vec![0x1f, 0x8b, 0x08, 0x00]);
+        let deflate = CrackResult::new(&Decoder::<DeflateDecoder>::new())
+            .with_candidate(format!("gur\tpng\n{}", "fng ba gur zng ".repeat(4)));
+        let mut caesar = CrackResult::new(&Decoder::<CaesarDecoder>::new())
+            .with_candidate("the cat\u{1b}[2J sat".to_string());
+        caesar.set_key("shift 13".to_string());
+        caesar.update_checker(&CheckResult {
+            is_identified: true,
+            text: String::new(),
+            description: String::new(),
+            checker_name: "English Checker",
+            checker_description: "",
+            link: "",
+            score: None,
+            reason: "has 3 English words".to_string(),
+            confidence: 0.9,
+        });
+        vec![hex, base64, deflate, caesar]
+    }
+
+    #[test]
+    fn each_step_is_a_line() {
+        let rendered = PathRenderer::new(false).render(&four_steps());
+        assert_eq!(
+            rendered,
+            [
+                "input \"4834734941\"",
+                "├─ Hexadecimal → \"H4sIA\"",
+                "├─ Base64 → 1f 8b 08 00 (4 bytes)",
+                "├─ Deflate → \"gur\\tpng\\nfng ba gur …zng fng ba gur zng \" (68 chars)",
+                "└─ Caesar Cipher (shift 13) → \"the cat\\u{1b}[2J sat\"",
+                "   ✓ English Checker identified it because it has 3 English words",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn colours_are_only_added_when_asked() {
+        let plain = PathRenderer::new(false).render(&four_steps());
+        assert!(!plain.contains("\u{1b}["));
+        let coloured = PathRenderer::new(true).render(&four_steps());
+        // The same lines, with colours
+        assert_eq!(coloured.lines().count(), plain.lines().count());
+        assert!(coloured.contains("\u{1b}[1mCaesar Cipher\u{1b}[0m"));
+        assert!(coloured.contains("\u{1b}[36mshift 13\u{1b}[0m"));
+        // The escape code in the decoded text is still escaped, so it can't clear the screen
+        assert!(coloured.contains("the cat\\u{1b}[2J sat"));
+        assert!(!coloured.contains("\u{1b}[2J"));
+    }
+
+    #[test]
+    fn long_text_is_cut_in_the_middle() {
+        let text = format!("start{}end", "x".repeat(100));
+        let shown = preview(&text);
+        assert!(shown.starts_with("\"startxxx"), "{shown}");
+        assert!(shown.ends_with("xxxend\" (108 chars)"), "{shown}");
+        assert!(shown.contains('…'));
+        assert_eq!(preview("short"), "\"short\"");
+    }
+
+    #[test]
+    fn unprintable_text_is_shown_as_hex() {
+        let step = CrackResult::new(&Decoder::<Base64Decoder>::new())
+            .with_candidate("\u{1}\u{2}\u{3}a".to_string());
+        let rendered = PathRenderer::new(false).render(&[step]);
+        assert!(
+            rendered.ends_with("Base64 → 01 02 03 61 (4 bytes)"),
+            "{rendered}"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Tells a program using Ares, or someone watching the CLI, how a long search is going.
+//! The search calls back with a `ProgressEvent` as it decodes, but not so often
+//! that calling back slows it down.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::timer::Instant;
+
+/// How often the callback is called at most, unless enough texts were decoded in between
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+/// How many texts are decoded between calls to the callback at most, unless enough time passed in between
+const REPORT_EXPANSIONS: usize = 100;
+/// The most characters of the text being decoded an event has
+const CURRENT_TEXT_CHARS: usize = 50;
+
+/// Called with how the search is going, see `perform_cracking_with_progress`
+pub type ProgressCallback = Box<dyn Fn(ProgressEvent) + Send>;
+
+/// How the search is going. The counts are for the whole run,
+/// so they only go up, even when the input is cracked line by line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    /// How many texts the search has decoded
+    pub nodes_expanded: usize,
+    /// How many decoders it took to get to the text being decoded
+    pub depth: usize,
+    /// How many texts are waiting to be decoded
+    pub frontier_size: usize,
+    /// How many times a decoder was run on some text
+    pub decoders_invoked: usize,
+    /// How long Ares has been cracking for
+    pub elapsed: Duration,
+    /// The start of the text being decoded, with "…" on the end if it was cut short
+    pub current_text: String,
+}
+
+/// Calls the callback with how the search is going, at most every `REPORT_INTERVAL`
+/// or every `REPORT_EXPANSIONS` texts decoded
+pub(crate) struct Progress {
+    /// The callback, and when it was last called
+    state: Mutex<ProgressState>,
+}
+
+/// What `Progress` keeps track of between events
+struct ProgressState {
+    /// Who is told how the search is going
+    callback: ProgressCallback,
+    /// When cracking started
+    started: Instant,
+    /// When the callback was last called, and how many texts had been decoded by then
+    last_sent: Option<(Instant, usize)>,
+    /// The latest event of the search running now, whether or not the callback was called with it
+    latest: Option<ProgressEvent>,
+    /// How many texts, and decoder runs, the searches which already finished did
+    finished_searches: (usize, usize),
+}
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress").finish_non_exhaustive()
+    }
+}
+
+impl Progress {
+    /// Reports to the callback, timing from now
+    pub fn new(callback: ProgressCallback) -> Self {
+        Progress {
+            state: Mutex::new(ProgressState {
+                callback,
+                started: Instant::now(),
+                last_sent: None,
+                latest: None,
+                finished_searches: (0, 0),
+            }),
+        }
+    }
+
+    /// The search is about to decode `text`. The counts are for this search alone.
+    /// The callback is only called if it's been long enough since it last was.
+    pub fn report(
+        &self,
+        text: &str,
+        depth: usize,
+        frontier_size: usize,
+        nodes_expanded: usize,
+        decoders_invoked: usize,
+    ) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("the progress callback never panics");
+        let (earlier_nodes, earlier_decoders) = state.finished_searches;
+        let event = ProgressEvent {
+            nodes_expanded: earlier_nodes + nodes_expanded,
+            depth,
+            frontier_size,
+            decoders_invoked: earlier_decoders + decoders_invoked,
+            elapsed: state.started.elapsed(),
+            current_text: truncate(text),
+        };
+        let now = Instant::now();
+        let due = state.last_sent.is_none_or(|(sent_at, sent_nodes)| {
+            now.duration_since(sent_at) >= REPORT_INTERVAL
+                || event.nodes_expanded - sent_nodes >= REPORT_EXPANSIONS
+        });
+        if due {
+            state.last_sent = Some((now, event.nodes_expanded));
+            (state.callback)(event.clone());
+        }
+        state.latest = Some(event);
+    }
+
+    /// A search finished having done this much, so the callback is told how it ended,
+    /// before the search returns, if it decoded anything. Later searches count on from here.
+    pub fn search_finished(&self, nodes_expanded: usize, decoders_invoked: usize) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("the progress callback never panics");
+        let (earlier_nodes, earlier_decoders) = state.finished_searches;
+        let totals = (
+            earlier_nodes + nodes_expanded,
+            earlier_decoders + decoders_invoked,
+        );
+        state.finished_searches = totals;
+        if let Some(mut event) = state.latest.take() {
+            event.nodes_expanded = totals.0;
+            event.decoders_invoked = totals.1;
+            event.elapsed = state.started.elapsed();
+            state.last_sent = Some((Instant::now(), totals.0));
+            (state.callback)(event);
+        }
+    }
+}
+
+/// The start of the text, so a long text doesn't make every event long
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(CURRENT_TEXT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{truncate, Progress, ProgressEvent, CURRENT_TEXT_CHARS, REPORT_EXPANSIONS};
+    use std::sync::mpsc;
+
+    fn collecting() -> (Progress, mpsc::Receiver<ProgressEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let progress = Progress::new(Box::new(move |event| {
+            sender.send(event).unwrap();
+        }));
+        (progress, receiver)
+    }
+
+    #[test]
+    fn events_are_rate_limited() {
+        let (progress, receiver) = collecting();
+        for expanded in 0..1000 {
+            progress.report("text", 1, 10, expanded, expanded * 20);
+        }
+        let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+        // The first event straight away, then one every hundred texts, unless the test is slow
+        assert!(events.len() >= 1000 / REPORT_EXPANSIONS, "{}", events.len());
+        assert!(events.len() < 100, "{}", events.len());
+        assert_eq!(events[0].nodes_expanded, 0);
+    }
+
+    #[test]
+    fn the_end_of_a_search_is_always_reported() {
+        let (progress, receiver) = collecting();
+        progress.report("first", 0, 1, 0, 0);
+        progress.report("second", 1, 5, 1, 20);
+        progress.search_finished(2, 40);
+        let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].current_text, "second");
+        assert_eq!(events[1].nodes_expanded, 2);
+        assert_eq!(events[1].decoders_invoked, 40);
+
+        // The next search counts on from the last one
+        progress.report("line", 0, 1, 0, 0);
+        progress.search_finished(1, 20);
+        let next = receiver.try_iter().last().unwrap();
+        assert_eq!(next.nodes_expanded, 3);
+        assert_eq!(next.decoders_invoked, 60);
+    }
+
+    #[test]
+    fn long_text_is_cut_short() {
+        assert_eq!(truncate("short"), "short");
+        let cut = truncate(&"é".repeat(200));
+        assert_eq!(cut.chars().count(), CURRENT_TEXT_CHARS + 1);
+        assert!(cut.ends_with('…'));
+    }
+}
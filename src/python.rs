@@ -0,0 +1,235 @@
+//! The `ares` Python module, so scripts get the plaintext and how it was decoded
+//! without running the binary and reading what it prints.
+//! ```python
+//! import ares
+//!
+//! outcome = ares.crack("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", timeout=10)
+//! if outcome.status == "found":
+//!     print(outcome.plaintext)
+//!     for step in outcome.path:
+//!         print(step["decoder"], step["key"], step["intermediate"])
+//! ```
+//! maturin builds it with the `python` feature, see pyproject.toml.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+
+use crate::checkers::wordlist_checker::Wordlist;
+use crate::config::Config;
+use crate::decoders::crack_results::CrackResult;
+use crate::decoders::interface::Decoder;
+use crate::decoders::list_decoders as decoders;
+use crate::{perform_cracking, CrackOutcome};
+
+/// How cracking ended, as Python sees it
+#[pyclass(name = "CrackOutcome", module = "ares", frozen, get_all)]
+pub struct PyCrackOutcome {
+    /// How it ended, like "found" or "timed_out", see `CrackOutcome::status`
+    status: &'static str,
+    /// The best plaintext, or None if there isn't one
+    plaintext: Option<String>,
+    /// The decoders from the input to the plaintext, each a dict with its
+    /// "decoder", "key" and "intermediate", the text it decoded to
+    path: Py<PyList>,
+    /// How many seconds the search ran for
+    elapsed: f64,
+}
+
+#[pymethods]
+impl PyCrackOutcome {
+    /// Like `CrackOutcome(status="found", plaintext="hello")`
+    fn __repr__(&self) -> String {
+        match &self.plaintext {
+            Some(plaintext) => format!(
+                "CrackOutcome(status={:?}, plaintext={plaintext:?})",
+                self.status
+            ),
+            None => format!("CrackOutcome(status={:?})", self.status),
+        }
+    }
+}
+
+/// One decoder on the way to the plaintext, as a dict
+fn step_to_dict<'py>(py: Python<'py>, step: &CrackResult) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("decoder", step.decoder)?;
+    dict.set_item("key", step.key.as_deref())?;
+    dict.set_item(
+        "intermediate",
+        step.unencrypted_text
+            .as_ref()
+            .and_then(|texts| texts.first()),
+    )?;
+    Ok(dict)
+}
+
+/// The outcome as Python sees it
+fn outcome_to_py(py: Python<'_>, outcome: CrackOutcome) -> PyResult<PyCrackOutcome> {
+    let status = outcome.status();
+    let elapsed = outcome.stats().elapsed.as_secs_f64();
+    let found = outcome.found();
+    let path = PyList::empty(py);
+    if let Some(found) = &found {
+        // The input itself being the plaintext is no decoders deep
+        for step in found
+            .path
+            .iter()
+            .filter(|step| step.decoder != Decoder::default().name)
+        {
+            path.append(step_to_dict(py, step)?)?;
+        }
+    }
+    Ok(PyCrackOutcome {
+        status,
+        plaintext: found.map(|found| found.plaintext),
+        path: path.unbind(),
+        elapsed,
+    })
+}
+
+/// Cracks the text, giving up after `timeout` seconds.
+/// The plaintext is what matches `regex`, or is a word in the `wordlist` file,
+/// or otherwise what Athena thinks is plaintext.
+/// `only` and `exclude` pick the decoders by name or tag, like the CLI's `--only` and `--exclude`.
+/// The search runs without the GIL, so other Python threads carry on meanwhile.
+#[pyfunction]
+#[pyo3(signature = (text, timeout=5.0, regex=None, wordlist=None, only=None, exclude=None))]
+fn crack(
+    py: Python<'_>,
+    text: &str,
+    timeout: f64,
+    regex: Option<String>,
+    wordlist: Option<PathBuf>,
+    only: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> PyResult<PyCrackOutcome> {
+    let timeout = Duration::try_from_secs_f64(timeout)
+        .map_err(|_| PyValueError::new_err("timeout must be a number of seconds, 0 or more"))?;
+    let mut config = Config {
+        timeout,
+        regex,
+        include_decoders: only.unwrap_or_default(),
+        exclude_decoders: exclude.unwrap_or_default(),
+        ..Config::default()
+    };
+    config
+        .check_decoder_names()
+        .map_err(|unknown| PyValueError::new_err(unknown.to_string()))?;
+    if let Some(Err(error)) = config.regex.as_deref().map(Regex::new) {
+        return Err(PyValueError::new_err(error.to_string()));
+    }
+    // An OSError in Python
+    config.wordlist = wordlist.as_ref().map(Wordlist::from_file).transpose()?;
+
+    let outcome = py.allow_threads(|| perform_cracking(text, config));
+    outcome_to_py(py, outcome)
+}
+
+/// Every decoder, as a dict with its "name", "tags", "popularity", "link" and "description",
+/// the most popular first, like `ares --list-decoders`
+#[pyfunction]
+fn list_decoders(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    let list = PyList::empty(py);
+    for decoder in decoders() {
+        let dict = PyDict::new(py);
+        dict.set_item("name", decoder.name)?;
+        dict.set_item("tags", decoder.tags)?;
+        dict.set_item("popularity", decoder.popularity)?;
+        dict.set_item("link", decoder.link)?;
+        dict.set_item("description", decoder.description)?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+/// The `ares` module
+#[pymodule]
+fn ares(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(crack, module)?)?;
+    module.add_function(wrap_pyfunction!(list_decoders, module)?)?;
+    module.add_class::<PyCrackOutcome>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+
+    use super::{outcome_to_py, step_to_dict};
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::{DecoderBuilder, DefaultDecoder};
+    use crate::{CrackOutcome, CrackSuccess, RunStats};
+
+    /// What a decoder called `name` did, decoding to `text`
+    fn step(name: &'static str, text: &str) -> CrackResult {
+        let decoder = DecoderBuilder::new(name)
+            .with_description("N/A")
+            .with_link("N/A")
+            .build::<DefaultDecoder>();
+        CrackResult::new(&decoder).with_candidate(text.to_string())
+    }
+
+    /// The dict's value for `key`, as a Rust value
+    fn get<'py, T: FromPyObject<'py>>(dict: &Bound<'py, PyDict>, key: &str) -> T {
+        dict.get_item(key).unwrap().unwrap().extract().unwrap()
+    }
+
+    #[test]
+    fn a_step_is_a_dict() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut caesar = step("Caesar Cipher", "héllo wörld, 你好");
+            caesar.key = Some("13".to_string());
+            let dict = step_to_dict(py, &caesar).unwrap();
+            assert_eq!(get::<String>(&dict, "decoder"), "Caesar Cipher");
+            assert_eq!(get::<Option<String>>(&dict, "key").as_deref(), Some("13"));
+            assert_eq!(get::<String>(&dict, "intermediate"), "héllo wörld, 你好");
+
+            let dict = step_to_dict(py, &step("Base64", "x")).unwrap();
+            assert_eq!(get::<Option<String>>(&dict, "key"), None);
+        });
+    }
+
+    #[test]
+    fn a_found_outcome_has_the_path() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let outcome = CrackOutcome::Found(vec![CrackSuccess {
+                plaintext: "ünïcödé plaintext 🎉".to_string(),
+                bytes: None,
+                path: vec![
+                    step("Hexadecimal", "w7xuw69jw7Zkw6k="),
+                    step("Base64", "ünïcödé plaintext 🎉"),
+                ],
+                stats: RunStats::default(),
+            }]);
+            let outcome = outcome_to_py(py, outcome).unwrap();
+            assert_eq!(outcome.status, "found");
+            assert_eq!(outcome.plaintext.as_deref(), Some("ünïcödé plaintext 🎉"));
+            let path = outcome.path.bind(py);
+            assert_eq!(path.len(), 2);
+            let last = path.get_item(1).unwrap();
+            let last = last.downcast::<PyDict>().unwrap();
+            assert_eq!(get::<String>(last, "decoder"), "Base64");
+            assert_eq!(get::<String>(last, "intermediate"), "ünïcödé plaintext 🎉");
+        });
+    }
+
+    #[test]
+    fn nothing_found_has_no_plaintext() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let outcome = outcome_to_py(py, CrackOutcome::Exhausted(RunStats::default())).unwrap();
+            assert_eq!(outcome.status, "exhausted");
+            assert_eq!(outcome.plaintext, None);
+            assert!(outcome.path.bind(py).is_empty());
+            assert_eq!(outcome.__repr__(), "CrackOutcome(status=\"exhausted\")");
+        });
+    }
+}
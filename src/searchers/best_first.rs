@@ -0,0 +1,303 @@
+use std::cmp::Ordering;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use super::frontier::Frontier;
+use super::search_node::SearchNode;
+use super::seen_strings::SeenStrings;
+use super::{score, Plaintexts, SearchLimits, SearchStats};
+use crate::checkers::CheckerTypes;
+use crate::cli_pretty_printing::decoded_how_many_times;
+use crate::filtration_system::invisible_characters::CleanedInput;
+use crate::timer::Timer;
+use crate::CrackOutcome;
+use log::trace;
+
+/// How much the checker's confidence in the text being decoded counts towards the text decoded from it.
+/// Text which was nearly plaintext, like English with some noise in it, is worth following further.
+const NEAR_MISS_WEIGHT: f64 = 0.5;
+
+/// Text waiting to be decoded, and how promising it is
+struct Node {
+    /// How promising the text is, the highest is decoded first
+    score: f64,
+    /// The order the node was found in, so ties go to the node found first
+    order: usize,
+    /// How worth following the path to the text is, the product of the priority of each decoder
+    /// and of the share of that decoder's candidates this text is
+    path_priority: f64,
+    /// The text and how we got to it
+    node: Arc<SearchNode>,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// Best first search, which decodes the most promising text first, rather than a whole level at a time.
+/// Text is promising when popular, quick decoders produced it, the checker was fairly confident about it
+/// or the text it was decoded from, it doesn't look random, and it isn't many decoders deep.
+/// When too much text is waiting, the least promising is dropped.
+/// https://en.wikipedia.org/wiki/Best-first_search
+pub fn best_first(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    limits: SearchLimits,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    let initial = SearchNode::root(&input.text);
+    if !limits.can_decode(&initial) {
+        return CrackOutcome::DepthLimited(stats.run_stats());
+    }
+    let mut timer = Timer::start(limits.timeout);
+    let mut seen_strings = SeenStrings::new(&input.text, limits.seen_capacity);
+    // Whether we left out text because it was too deep to decode
+    let mut depth_limited = false;
+    let mut plaintexts = Plaintexts::new(limits.results_wanted);
+    let mut frontier = Frontier::new(limits.max_frontier);
+    let mut found = 0;
+    // What the input hides is decoded first, as ties go to the node found first
+    if let Some(hidden) = super::hidden_message_node(input) {
+        if seen_strings.insert(&hidden) {
+            if limits.can_decode(&hidden) {
+                frontier.push(Node {
+                    score: 0.0,
+                    order: found,
+                    path_priority: 1.0,
+                    node: Arc::new(hidden),
+                });
+                found += 1;
+            } else {
+                depth_limited = true;
+            }
+        }
+    }
+    frontier.push(Node {
+        score: 0.0,
+        order: found,
+        path_priority: 1.0,
+        node: Arc::new(initial),
+    });
+
+    while let Some(queued) = frontier.pop() {
+        let depth = queued.node.depth() as u32 + 1;
+        let waiting = || {
+            let frontier = frontier.iter().map(|waiting| waiting.node.as_ref());
+            std::iter::once(queued.node.as_ref()).chain(frontier)
+        };
+        if let Some(outcome) = super::stopped_early(&mut timer, &limits, stats, depth, waiting) {
+            return plaintexts.finish(outcome);
+        }
+        trace!(
+            "Decoding {:?} at depth {} with a score of {:.2}",
+            queued.node.text,
+            depth,
+            queued.score
+        );
+        stats.report_progress(&queued.node, frontier.len());
+
+        let results = match super::expand(
+            &queued.node,
+            &limits.decoders,
+            checker,
+            stats,
+            &mut plaintexts,
+        ) {
+            ControlFlow::Break(outcome) => {
+                decoded_how_many_times(depth);
+                return outcome;
+            }
+            ControlFlow::Continue(results) => results,
+        };
+        let evicted_before = frontier.evicted();
+        // Text the checker nearly took for plaintext is likely one decoding away from it
+        let near_miss = NEAR_MISS_WEIGHT * f64::from(queued.node.checker_confidence());
+        for result in results {
+            let priority = queued.path_priority * f64::from(result.priority);
+            let candidates = queued.node.children(result);
+            // Each of a decoder's candidates is less likely the more of them there are, like Caesar's 25 shifts
+            let priority = priority / candidates.len().max(1) as f64;
+            for candidate in candidates {
+                if !seen_strings.insert(&candidate) {
+                    continue;
+                }
+                // It was checked when it was decoded, but is too deep to decode further
+                if !limits.can_decode(&candidate) {
+                    depth_limited = true;
+                    continue;
+                }
+                found += 1;
+                let confidence = f64::from(candidate.checker_confidence());
+                frontier.push(Node {
+                    score: score(&candidate, priority, confidence) + near_miss,
+                    order: found,
+                    path_priority: priority,
+                    node: Arc::new(candidate),
+                });
+            }
+        }
+        stats.count_nodes_evicted(frontier.evicted() - evicted_before);
+    }
+
+    plaintexts.finish(super::nothing_left_to_decode(
+        depth_limited,
+        seen_strings.is_full(),
+        stats,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::best_first;
+    use crate::checkers::athena::Athena;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::checkers::CheckerTypes;
+    use crate::filtration_system::invisible_characters::clean_input;
+    use crate::searchers::{bfs::bfs, SearchLimits, SearchStats};
+    use crate::CrackOutcome;
+    use std::time::{Duration, Instant};
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    #[test]
+    fn best_first_succeeds() {
+        let result = best_first(
+            &clean_input("b2xsZWg="),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &SearchStats::default(),
+        );
+        assert_eq!(result.found().unwrap().plaintext, "hello");
+    }
+
+    #[test]
+    fn single_layer_needs_no_more_decoder_runs_than_bfs() {
+        let input = "aGVsbG8gdGhlcmUgZ2VuZXJhbA==";
+        let best_first_stats = SearchStats::default();
+        let result = best_first(
+            &clean_input(input),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &best_first_stats,
+        )
+        .found()
+        .unwrap();
+        assert_eq!(result.plaintext, "hello there general");
+
+        let bfs_stats = SearchStats::default();
+        assert!(bfs(
+            &clean_input(input),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &bfs_stats
+        )
+        .is_found());
+        assert!(best_first_stats.decoder_invocations() <= bfs_stats.decoder_invocations());
+    }
+
+    #[test]
+    fn four_layers_need_fewer_decoder_runs_than_bfs() {
+        // ROT13, then hexadecimal, then base32, then base64
+        let input = "R1kzVE9OSlhHSVpEQU5SV0c0WkRPTUJXR1UzVEVOUlhHSVlET05SV0dZWkRBTlpXR1lZVEVNQldHNDNUS05aU0dJWURNT0pXTVUzRFFOWlpHWTNRPT09PQ==";
+        let best_first_stats = SearchStats::default();
+        let result = best_first(
+            &clean_input(input),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &best_first_stats,
+        )
+        .found()
+        .unwrap();
+        assert_eq!(result.plaintext, "the secret is in the vault");
+        assert_eq!(result.path.len(), 4);
+
+        let bfs_stats = SearchStats::default();
+        bfs(
+            &clean_input(input),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &bfs_stats,
+        );
+        assert!(
+            best_first_stats.decoder_invocations() < bfs_stats.decoder_invocations(),
+            "best first ran {} decoders, BFS ran {}",
+            best_first_stats.decoder_invocations(),
+            bfs_stats.decoder_invocations()
+        );
+    }
+
+    #[test]
+    fn text_which_is_nearly_english_is_decoded_first() {
+        // ROT13 of "the gate at dawn" with noise after it, so the checker doesn't take it for plaintext,
+        // though it's far closer than anything else the input decodes to
+        let input = "gur tngr ng qnja dkmi xjcw oaeg yzmd ijkx mmdg";
+        let nearly_english = Some("the gate at dawn qxzv kwpj bnrt lmzq vwxk zzqt".to_string());
+        let limits = SearchLimits {
+            max_depth: 3,
+            max_frontier: 10,
+            ..SearchLimits::default()
+        };
+        // Which text one decoder deep each text the search decoded came from
+        let branches = |checker: &CheckerTypes| {
+            let stats = SearchStats::default();
+            best_first(&clean_input(input), checker, limits.clone(), &stats);
+            stats.branches_expanded()
+        };
+
+        // How many texts were decoded before the ROT13 text was
+        let decoded_before = |branches: &[Option<String>]| {
+            branches
+                .iter()
+                .position(|branch| *branch == nearly_english)
+                .unwrap_or(branches.len())
+        };
+
+        let near_misses = branches(&get_athena_checker());
+        // It's among the first texts decoded, then what it decodes to straight after, before any other branch
+        let first = decoded_before(&near_misses);
+        assert!(first <= 2, "{near_misses:?}");
+        assert_eq!(near_misses[first + 1], nearly_english, "{near_misses:?}");
+
+        // A checker which is never any surer about one text than another leaves it among Caesar's other shifts
+        let baseline = branches(&CheckerTypes::from_closure("Never", |_| None));
+        assert!(decoded_before(&baseline) > first, "{baseline:?}");
+    }
+
+    #[test]
+    fn rot13_fixed_point_terminates_quickly() {
+        // ROT13 leaves punctuation alone, so this decodes to itself
+        let checker = CheckerTypes::from_closure("Never", |_| None);
+        let started = Instant::now();
+        let outcome = best_first(
+            &clean_input("!!!"),
+            &checker,
+            SearchLimits::default(),
+            &SearchStats::default(),
+        );
+        assert!(matches!(outcome, CrackOutcome::Exhausted(_)));
+        // Well before the timeout, as it ran out of new text
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -1,138 +1,160 @@
-use crate::filtration_system::MyResults;
-use crate::{cli_pretty_printing::decoded_how_many_times, config::get_config};
-use crossbeam::{channel::bounded, select};
+use super::frontier::keep_most_promising;
+use super::search_node::SearchNode;
+use super::seen_strings::SeenStrings;
+use super::{score, Plaintexts, SearchLimits, SearchStats};
+use crate::checkers::CheckerTypes;
+use crate::cli_pretty_printing::decoded_how_many_times;
+use crate::timer::Timer;
 use log::{debug, trace};
-use std::collections::HashSet;
+use std::ops::ControlFlow;
+use std::sync::Arc;
 
-use crate::{timer, DecoderResult};
+use crate::filtration_system::invisible_characters::CleanedInput;
+use crate::CrackOutcome;
 
 /// Breadth first search is our search algorithm
+/// When too much text is waiting, the least promising of the next level is dropped.
 /// https://en.wikipedia.org/wiki/Breadth-first_search
-pub fn bfs(input: &str) -> Option<DecoderResult> {
-    let config = get_config();
-    let initial = DecoderResult {
-        text: vec![input.to_string()],
-        path: vec![],
-    };
-    let mut seen_strings = HashSet::new();
+pub fn bfs(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    limits: SearchLimits,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    let initial = Arc::new(SearchNode::root(&input.text));
+    if !limits.can_decode(&initial) {
+        return CrackOutcome::DepthLimited(stats.run_stats());
+    }
+    let mut seen_strings = SeenStrings::new(&input.text, limits.seen_capacity);
     // all strings to search through
     let mut current_strings = vec![initial];
+    // Whether we left out text because it was too deep to decode
+    let mut depth_limited = false;
+    // What the input hides is decoded first
+    if let Some(hidden) = super::hidden_message_node(input) {
+        if seen_strings.insert(&hidden) {
+            if limits.can_decode(&hidden) {
+                current_strings.insert(0, Arc::new(hidden));
+            } else {
+                depth_limited = true;
+            }
+        }
+    }
+    let mut plaintexts = Plaintexts::new(limits.results_wanted);
 
     let mut curr_depth: u32 = 1; // as we have input string, so we start from 1
 
-    let (result_send, result_recv) = bounded(1);
-    let timer = timer::start(config.timeout);
+    let mut timer = Timer::start(limits.timeout);
 
     // loop through all of the strings in the vec
     while !current_strings.is_empty() {
         trace!("Number of potential decodings: {}", current_strings.len());
         trace!("Current depth is {:?}", curr_depth);
 
-        let mut new_strings: Vec<DecoderResult> = vec![];
-
-        current_strings.into_iter().try_for_each(|current_string| {
-            let res = super::perform_decoding(&current_string);
-
-            match res {
+        let mut new_strings: Vec<SearchNode> = vec![];
+
+        for (index, current_string) in current_strings.iter().enumerate() {
+            // The rest of this level, and what this level has decoded to so far
+            let waiting = || {
+                current_strings[index..]
+                    .iter()
+                    .map(Arc::as_ref)
+                    .chain(new_strings.iter())
+            };
+            if let Some(outcome) =
+                super::stopped_early(&mut timer, &limits, stats, curr_depth, waiting)
+            {
+                return plaintexts.finish(outcome);
+            }
+            let waiting = current_strings.len() - index - 1 + new_strings.len();
+            stats.report_progress(current_string, waiting);
+
+            match super::expand(
+                current_string,
+                &limits.decoders,
+                checker,
+                stats,
+                &mut plaintexts,
+            ) {
                 // if it's Break variant, we have cracked the text successfully
                 // so just stop processing further.
-                MyResults::Break(res) => {
-                    let mut decoders_used = current_string.path;
-                    let text = res.unencrypted_text.clone().unwrap_or_default();
-                    decoders_used.push(res);
-                    let result_text = DecoderResult {
-                        text,
-                        path: decoders_used,
-                    };
-
-                    result_send
-                        .send(result_text)
-                        .expect("Succesfully send the result");
-                    None // short-circuits the iterator
-                }
-                MyResults::Continue(results_vec) => {
-                    new_strings.extend(
-                        results_vec
-                            .into_iter()
-                            .map(|r| {
-                                let mut decoders_used = current_string.path.clone();
-                                // text is a vector of strings
-                                let text = r.unencrypted_text.clone().unwrap_or_default();
-                                decoders_used.push(r);
-                                DecoderResult {
-                                    // and this is a vector of strings
-                                    // TODO we should probably loop through all `text` and create Text structs for each one
-                                    // and append those structs
-                                    // I think we should keep text as a single string
-                                    // and just create more of them....
-                                    text,
-                                    path: decoders_used.to_vec(),
-                                }
-                            })
-                            .filter(|s| seen_strings.insert(s.text.clone())),
-                    );
-                    Some(()) // indicate we want to continue processing
+                ControlFlow::Break(outcome) => {
+                    decoded_how_many_times(curr_depth);
+                    debug!("Found exit result: {:?}", outcome);
+                    return outcome;
                 }
-            }
-        });
-        let mut new_strings_to_be_added = Vec::new();
-        for text_struct in new_strings {
-            for decoded_text in text_struct.text {
-                if check_if_string_cant_be_decoded(&decoded_text) {
-                    continue;
+                ControlFlow::Continue(results_vec) => {
+                    // Text we've seen is left out before the frontier is capped,
+                    // so only new text counts as evicted
+                    for candidate in results_vec
+                        .into_iter()
+                        .flat_map(|r| current_string.children(r))
+                    {
+                        if !seen_strings.insert(&candidate) {
+                            continue;
+                        }
+                        // It was checked when it was decoded, but is too deep to decode further
+                        if !limits.can_decode(&candidate) {
+                            depth_limited = true;
+                            continue;
+                        }
+                        new_strings.push(candidate);
+                    }
+                    let room = limits
+                        .max_frontier
+                        .saturating_sub(current_strings.len() - index - 1);
+                    let evicted = keep_most_promising(&mut new_strings, room, |candidate| {
+                        score(
+                            candidate,
+                            f64::from(candidate.decoder_priority()),
+                            f64::from(candidate.checker_confidence()),
+                        )
+                    });
+                    stats.count_nodes_evicted(evicted);
                 }
-                new_strings_to_be_added.push(DecoderResult {
-                    text: vec![decoded_text],
-                    // quick hack
-                    path: text_struct.path.clone(),
-                })
             }
         }
-        current_strings = new_strings_to_be_added;
+        current_strings = new_strings.into_iter().map(Arc::new).collect();
         curr_depth += 1;
 
-        select! {
-            recv(result_recv) -> exit_result => {
-                // if we find an element that matches our exit condition, return it!
-                // technically this won't check if the initial string matches our exit condition
-                // but this is a demo and i'll be lazy :P
-                let exit_result = exit_result.ok(); // convert Result to Some
-                if exit_result.is_some() {
-                    decoded_how_many_times(curr_depth);
-                    debug!("Found exit result: {:?}", exit_result);
-                    return exit_result;
-                }
-            },
-            recv(timer) -> _ => {
-                decoded_how_many_times(curr_depth);
-                debug!("Ares has failed to decode");
-                return None;
-            },
-            default => continue,
-        };
-
         trace!("Refreshed the vector, {:?}", current_strings);
     }
 
-    None
-}
-
-/// If this returns False it will not attempt to decode that string
-fn check_if_string_cant_be_decoded(text: &str) -> bool {
-    text.len() <= 2
+    plaintexts.finish(super::nothing_left_to_decode(
+        depth_limited,
+        seen_strings.is_full(),
+        stats,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkers::athena::Athena;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::decoders::caesar_decoder::CaesarDecoder;
+    use crate::decoders::interface::{Crack, Decoder};
+    use crate::decoders::reverse_decoder::ReverseDecoder;
+    use crate::filtration_system::invisible_characters::clean_input;
+    use crate::filtration_system::Decoders;
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
 
     #[test]
     fn bfs_succeeds() {
         // this will work after english checker can identify "CANARY: hello"
-        let result = bfs("b2xsZWg=");
-        assert!(result.is_some());
-        let txt = result.unwrap().text;
-        assert!(txt[0] == "hello");
+        let result = bfs(
+            &clean_input("b2xsZWg="),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &SearchStats::default(),
+        );
+        assert!(result.is_found());
+        assert_eq!(result.found().unwrap().plaintext, "hello");
     }
 
     // Vector storing the strings to perform decoding in next iteraion
@@ -147,22 +169,55 @@ mod tests {
     #[test]
     fn non_deterministic_like_behaviour_regression_test() {
         // Caesar Cipher (Rot13) -> Base64
-        let result = bfs("MTkyLjE2OC4wLjE=");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().text[0], "192.168.0.1");
+        let result = bfs(
+            &clean_input("MTkyLjE2OC4wLjE="),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &SearchStats::default(),
+        );
+        assert!(result.is_found());
+        assert_eq!(result.found().unwrap().plaintext, "192.168.0.1");
     }
 
     #[test]
-    fn string_size_checker_returns_bad_if_string_cant_be_decoded() {
-        // Should return true because it cant decode it
-        let text = "12";
-        assert!(check_if_string_cant_be_decoded(text));
+    fn bfs_stops_at_a_file_and_keeps_its_bytes() {
+        // base64 of a PNG header
+        let result = bfs(
+            &clean_input("iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJ"),
+            &get_athena_checker(),
+            SearchLimits::default(),
+            &SearchStats::default(),
+        )
+        .found()
+        .unwrap();
+        assert!(result.bytes.unwrap().starts_with(b"\x89PNG"));
+        let last = result.path.last().unwrap();
+        assert_eq!(last.decoder, "Base64");
+        assert_eq!(last.checker_name, "File Signature Checker");
     }
 
     #[test]
-    fn string_size_checker_returns_ok_if_string_can_be_decoded() {
-        // Should return true because it cant decode it
-        let text = "123";
-        assert!(!check_if_string_cant_be_decoded(text));
+    fn text_already_seen_isnt_counted_as_evicted() {
+        // Reversing a shift is shifting the reverse, so the second level decodes to each text twice.
+        // Counted twice, they'd be more than the frontier holds.
+        let limits = SearchLimits {
+            max_frontier: 30,
+            decoders: Arc::new(Decoders {
+                components: vec![
+                    Box::new(Decoder::<CaesarDecoder>::new()),
+                    Box::new(Decoder::<ReverseDecoder>::new()),
+                ],
+            }),
+            ..SearchLimits::default()
+        };
+        let stats = SearchStats::default();
+        let outcome = bfs(
+            &clean_input("zqxv kjhg wpfd"),
+            &CheckerTypes::from_closure("Never", |_| None),
+            limits,
+            &stats,
+        );
+        assert_eq!(stats.nodes_evicted(), 0);
+        assert!(matches!(outcome, CrackOutcome::Exhausted(_)), "{outcome:?}");
     }
 }
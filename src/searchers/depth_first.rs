@@ -0,0 +1,296 @@
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use super::frontier::keep_most_promising;
+use super::search_node::SearchNode;
+use super::seen_strings::SeenDepths;
+use super::{score, Plaintexts, SearchLimits, SearchStats};
+use crate::checkers::CheckerTypes;
+use crate::cli_pretty_printing::decoded_how_many_times;
+use crate::filtration_system::invisible_characters::CleanedInput;
+use crate::timer::Timer;
+use crate::CrackOutcome;
+use log::{debug, trace};
+
+/// Depth first search, which follows the most promising decoding as deep as `limits.max_depth`
+/// allows before it tries the next. It only keeps the text next to the chain it's following,
+/// so a deep, narrow chain of decodings takes little memory.
+/// https://en.wikipedia.org/wiki/Depth-first_search
+pub fn depth_first(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    limits: SearchLimits,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    let mut timer = Timer::start(limits.timeout);
+    let mut plaintexts = Plaintexts::new(limits.results_wanted);
+    let (outcome, _) = bounded_depth_first(
+        input,
+        checker,
+        &limits,
+        stats,
+        &mut timer,
+        &mut plaintexts,
+        None,
+    );
+    plaintexts.finish(outcome)
+}
+
+/// Iterative deepening search, which does a depth first search one decoder deep, then two,
+/// up to `limits.max_depth`, stopping once a search wasn't stopped by its depth.
+/// Like breadth first search it finds the shallowest plaintext first, with the memory of depth first search,
+/// at the cost of decoding the shallow text again each time.
+/// https://en.wikipedia.org/wiki/Iterative_deepening_depth-first_search
+pub fn iterative_deepening(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    limits: SearchLimits,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    let mut timer = Timer::start(limits.timeout);
+    let mut plaintexts = Plaintexts::new(limits.results_wanted);
+    let mut previous = None;
+    for max_depth in 1..=limits.max_depth {
+        debug!("Searching depth first to {} decoders deep", max_depth);
+        let bounded = SearchLimits {
+            max_depth,
+            ..limits.clone()
+        };
+        let (outcome, seen) = bounded_depth_first(
+            input,
+            checker,
+            &bounded,
+            stats,
+            &mut timer,
+            &mut plaintexts,
+            previous.as_ref(),
+        );
+        // Only a search its depth stopped can find more deeper down
+        if !matches!(outcome, CrackOutcome::DepthLimited(_)) || max_depth == limits.max_depth {
+            return plaintexts.finish(outcome);
+        }
+        previous = Some(seen);
+    }
+    plaintexts.finish(CrackOutcome::DepthLimited(stats.run_stats()))
+}
+
+/// A depth first search to `limits.max_depth`, on the timer of the whole search.
+///
+/// `previous` is what the search one decoder less deep saw. Text this search finds at its deepest
+/// which that one saw is skipped, as this search finds it less deep too, with more depth left to decode it.
+/// So a search is only depth limited if there's new text deeper down.
+/// Text less deep isn't checked against it, as a text that search saw only at its deepest wasn't decoded.
+/// Returns how the search ended, and the text it saw.
+fn bounded_depth_first(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    limits: &SearchLimits,
+    stats: &SearchStats,
+    timer: &mut Timer,
+    plaintexts: &mut Plaintexts,
+    previous: Option<&SeenDepths>,
+) -> (CrackOutcome, SeenDepths) {
+    let mut seen = SeenDepths::new(&input.text, limits.seen_capacity);
+    let initial = SearchNode::root(&input.text);
+    if !limits.can_decode(&initial) {
+        return (CrackOutcome::DepthLimited(stats.run_stats()), seen);
+    }
+    // Whether we left out text because it was too deep to decode
+    let mut depth_limited = false;
+    // The text waiting to be decoded and how promising it is, the next to decode on top
+    let mut stack = vec![(0.0, Arc::new(initial))];
+    // What the input hides is decoded first
+    if let Some(hidden) = super::hidden_message_node(input) {
+        if seen.insert(&hidden) {
+            if limits.can_decode(&hidden) {
+                stack.push((0.0, Arc::new(hidden)));
+            } else {
+                depth_limited = true;
+            }
+        }
+    }
+
+    while let Some((_, node)) = stack.pop() {
+        let depth = node.depth() as u32 + 1;
+        let waiting = || {
+            let stack = stack.iter().rev().map(|(_, waiting)| waiting.as_ref());
+            std::iter::once(node.as_ref()).chain(stack)
+        };
+        if let Some(outcome) = super::stopped_early(timer, limits, stats, depth, waiting) {
+            return (outcome, seen);
+        }
+        trace!("Decoding {:?} at depth {}", node.text, depth);
+        stats.report_progress(&node, stack.len());
+
+        let results = match super::expand(&node, &limits.decoders, checker, stats, plaintexts) {
+            ControlFlow::Break(outcome) => {
+                decoded_how_many_times(depth);
+                return (outcome, seen);
+            }
+            ControlFlow::Continue(results) => results,
+        };
+        let mut children = Vec::new();
+        for result in results {
+            let candidates = node.children(result);
+            // Each of a decoder's candidates is less likely the more of them there are, like Caesar's 25 shifts
+            let priority = candidates
+                .first()
+                .map_or(0.0, |candidate| f64::from(candidate.decoder_priority()))
+                / candidates.len().max(1) as f64;
+            for candidate in candidates {
+                let deepest = candidate.depth() == limits.max_depth as usize;
+                if deepest && previous.is_some_and(|previous| previous.contains(&candidate)) {
+                    continue;
+                }
+                if !seen.insert(&candidate) {
+                    continue;
+                }
+                // It was checked when it was decoded, but is too deep to decode further
+                if !limits.can_decode(&candidate) {
+                    depth_limited = true;
+                    continue;
+                }
+                let confidence = f64::from(candidate.checker_confidence());
+                children.push((score(&candidate, priority, confidence), Arc::new(candidate)));
+            }
+        }
+        // The most promising child goes on top, so it's decoded next
+        children.sort_by(|a, b| a.0.total_cmp(&b.0));
+        stack.extend(children);
+        let evicted = keep_most_promising(&mut stack, limits.max_frontier, |(score, _)| *score);
+        stats.count_nodes_evicted(evicted);
+    }
+
+    let seen_full = seen.is_full();
+    (
+        super::nothing_left_to_decode(depth_limited, seen_full, stats),
+        seen,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{depth_first, iterative_deepening};
+    use crate::checkers::athena::Athena;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::checkers::CheckerTypes;
+    use crate::filtration_system::invisible_characters::clean_input;
+    use crate::searchers::{SearchLimits, SearchStats};
+    use crate::CrackOutcome;
+    use base64::{engine::general_purpose, Engine as _};
+    use std::time::{Duration, Instant};
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
+
+    /// The plaintext encoded with base64 and then hexadecimal, four times over
+    fn eight_layers(plaintext: &str) -> String {
+        (0..4).fold(plaintext.to_string(), |text, _| {
+            data_encoding::HEXLOWER.encode(general_purpose::STANDARD.encode(text).as_bytes())
+        })
+    }
+
+    #[test]
+    fn depth_first_solves_a_deep_chain_in_little_memory() {
+        let input = eight_layers("the secret is in the vault");
+        let limits = SearchLimits {
+            max_depth: 10,
+            timeout: Duration::from_secs(10),
+            ..SearchLimits::default()
+        };
+        let stats = SearchStats::default();
+        let success = depth_first(&clean_input(&input), &get_athena_checker(), limits, &stats)
+            .found()
+            .expect("the input decodes");
+        assert_eq!(success.plaintext, "the secret is in the vault");
+        assert_eq!(success.path.len(), 8);
+        // Only the text next to the chain is kept, a few dozen texts a level at most
+        assert!(
+            stats.largest_frontier() < 500,
+            "{} texts were waiting",
+            stats.largest_frontier()
+        );
+    }
+
+    #[test]
+    fn iterative_deepening_finds_the_shallowest_plaintext() {
+        // base64 of "flag_one galf", which reversed is "flag eno_galf"
+        let flag_checker = CheckerTypes::from_closure("Flag Checker", |text| {
+            (text.starts_with("flag") && text.ends_with("galf"))
+                .then(|| "is wrapped in a flag".to_string())
+        });
+        let outcome = iterative_deepening(
+            &clean_input("ZmxhZ19vbmUgZ2FsZg=="),
+            &flag_checker,
+            SearchLimits::default(),
+            &SearchStats::default(),
+        );
+        let success = outcome.found().expect("the input decodes");
+        assert_eq!(success.plaintext, "flag_one galf");
+        assert_eq!(success.path.len(), 1);
+    }
+
+    #[test]
+    fn iterative_deepening_stops_when_nothing_is_new_deeper_down() {
+        // ROT13 leaves punctuation alone, and reversing it gives the same text,
+        // so nothing new turns up however deep it goes
+        let never = CheckerTypes::from_closure("Never", |_| None);
+        for search in [depth_first, iterative_deepening] {
+            let outcome = search(
+                &clean_input("!!!"),
+                &never,
+                SearchLimits::default(),
+                &SearchStats::default(),
+            );
+            assert!(matches!(outcome, CrackOutcome::Exhausted(_)), "{outcome:?}");
+        }
+    }
+
+    #[test]
+    fn random_hex_times_out_promptly() {
+        let input = "d649882406c3fb4b62cadfccf888b0057a66be373f933c0ad353c4c374779d19373a8abd93198421d016887461fa1b1a";
+        // Depth first dives deep enough to find Athena's false positives, so nothing is plaintext here
+        let never = CheckerTypes::from_closure("Never", |_| None);
+        let limits = SearchLimits {
+            max_depth: 25,
+            timeout: Duration::from_millis(200),
+            ..SearchLimits::default()
+        };
+        for search in [depth_first, iterative_deepening] {
+            let started = Instant::now();
+            let outcome = search(
+                &clean_input(input),
+                &never,
+                limits.clone(),
+                &SearchStats::default(),
+            );
+            assert!(matches!(outcome, CrackOutcome::TimedOut(_)), "{outcome:?}");
+            assert!(started.elapsed() < Duration::from_secs(2));
+        }
+    }
+
+    #[test]
+    fn depth_limit_stops_both() {
+        // Hexadecimal, then base64
+        let input = "NzQ2ODY1MjA2MzYxNzQyMDczNjE3NDIwNmY2ZTIwNzQ2ODY1MjA2ZDYxNzQ=";
+        let limits = SearchLimits {
+            max_depth: 1,
+            ..SearchLimits::default()
+        };
+        for search in [depth_first, iterative_deepening] {
+            let outcome = search(
+                &clean_input(input),
+                &get_athena_checker(),
+                limits.clone(),
+                &SearchStats::default(),
+            );
+            assert!(
+                matches!(outcome, CrackOutcome::DepthLimited(_)),
+                "{outcome:?}"
+            );
+        }
+    }
+}
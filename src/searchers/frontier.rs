@@ -0,0 +1,157 @@
+//! Text waiting to be decoded, kept to a fixed number so an input which decodes into
+//! endless garbage can't fill up memory.
+
+use std::collections::BinaryHeap;
+
+use log::warn;
+
+/// The most texts waiting to be decoded a search keeps by default.
+/// A random looking input makes lots of decoders "succeed" with garbage,
+/// and without a cap the frontier grows until Ares runs out of memory.
+pub const DEFAULT_MAX_FRONTIER: usize = 100_000;
+
+/// How many texts a full frontier keeps, so it isn't trimmed again on the next push
+fn kept_when_full(capacity: usize) -> usize {
+    (capacity - capacity / 10).max(1)
+}
+
+/// The texts waiting to be decoded, the greatest first. When there are more than its capacity,
+/// the least promising are dropped, rather than stopping the search.
+pub struct Frontier<T: Ord> {
+    /// The texts, the greatest on top
+    heap: BinaryHeap<T>,
+    /// The most texts it keeps
+    capacity: usize,
+    /// How many texts were dropped because it was full
+    evicted: usize,
+}
+
+impl<T: Ord> Frontier<T> {
+    /// An empty frontier which keeps up to `capacity` texts
+    pub fn new(capacity: usize) -> Self {
+        Frontier {
+            heap: BinaryHeap::new(),
+            capacity,
+            evicted: 0,
+        }
+    }
+
+    /// Queues the text. If that's too many, the least promising are dropped,
+    /// down to nine tenths of the capacity, so the cost of trimming is shared out.
+    pub fn push(&mut self, item: T) {
+        self.heap.push(item);
+        if self.heap.len() <= self.capacity {
+            return;
+        }
+        let keep = kept_when_full(self.capacity);
+        let mut items = std::mem::take(&mut self.heap).into_vec();
+        // The greatest `keep` go first, in no particular order
+        items.select_nth_unstable_by(keep - 1, |a, b| b.cmp(a));
+        if self.evicted == 0 {
+            warn!(
+                "More than {} texts are waiting to be decoded, so the least promising are dropped",
+                self.capacity
+            );
+        }
+        self.evicted += items.len() - keep;
+        items.truncate(keep);
+        self.heap = BinaryHeap::from(items);
+    }
+
+    /// Takes the most promising text
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    /// How many texts are waiting
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Every text waiting, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.heap.iter()
+    }
+
+    /// How many texts were dropped because the frontier was full
+    pub fn evicted(&self) -> usize {
+        self.evicted
+    }
+}
+
+/// Drops the least promising of `items`, by `score`, until there are at most `room`,
+/// keeping the rest in order. Like `Frontier::push` it trims to nine tenths of the room.
+/// Returns how many were dropped.
+pub fn keep_most_promising<T>(items: &mut Vec<T>, room: usize, score: impl Fn(&T) -> f64) -> usize {
+    if items.len() <= room {
+        return 0;
+    }
+    let keep = if room == 0 { 0 } else { kept_when_full(room) };
+    let mut scored: Vec<(f64, usize)> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| (score(item), index))
+        .collect();
+    // The most promising first, and of equally promising, the first found
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    let mut kept = vec![false; items.len()];
+    for &(_, index) in &scored[..keep] {
+        kept[index] = true;
+    }
+    let mut index = 0;
+    items.retain(|_| {
+        index += 1;
+        kept[index - 1]
+    });
+    scored.len() - keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keep_most_promising, Frontier};
+
+    #[test]
+    fn a_million_pushes_stay_under_the_cap() {
+        let capacity = 1_000;
+        let mut frontier: Frontier<u64> = Frontier::new(capacity);
+        // Like a search where every text decodes into ten more, none of them plaintext
+        let mut pushed: u64 = 0;
+        let mut popped = 0;
+        let mut largest = 0;
+        while pushed < 1_000_000 {
+            let parent = frontier.pop().inspect(|_| popped += 1).unwrap_or(0);
+            for child in 0..10 {
+                // Scrambled, so the scores aren't in order
+                frontier.push((parent.wrapping_mul(31) + child * 7_919 + pushed) % 1_000_003);
+                pushed += 1;
+            }
+            largest = largest.max(frontier.len());
+        }
+        assert!(largest <= capacity, "{largest}");
+        // Everything pushed was either decoded, dropped or is still waiting
+        assert_eq!(popped + frontier.evicted() + frontier.len(), 1_000_000);
+        assert!(frontier.evicted() > 800_000, "{}", frontier.evicted());
+    }
+
+    #[test]
+    fn the_least_promising_are_dropped() {
+        let mut frontier = Frontier::new(10);
+        for score in 0..11 {
+            frontier.push(score);
+        }
+        assert_eq!(frontier.len(), 9);
+        assert_eq!(frontier.evicted(), 2);
+        let mut kept: Vec<u32> = frontier.iter().copied().collect();
+        kept.sort_unstable();
+        assert_eq!(kept, (2..11).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn the_most_promising_are_kept_in_order() {
+        let mut items = vec![5, 1, 9, 3, 7, 2, 8, 4, 6, 0, 10, 11];
+        let dropped = keep_most_promising(&mut items, 10, |&item| f64::from(item));
+        assert_eq!(dropped, 3);
+        assert_eq!(items, [5, 9, 3, 7, 8, 4, 6, 10, 11]);
+        assert_eq!(keep_most_promising(&mut items, 10, |_| 0.0), 0);
+    }
+}
@@ -3,14 +3,284 @@
 //! Click here to find out more:
 //! https://broadleaf-angora-7db.notion.site/Search-Nodes-Edges-What-should-they-look-like-b74c43ca7ac341a1a5cfdbeb84a7eef0
 
-use crate::checkers::athena::Athena;
-use crate::checkers::checker_type::{Check, Checker};
-use crate::checkers::CheckerTypes;
-use crate::filtration_system::{filter_and_get_decoders, MyResults};
-use crate::DecoderResult;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cancellation::CancellationToken;
+use crate::checkers::{shannon_entropy, CheckerTypes};
+use crate::cli_pretty_printing::decoded_how_many_times;
+use crate::config::{Config, SearchStrategy};
+use crate::decoders::crack_results::CrackResult;
+use crate::filtration_system::invisible_characters::CleanedInput;
+use crate::filtration_system::{filter_and_get_decoders, Decoders, MyResults};
+use crate::progress::Progress;
+use crate::timer::{Instant, Timer};
+use crate::{CrackOutcome, CrackSuccess, PartialCandidate, PartialReport, RunStats};
+use log::debug;
+use search_node::SearchNode;
+
+/// Best first search, which decodes the most promising text first
+mod best_first;
 /// This module provides access to the breadth first search
 /// which searches for the plaintext.
 mod bfs;
+/// Depth first and iterative deepening search, which follow one chain of decodings at a time
+mod depth_first;
+/// Text waiting to be decoded, kept to a fixed number
+pub mod frontier;
+/// Decides which decoders are worth running first
+pub mod priority;
+/// Text the search has found, linked to the text it was decoded from
+mod search_node;
+/// Remembers what a search has seen, so no text is decoded twice
+mod seen_strings;
+
+/// Counts what a search did, so search strategies can be compared,
+/// and tells the progress callback, if there is one, how it's going
+#[derive(Debug)]
+pub struct SearchStats<'a> {
+    /// How many times a decoder was run on some text
+    decoder_invocations: AtomicUsize,
+    /// How many times a decoder wasn't run, as it couldn't decode the text
+    decoders_pruned: AtomicUsize,
+    /// How many texts the search decoded
+    nodes_expanded: AtomicUsize,
+    /// How many texts the search dropped, as too many were waiting to be decoded
+    nodes_evicted: AtomicUsize,
+    /// The most texts waiting to be decoded at once
+    largest_frontier: AtomicUsize,
+    /// Told how the search is going
+    progress: Option<&'a Progress>,
+    /// When the search started
+    started: Instant,
+    /// Which text one decoder deep each text the search decoded came from, in the order
+    /// they were decoded, so tests can see which branches it followed first. None for the input.
+    #[cfg(test)]
+    branches_expanded: std::sync::Mutex<Vec<Option<String>>>,
+}
+
+/// Counts from zero, starting now
+impl Default for SearchStats<'_> {
+    fn default() -> Self {
+        SearchStats {
+            decoder_invocations: AtomicUsize::default(),
+            decoders_pruned: AtomicUsize::default(),
+            nodes_expanded: AtomicUsize::default(),
+            nodes_evicted: AtomicUsize::default(),
+            largest_frontier: AtomicUsize::default(),
+            progress: None,
+            started: Instant::now(),
+            #[cfg(test)]
+            branches_expanded: std::sync::Mutex::default(),
+        }
+    }
+}
+
+impl<'a> SearchStats<'a> {
+    /// Counts from zero, telling `progress` how the search is going
+    fn reporting_to(progress: Option<&'a Progress>) -> Self {
+        SearchStats {
+            progress,
+            ..SearchStats::default()
+        }
+    }
+
+    /// Counts one more decoder run
+    pub fn count_decoder_invocation(&self) {
+        self.decoder_invocations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// How many times a decoder was run on some text
+    pub fn decoder_invocations(&self) -> usize {
+        self.decoder_invocations.load(Ordering::Relaxed)
+    }
+
+    /// Counts one more decoder skipped by `can_attempt`
+    pub fn count_decoder_pruned(&self) {
+        self.decoders_pruned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// How many times a decoder wasn't run, as it couldn't decode the text
+    pub fn decoders_pruned(&self) -> usize {
+        self.decoders_pruned.load(Ordering::Relaxed)
+    }
+
+    /// Counts one more text decoded
+    #[cfg_attr(not(test), allow(unused_variables))]
+    fn count_node_expanded(&self, node: &SearchNode) {
+        self.nodes_expanded.fetch_add(1, Ordering::Relaxed);
+        #[cfg(test)]
+        self.branches_expanded.lock().unwrap().push(
+            node.path()
+                .first()
+                .and_then(|step| step.unencrypted_text.as_ref()?.first().cloned()),
+        );
+    }
+
+    /// Which text one decoder deep each text the search decoded came from, see `branches_expanded`
+    #[cfg(test)]
+    fn branches_expanded(&self) -> Vec<Option<String>> {
+        self.branches_expanded.lock().unwrap().clone()
+    }
+
+    /// How many texts the search decoded
+    pub fn nodes_expanded(&self) -> usize {
+        self.nodes_expanded.load(Ordering::Relaxed)
+    }
+
+    /// Counts texts dropped because too many were waiting to be decoded
+    fn count_nodes_evicted(&self, evicted: usize) {
+        self.nodes_evicted.fetch_add(evicted, Ordering::Relaxed);
+    }
+
+    /// How many texts the search dropped, as too many were waiting to be decoded
+    pub fn nodes_evicted(&self) -> usize {
+        self.nodes_evicted.load(Ordering::Relaxed)
+    }
+
+    /// The most texts waiting to be decoded at once
+    pub fn largest_frontier(&self) -> usize {
+        self.largest_frontier.load(Ordering::Relaxed)
+    }
+
+    /// What the search has done so far, for the outcome
+    fn run_stats(&self) -> RunStats {
+        RunStats {
+            nodes_expanded: self.nodes_expanded(),
+            decoder_invocations: self.decoder_invocations(),
+            decoders_pruned: self.decoders_pruned(),
+            nodes_evicted: self.nodes_evicted(),
+            largest_frontier: self.largest_frontier(),
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    /// Tells the progress callback the search is about to decode `node`,
+    /// with `frontier_size` more texts waiting
+    fn report_progress(&self, node: &SearchNode, frontier_size: usize) {
+        self.largest_frontier
+            .fetch_max(frontier_size, Ordering::Relaxed);
+        if let Some(progress) = self.progress {
+            progress.report(
+                &node.text,
+                node.depth(),
+                frontier_size,
+                self.nodes_expanded(),
+                self.decoder_invocations(),
+            );
+        }
+    }
+}
+
+/// How far and for how long a search may go, which decoders it runs, and how many plaintexts it looks for
+#[derive(Debug, Clone)]
+struct SearchLimits {
+    /// The most decoders in a row the search tries
+    max_depth: u32,
+    /// How long the search may run for
+    timeout: Duration,
+    /// How many different plaintexts the search looks for before it stops
+    results_wanted: usize,
+    /// The most texts waiting to be decoded the search keeps
+    max_frontier: usize,
+    /// The most texts the search remembers seeing, see `SeenStrings`
+    seen_capacity: usize,
+    /// Stops the search early when it's cancelled
+    cancel: CancellationToken,
+    /// The decoders the search runs on every text, built once for the whole search
+    decoders: Arc<Decoders>,
+}
+
+impl SearchLimits {
+    /// The limits the config sets
+    fn from_config(config: &Config) -> Self {
+        SearchLimits {
+            max_depth: config.max_depth,
+            timeout: config.timeout,
+            results_wanted: config.results_wanted,
+            max_frontier: config.max_frontier,
+            seen_capacity: seen_strings::DEFAULT_SEEN_CAPACITY,
+            cancel: CancellationToken::new(),
+            decoders: Arc::new(filter_and_get_decoders(config)),
+        }
+    }
+
+    /// Whether the candidate may be decoded, as decoding it adds one more decoder to its path
+    fn can_decode(&self, candidate: &SearchNode) -> bool {
+        candidate.depth() < self.max_depth as usize
+    }
+}
+
+/// The limits of the default config
+impl Default for SearchLimits {
+    fn default() -> Self {
+        SearchLimits::from_config(&Config::default())
+    }
+}
+
+/// The plaintexts a search has found so far
+struct Plaintexts {
+    /// How many the search looks for
+    wanted: usize,
+    /// The plaintexts, in the order they were found
+    found: Vec<CrackSuccess>,
+}
+
+impl Plaintexts {
+    /// Looks for `wanted` plaintexts
+    fn new(wanted: usize) -> Self {
+        Plaintexts {
+            wanted,
+            found: Vec::new(),
+        }
+    }
+
+    /// Keeps the plaintext, unless another path already found the same text.
+    /// Returns whether we found all we wanted.
+    fn add(&mut self, plaintext: CrackSuccess) -> bool {
+        if !self
+            .found
+            .iter()
+            .any(|found| found.plaintext == plaintext.plaintext)
+        {
+            self.found.push(plaintext);
+        }
+        self.found.len() >= self.wanted
+    }
+
+    /// How the search ends: with the plaintexts found, the best first,
+    /// or `otherwise` if it found none. Either way, with what the search did up to `otherwise`.
+    fn finish(&mut self, otherwise: CrackOutcome) -> CrackOutcome {
+        if self.found.is_empty() {
+            return otherwise;
+        }
+        let stats = otherwise.stats();
+        let mut found = std::mem::take(&mut self.found);
+        for plaintext in &mut found {
+            plaintext.stats = stats.clone();
+        }
+        rank_plaintexts(&mut found);
+        CrackOutcome::Found(found)
+    }
+}
+
+/// Sorts plaintexts so the ones the checker was most confident about come first,
+/// and of those, the ones which took the fewest decoders
+pub fn rank_plaintexts(plaintexts: &mut [CrackSuccess]) {
+    let confidence = |plaintext: &CrackSuccess| {
+        plaintext
+            .path
+            .last()
+            .map_or(0.0, |result| result.checker_confidence)
+    };
+    plaintexts.sort_by(|a, b| {
+        confidence(b)
+            .total_cmp(&confidence(a))
+            .then_with(|| a.path.len().cmp(&b.path.len()))
+    });
+}
 
 /*pub struct Tree <'a> {
     // Wrap in a box because
@@ -25,27 +295,264 @@ mod bfs;
 /// We need to loop through these and determine:
 /// 1. Did we reach our exit condition?
 /// 2. If not, create new nodes out of them and add them to the queue.
+///
 /// We can return an Option? An Enum? And then match on that
 /// So if we return CrackSuccess we return
 /// Else if we return an array, we add it to the children and go again.
-pub fn search_for_plaintext(input: &str) -> Option<DecoderResult> {
-    // Change this to select which search algorithm we want to use.
-    bfs::bfs(input)
+/// The checker decides whether we found the plaintext, and is used for the whole search.
+/// The search stops at the depth and time limits in `config`, and uses its strategy and decoders.
+/// It decodes the cleaned input, and the message hidden in its zero-width characters if there is one.
+/// It stops early when `cancel` is cancelled, with what it was about to decode.
+/// `progress` is told how the search is going, and how it ended before it returns.
+pub fn search_for_plaintext(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    config: &Config,
+    cancel: &CancellationToken,
+    progress: Option<&Progress>,
+) -> CrackOutcome {
+    let stats = SearchStats::reporting_to(progress);
+    let limits = SearchLimits {
+        cancel: cancel.clone(),
+        ..SearchLimits::from_config(config)
+    };
+    let outcome = search_with_strategy(input, checker, config.search_strategy, limits, &stats);
+    debug!(
+        "The search decoded {} texts, ran {} decoders, skipped {} which couldn't decode their text, \
+        and dropped {} texts with at most {} waiting",
+        stats.nodes_expanded(),
+        stats.decoder_invocations(),
+        stats.decoders_pruned(),
+        stats.nodes_evicted(),
+        stats.largest_frontier()
+    );
+    if let Some(progress) = progress {
+        progress.search_finished(stats.nodes_expanded(), stats.decoder_invocations());
+    }
+    outcome
+}
+
+/// Searches with the given strategy within the limits, counting what it did in `stats`
+fn search_with_strategy(
+    input: &CleanedInput,
+    checker: &CheckerTypes,
+    strategy: SearchStrategy,
+    limits: SearchLimits,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    match strategy {
+        SearchStrategy::BestFirst => best_first::best_first(input, checker, limits, stats),
+        SearchStrategy::BreadthFirst => bfs::bfs(input, checker, limits, stats),
+        SearchStrategy::DepthFirst => depth_first::depth_first(input, checker, limits, stats),
+        SearchStrategy::IterativeDeepening => {
+            depth_first::iterative_deepening(input, checker, limits, stats)
+        }
+    }
+}
+
+/// How the search ends if it has to stop before decoding its next text, to `depth` decoders deep,
+/// because it ran out of time or was cancelled.
+/// A cancelled search reports the text in `frontier`, which it was about to decode.
+fn stopped_early<'a, F>(
+    timer: &mut Timer,
+    limits: &SearchLimits,
+    stats: &SearchStats,
+    depth: u32,
+    frontier: impl FnOnce() -> F,
+) -> Option<CrackOutcome>
+where
+    F: IntoIterator<Item = &'a SearchNode>,
+{
+    // Checked before every decoding, as one level can take far longer than the timeout
+    if timer.expired() {
+        decoded_how_many_times(depth);
+        debug!("Ares ran out of time after {:?}", timer.elapsed());
+        return Some(CrackOutcome::TimedOut(stats.run_stats()));
+    }
+    if limits.cancel.is_cancelled() {
+        decoded_how_many_times(depth);
+        return Some(cancelled(frontier(), stats));
+    }
+    None
+}
+
+/// The message hidden in the input's zero-width characters, decoded from the input as it was given,
+/// if there is one
+fn hidden_message_node(input: &CleanedInput) -> Option<SearchNode> {
+    let hidden = input.hidden_message.clone()?;
+    Arc::new(SearchNode::root(&input.original))
+        .children(hidden)
+        .pop()
+}
+
+/// How a search ends when it has nothing left to decode.
+/// If it dropped text as too much was waiting, or left text out as it had seen as much as it remembers,
+/// the plaintext may have been among it, and if it left out text which was too deep, the depth limit stopped it.
+fn nothing_left_to_decode(
+    depth_limited: bool,
+    seen_full: bool,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    debug!("Ares has failed to decode");
+    let run_stats = stats.run_stats();
+    if run_stats.nodes_evicted > 0 {
+        CrackOutcome::FrontierLimited(run_stats)
+    } else if seen_full {
+        CrackOutcome::SeenLimited(run_stats)
+    } else if depth_limited {
+        CrackOutcome::DepthLimited(run_stats)
+    } else {
+        CrackOutcome::Exhausted(run_stats)
+    }
+}
+
+/// How much each level deeper costs, so long chains are tried after short ones
+const DEPTH_PENALTY: f64 = 0.1;
+/// How much the text's entropy, as a share of the most possible, counts against it.
+/// Random looking text is less likely to decode into anything.
+const ENTROPY_WEIGHT: f64 = 0.5;
+/// How much the checker's confidence that the text is plaintext counts for it.
+/// Text it nearly took for plaintext is worth decoding first, but noise can spell a few words,
+/// so it mustn't outweigh everything else.
+const CONFIDENCE_WEIGHT: f64 = 0.5;
+
+/// How promising the candidate is, where the path to it has `path_priority`
+/// and the checker had `confidence` it was plaintext.
+/// Best first search decodes the most promising first, and both searches
+/// drop the least promising when too much is waiting.
+fn score(candidate: &SearchNode, path_priority: f64, confidence: f64) -> f64 {
+    let bytes = candidate
+        .bytes
+        .as_deref()
+        .unwrap_or(candidate.text.as_bytes());
+    let entropy = shannon_entropy(bytes) / 8.0;
+    let depth = candidate.depth() as f64;
+    path_priority + CONFIDENCE_WEIGHT * confidence
+        - ENTROPY_WEIGHT * entropy
+        - DEPTH_PENALTY * depth
+}
+
+/// The most text a cancelled search reports it was about to decode
+const PARTIAL_CANDIDATES: usize = 10;
+
+/// How a search ends when it's cancelled, with the most promising of the text it was
+/// about to decode, `frontier`, and what it had done
+fn cancelled<'a>(
+    frontier: impl IntoIterator<Item = &'a SearchNode>,
+    stats: &SearchStats,
+) -> CrackOutcome {
+    let stats = stats.run_stats();
+    debug!("The search was cancelled after {:?}", stats.elapsed);
+    let mut candidates: Vec<PartialCandidate> = Vec::new();
+    for node in frontier {
+        if candidates
+            .iter()
+            .any(|candidate| candidate.text == *node.text && candidate.bytes == node.bytes)
+        {
+            continue;
+        }
+        let bytes = node.bytes.as_deref().unwrap_or(node.text.as_bytes());
+        candidates.push(PartialCandidate {
+            text: node.text.to_string(),
+            bytes: node.bytes.clone(),
+            checker_confidence: node.checker_confidence(),
+            entropy: shannon_entropy(bytes) / 8.0,
+            path: node.path(),
+        });
+    }
+    candidates.sort_by(|a, b| {
+        b.checker_confidence
+            .total_cmp(&a.checker_confidence)
+            .then_with(|| a.entropy.total_cmp(&b.entropy))
+    });
+    candidates.truncate(PARTIAL_CANDIDATES);
+    CrackOutcome::Cancelled(PartialReport { candidates, stats })
+}
+
+/// Decodes the text, and stops the search if it found what it was looking for.
+/// When the search wants one plaintext, the first decoder to succeed is the answer.
+/// When it wants more, every plaintext is kept in `plaintexts`, and they carry on
+/// being decoded like anything else, as the real answer may be deeper.
+fn expand(
+    node: &SearchNode,
+    decoders: &Decoders,
+    checker: &CheckerTypes,
+    stats: &SearchStats,
+    plaintexts: &mut Plaintexts,
+) -> ControlFlow<CrackOutcome, Vec<CrackResult>> {
+    stats.count_node_expanded(node);
+    if plaintexts.wanted <= 1 {
+        return match perform_decoding(node, decoders, checker, stats) {
+            MyResults::Break(res) => {
+                let plaintext = node.identified(*res, stats.run_stats());
+                ControlFlow::Break(CrackOutcome::Found(vec![plaintext]))
+            }
+            MyResults::Continue(results) => ControlFlow::Continue(results),
+        };
+    }
+
+    let results = decoders.run_all(
+        &node.text,
+        node.bytes.as_deref(),
+        node.last_decoder(),
+        checker,
+        stats,
+    );
+    for result in results.iter().filter(|result| result.success) {
+        if plaintexts.add(node.identified(result.clone(), stats.run_stats())) {
+            return ControlFlow::Break(
+                plaintexts.finish(CrackOutcome::Exhausted(stats.run_stats())),
+            );
+        }
+    }
+    ControlFlow::Continue(results)
 }
 
-/// Performs the decodings by getting all of the decoders
-/// and calling `.run` which in turn loops through them and calls
-/// `.crack()`.
-fn perform_decoding(text: &DecoderResult) -> MyResults {
-    let decoders = filter_and_get_decoders(text);
-    let athena_checker = Checker::<Athena>::new();
-    let checker = CheckerTypes::CheckAthena(athena_checker);
-    decoders.run(&text.text[0], checker)
+/// Performs the decodings by calling `.run` on the search's decoders,
+/// which in turn loops through them and calls `.crack()`.
+fn perform_decoding(
+    node: &SearchNode,
+    decoders: &Decoders,
+    checker: &CheckerTypes,
+    stats: &SearchStats,
+) -> MyResults {
+    decoders.run(
+        &node.text,
+        node.bytes.as_deref(),
+        node.last_decoder(),
+        checker,
+        stats,
+    )
+}
+
+/// If this returns False it will not attempt to decode that string
+fn check_if_string_cant_be_decoded(text: &str) -> bool {
+    text.len() <= 2
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checkers::athena::Athena;
+    use crate::checkers::checker_type::{Check, Checker};
+    use crate::decoders::interface::Decoder;
+    use crate::decoders::DECODERS_BUILT;
+    use crate::filtration_system::invisible_characters::clean_input;
+    use std::time::Instant;
+
+    /// Every search strategy, for tests which should hold for all of them
+    const STRATEGIES: [SearchStrategy; 4] = [
+        SearchStrategy::BestFirst,
+        SearchStrategy::BreadthFirst,
+        SearchStrategy::DepthFirst,
+        SearchStrategy::IterativeDeepening,
+    ];
+
+    // helper for tests
+    fn get_athena_checker() -> CheckerTypes {
+        let athena_checker = Checker::<Athena>::new();
+        CheckerTypes::CheckAthena(athena_checker)
+    }
 
     // https://github.com/bee-san/Ares/pull/14/files#diff-b8829c7e292562666c7fa5934de7b478c4a5de46d92e42c46215ac4d9ff89db2R37
     // Only used for tests!
@@ -70,8 +577,14 @@ mod tests {
 
     #[test]
     fn perform_decoding_succeeds() {
-        let dc = DecoderResult::_new("aHR0cHM6Ly93d3cuZ29vZ2xlLmNvbQ==");
-        let result = perform_decoding(&dc);
+        let dc = SearchNode::root("aHR0cHM6Ly93d3cuZ29vZ2xlLmNvbQ==");
+        let decoders = filter_and_get_decoders(&Config::default());
+        let result = perform_decoding(
+            &dc,
+            &decoders,
+            &get_athena_checker(),
+            &SearchStats::default(),
+        );
         assert!(
             result
                 ._break_value()
@@ -83,8 +596,535 @@ mod tests {
     #[test]
     fn perform_decoding_succeeds_empty_string() {
         // Some decoders like base64 return even when the string is empty.
-        let dc = DecoderResult::_new("");
-        let result = perform_decoding(&dc);
+        let dc = SearchNode::root("");
+        let decoders = filter_and_get_decoders(&Config::default());
+        let result = perform_decoding(
+            &dc,
+            &decoders,
+            &get_athena_checker(),
+            &SearchStats::default(),
+        );
         assert!(result._break_value().is_none());
     }
+
+    #[test]
+    fn string_size_checker_returns_bad_if_string_cant_be_decoded() {
+        // Should return true because it cant decode it
+        let text = "12";
+        assert!(check_if_string_cant_be_decoded(text));
+    }
+
+    #[test]
+    fn string_size_checker_returns_ok_if_string_can_be_decoded() {
+        // Should return true because it cant decode it
+        let text = "123";
+        assert!(!check_if_string_cant_be_decoded(text));
+    }
+
+    #[test]
+    fn same_input_decodes_the_same_way_every_time() {
+        let input = "dXJ5eWIgZ3VyZXIgdHJhcmVueSB4cmFib3Y=";
+        let checker = get_athena_checker();
+        let path_of = || {
+            search_with_strategy(
+                &clean_input(input),
+                &checker,
+                SearchStrategy::BestFirst,
+                SearchLimits::default(),
+                &SearchStats::default(),
+            )
+            .found()
+            .expect("the input decodes")
+            .path
+            .iter()
+            .map(|result| result.decoder)
+            .collect::<Vec<_>>()
+        };
+        let first = path_of();
+        for _ in 0..19 {
+            assert_eq!(path_of(), first);
+        }
+    }
+
+    #[test]
+    fn every_strategy_finds_the_same_plaintext() {
+        let fixtures = [
+            ("b2xsZWg=", "hello"),
+            ("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", "hello there general"),
+            (
+                "NzQ2ODY1MjA2MzYxNzQyMDczNjE3NDIwNmY2ZTIwNzQ2ODY1MjA2ZDYxNzQ=",
+                "the cat sat on the mat",
+            ),
+            (
+                include_str!("../../tests/test_fixtures/base64_3_times_with_no_new_line"),
+                "Multiple base64 encodings",
+            ),
+            (
+                include_str!("../../tests/test_fixtures/rot13_base64_hex_with_newline"),
+                "This is a test!",
+            ),
+        ];
+        for (input, plaintext) in fixtures {
+            for strategy in STRATEGIES {
+                let outcome = search_with_strategy(
+                    &clean_input(input.trim()),
+                    &get_athena_checker(),
+                    strategy,
+                    SearchLimits::default(),
+                    &SearchStats::default(),
+                );
+                assert_eq!(
+                    outcome.found().map(|success| success.plaintext),
+                    Some(plaintext.to_string()),
+                    "{strategy:?} on {input}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_hex_times_out_promptly() {
+        let input = "d649882406c3fb4b62cadfccf888b0057a66be373f933c0ad353c4c374779d19373a8abd93198421d016887461fa1b1a";
+        let limits = SearchLimits {
+            max_depth: 25,
+            timeout: Duration::from_millis(200),
+            ..SearchLimits::default()
+        };
+        for strategy in [SearchStrategy::BestFirst, SearchStrategy::BreadthFirst] {
+            let started = Instant::now();
+            let outcome = search_with_strategy(
+                &clean_input(input),
+                &get_athena_checker(),
+                strategy,
+                limits.clone(),
+                &SearchStats::default(),
+            );
+            assert!(matches!(outcome, CrackOutcome::TimedOut(_)), "{strategy:?}");
+            assert!(started.elapsed() < Duration::from_secs(2), "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn cancelling_stops_the_search_with_a_partial_report() {
+        let input = "d649882406c3fb4b62cadfccf888b0057a66be373f933c0ad353c4c374779d19373a8abd93198421d016887461fa1b1a";
+        for strategy in STRATEGIES {
+            let cancel = CancellationToken::new();
+            let limits = SearchLimits {
+                max_depth: 25,
+                timeout: Duration::from_secs(60),
+                cancel: cancel.clone(),
+                ..SearchLimits::default()
+            };
+            let canceller = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                cancel.cancel();
+            });
+            let started = Instant::now();
+            let outcome = search_with_strategy(
+                &clean_input(input),
+                &CheckerTypes::from_closure("Never", |_| None),
+                strategy,
+                limits,
+                &SearchStats::default(),
+            );
+            canceller.join().unwrap();
+            assert!(started.elapsed() < Duration::from_secs(2), "{strategy:?}");
+            let CrackOutcome::Cancelled(report) = outcome else {
+                panic!("{strategy:?} wasn't cancelled: {outcome:?}");
+            };
+            assert!(!report.candidates.is_empty(), "{strategy:?}");
+            assert!(report.candidates.len() <= PARTIAL_CANDIDATES);
+            assert!(report.stats.nodes_expanded > 0, "{strategy:?}");
+            assert!(report.stats.decoder_invocations > 0, "{strategy:?}");
+            // The least random text comes first when the checker is never confident
+            assert!(report
+                .candidates
+                .windows(2)
+                .all(|pair| pair[0].entropy <= pair[1].entropy));
+        }
+    }
+
+    #[test]
+    fn a_search_which_finishes_ignores_the_token() {
+        let cancel = CancellationToken::new();
+        let limits = SearchLimits {
+            cancel: cancel.clone(),
+            ..SearchLimits::default()
+        };
+        for strategy in STRATEGIES {
+            let outcome = search_with_strategy(
+                &clean_input("aGVsbG8gdGhlcmUgZ2VuZXJhbA=="),
+                &get_athena_checker(),
+                strategy,
+                limits.clone(),
+                &SearchStats::default(),
+            );
+            assert_eq!(
+                outcome.found().unwrap().plaintext,
+                "hello there general",
+                "{strategy:?}"
+            );
+        }
+        assert!(!cancel.is_cancelled());
+    }
+
+    #[test]
+    fn the_frontier_stays_under_its_cap() {
+        let input = "q8ZkR2vW0pLx7NcT4bYh9JmE1sGa6FuD3oKi5VzQ";
+        let never = CheckerTypes::from_closure("Never", |_| None);
+        for strategy in STRATEGIES {
+            let limits = SearchLimits {
+                timeout: Duration::from_millis(500),
+                max_frontier: 30,
+                ..SearchLimits::default()
+            };
+            let stats = SearchStats::default();
+            search_with_strategy(&clean_input(input), &never, strategy, limits, &stats);
+            assert!(stats.nodes_evicted() > 0, "{strategy:?}");
+            assert!(stats.largest_frontier() <= 30, "{strategy:?}");
+
+            // Having dropped text, running out of it isn't the same as trying everything
+            let limits = SearchLimits {
+                max_depth: 2,
+                max_frontier: 20,
+                ..SearchLimits::default()
+            };
+            let outcome = search_with_strategy(
+                &clean_input(input),
+                &never,
+                strategy,
+                limits,
+                &SearchStats::default(),
+            );
+            assert!(
+                matches!(outcome, CrackOutcome::FrontierLimited(_)),
+                "{strategy:?}: {outcome:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_search_which_saw_too_much_to_remember_says_so() {
+        let input = "q8ZkR2vW0pLx7NcT4bYh9JmE1sGa6FuD3oKi5VzQ";
+        let never = CheckerTypes::from_closure("Never", |_| None);
+        for strategy in STRATEGIES {
+            let limits = SearchLimits {
+                max_depth: 2,
+                seen_capacity: 10,
+                ..SearchLimits::default()
+            };
+            let outcome = search_with_strategy(
+                &clean_input(input),
+                &never,
+                strategy,
+                limits,
+                &SearchStats::default(),
+            );
+            // It left out text it hadn't seen, so it didn't try everything
+            assert!(
+                matches!(outcome, CrackOutcome::SeenLimited(_)),
+                "{strategy:?}: {outcome:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_normal_decode_is_unaffected_by_the_cap() {
+        // Hexadecimal, then base64
+        let input = "NzQ2ODY1MjA2MzYxNzQyMDczNjE3NDIwNmY2ZTIwNzQ2ODY1MjA2ZDYxNzQ=";
+        for strategy in STRATEGIES {
+            let stats = SearchStats::default();
+            let success = search_with_strategy(
+                &clean_input(input),
+                &get_athena_checker(),
+                strategy,
+                SearchLimits::default(),
+                &stats,
+            )
+            .found()
+            .expect("the input decodes");
+            assert_eq!(success.plaintext, "the cat sat on the mat", "{strategy:?}");
+            assert_eq!(stats.nodes_evicted(), 0, "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn depth_limit_stops_a_deeper_decode() {
+        // Hexadecimal, then base64
+        let input = "NzQ2ODY1MjA2MzYxNzQyMDczNjE3NDIwNmY2ZTIwNzQ2ODY1MjA2ZDYxNzQ=";
+        let limits = |max_depth| SearchLimits {
+            max_depth,
+            timeout: Duration::from_secs(5),
+            ..SearchLimits::default()
+        };
+        for strategy in STRATEGIES {
+            let search = |max_depth| {
+                search_with_strategy(
+                    &clean_input(input),
+                    &get_athena_checker(),
+                    strategy,
+                    limits(max_depth),
+                    &SearchStats::default(),
+                )
+            };
+            assert!(
+                matches!(search(1), CrackOutcome::DepthLimited(_)),
+                "{strategy:?}"
+            );
+            let result = search(5).found().expect("the input decodes");
+            assert_eq!(result.plaintext, "the cat sat on the mat");
+            assert_eq!(result.path.len(), 2);
+        }
+    }
+
+    #[test]
+    fn decoders_which_cant_decode_english_are_not_run() {
+        let input = "It was a bright cold day in April, and the clocks were striking thirteen. \
+            Winston Smith, his chin nuzzled into his breast in an effort to escape the vile wind, \
+            slipped quickly through the glass doors of Victory Mansions.";
+        let limits = SearchLimits {
+            max_depth: 1,
+            ..SearchLimits::default()
+        };
+        let stats = SearchStats::default();
+        search_with_strategy(
+            &clean_input(input),
+            &CheckerTypes::from_closure("Never", |_| None),
+            SearchStrategy::BestFirst,
+            limits,
+            &stats,
+        );
+        let run = stats.decoder_invocations();
+        let pruned = stats.decoders_pruned();
+        // The base and number decoders can't decode prose, so a good share are never run
+        assert!(pruned * 3 >= run + pruned, "{run} run, {pruned} pruned");
+    }
+
+    #[test]
+    fn three_layers_give_the_path_in_order() {
+        // ROT13, then hexadecimal, then base64
+        let input = "Njc3NTcyMjA2NjcyNzA2NTcyNjcyMDc2NjYyMDc2NjEyMDY3NzU3MjIwNjk2ZTY4Nzk2Nw==";
+        let hex = "677572206672706572672076662076612067757220696e687967";
+        let success = search_for_plaintext(
+            &clean_input(input),
+            &get_athena_checker(),
+            &Config::default(),
+            &CancellationToken::new(),
+            None,
+        )
+        .found()
+        .expect("the input decodes");
+        assert_eq!(success.plaintext, "the secret is in the vault");
+        let steps: Vec<(&str, &str, Vec<String>)> = success
+            .path
+            .iter()
+            .map(|step| {
+                (
+                    step.decoder,
+                    step.encrypted_text.as_str(),
+                    step.unencrypted_text.clone().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("Base64", input, vec![hex.to_string()]),
+                (
+                    "Hexadecimal",
+                    hex,
+                    vec!["gur frperg vf va gur inhyg".to_string()]
+                ),
+                (
+                    "Caesar Cipher",
+                    "gur frperg vf va gur inhyg",
+                    vec!["the secret is in the vault".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn one_layer_gives_a_one_step_path() {
+        let success = search_for_plaintext(
+            &clean_input("aGVsbG8gdGhlcmUgZ2VuZXJhbA=="),
+            &get_athena_checker(),
+            &Config::default(),
+            &CancellationToken::new(),
+            None,
+        )
+        .found()
+        .expect("the input decodes");
+        assert_eq!(success.plaintext, "hello there general");
+        assert_eq!(success.path.len(), 1);
+        assert_eq!(success.path[0].decoder, "Base64");
+        assert_eq!(
+            success.path[0].unencrypted_text,
+            Some(vec!["hello there general".to_string()])
+        );
+    }
+
+    #[test]
+    fn path_records_the_caesar_shift_followed() {
+        // base64, then ROT13, so Caesar has 25 candidates and only one of them is base64
+        let success = search_for_plaintext(
+            &clean_input("qTuyVUS1nJAeVTWlo3qhVTMirPOdqJ1jplOiqzIlVUEbMFOfLKc5VTEiMj=="),
+            &get_athena_checker(),
+            &Config::default(),
+            &CancellationToken::new(),
+            None,
+        )
+        .found()
+        .expect("the input decodes");
+        assert_eq!(
+            success.plaintext,
+            "the quick brown fox jumps over the lazy dog"
+        );
+        let caesar = &success.path[0];
+        assert_eq!(caesar.decoder, "Caesar Cipher");
+        assert_eq!(
+            caesar.unencrypted_text,
+            Some(vec![
+                "dGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw==".to_string()
+            ])
+        );
+        assert_eq!(
+            success.path[1].encrypted_text,
+            "dGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw=="
+        );
+    }
+
+    /// A checker for text between "flag" and "galf", which reads the same both ways round
+    fn flag_checker() -> CheckerTypes {
+        CheckerTypes::from_closure("Flag Checker", |text| {
+            (text.starts_with("flag") && text.ends_with("galf"))
+                .then(|| "is wrapped in a flag".to_string())
+        })
+    }
+
+    #[test]
+    fn finds_plaintexts_at_different_depths_when_more_are_wanted() {
+        // base64 of "flag_one galf", which reversed is "flag eno_galf"
+        let input = "ZmxhZ19vbmUgZ2FsZg==";
+        let limits = SearchLimits {
+            results_wanted: 2,
+            ..SearchLimits::default()
+        };
+        for strategy in STRATEGIES {
+            let found = search_with_strategy(
+                &clean_input(input),
+                &flag_checker(),
+                strategy,
+                limits.clone(),
+                &SearchStats::default(),
+            )
+            .found_all();
+            let texts: Vec<&str> = found
+                .iter()
+                .map(|result| result.plaintext.as_str())
+                .collect();
+            // Equally confident, so the shallower comes first
+            assert_eq!(texts, ["flag_one galf", "flag eno_galf"], "{strategy:?}");
+            assert_eq!(found[1].path.last().unwrap().decoder, "Reverse");
+        }
+    }
+
+    #[test]
+    fn one_result_wanted_stops_at_the_first_plaintext() {
+        for strategy in STRATEGIES {
+            let found = search_with_strategy(
+                &clean_input("ZmxhZ19vbmUgZ2FsZg=="),
+                &flag_checker(),
+                strategy,
+                SearchLimits::default(),
+                &SearchStats::default(),
+            )
+            .found_all();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].plaintext, "flag_one galf");
+            assert_eq!(found[0].path.len(), 1);
+        }
+    }
+
+    #[test]
+    fn plaintexts_are_distinct_and_ranked() {
+        let plaintext = |text: &str, confidence: f32, depth: usize| {
+            let mut result =
+                CrackResult::new(&Decoder::default()).with_encrypted_text("input".to_string());
+            result.checker_confidence = confidence;
+            CrackSuccess {
+                plaintext: text.to_string(),
+                bytes: None,
+                path: vec![result; depth],
+                stats: RunStats::default(),
+            }
+        };
+        let mut plaintexts = Plaintexts::new(3);
+        assert!(!plaintexts.add(plaintext("deep", 0.9, 3)));
+        assert!(!plaintexts.add(plaintext("deep", 0.9, 1)));
+        assert!(!plaintexts.add(plaintext("sure", 1.0, 4)));
+        assert!(plaintexts.add(plaintext("shallow", 0.9, 1)));
+
+        let stats = RunStats {
+            nodes_expanded: 7,
+            ..RunStats::default()
+        };
+        let found = plaintexts
+            .finish(CrackOutcome::Exhausted(stats.clone()))
+            .found_all();
+        let texts: Vec<&str> = found
+            .iter()
+            .map(|result| result.plaintext.as_str())
+            .collect();
+        assert_eq!(texts, ["sure", "shallow", "deep"]);
+        // They all have the stats of the whole search
+        assert!(found.iter().all(|result| result.stats == stats));
+        assert!(matches!(
+            Plaintexts::new(2).finish(CrackOutcome::TimedOut(RunStats::default())),
+            CrackOutcome::TimedOut(_)
+        ));
+    }
+
+    #[test]
+    fn a_search_builds_its_decoders_once() {
+        // "the quick brown fox jumps over the lazy dog" in ROT13, then base64, then hexadecimal
+        let input = "5a3356794947526f646e42344947396c596d706849484e69617942336148706a5a69426961584a6c4947643163694235626d31734948466964413d3d";
+        let checker = get_athena_checker();
+        for strategy in STRATEGIES {
+            let built = || DECODERS_BUILT.with(|built| built.get());
+            let before = built();
+            let outcome = search_with_strategy(
+                &clean_input(input),
+                &checker,
+                strategy,
+                SearchLimits::default(),
+                &SearchStats::default(),
+            );
+            let found = outcome.found().expect("the three layers are cracked");
+            assert_eq!(found.path.len(), 3, "{strategy:?}");
+            assert!(found.stats.nodes_expanded > 1, "{strategy:?}");
+            assert_eq!(built() - before, 1, "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn expanding_a_node_costs_little_besides_its_decoders() {
+        let decoders = Decoders {
+            components: Vec::new(),
+        };
+        let node = SearchNode::root("aGVsbG8gdGhlcmU=");
+        let checker = get_athena_checker();
+        let stats = SearchStats::default();
+        let built = || DECODERS_BUILT.with(|built| built.get());
+        let before = built();
+        for _ in 0..10_000 {
+            let expanded = expand(&node, &decoders, &checker, &stats, &mut Plaintexts::new(1));
+            assert!(matches!(expanded, ControlFlow::Continue(results) if results.is_empty()));
+        }
+        // Nodes are expanded with the search's decoders, rather than each building every decoder again
+        assert_eq!(built(), before);
+        let stats = stats.run_stats();
+        assert_eq!(stats.nodes_expanded, 10_000);
+        assert_eq!(stats.decoder_invocations, 0);
+    }
 }
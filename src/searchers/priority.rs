@@ -0,0 +1,127 @@
+//! Decides which decoders are worth running first, from what each says about itself.
+//! A decoder is worth more the more popular it is and the likelier it is to succeed,
+//! and worth less the longer it takes, especially when it takes long to fail,
+//! as most of the time a decoder fails.
+
+use crate::decoders::interface::{Crack, DEFAULT_EXPECTED_RUNTIME, DEFAULT_EXPECTED_SUCCESS};
+
+/// The fewest seconds a decoder is taken to run for, so one which claims to take no time
+/// isn't infinitely worth running
+const MIN_RUNTIME: f32 = 0.0001;
+
+/// What a decoder says about how likely it is to work, and how long it takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecoderCosts {
+    /// See `Decoder.popularity`
+    pub popularity: f32,
+    /// See `Decoder.expected_success`
+    pub expected_success: f32,
+    /// See `Decoder.expected_runtime`
+    pub expected_runtime: f32,
+    /// See `Decoder.failure_runtime`
+    pub failure_runtime: f32,
+}
+
+impl DecoderCosts {
+    /// What the decoder says about itself
+    pub fn of(decoder: &(dyn Crack + Send + Sync)) -> Self {
+        DecoderCosts {
+            popularity: decoder.get_popularity(),
+            expected_success: decoder.get_expected_success(),
+            expected_runtime: decoder.get_expected_runtime(),
+            failure_runtime: decoder.get_failure_runtime(),
+        }
+    }
+
+    /// How many seconds one run is expected to take, whether it succeeds or fails
+    fn expected_cost(&self) -> f32 {
+        let success = self.expected_success.clamp(0.0, 1.0);
+        let cost = success * self.expected_runtime + (1.0 - success) * self.failure_runtime;
+        cost.max(MIN_RUNTIME)
+    }
+
+    /// How worth running the decoder is, the highest first.
+    /// It's `popularity * expected_success / expected_runtime`, where the runtime
+    /// counts the time taken to fail as often as the decoder is expected to.
+    pub fn priority(&self) -> f64 {
+        f64::from(self.popularity * self.expected_success.clamp(0.0, 1.0))
+            / f64::from(self.expected_cost())
+    }
+
+    /// The priority as a share of a typical decoder's, one as popular as can be
+    /// which says nothing else about itself, from 0 to 1.
+    /// A decoder which only sets its popularity gets its popularity.
+    pub fn relative_priority(&self) -> f32 {
+        let typical = DecoderCosts {
+            popularity: 1.0,
+            expected_success: DEFAULT_EXPECTED_SUCCESS,
+            expected_runtime: DEFAULT_EXPECTED_RUNTIME,
+            failure_runtime: DEFAULT_EXPECTED_RUNTIME,
+        };
+        (self.priority() / typical.priority()).min(1.0) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecoderCosts;
+    use crate::decoders::interface::{DEFAULT_EXPECTED_RUNTIME, DEFAULT_EXPECTED_SUCCESS};
+
+    fn costs(popularity: f32, expected_success: f32, runtime: f32, failure: f32) -> DecoderCosts {
+        DecoderCosts {
+            popularity,
+            expected_success,
+            expected_runtime: runtime,
+            failure_runtime: failure,
+        }
+    }
+
+    #[test]
+    fn decoders_are_ordered_by_priority() {
+        let popular_and_fast = costs(1.0, 0.5, 0.01, 0.01);
+        let less_popular = costs(0.5, 0.5, 0.01, 0.01);
+        let rarely_works = costs(1.0, 0.05, 0.01, 0.01);
+        let slow = costs(1.0, 0.5, 0.5, 0.5);
+        let slow_to_fail = costs(1.0, 0.5, 0.01, 1.0);
+
+        let mut decoders = [
+            ("slow to fail", slow_to_fail),
+            ("rarely works", rarely_works),
+            ("slow", slow),
+            ("less popular", less_popular),
+            ("popular and fast", popular_and_fast),
+        ];
+        decoders.sort_by(|(_, a), (_, b)| b.priority().total_cmp(&a.priority()));
+        let order: Vec<&str> = decoders.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            order,
+            [
+                "popular and fast",
+                "less popular",
+                "rarely works",
+                "slow",
+                "slow to fail"
+            ]
+        );
+    }
+
+    #[test]
+    fn taking_long_to_fail_counts_against_a_decoder() {
+        let fails_fast = costs(0.8, 0.2, 0.1, 0.01);
+        let fails_slowly = costs(0.8, 0.2, 0.1, 0.2);
+        assert!(fails_fast.priority() > fails_slowly.priority());
+    }
+
+    #[test]
+    fn typical_decoders_keep_their_popularity() {
+        let typical = costs(
+            0.3,
+            DEFAULT_EXPECTED_SUCCESS,
+            DEFAULT_EXPECTED_RUNTIME,
+            DEFAULT_EXPECTED_RUNTIME,
+        );
+        assert!((typical.relative_priority() - 0.3).abs() < 1e-6);
+        assert!(costs(1.0, 0.5, 1.0, 1.0).relative_priority() < 0.02);
+        assert_eq!(costs(1.0, 1.0, 0.0, 0.0).relative_priority(), 1.0);
+    }
+}
@@ -1,17 +1,216 @@
-///! This is the struct used to design what a search node looks like.
-///! At each level, we have a node with some text, T.
-///! And then the edges of that node are the decryption modules.
-
-/*struct Nodes<V> {
-    /// When we expand the node, we generate children node
-    /// This is an vector of children.
-    children: Vec<Nodes<V>>,
-    /// Value is the text we are using
-    value: V
-    /// Edges so far enables us to know the decryption route
-    /// Because decryptions are edges, we can write the route like:
-    /// vec!["Base64", "Base32", "Rot13"] and so on indicating it
-    /// started from base64, then base32, and finally rot13.
-    edges_so_far: Vec<&str>
-}
-*/
\ No newline at end of file
+//! This is the struct used to design what a search node looks like.
+//! At each level, we have a node with some text, T.
+//! And then the edges of that node are the decryption modules.
+
+use std::sync::Arc;
+
+use crate::decoders::crack_results::CrackResult;
+use crate::{CrackSuccess, RunStats};
+
+/// Text the search has found, and a link to the node it was decoded from.
+/// Nodes link to their parents rather than carrying the whole path, so a wide search
+/// doesn't copy every path for every candidate. The path is only built when it is needed,
+/// once the plaintext is found.
+/// The decoding a node links to doesn't keep its candidates or the text it decoded,
+/// as the nodes have them, so no text is stored twice.
+#[derive(Debug)]
+pub struct SearchNode {
+    /// The text to decode
+    pub text: Arc<str>,
+    /// When the decoder produced binary rather than text, these are the bytes,
+    /// and `text` is a lossy copy of them
+    pub bytes: Option<Vec<u8>>,
+    /// The node which was decoded to get this one, and the decoding.
+    /// Every candidate of a decoder shares its result. None for the input.
+    parent: Option<(Arc<SearchNode>, Arc<CrackResult>)>,
+    /// Whether the checker's verdict on the decoding is about this text.
+    /// Decoders put the candidate the checker liked best first, and it's that one.
+    checked: bool,
+    /// How many decoders it took to get here
+    depth: usize,
+}
+
+impl SearchNode {
+    /// The node the search starts from, the input
+    pub fn root(input: &str) -> Self {
+        SearchNode {
+            text: Arc::from(input),
+            bytes: None,
+            parent: None,
+            checked: false,
+            depth: 0,
+        }
+    }
+
+    /// How many decoders it took to get here
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The name of the decoder which produced this text, None for the input
+    pub fn last_decoder(&self) -> Option<&'static str> {
+        self.parent.as_ref().map(|(_, result)| result.decoder)
+    }
+
+    /// How sure the checker was that this text is plaintext, even though it wasn't identified.
+    /// 0 for the input, and for the candidates of a decoder other than the one the checker liked best.
+    pub fn checker_confidence(&self) -> f32 {
+        match &self.parent {
+            Some((_, result)) if self.checked => result.checker_confidence,
+            _ => 0.0,
+        }
+    }
+
+    /// How worth running the decoder which produced this text was, 1 for the input
+    pub fn decoder_priority(&self) -> f32 {
+        self.parent
+            .as_ref()
+            .map_or(1.0, |(_, result)| result.priority)
+    }
+
+    /// One child per candidate the decoder produced from this node.
+    /// Binary carries on as bytes, with a lossy copy as its text.
+    /// The candidates are moved into the children rather than copied.
+    pub fn children(self: &Arc<Self>, mut result: CrackResult) -> Vec<SearchNode> {
+        let texts = result.unencrypted_text.take().unwrap_or_default();
+        let binaries = result.unencrypted_bytes.take().unwrap_or_default();
+        // It's this node's text, which `path` puts back
+        result.encrypted_text = String::new();
+        let result = Arc::new(result);
+        let child = |(index, (text, bytes)): (usize, (Arc<str>, Option<Vec<u8>>))| SearchNode {
+            text,
+            bytes,
+            parent: Some((Arc::clone(self), Arc::clone(&result))),
+            checked: index == 0,
+            depth: self.depth + 1,
+        };
+        texts
+            .into_iter()
+            .map(|text| (Arc::from(text), None))
+            .chain(
+                binaries
+                    .into_iter()
+                    .map(|bytes| (Arc::from(String::from_utf8_lossy(&bytes)), Some(bytes))),
+            )
+            .enumerate()
+            .map(child)
+            .collect()
+    }
+
+    /// Every decoding from the input to this node, in order.
+    /// Each only has the candidate which was followed, so it's clear which
+    /// of Caesar's shifts the search carried on with.
+    pub fn path(&self) -> Vec<CrackResult> {
+        let mut path = Vec::with_capacity(self.depth);
+        let mut node = self;
+        while let Some((parent, result)) = &node.parent {
+            let mut result = followed(result, node.text.to_string(), node.bytes.clone());
+            result.encrypted_text = parent.text.to_string();
+            path.push(result);
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// The answer, when decoding this node gave `result`, which the checker identified,
+    /// after the search did what `stats` says
+    pub fn identified(&self, result: CrackResult, stats: RunStats) -> CrackSuccess {
+        // When the answer is a file rather than text, keep its bytes, with a lossy copy as the text
+        let (plaintext, bytes) = match result.identified_text() {
+            Some(text) => (text.clone(), None),
+            None => {
+                let bytes = result
+                    .unencrypted_bytes
+                    .as_ref()
+                    .and_then(|bytes| bytes.first())
+                    .cloned()
+                    .unwrap_or_default();
+                (String::from_utf8_lossy(&bytes).to_string(), Some(bytes))
+            }
+        };
+        let mut path = self.path();
+        path.push(followed(&result, plaintext.clone(), bytes.clone()));
+        CrackSuccess {
+            plaintext,
+            bytes,
+            path,
+            stats,
+        }
+    }
+}
+
+/// The decoding, with only the candidate the search followed
+fn followed(result: &CrackResult, text: String, bytes: Option<Vec<u8>>) -> CrackResult {
+    let mut result = result.clone();
+    match bytes {
+        Some(bytes) => {
+            result.unencrypted_text = None;
+            result.unencrypted_bytes = Some(vec![bytes]);
+        }
+        None => {
+            result.unencrypted_text = Some(vec![text]);
+            result.unencrypted_bytes = None;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchNode;
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::Decoder;
+    use crate::RunStats;
+    use std::sync::Arc;
+
+    fn decoded(input: &str, candidates: &[&str]) -> CrackResult {
+        CrackResult::new(&Decoder::default())
+            .with_encrypted_text(input.to_string())
+            .with_candidates(candidates.iter().map(|text| text.to_string()).collect())
+    }
+
+    #[test]
+    fn each_candidate_is_a_child() {
+        let root = Arc::new(SearchNode::root("input"));
+        let children = root.children(decoded("input", &["first", "second"]));
+        let texts: Vec<&str> = children.iter().map(|child| &*child.text).collect();
+        assert_eq!(texts, ["first", "second"]);
+        assert!(children.iter().all(|child| child.depth() == 1));
+    }
+
+    #[test]
+    fn path_records_the_candidate_followed() {
+        let root = Arc::new(SearchNode::root("input"));
+        let mut children = root.children(decoded("input", &["first", "second"]));
+        let second = Arc::new(children.remove(1));
+        let mut result = decoded("second", &["plaintext", "other"]);
+        result.success = true;
+        let success = second.identified(result, RunStats::default());
+
+        assert_eq!(success.plaintext, "plaintext");
+        assert_eq!(success.path.len(), 2);
+        assert_eq!(success.path[0].encrypted_text, "input");
+        assert_eq!(
+            success.path[0].unencrypted_text,
+            Some(vec!["second".to_string()])
+        );
+        assert_eq!(success.path[1].encrypted_text, "second");
+        assert_eq!(
+            success.path[1].unencrypted_text,
+            Some(vec!["plaintext".to_string()])
+        );
+    }
+
+    #[test]
+    fn binary_candidates_keep_their_bytes() {
+        let root = Arc::new(SearchNode::root("input"));
+        let result = CrackResult::new(&Decoder::default())
+            .with_encrypted_text("input".to_string())
+            .with_bytes_candidate(vec![0xff, b'h', b'i']);
+        let child = Arc::new(root.children(result).remove(0));
+        assert_eq!(child.bytes, Some(vec![0xff, b'h', b'i']));
+        assert_eq!(&*child.text, "\u{fffd}hi");
+        assert_eq!(child.path()[0].unencrypted_text, None);
+    }
+}
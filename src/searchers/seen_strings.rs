@@ -0,0 +1,227 @@
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use super::check_if_string_cant_be_decoded;
+use super::search_node::SearchNode;
+use log::warn;
+
+/// The most candidates a search remembers by default.
+/// Each is remembered by a 64 bit hash, so this is roughly 40MB.
+pub const DEFAULT_SEEN_CAPACITY: usize = 2_000_000;
+
+/// Hashes of every candidate a search has queued, so no text is decoded twice.
+/// Reciprocal decoders like Reverse and ROT13, and different paths which end in
+/// the same text, would otherwise send the search round in circles.
+///
+/// Once it holds `capacity` hashes it stops: no candidate is new after that,
+/// so the search decodes what it already queued and then gives up,
+/// rather than growing without bound on massive runs. It ends `SeenLimited` rather than `Exhausted`,
+/// as the plaintext may have been among what it didn't queue.
+pub struct SeenStrings {
+    /// The hashes of the candidates we have seen
+    hashes: HashSet<u64>,
+    /// The most hashes we keep
+    capacity: usize,
+    /// Whether we filled up, so we only warn about it once
+    full: bool,
+}
+
+impl SeenStrings {
+    /// Remembers up to `capacity` candidates, starting with the input,
+    /// so nothing decodes back to the input and searches it again
+    pub fn new(input: &str, capacity: usize) -> Self {
+        let mut seen = SeenStrings {
+            hashes: HashSet::new(),
+            capacity,
+            full: false,
+        };
+        seen.insert(&SearchNode::root(input));
+        seen
+    }
+
+    /// Whether the candidate is worth queueing, remembering it if it is.
+    /// Candidates we've seen, ones too short to decode, and any once we're full aren't.
+    pub fn insert(&mut self, candidate: &SearchNode) -> bool {
+        let Some(key) = key(candidate) else {
+            return false;
+        };
+        if self.hashes.contains(&key) {
+            return false;
+        }
+        if self.hashes.len() >= self.capacity {
+            warn_full(&mut self.full, self.capacity);
+            return false;
+        }
+        self.hashes.insert(key)
+    }
+
+    /// Whether it filled up, so candidates were left out which weren't seen before
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+}
+
+/// Like `SeenStrings`, but it remembers how deep each candidate was found, for depth first search.
+/// Depth first search can find text down a long path before it finds it down a short one,
+/// and the short one leaves more depth to decode it with, so the text is worth queueing again.
+pub struct SeenDepths {
+    /// The hashes of the candidates we have seen, and the least deep each was found
+    depths: HashMap<u64, usize>,
+    /// The most hashes we keep
+    capacity: usize,
+    /// Whether we filled up, so we only warn about it once
+    full: bool,
+}
+
+impl SeenDepths {
+    /// Remembers up to `capacity` candidates, starting with the input
+    pub fn new(input: &str, capacity: usize) -> Self {
+        let mut seen = SeenDepths {
+            depths: HashMap::new(),
+            capacity,
+            full: false,
+        };
+        seen.insert(&SearchNode::root(input));
+        seen
+    }
+
+    /// Whether the candidate is worth queueing, remembering how deep it is if it is.
+    /// Like `SeenStrings::insert`, except a candidate we've seen deeper down is worth queueing.
+    pub fn insert(&mut self, candidate: &SearchNode) -> bool {
+        let Some(key) = key(candidate) else {
+            return false;
+        };
+        let full = self.depths.len() >= self.capacity;
+        match self.depths.entry(key) {
+            Entry::Occupied(mut seen) if candidate.depth() < *seen.get() => {
+                seen.insert(candidate.depth());
+                true
+            }
+            Entry::Occupied(_) => false,
+            Entry::Vacant(_) if full => {
+                warn_full(&mut self.full, self.capacity);
+                false
+            }
+            Entry::Vacant(unseen) => {
+                unseen.insert(candidate.depth());
+                true
+            }
+        }
+    }
+
+    /// Whether it filled up, like `SeenStrings::is_full`
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Whether we've seen the candidate, at any depth
+    pub fn contains(&self, candidate: &SearchNode) -> bool {
+        key(candidate).is_some_and(|key| self.depths.contains_key(&key))
+    }
+}
+
+/// The key we remember the candidate by, or None if it's too short to be worth decoding
+fn key(candidate: &SearchNode) -> Option<u64> {
+    let too_short = match &candidate.bytes {
+        Some(bytes) => bytes.len() <= 2,
+        None => check_if_string_cant_be_decoded(&candidate.text),
+    };
+    (!too_short).then(|| hash_candidate(candidate))
+}
+
+/// Warns that the search has seen as much as it remembers, the first time it has
+fn warn_full(full: &mut bool, capacity: usize) {
+    if !*full {
+        warn!("The search has seen {capacity} texts, so it won't queue any more");
+        *full = true;
+    }
+}
+
+/// The key we remember the candidate by. Binary is told apart by its bytes, as its text is a lossy copy.
+fn hash_candidate(candidate: &SearchNode) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    candidate.bytes.hash(&mut hasher);
+    candidate.text.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SeenDepths, SeenStrings, DEFAULT_SEEN_CAPACITY};
+    use crate::decoders::crack_results::CrackResult;
+    use crate::decoders::interface::Decoder;
+    use crate::searchers::search_node::SearchNode;
+    use std::sync::Arc;
+
+    fn text(text: &str) -> SearchNode {
+        SearchNode::root(text)
+    }
+
+    #[test]
+    fn input_is_seen_from_the_start() {
+        let mut seen = SeenStrings::new("aGVsbG8=", DEFAULT_SEEN_CAPACITY);
+        assert!(!seen.insert(&text("aGVsbG8=")));
+        assert!(seen.insert(&text("hello")));
+    }
+
+    #[test]
+    fn reverse_ping_pong_expands_each_string_once() {
+        let mut seen = SeenStrings::new("stressed", DEFAULT_SEEN_CAPACITY);
+        assert!(seen.insert(&text("desserts")));
+        // Reversing again only gets back where we started
+        assert!(!seen.insert(&text("stressed")));
+        assert!(!seen.insert(&text("desserts")));
+    }
+
+    #[test]
+    fn short_candidates_are_never_queued() {
+        let mut seen = SeenStrings::new("input", DEFAULT_SEEN_CAPACITY);
+        assert!(!seen.insert(&text("ab")));
+        assert!(!seen.insert(&text("")));
+        let mut bytes = text("\u{1}\u{2}");
+        bytes.bytes = Some(vec![1, 2]);
+        assert!(!seen.insert(&bytes));
+    }
+
+    #[test]
+    fn binary_is_told_apart_by_its_bytes() {
+        let mut seen = SeenStrings::new("input", DEFAULT_SEEN_CAPACITY);
+        let mut first = text("\u{fffd}bc");
+        first.bytes = Some(vec![0xff, b'b', b'c']);
+        let mut second = text("\u{fffd}bc");
+        second.bytes = Some(vec![0xfe, b'b', b'c']);
+        assert!(seen.insert(&first));
+        assert!(seen.insert(&second));
+        assert!(!seen.insert(&first));
+    }
+
+    #[test]
+    fn text_found_less_deep_is_queued_again() {
+        let mut seen = SeenDepths::new("input", DEFAULT_SEEN_CAPACITY);
+        let decoded = |parent: &Arc<SearchNode>, text: &str| {
+            let result = CrackResult::new(&Decoder::default()).with_candidate(text.to_string());
+            Arc::new(parent.children(result).remove(0))
+        };
+        let root = Arc::new(text("input"));
+        let deep = decoded(&decoded(&root, "middle"), "target");
+        let shallow = decoded(&root, "target");
+        assert!(seen.insert(&deep));
+        assert!(!seen.insert(&deep));
+        assert!(seen.insert(&shallow));
+        assert!(!seen.insert(&deep));
+        assert!(seen.contains(&deep));
+        assert!(!seen.contains(&text("middle")));
+    }
+
+    #[test]
+    fn nothing_is_queued_once_full() {
+        // The input takes the first place
+        let mut seen = SeenStrings::new("input", 2);
+        assert!(seen.insert(&text("first")));
+        assert!(!seen.is_full());
+        assert!(!seen.insert(&text("second")));
+        assert!(!seen.insert(&text("first")));
+        assert!(seen.is_full());
+    }
+}
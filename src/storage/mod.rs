@@ -1,27 +1,42 @@
-use include_dir::include_dir;
-use include_dir::Dir;
+use crate::checkers::word_set::WordSet;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::collections::HashSet;
-
-/// Tells Rust to load the dictionaries into the binary
-/// at compile time. Which means that we do not waste
-/// time loading them at runtime.
-pub static DICTIONARIES: Lazy<HashMap<&str, HashSet<&str>>> = Lazy::new(|| {
-    /// The directory where our dictionaries are stored.
-    static DICTIONARIES_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/storage/dictionaries");
-    let mut entries = HashMap::new();
 
-    for entry in DICTIONARIES_DIR.files() {
-        let content = entry.contents_utf8().expect("The file you moved into the dictionaries folder is not UTF-8. The storage module only works on UTF-8 files.");
-        let hash_set: HashSet<&str> = content.split_ascii_whitespace().collect();
+/// English quadgram statistics for scoring text
+pub mod quadgrams;
 
-        let filename = entry.path().to_str().expect(
-            "Cannot turn filename of the file you moved into the Dictionaries folder into a string",
-        );
+/// The English dictionaries, which build.rs compiled into an fst set.
+/// It's in the binary, so loading it is just using the bytes.
+static ENGLISH_FST: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/en.fst"));
 
-        entries.insert(filename, hash_set);
-    }
+/// The words of the English dictionaries, every file in `dictionaries/`.
+pub static DICTIONARY: Lazy<WordSet> = Lazy::new(|| WordSet::from_fst(ENGLISH_FST));
+
+/// The most common words of the other languages we can check for, keyed by language code.
+/// Each language is behind its own feature, so you only pay for the ones you want.
+pub static LANGUAGE_DICTIONARIES: Lazy<HashMap<&str, WordSet>> = Lazy::new(|| {
+    #[allow(unused_mut)]
+    let mut entries = HashMap::new();
+    #[cfg(feature = "lang-de")]
+    entries.insert(
+        "de",
+        WordSet::from_fst(include_bytes!(concat!(env!("OUT_DIR"), "/de.fst"))),
+    );
+    #[cfg(feature = "lang-fr")]
+    entries.insert(
+        "fr",
+        WordSet::from_fst(include_bytes!(concat!(env!("OUT_DIR"), "/fr.fst"))),
+    );
+    #[cfg(feature = "lang-es")]
+    entries.insert(
+        "es",
+        WordSet::from_fst(include_bytes!(concat!(env!("OUT_DIR"), "/es.fst"))),
+    );
+    #[cfg(feature = "lang-it")]
+    entries.insert(
+        "it",
+        WordSet::from_fst(include_bytes!(concat!(env!("OUT_DIR"), "/it.fst"))),
+    );
     entries
 });
 
@@ -29,18 +44,77 @@ pub static DICTIONARIES: Lazy<HashMap<&str, HashSet<&str>>> = Lazy::new(|| {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
+    use std::time::{Duration, Instant};
+
+    /// The English dictionaries as they're written
+    const DICTIONARIES: [&str; 2] = [
+        include_str!("dictionaries/words.txt"),
+        include_str!("dictionaries/clean.py"),
+    ];
 
     #[test]
-    fn test_dictionary_loads_words_txt() {
-        assert!(DICTIONARIES.contains_key("words.txt"))
+    fn test_dictionary_contains_hello() {
+        assert!(DICTIONARY.contains("hello"))
     }
+
+    #[cfg(feature = "lang-de")]
     #[test]
-    fn test_dictionary_loads_words_txt_contains_hello() {
-        assert!(DICTIONARIES.get("words.txt").unwrap().contains("hello"))
+    fn language_dictionaries_keep_accented_words() {
+        assert!(LANGUAGE_DICTIONARIES.get("de").unwrap().contains("für"));
     }
 
     #[test]
     fn test_dictionary_does_not_contain_single_letter_words() {
-        assert!(!DICTIONARIES.get("words.txt").unwrap().contains("a"))
+        assert!(!DICTIONARY.contains("a"))
+    }
+
+    #[test]
+    fn dictionary_agrees_with_a_hash_set_of_its_words() {
+        let hashed: HashSet<&str> = DICTIONARIES
+            .iter()
+            .flat_map(|dictionary| dictionary.split_ascii_whitespace())
+            .collect();
+        assert_eq!(DICTIONARY.len(), hashed.len());
+
+        // 1,000 words from all through the dictionary, and 1,000 made from them which mostly aren't words
+        let mut words: Vec<&str> = hashed.iter().copied().collect();
+        words.sort_unstable();
+        let sample: Vec<&str> = words
+            .iter()
+            .step_by(words.len() / 1000)
+            .take(1000)
+            .copied()
+            .collect();
+        assert_eq!(sample.len(), 1000);
+        for word in &sample {
+            assert!(DICTIONARY.contains(word), "{word}");
+        }
+        let changed = sample
+            .iter()
+            .enumerate()
+            .map(|(index, word)| match index % 4 {
+                0 => format!("{word}q"),
+                1 => word.chars().rev().collect(),
+                2 => word.to_uppercase(),
+                _ => word.chars().skip(1).collect(),
+            });
+        for text in changed {
+            assert_eq!(
+                DICTIONARY.contains(&text),
+                hashed.contains(text.as_str()),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn loading_the_dictionary_only_uses_its_bytes() {
+        let started = Instant::now();
+        let dictionary = WordSet::from_fst(ENGLISH_FST);
+        assert!(started.elapsed() < Duration::from_millis(10));
+        assert!(dictionary.contains("hello"));
+        // Shared prefixes and suffixes make it far smaller than the words as text
+        assert!(ENGLISH_FST.len() < DICTIONARIES[0].len() / 2);
     }
 }
@@ -0,0 +1,77 @@
+//! English quadgram statistics, used to score how English a piece of text is.
+//! The counts in english_quadgrams.txt were taken from a Project Gutenberg etext and the prose of the Rust books.
+
+use once_cell::sync::Lazy;
+
+/// Every possible quadgram of A-Z
+const QUADGRAM_COUNT: usize = 26 * 26 * 26 * 26;
+
+/// The log10 probability of every quadgram, indexed by `quadgram_index`.
+/// Quadgrams we never saw get a small floor probability instead of negative infinity.
+pub static ENGLISH_QUADGRAMS: Lazy<Vec<f64>> = Lazy::new(|| {
+    let counts: Vec<(usize, f64)> = include_str!("english_quadgrams.txt")
+        .lines()
+        .filter_map(|line| {
+            let (quadgram, count) = line.split_once(' ')?;
+            let letters: Vec<u8> = quadgram.bytes().map(|byte| byte - b'A').collect();
+            Some((quadgram_index(&letters), count.trim().parse::<f64>().ok()?))
+        })
+        .collect();
+    let total: f64 = counts.iter().map(|(_, count)| count).sum();
+
+    let mut log_probabilities = vec![(0.01 / total).log10(); QUADGRAM_COUNT];
+    for (index, count) in counts {
+        log_probabilities[index] = (count / total).log10();
+    }
+    log_probabilities
+});
+
+/// Turns four letters, each 0 to 25, into an index into `ENGLISH_QUADGRAMS`
+fn quadgram_index(letters: &[u8]) -> usize {
+    letters
+        .iter()
+        .fold(0, |index, &letter| index * 26 + usize::from(letter))
+}
+
+/// Sums the log10 probability of every quadgram in the letters, which are 0 to 25.
+/// Higher is more English. Returns 0 if there are fewer than 4 letters.
+pub fn quadgram_score(letters: &[u8]) -> f64 {
+    let table = &*ENGLISH_QUADGRAMS;
+    letters
+        .windows(4)
+        .map(|window| table[quadgram_index(window)])
+        .sum()
+}
+
+/// The average quadgram score of the letters in the text, ignoring everything else.
+/// Returns None if there are fewer than 4 letters.
+pub fn average_quadgram_score(text: &str) -> Option<f64> {
+    let mut letters = Vec::with_capacity(text.len());
+    letters.extend(
+        text.bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|byte| byte.to_ascii_uppercase() - b'A'),
+    );
+    if letters.len() < 4 {
+        return None;
+    }
+    Some(quadgram_score(&letters) / (letters.len() - 3) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadgrams_score_english_higher_than_gibberish() {
+        let english =
+            average_quadgram_score("the quick brown fox jumps over the lazy dog").unwrap();
+        let gibberish = average_quadgram_score("qzx vbnm kjhg wpqz xcvb nmlk jhgf dsaq").unwrap();
+        assert!(english > gibberish);
+    }
+
+    #[test]
+    fn quadgrams_need_four_letters() {
+        assert!(average_quadgram_score("abc").is_none());
+    }
+}
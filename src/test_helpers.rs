@@ -0,0 +1,25 @@
+//! Fixtures shared by the unit tests of more than one module
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// A writer the test can read back
+#[derive(Clone, Default)]
+pub(crate) struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedWriter {
+    /// Everything written so far
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
@@ -0,0 +1,26 @@
+//! `std::time::Instant` panics on `wasm32-unknown-unknown`, as there's no clock to read,
+//! so this reads the browser's with `Date.now()`. It only has what Ares uses of `Instant`.
+
+use std::time::Duration;
+
+/// A moment on the browser's clock, in milliseconds since the epoch
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Instant(f64);
+
+impl Instant {
+    /// Now
+    pub fn now() -> Instant {
+        Instant(js_sys::Date::now())
+    }
+
+    /// How long after `earlier` this is, or zero if the clock went back in between,
+    /// as `Date.now()` isn't monotonic
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_secs_f64((self.0 - earlier.0).max(0.0) / 1000.0)
+    }
+
+    /// How long ago this was
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+}
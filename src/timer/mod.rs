@@ -1,42 +1,125 @@
-use crossbeam::channel::{bounded, Receiver};
-use std::sync::atomic::Ordering::Relaxed;
-use std::{
-    sync::atomic::AtomicBool,
-    thread::{self, sleep},
-    time::Duration,
-};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(target_arch = "wasm32")]
+pub use browser_clock::Instant;
+/// The monotonic clock. wasm32 has no clock of its own, so there it's the browser's.
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+/// The browser's clock, for wasm32
+#[cfg(target_arch = "wasm32")]
+mod browser_clock;
 
 use crate::cli_pretty_printing::countdown_until_program_ends;
 
-/// Indicate whether timer is paused
-static PAUSED: AtomicBool = AtomicBool::new(false);
-
-/// Start the timer with duration in seconds
-pub fn start(duration: u32) -> Receiver<()> {
-    let (sender, recv) = bounded(1);
-    thread::spawn(move || {
-        let mut time_spent = 0;
-
-        while time_spent < duration {
-            if !PAUSED.load(Relaxed) {
-                sleep(Duration::from_secs(1));
-                time_spent += 1;
-                // Some pretty printing support
-                countdown_until_program_ends(time_spent, duration);
-            }
+/// When the timer was paused, if it is paused
+static PAUSED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+/// How long the timer has been paused for, over the whole program, ended pauses only
+static PAUSED_FOR: Mutex<Duration> = Mutex::new(Duration::ZERO);
+
+/// Measures how long a search has run on the monotonic clock,
+/// leaving out the time the human checker spends waiting for an answer
+pub struct Timer {
+    /// When the search started
+    started: Instant,
+    /// How long the timer was paused for before the search started
+    paused_before: Duration,
+    /// How long the search may run for
+    timeout: Duration,
+    /// How many whole seconds we've told the user have passed
+    seconds_reported: u64,
+}
+
+impl Timer {
+    /// Start the timer, which expires after `timeout`
+    pub fn start(timeout: Duration) -> Timer {
+        Timer {
+            started: Instant::now(),
+            paused_before: paused_for(),
+            timeout,
+            seconds_reported: 0,
+        }
+    }
+
+    /// How long the search has run for, not counting pauses
+    pub fn elapsed(&self) -> Duration {
+        let paused = paused_for().saturating_sub(self.paused_before);
+        self.started.elapsed().saturating_sub(paused)
+    }
+
+    /// Whether the search has run out of time.
+    /// Some pretty printing support, every second it counts down to the end.
+    pub fn expired(&mut self) -> bool {
+        let elapsed = self.elapsed();
+        let seconds = elapsed.as_secs().min(self.timeout.as_secs());
+        while self.seconds_reported < seconds {
+            self.seconds_reported += 1;
+            countdown_until_program_ends(
+                self.seconds_reported as u32,
+                self.timeout.as_secs() as u32,
+            );
         }
-        sender.send(()).expect("Timer should send succesfully");
-    });
+        elapsed >= self.timeout
+    }
+}
 
-    recv
+/// How long the timer has been paused for over the whole program, including a pause still going on
+fn paused_for() -> Duration {
+    let ended = *PAUSED_FOR.lock().expect("the timer is never poisoned");
+    let ongoing = PAUSED_AT
+        .lock()
+        .expect("the timer is never poisoned")
+        .map_or(Duration::ZERO, |paused_at| paused_at.elapsed());
+    ended + ongoing
 }
 
 /// Pause timer
 pub fn pause() {
-    PAUSED.store(true, Relaxed);
+    let mut paused_at = PAUSED_AT.lock().expect("the timer is never poisoned");
+    if paused_at.is_none() {
+        *paused_at = Some(Instant::now());
+    }
 }
 
 /// Resume timer
 pub fn resume() {
-    PAUSED.store(false, Relaxed);
+    let paused_at = PAUSED_AT
+        .lock()
+        .expect("the timer is never poisoned")
+        .take();
+    if let Some(paused_at) = paused_at {
+        *PAUSED_FOR.lock().expect("the timer is never poisoned") += paused_at.elapsed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pause, resume, Timer};
+    use std::sync::Mutex;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    /// Pausing pauses every timer, so these tests take turns
+    static TURN: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn expires_after_the_timeout() {
+        let _turn = TURN.lock().unwrap();
+        let mut timer = Timer::start(Duration::from_millis(50));
+        assert!(!timer.expired());
+        sleep(Duration::from_millis(60));
+        assert!(timer.expired());
+        assert!(Timer::start(Duration::ZERO).expired());
+    }
+
+    #[test]
+    fn pauses_are_not_counted() {
+        let _turn = TURN.lock().unwrap();
+        let timer = Timer::start(Duration::from_secs(5));
+        pause();
+        sleep(Duration::from_millis(100));
+        resume();
+        assert!(timer.elapsed() < Duration::from_millis(100));
+    }
 }
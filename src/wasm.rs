@@ -0,0 +1,160 @@
+//! `crack` for web pages, which call it from JavaScript once the library is built for wasm32
+//! with the `wasm` feature. The options and the outcome are JSON, so they cross to JavaScript as strings.
+//! ```js
+//! import init, { crack } from "./pkg/ares.js";
+//! await init();
+//! const outcome = JSON.parse(crack("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", '{"timeout_ms": 2000}'));
+//! console.log(outcome.plaintext);
+//! ```
+//! There are no files in the browser, so a wordlist is given as `words` rather than a path,
+//! and there are no threads, so the search checks its deadline, `timeout_ms`, as it goes.
+
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::checkers::dictionary_checker::Lang;
+use crate::checkers::wordlist_checker::Wordlist;
+use crate::config::{Config, SearchStrategy};
+use crate::perform_cracking;
+
+/// What `crack` can be told, as JSON. Every key is optional, like in the CLI's config file.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrackOptions {
+    /// How many milliseconds to crack for, 5 seconds if it isn't given
+    pub timeout_ms: Option<u64>,
+    /// See `Config::max_depth`
+    pub max_depth: Option<u32>,
+    /// See `Config::results_wanted`
+    pub results_wanted: Option<usize>,
+    /// See `Config::max_frontier`
+    pub max_frontier: Option<usize>,
+    /// How the search picks what to decode next, like "best-first" or "depth-first"
+    pub strategy: Option<SearchStrategy>,
+    /// Only use these decoders, by name or tag, see `Config::include_decoders`
+    pub only: Vec<String>,
+    /// Never use these decoders, by name or tag, see `Config::exclude_decoders`
+    pub exclude: Vec<String>,
+    /// The codes of the languages to look for words of, like `["en", "de"]`
+    pub languages: Option<Vec<Lang>>,
+    /// See `Config::regex`
+    pub regex: Option<String>,
+    /// Words the plaintext may be, in place of the CLI's wordlist file
+    pub words: Option<Vec<String>>,
+    /// See `Config::confidence_threshold`
+    pub confidence_threshold: Option<f32>,
+}
+
+impl CrackOptions {
+    /// Parses the options, where an empty string means the defaults
+    /// # Errors
+    /// Returns the serde_json error if it isn't JSON, a key is unknown or has the wrong kind of value.
+    pub fn parse(json: &str) -> Result<CrackOptions, serde_json::Error> {
+        if json.trim().is_empty() {
+            return Ok(CrackOptions::default());
+        }
+        serde_json::from_str(json)
+    }
+
+    /// The config these options make, from the default one
+    pub fn config(&self) -> Config {
+        let mut config = Config::default();
+        if let Some(timeout_ms) = self.timeout_ms {
+            config.timeout = Duration::from_millis(timeout_ms);
+        }
+        if let Some(max_depth) = self.max_depth {
+            config.max_depth = max_depth;
+        }
+        if let Some(results_wanted) = self.results_wanted {
+            config.results_wanted = results_wanted;
+        }
+        if let Some(max_frontier) = self.max_frontier {
+            config.max_frontier = max_frontier;
+        }
+        if let Some(strategy) = self.strategy {
+            config.search_strategy = strategy;
+        }
+        config.include_decoders = self.only.clone();
+        config.exclude_decoders = self.exclude.clone();
+        if let Some(languages) = &self.languages {
+            config.languages = languages.clone();
+        }
+        config.regex = self.regex.clone();
+        config.wordlist = self
+            .words
+            .as_ref()
+            .map(|words| Wordlist::from_words(words.iter().map(String::as_str)));
+        if let Some(confidence_threshold) = self.confidence_threshold {
+            config.confidence_threshold = confidence_threshold;
+        }
+        config
+    }
+}
+
+/// Cracks the text with the options, both given as JSON, and gives back the outcome as JSON,
+/// see `CrackOutcome::to_json`. Empty options mean the defaults.
+/// When the options can't be used it's `{"status":"error","error":"..."}`.
+/// ```rust
+/// use ares::wasm::crack;
+///
+/// // "hello there general" in base64
+/// let json = crack("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", r#"{"timeout_ms": 5000}"#);
+/// let outcome: serde_json::Value = serde_json::from_str(&json).unwrap();
+/// assert_eq!(outcome["status"], "found");
+/// assert_eq!(outcome["plaintext"], "hello there general");
+///
+/// let json = crack("aGVsbG8=", r#"{"timeout": 5}"#);
+/// let outcome: serde_json::Value = serde_json::from_str(&json).unwrap();
+/// assert_eq!(outcome["status"], "error");
+/// ```
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn crack(text: &str, options_json: &str) -> String {
+    match crack_with_options(text, options_json) {
+        Ok(json) => json,
+        Err(error) => serde_json::json!({ "status": "error", "error": error }).to_string(),
+    }
+}
+
+/// Cracks the text like `crack`, or says why the options can't be used
+fn crack_with_options(text: &str, options_json: &str) -> Result<String, String> {
+    let options = CrackOptions::parse(options_json).map_err(|error| error.to_string())?;
+    let config = options.config();
+    config
+        .check_decoder_names()
+        .map_err(|unknown| unknown.to_string())?;
+    if let Some(Err(error)) = config.regex.as_deref().map(Regex::new) {
+        return Err(error.to_string());
+    }
+    perform_cracking(text, config)
+        .to_json()
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crack, CrackOptions};
+    use crate::config::SearchStrategy;
+
+    #[test]
+    fn options_are_checked() {
+        let options =
+            CrackOptions::parse(r#"{"timeout_ms": 100, "strategy": "depth-first"}"#).unwrap();
+        assert_eq!(options.config().timeout.as_millis(), 100);
+        assert_eq!(options.config().search_strategy, SearchStrategy::DepthFirst);
+
+        for bad in [
+            "{",
+            r#"{"timeout": 5}"#,
+            r#"{"only": ["base46"]}"#,
+            r#"{"regex": "("}"#,
+        ] {
+            let outcome: serde_json::Value = serde_json::from_str(&crack("aGVsbG8=", bad)).unwrap();
+            assert_eq!(outcome["status"], "error", "{bad}");
+            assert!(outcome["error"]
+                .as_str()
+                .is_some_and(|error| !error.is_empty()));
+        }
+    }
+}
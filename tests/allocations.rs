@@ -0,0 +1,71 @@
+// Counts the allocations a search makes. It's a test file of its own, as the allocator is global.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use ares::config::Config;
+use ares::perform_cracking;
+
+/// The system allocator, counting how many allocations it makes
+struct Counting;
+
+/// How many allocations there have been
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// "the quick brown fox jumps over the lazy dog" in ROT13, then base64, then hexadecimal
+const THREE_LAYERS: &str = "5a3356794947526f646e42344947396c596d706849484e69617942336148706a5a69426961584a6c4947643163694235626d31734948466964413d3d";
+
+/// The most allocations cracking `THREE_LAYERS` may make. It made 3301 when this was set,
+/// so there's room for a few more decoders, but not for the decoders and checkers going back to
+/// copying every text they're given, whether or not they decode or identify it, which made 6996
+const MOST_ALLOCATIONS: usize = 4000;
+
+#[test]
+fn cracking_allocates_no_more_than_the_most_allowed() {
+    let config = || Config {
+        timeout: Duration::from_secs(60),
+        ..Config::default()
+    };
+    // The first crack loads the dictionaries and starts the threads, which only happens once
+    perform_cracking(THREE_LAYERS, config());
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let outcome = perform_cracking(THREE_LAYERS, config());
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let found = outcome.found().expect("the three layers are cracked");
+    assert_eq!(
+        found.plaintext,
+        "the quick brown fox jumps over the lazy dog"
+    );
+    assert!(
+        allocations <= MOST_ALLOCATIONS,
+        "cracking made {allocations} allocations"
+    );
+
+    // Each decoder on the way still has the text it decoded
+    let mut encrypted = THREE_LAYERS;
+    for step in &found.path {
+        assert_eq!(step.encrypted_text, encrypted, "{}", step.decoder);
+        encrypted = &step.unencrypted_text.as_ref().unwrap()[0];
+    }
+}
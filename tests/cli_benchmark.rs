@@ -0,0 +1,63 @@
+// Runs `ares benchmark`, which times every decoder, with one iteration so it's quick
+use ares::decoders::get_all_decoders;
+use std::process::{Command, Output};
+
+/// Runs `ares benchmark --iterations 1` with the arguments
+fn ares_benchmark(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(["benchmark", "--iterations", "1"])
+        .args(args)
+        .output()
+        .expect("the ares binary runs");
+    assert_eq!(output.status.code(), Some(0));
+    output
+}
+
+#[test]
+fn the_table_has_every_decoder() {
+    let output = ares_benchmark(&[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Name "), "{stdout}");
+    for decoder in get_all_decoders() {
+        assert!(
+            stdout
+                .lines()
+                .any(|line| line.starts_with(&format!("{}  ", decoder.get_name()))),
+            "{} is missing from\n{stdout}",
+            decoder.get_name()
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn the_json_report_times_giving_up_on_the_wrong_alphabet() {
+    let output = ares_benchmark(&["--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let decoders = json["decoders"].as_array().unwrap();
+    assert_eq!(decoders.len(), get_all_decoders().len());
+    let hexadecimal = decoders
+        .iter()
+        .find(|decoder| decoder["name"] == "Hexadecimal")
+        .unwrap();
+    // Three texts of hex it decodes, and three of random text which isn't hex
+    assert_eq!(hexadecimal["success"]["samples"], 3);
+    assert_eq!(hexadecimal["failure"]["samples"], 3);
+    assert!(hexadecimal["failure"]["mean_seconds"].as_f64().unwrap() > 0.0);
+    assert_eq!(hexadecimal["expected_runtime"], 0.01);
+}
+
+#[test]
+fn suggest_names_builder_methods() {
+    let output = ares_benchmark(&["--suggest"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // Hexadecimal gives up on text which isn't hex far quicker than the 10ms it's built with
+    assert!(
+        stdout.contains("Hexadecimal: .with_failure_runtime("),
+        "{stdout}"
+    );
+    assert!(
+        stdout.lines().all(|line| line.contains(": .with_")),
+        "{stdout}"
+    );
+}
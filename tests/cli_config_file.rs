@@ -0,0 +1,95 @@
+// Runs the ares binary with an ares.toml in the directory it's run from
+#![cfg(feature = "serde")]
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// A directory of its own for the test, with the config file if there is one.
+/// The platform's config directory is in it too, so the user's own config isn't read.
+fn directory(name: &str, config: Option<&str>) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ares_config_file_{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    if let Some(config) = config {
+        std::fs::write(dir.join("ares.toml"), config).unwrap();
+    }
+    dir
+}
+
+/// Runs ares in the directory on the text, with the extra arguments
+fn ares(dir: &PathBuf, text: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ares"))
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .arg("--disable-human-checker")
+        .args(args)
+        .arg(text)
+        .output()
+        .expect("the ares binary runs")
+}
+
+#[test]
+fn flags_win_over_the_file() {
+    let dir = directory(
+        "flags_win",
+        Some("output_format = \"json\"\nonly = [\"Morse Code\"]\ntimeout = 10\n"),
+    );
+    let base64 = "aGVsbG8gdGhlcmUgZ2VuZXJhbA==";
+    // Only morse code, from the file, can't decode base64
+    let output = ares(&dir, base64, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["plaintext"].is_null());
+
+    let output = ares(&dir, base64, &["--only", "base"]);
+    assert_eq!(output.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["plaintext"], "hello there general");
+}
+
+#[test]
+fn unknown_keys_are_warned_about() {
+    let dir = directory("unknown_keys", Some("timout = 5\n"));
+    let output = ares(&dir, "aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unknown key `timout` is ignored"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn a_bad_value_is_an_error_naming_the_key() {
+    let dir = directory("bad_value", Some("max_depth = \"deep\"\n"));
+    let output = ares(&dir, "aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("ares.toml") && stderr.contains("max_depth"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn without_a_file_the_defaults_are_used() {
+    let dir = directory("no_file", None);
+    let output = ares(&dir, "aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("hello there general"), "{stdout}");
+    // The text output for people, not JSON
+    assert!(serde_json::from_str::<serde_json::Value>(&stdout).is_err());
+}
+
+#[test]
+fn the_platform_config_directory_is_read() {
+    let dir = directory("platform", None);
+    let config = dir.join("config").join("ares");
+    std::fs::create_dir_all(&config).unwrap();
+    std::fs::write(config.join("config.toml"), "output_format = \"json\"\n").unwrap();
+    let output = ares(&dir, "aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &[]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["plaintext"], "hello there general");
+}
@@ -0,0 +1,65 @@
+// Runs the ares binary with `--only` and `--exclude`, which pick the decoders for the whole run
+#![cfg(feature = "serde")]
+use std::process::{Command, Output};
+
+/// Runs ares on the text with JSON output and the extra arguments
+fn ares(text: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(["--disable-human-checker", "-c", "10"])
+        .args(["--output-format", "json"])
+        .args(args)
+        .arg(text)
+        .output()
+        .expect("the ares binary runs")
+}
+
+/// The decoders ares used to get to the plaintext, or None if it didn't find one
+fn decoders_used(output: &Output) -> Option<Vec<String>> {
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    (json["status"] == "found").then(|| {
+        json["path"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|step| step["decoder"].as_str().unwrap().to_string())
+            .collect()
+    })
+}
+
+/// "hello there" in morse code
+const MORSE: &str = ".... . .-.. .-.. --- / - .... . .-. .";
+
+#[test]
+fn only_the_base_decoders_solve_base64_but_not_morse() {
+    let output = ares("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &["--only", "base"]);
+    assert_eq!(decoders_used(&output).unwrap(), ["Base64"]);
+
+    let output = ares(MORSE, &["--only", "base"]);
+    assert_eq!(decoders_used(&output), None);
+    assert_eq!(output.status.code(), Some(1));
+    // Which it does with every decoder
+    assert_eq!(decoders_used(&ares(MORSE, &[])).unwrap(), ["Morse Code"]);
+}
+
+#[test]
+fn excluding_base64_leaves_its_url_safe_variant() {
+    // "meet me at the old mill?? at ~noon>>" in URL safe base64. The URL safe decoder is
+    // tagged "base64" too, but "base64" is the Base64 decoder's name, so only that is excluded.
+    let output = ares(
+        "bWVldCBtZSBhdCB0aGUgb2xkIG1pbGw_PyBhdCB-bm9vbj4-",
+        &["--only", "base", "--exclude", "base64,hexadecimal"],
+    );
+    assert_eq!(decoders_used(&output).unwrap(), ["Base64 URL"]);
+}
+
+#[test]
+fn unknown_decoders_are_an_error_with_near_matches() {
+    let output = ares("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &["--only", "base,caeser"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no decoder is called or tagged \"caeser\", did you mean \"caesar\"?"),
+        "{stderr}"
+    );
+}
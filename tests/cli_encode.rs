@@ -0,0 +1,73 @@
+// Runs `ares encode`, which encodes text with a chain of decoders to make test data
+use ares::config::Config;
+use ares::perform_cracking;
+use std::process::{Command, Output};
+
+/// Runs `ares encode` with the arguments
+fn ares_encode(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ares"))
+        .arg("encode")
+        .args(args)
+        .output()
+        .expect("the ares binary runs")
+}
+
+/// What ares printed on stdout, without the newline at the end
+fn stdout(output: &Output) -> String {
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    stdout.trim_end_matches('\n').to_string()
+}
+
+#[test]
+fn a_two_step_chain_is_cracked_back() {
+    let output = ares_encode(&["--chain", "base64,hex", "hello there general"]);
+    assert_eq!(output.status.code(), Some(0));
+    let encoded = stdout(&output);
+    assert_eq!(
+        encoded,
+        "61475673624738676447686c636d55675a3256755a584a6862413d3d"
+    );
+
+    let config = Config {
+        human_checker_on: false,
+        ..Config::default()
+    };
+    let found = perform_cracking(&encoded, config).found().unwrap();
+    assert_eq!(found.plaintext, "hello there general");
+    let decoders: Vec<&str> = found.path.iter().map(|step| step.decoder).collect();
+    assert_eq!(decoders, ["Hexadecimal", "Base64"]);
+}
+
+#[test]
+fn reverse_decodes_with_the_chain() {
+    let encoded = stdout(&ares_encode(&[
+        "--chain",
+        "base64,hex,rot13",
+        "hello world",
+    ]));
+    let output = ares_encode(&["--chain", "base64,hex,rot13", "--reverse", &encoded]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "hello world");
+}
+
+#[test]
+fn unknown_names_list_the_decoders_which_can_encode() {
+    let output = ares_encode(&["--chain", "base64,rot14", "hello"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("\"rot14\""), "{stderr}");
+    assert!(stderr.contains("Base64, Base64 URL"), "{stderr}");
+    assert!(stderr.contains("Caesar Cipher"), "{stderr}");
+}
+
+#[test]
+fn decoders_which_cant_encode_are_refused() {
+    let output = ares_encode(&["--chain", "base64,xor single byte", "hello"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("XOR Single Byte can only decode, not encode"),
+        "{stderr}"
+    );
+}
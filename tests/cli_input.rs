@@ -0,0 +1,90 @@
+// Runs the ares binary with its input in a file, on stdin, or as an argument
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// "hello there general" in base64
+const HELLO: &str = "aGVsbG8gdGhlcmUgZ2VuZXJhbA==";
+
+/// Runs ares with the arguments, giving it `stdin`
+fn ares(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(["--disable-human-checker", "-c", "10"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the ares binary runs");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Writes the contents to a file in the temp directory, named after the test so tests don't clash
+fn input_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("ares_cli_input_{name}"));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Whether ares printed the plaintext of `HELLO`
+fn cracked_hello(output: &Output) -> bool {
+    output.status.success()
+        && String::from_utf8_lossy(&output.stdout).contains("hello there general")
+}
+
+#[test]
+fn text_can_be_an_argument() {
+    assert!(cracked_hello(&ares(&[HELLO], b"")));
+    assert!(cracked_hello(&ares(&["-t", HELLO], b"")));
+}
+
+#[test]
+fn text_can_be_in_a_file() {
+    let path = input_file("file", format!("{HELLO}\n").as_bytes());
+    assert!(cracked_hello(&ares(&["-f", path.to_str().unwrap()], b"")));
+}
+
+#[test]
+fn text_can_be_piped_in() {
+    assert!(cracked_hello(&ares(&[], format!("{HELLO}\n").as_bytes())));
+}
+
+#[test]
+fn a_file_with_windows_line_endings_is_cracked() {
+    let path = input_file("crlf", format!("{HELLO}\r\n").as_bytes());
+    assert!(cracked_hello(&ares(
+        &["--file", path.to_str().unwrap()],
+        b""
+    )));
+}
+
+#[test]
+fn a_file_with_a_byte_order_mark_is_cracked() {
+    let path = input_file("bom", format!("\u{FEFF}{HELLO}\n").as_bytes());
+    assert!(cracked_hello(&ares(
+        &["--file", path.to_str().unwrap()],
+        b""
+    )));
+}
+
+#[test]
+fn text_and_a_file_together_are_refused() {
+    let path = input_file("conflict", HELLO.as_bytes());
+    for text in [vec![HELLO], vec!["-t", HELLO]] {
+        let mut args = text;
+        args.extend(["-f", path.to_str().unwrap()]);
+        let output = ares(&args, b"");
+        assert_eq!(output.status.code(), Some(2));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cannot be used with"), "{stderr}");
+    }
+}
+
+#[test]
+fn a_missing_file_is_reported() {
+    let output = ares(&["-f", "tests/test_fixtures/there_is_no_such_file"], b"");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("couldn't read"), "{stderr}");
+}
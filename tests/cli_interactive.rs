@@ -0,0 +1,35 @@
+// Runs the ares binary with `--interactive` where there's no terminal, as a script would
+use std::process::{Command, Stdio};
+
+#[test]
+fn without_a_terminal_nobody_is_asked() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(["--interactive", "-c", "10", "--results-wanted", "2"])
+        .args(["-r", "^tzou .. flag$", "galf xx uozt"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the ares binary runs");
+    // Kept open until ares is done, so waiting for an answer would hang the test
+    let stdin = child.stdin.take();
+    let output = child.wait_with_output().unwrap();
+    drop(stdin);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // The best plaintext, as if it was picked
+    assert!(stdout.contains("tzou cc flag"), "{stdout}");
+    assert!(!stdout.contains("tzou xx flag"), "{stdout}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("Which is the plaintext?"), "{stderr}");
+}
+
+#[test]
+fn interactive_and_quiet_are_refused_together() {
+    let output = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(["--interactive", "--quiet", "aGVsbG8="])
+        .output()
+        .expect("the ares binary runs");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,75 @@
+// Runs the ares binary with `--output-format json`, as a script would
+#![cfg(feature = "serde")]
+use std::process::{Command, Output};
+
+/// Runs ares on the text with JSON output and the extra arguments
+fn ares_json(text: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args([
+            "--disable-human-checker",
+            "--output-format",
+            "json",
+            "-t",
+            text,
+        ])
+        .args(args)
+        .output()
+        .expect("the ares binary runs")
+}
+
+/// The one JSON object ares printed on stdout
+fn stdout_json(output: &Output) -> serde_json::Value {
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    serde_json::from_str(&stdout).unwrap()
+}
+
+#[test]
+fn a_found_plaintext_is_one_json_object() {
+    // Hexadecimal, then base64
+    let output = ares_json(
+        "NzQ2ODY1MjA2MzYxNzQyMDczNjE3NDIwNmY2ZTIwNzQ2ODY1MjA2ZDYxNzQ=",
+        &["-c", "10"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let json = stdout_json(&output);
+    assert_eq!(json["status"], "found");
+    assert_eq!(json["plaintext"], "the cat sat on the mat");
+    assert!(json["confidence"].as_f64().unwrap() > 0.0);
+    let decoders: Vec<&str> = json["path"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|step| step["decoder"].as_str().unwrap())
+        .collect();
+    assert_eq!(decoders, ["Base64", "Hexadecimal"]);
+    assert!(json["path"][0]["link"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://"));
+    assert!(json["stats"]["nodes_expanded"].as_u64().unwrap() >= 1);
+}
+
+#[test]
+fn newlines_and_non_ascii_survive_the_round_trip() {
+    // "the café is open\nsee you at noon ✓" in base64
+    let output = ares_json(
+        "dGhlIGNhZsOpIGlzIG9wZW4Kc2VlIHlvdSBhdCBub29uIOKckw==",
+        &["-c", "10", "--regex", "café"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let json = stdout_json(&output);
+    assert_eq!(json["plaintext"], "the café is open\nsee you at noon ✓");
+    assert_eq!(json["results"][0]["checker"], "Regex Checker");
+}
+
+#[test]
+fn no_plaintext_is_still_a_json_object_and_fails() {
+    // Nothing decodes to this
+    let output = ares_json("aGVsbG8=", &["-c", "1", "--regex", "^zzzz$"]);
+    assert_eq!(output.status.code(), Some(1));
+    let json = stdout_json(&output);
+    assert_eq!(json["status"], "timed_out");
+    assert!(json["plaintext"].is_null());
+    assert!(json["results"].as_array().unwrap().is_empty());
+}
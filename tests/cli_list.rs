@@ -0,0 +1,72 @@
+// Runs `ares --list-decoders` and `ares --list-checkers`, which list what Ares can use
+use ares::checkers::list_checkers;
+use ares::decoders::get_all_decoders;
+use std::process::Command;
+
+/// Runs ares with the arguments, and returns what it printed on stdout
+fn ares(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(args)
+        .output()
+        .expect("the ares binary runs");
+    assert_eq!(output.status.code(), Some(0));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// How many rows of the table are for `name`. Columns are two spaces apart,
+/// so "Base64" doesn't count the row for "Base64 URL".
+fn rows_for(table: &str, name: &str) -> usize {
+    let cell = format!("{name}  ");
+    table.lines().filter(|row| row.starts_with(&cell)).count()
+}
+
+#[test]
+fn every_decoder_is_listed_once() {
+    let table = ares(&["--list-decoders"]);
+    let decoders = get_all_decoders();
+    // And the headings
+    assert_eq!(table.lines().count(), decoders.len() + 1);
+    for decoder in decoders {
+        assert_eq!(rows_for(&table, decoder.get_name()), 1, "{table}");
+    }
+    assert!(table.contains("https://en.wikipedia.org/wiki/Base64"));
+}
+
+#[test]
+fn every_checker_is_listed_once() {
+    let table = ares(&["--list-checkers"]);
+    let checkers = list_checkers();
+    assert_eq!(table.lines().count(), checkers.len() + 1);
+    for checker in checkers {
+        assert_eq!(rows_for(&table, checker.name), 1, "{table}");
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn the_lists_are_json_arrays() {
+    let json: serde_json::Value =
+        serde_json::from_str(&ares(&["--list-decoders", "--output-format", "json"])).unwrap();
+    let listed = json.as_array().unwrap();
+    assert_eq!(listed.len(), get_all_decoders().len());
+    let base64 = listed
+        .iter()
+        .find(|decoder| decoder["name"] == "Base64")
+        .unwrap();
+    assert!(base64["tags"].as_array().unwrap().contains(&"base".into()));
+    assert!(base64["popularity"].as_f64().unwrap() > 0.0);
+    assert_eq!(base64["link"], "https://en.wikipedia.org/wiki/Base64");
+    let popularity: Vec<f64> = listed
+        .iter()
+        .map(|decoder| decoder["popularity"].as_f64().unwrap())
+        .collect();
+    assert!(popularity.windows(2).all(|pair| pair[0] >= pair[1]));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&ares(&["--list-checkers", "--output-format", "json"])).unwrap();
+    let listed = json.as_array().unwrap();
+    assert_eq!(listed.len(), list_checkers().len());
+    assert!(listed
+        .iter()
+        .all(|checker| checker["name"].is_string() && checker["tags"].is_array()));
+}
@@ -0,0 +1,115 @@
+// Runs the ares binary with `--output`, which writes the plaintext to a file
+use base64::{engine::general_purpose, Engine as _};
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// A directory of its own for the test, so the files in it are only its own.
+/// The platform's config directory is in it too, so the user's own config isn't read.
+fn directory(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ares_output_{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs ares in the directory on the text, with the extra arguments
+fn ares(dir: &PathBuf, text: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ares"))
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .args(["--disable-human-checker", "-c", "10"])
+        .args(args)
+        .arg(text)
+        .output()
+        .expect("the ares binary runs")
+}
+
+/// The signature and IHDR chunk of a 1x1 PNG, and some bytes which aren't UTF-8
+fn png() -> Vec<u8> {
+    let mut png =
+        b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0\x1f\x15\xc4\x89".to_vec();
+    png.extend(0..=255);
+    png
+}
+
+#[test]
+fn a_file_is_written_byte_for_byte() {
+    let dir = directory("png");
+    let output = ares(
+        &dir,
+        &general_purpose::STANDARD.encode(png()),
+        &["-o", "decoded"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(std::fs::read(dir.join("decoded")).unwrap(), png());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("written to"), "{stdout}");
+    assert!(stdout.contains("Base64"), "{stdout}");
+    assert!(!stdout.contains("PNG\r\n"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("looks like a PNG image, consider naming it .png"),
+        "{stderr}"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn a_chain_ending_in_gzip_is_written_byte_for_byte() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let dir = directory("gzip");
+    // gzip is only the answer when the checkers accept less than they usually do
+    std::fs::write(dir.join("ares.toml"), "confidence_threshold = 0.5\n").unwrap();
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&[0, 1, 2, 254, 255].repeat(50)).unwrap();
+    let gzip = encoder.finish().unwrap();
+    let input = data_encoding::HEXLOWER.encode(general_purpose::STANDARD.encode(&gzip).as_bytes());
+
+    let output = ares(&dir, &input, &["--output", "decoded.gz"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(std::fs::read(dir.join("decoded.gz")).unwrap(), gzip);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("├─ Hexadecimal →"), "{stdout}");
+    assert!(stdout.contains("└─ Base64 → 1f 8b 08"), "{stdout}");
+    // It's named .gz already
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("consider naming it"));
+}
+
+#[test]
+fn text_is_written_as_utf8() {
+    let dir = directory("text");
+    // "the café is open ✓" in base64
+    let output = ares(&dir, "dGhlIGNhZsOpIGlzIG9wZW4g4pyT", &["-o", "decoded.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read(dir.join("decoded.txt")).unwrap(),
+        "the café is open ✓".as_bytes()
+    );
+}
+
+#[test]
+fn an_existing_file_is_only_overwritten_with_force() {
+    let dir = directory("overwrite");
+    std::fs::write(dir.join("decoded.txt"), "keep me").unwrap();
+    let output = ares(&dir, "aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &["-o", "decoded.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("already exists, use --force to overwrite it"),
+        "{stderr}"
+    );
+    assert_eq!(std::fs::read(dir.join("decoded.txt")).unwrap(), b"keep me");
+
+    let output = ares(
+        &dir,
+        "aGVsbG8gdGhlcmUgZ2VuZXJhbA==",
+        &["-o", "decoded.txt", "--force"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read(dir.join("decoded.txt")).unwrap(),
+        b"hello there general"
+    );
+}
@@ -0,0 +1,57 @@
+// Runs the ares binary with `--quiet`, as a shell pipeline would
+use std::process::{Command, Output};
+
+/// Runs ares quietly on the text with the extra arguments
+fn ares_quiet(text: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ares"))
+        .arg("-q")
+        .args(args)
+        .arg(text)
+        .output()
+        .expect("the ares binary runs")
+}
+
+#[test]
+fn stdout_is_only_the_plaintext() {
+    let output = ares_quiet("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &["-c", "10"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"hello there general\n");
+    assert!(output.stderr.is_empty(), "{:?}", output.stderr);
+}
+
+#[test]
+fn several_plaintexts_are_one_per_line() {
+    // Reversed it's "tzou xx flag", and with Atbash "tzou cc flag"
+    let output = ares_quiet(
+        "galf xx uozt",
+        &["-c", "10", "-r", "^tzou .. flag$", "--results-wanted", "2"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"tzou cc flag\ntzou xx flag\n");
+}
+
+#[test]
+fn failing_prints_nothing_and_the_exit_code_says_why() {
+    // Nothing new turns up however deep the search goes
+    let output = ares_quiet("!!!", &["-c", "10", "-r", "^zzzz$"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+
+    // Running out of time
+    let output = ares_quiet("aGVsbG8=", &["-c", "1", "-r", "^zzzz$"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn quiet_is_another_output_format() {
+    let output = ares_quiet("aGVsbG8gdGhlcmUgZ2VuZXJhbA==", &["--output-format", "text"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .args(["--output-format", "plain", "aGVsbG8gdGhlcmUgZ2VuZXJhbA=="])
+        .output()
+        .unwrap();
+    assert_eq!(output.stdout, b"hello there general\n");
+}
@@ -0,0 +1,189 @@
+// Calls the C functions as a C program would
+#![cfg(feature = "ffi")]
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use ares::ffi::{
+    ares_crack, ares_result_error, ares_result_free, ares_result_line_number,
+    ares_result_line_plaintext, ares_result_lines_len, ares_result_path_len, ares_result_path_step,
+    ares_result_plaintext, ares_result_status, AresOptions, AresStatus,
+};
+
+/// The string Ares gave back, or None for NULL
+fn read(string: *const std::ffi::c_char) -> Option<String> {
+    (!string.is_null()).then(|| {
+        unsafe { CStr::from_ptr(string) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    })
+}
+
+#[test]
+fn cracks_two_layers() {
+    // "hello there general" in base64, then hexadecimal
+    let text = CString::new("61475673624738676447686c636d55675a3256755a584a6862413d3d").unwrap();
+    let result = unsafe { ares_crack(text.as_ptr(), ptr::null()) };
+    assert_eq!(ares_result_status(result), AresStatus::Found);
+    assert_eq!(
+        read(ares_result_plaintext(result)).as_deref(),
+        Some("hello there general")
+    );
+    assert_eq!(ares_result_path_len(result), 2);
+    assert_eq!(
+        read(ares_result_path_step(result, 0)).as_deref(),
+        Some("Hexadecimal")
+    );
+    assert_eq!(
+        read(ares_result_path_step(result, 1)).as_deref(),
+        Some("Base64")
+    );
+    assert!(ares_result_path_step(result, 2).is_null());
+    assert!(ares_result_error(result).is_null());
+    ares_result_free(result);
+}
+
+#[test]
+fn each_line_is_cracked_when_the_whole_text_cannot_be() {
+    // Base64, then a blank line, then hexadecimal and base32
+    let text = CString::new(
+        "dGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZw==\n\n\
+         6d656574206d652061742074686520757375616c20706c6163652061742074656e\n\
+         OJXXIYLUMUQHI2DFEBVWK6LTEBSXMZLSPEQHG2LOM5WGKIDXMVSWW===",
+    )
+    .unwrap();
+    let result = unsafe { ares_crack(text.as_ptr(), ptr::null()) };
+    assert_eq!(ares_result_status(result), AresStatus::Lines);
+    assert!(ares_result_plaintext(result).is_null());
+    assert_eq!(ares_result_path_len(result), 0);
+    assert_eq!(ares_result_lines_len(result), 3);
+    let lines: Vec<(usize, Option<String>)> = (0..3)
+        .map(|index| {
+            (
+                ares_result_line_number(result, index),
+                read(ares_result_line_plaintext(result, index)),
+            )
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            (
+                1,
+                Some("the quick brown fox jumps over the lazy dog".to_string())
+            ),
+            (3, Some("meet me at the usual place at ten".to_string())),
+            (4, Some("rotate the keys every single week".to_string())),
+        ]
+    );
+    assert_eq!(ares_result_line_number(result, 3), 0);
+    assert!(ares_result_line_plaintext(result, 3).is_null());
+    ares_result_free(result);
+}
+
+#[test]
+fn nothing_found() {
+    let text = CString::new("zqxv kjhg wpfd").unwrap();
+    let regex = CString::new("^flag\\{").unwrap();
+    let opts = AresOptions {
+        timeout_ms: 2000,
+        max_depth: 2,
+        regex: regex.as_ptr(),
+    };
+    let result = unsafe { ares_crack(text.as_ptr(), &opts) };
+    assert_ne!(ares_result_status(result), AresStatus::Found);
+    assert!(ares_result_plaintext(result).is_null());
+    assert_eq!(ares_result_path_len(result), 0);
+    assert!(ares_result_path_step(result, 0).is_null());
+    ares_result_free(result);
+}
+
+#[test]
+fn each_crack_uses_its_own_options_even_at_once() {
+    // "flag{zqxv_9}" in base64, then hexadecimal, and "hello there general" in base64
+    let flag = CString::new("5a6d78685a33743663586832587a6c39").unwrap();
+    let hello = CString::new("aGVsbG8gdGhlcmUgZ2VuZXJhbA==").unwrap();
+    let regex = CString::new("^flag\\{").unwrap();
+    let crack_flag = || {
+        let opts = AresOptions {
+            timeout_ms: 0,
+            max_depth: 0,
+            regex: regex.as_ptr(),
+        };
+        let result = unsafe { ares_crack(flag.as_ptr(), &opts) };
+        let plaintext = read(ares_result_plaintext(result));
+        ares_result_free(result);
+        plaintext
+    };
+    let crack_hello = || {
+        let result = unsafe { ares_crack(hello.as_ptr(), ptr::null()) };
+        let plaintext = read(ares_result_plaintext(result));
+        ares_result_free(result);
+        plaintext
+    };
+
+    assert_eq!(crack_flag().as_deref(), Some("flag{zqxv_9}"));
+    // The regex isn't kept for the next crack, which looks for English
+    assert_eq!(crack_hello().as_deref(), Some("hello there general"));
+    std::thread::scope(|scope| {
+        let flag = scope.spawn(crack_flag);
+        let hello = scope.spawn(crack_hello);
+        assert_eq!(flag.join().unwrap().as_deref(), Some("flag{zqxv_9}"));
+        assert_eq!(
+            hello.join().unwrap().as_deref(),
+            Some("hello there general")
+        );
+    });
+}
+
+#[test]
+fn null_and_invalid_arguments_are_errors() {
+    let result = unsafe { ares_crack(ptr::null(), ptr::null()) };
+    assert_eq!(ares_result_status(result), AresStatus::NullArgument);
+    assert_eq!(
+        read(ares_result_error(result)).as_deref(),
+        Some("the text is NULL")
+    );
+    assert!(ares_result_plaintext(result).is_null());
+    ares_result_free(result);
+
+    let text = CString::new("aGVsbG8=").unwrap();
+    let regex = CString::new("(").unwrap();
+    let opts = AresOptions {
+        timeout_ms: 0,
+        max_depth: 0,
+        regex: regex.as_ptr(),
+    };
+    let result = unsafe { ares_crack(text.as_ptr(), &opts) };
+    assert_eq!(ares_result_status(result), AresStatus::InvalidArgument);
+    assert!(read(ares_result_error(result)).is_some());
+    ares_result_free(result);
+
+    // A NULL result is never one Ares gave out
+    assert_eq!(ares_result_status(ptr::null()), AresStatus::InvalidResult);
+    assert!(ares_result_plaintext(ptr::null()).is_null());
+    assert_eq!(ares_result_path_len(ptr::null()), 0);
+    assert_eq!(ares_result_lines_len(ptr::null()), 0);
+    ares_result_free(ptr::null_mut());
+}
+
+#[test]
+fn freeing_twice_does_nothing() {
+    // "hello there general" in base64
+    let text = CString::new("aGVsbG8gdGhlcmUgZ2VuZXJhbA==").unwrap();
+    let result = unsafe { ares_crack(text.as_ptr(), ptr::null()) };
+    let other = unsafe { ares_crack(text.as_ptr(), ptr::null()) };
+    ares_result_free(result);
+    ares_result_free(result);
+
+    // The freed result can be told from a live one, and the live one is untouched
+    assert_eq!(ares_result_status(result), AresStatus::InvalidResult);
+    assert!(ares_result_plaintext(result).is_null());
+    assert_eq!(ares_result_status(other), AresStatus::Found);
+    assert_eq!(
+        read(ares_result_plaintext(other)).as_deref(),
+        Some("hello there general")
+    );
+    ares_result_free(other);
+}
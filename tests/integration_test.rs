@@ -1,6 +1,14 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ares::checkers::checker_type::Checker;
+use ares::checkers::preview_checker::DEFAULT_PREVIEW_SIZE;
+use ares::checkers::regex_checker::RegexChecker;
+use ares::checkers::CheckerTypes;
 use ares::cli::read_and_parse_file;
 use ares::config::Config;
-use ares::perform_cracking;
+use ares::{perform_cracking, perform_cracking_with_checker, CrackOutcome, DEFAULT_MAX_INPUT_SIZE};
+use base64::{engine::general_purpose, Engine as _};
 
 // TODO Below fails because Library API is broken.
 // https://github.com/bee-san/Ares/issues/48
@@ -29,7 +37,7 @@ fn test_program_parses_files_and_cracks() {
     let to_crack = read_and_parse_file(file_path.to_string());
     let result = perform_cracking(&to_crack, config);
     assert_eq!(true, true);
-    assert!(result.unwrap().text[0] == "Multiple base64 encodings");
+    assert!(result.found().unwrap().plaintext == "Multiple base64 encodings");
 }
 
 #[test]
@@ -40,5 +48,194 @@ fn test_program_parses_files_with_new_line_and_cracks() {
     let to_crack = read_and_parse_file(file_path.to_string());
     let result = perform_cracking(&to_crack, config);
     assert_eq!(true, true);
-    assert!(result.unwrap().text[0] == "This is a test!");
+    assert!(result.found().unwrap().plaintext == "This is a test!");
+}
+
+#[test]
+fn test_program_cracks_gzip_inside_base64() {
+    // Plaintext was gzipped and then base64 encoded
+    let config = Config::default();
+    let result = perform_cracking(
+        "H4sIAAAAAAACAwvJyCxWAKJEhZLU4hKF/DSFkoxUhfSqzAKFlNTk/NyCotTi4sz8PIWcxMrUIkUAtdYWzC8AAAA=",
+        config,
+    );
+    assert!(result.found().unwrap().plaintext == "This is a test of the gzip decompression layer!");
+}
+
+#[test]
+fn test_program_cracks_gzip_inside_hex_as_bytes() {
+    // Plaintext was gzipped and then hex encoded, so the layer in between isn't UTF-8
+    let config = Config::default();
+    let result = perform_cracking(
+        "1f8b080000000000020373cacc4b2caa54c849ac4c2d2a56482c4a55c8cb57c8c9cf4b4f2d0252c5250a49a925e5a9a9790a29a9c9f9294035006569a38431000000",
+        config,
+    )
+    .found()
+    .unwrap();
+    assert_eq!(
+        result.plaintext,
+        "Binary layers are no longer lost between decoders"
+    );
+    assert!(result.path[0].unencrypted_bytes.is_some());
+}
+
+#[test]
+fn test_three_layers_are_cracked_through_the_library() {
+    // "This is a test!" in ROT13, then base64, then hexadecimal
+    let to_crack = read_and_parse_file("tests/test_fixtures/rot13_base64_hex_with_newline".into());
+    let outcome = perform_cracking(&to_crack, Config::default());
+    let stats = outcome.stats();
+    let result = outcome.found().unwrap();
+    assert_eq!(result.plaintext, "This is a test!");
+    let decoders: Vec<&str> = result.path.iter().map(|step| step.decoder).collect();
+    assert_eq!(decoders, ["Hexadecimal", "Base64", "Caesar Cipher"]);
+    assert_eq!(result.path[0].encrypted_text, to_crack);
+    assert_eq!(result.path[2].key.as_deref(), Some("shift 13"));
+    // Each decoder's output is the next one's input
+    for pair in result.path.windows(2) {
+        assert_eq!(
+            pair[0].unencrypted_text.as_ref().unwrap()[0],
+            pair[1].encrypted_text
+        );
+    }
+    assert!(stats.nodes_expanded >= 3, "{stats:?}");
+    assert!(stats.decoder_invocations >= stats.nodes_expanded);
+    assert_eq!(result.stats, stats);
+}
+
+#[test]
+fn an_input_over_the_size_limit_is_an_error() {
+    let started = Instant::now();
+    let outcome = perform_cracking(&"a".repeat(DEFAULT_MAX_INPUT_SIZE + 1), Config::default());
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let CrackOutcome::TooLarge(too_large) = &outcome else {
+        panic!("the input is too big to crack, but it ended with {outcome:?}");
+    };
+    assert_eq!(too_large.size, DEFAULT_MAX_INPUT_SIZE + 1);
+    assert_eq!(too_large.limit, DEFAULT_MAX_INPUT_SIZE);
+    assert!(too_large.to_string().contains("raise the limit"));
+    assert_eq!(outcome.status(), "too_large");
+    assert_eq!(outcome.stats().nodes_expanded, 0);
+}
+
+#[test]
+fn checkers_only_see_the_start_of_a_big_input_but_it_is_all_decoded() {
+    let sentence = "the quick brown fox jumps over the lazy dog. ";
+    let plaintext = sentence.repeat(500 * 1024 * 3 / 4 / sentence.len());
+    let encoded = general_purpose::STANDARD.encode(&plaintext);
+    assert!(encoded.len() > 500 * 1000);
+
+    let lengths = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&lengths);
+    let checker = CheckerTypes::from_closure("Length Recording Checker", move |text| {
+        seen.lock().unwrap().push(text.len());
+        text.starts_with(sentence)
+            .then(|| "starts with the sentence".to_string())
+    });
+    let result = perform_cracking_with_checker(&encoded, Config::default(), checker)
+        .found()
+        .unwrap();
+    assert_eq!(result.plaintext, plaintext);
+    assert_eq!(result.path[0].decoder, "Base64");
+
+    let lengths = lengths.lock().unwrap();
+    assert!(!lengths.is_empty());
+    assert!(
+        lengths.iter().all(|&length| length <= DEFAULT_PREVIEW_SIZE),
+        "{lengths:?}"
+    );
+}
+
+#[test]
+fn a_regex_anchored_to_the_end_sees_all_of_a_big_input() {
+    // The flag is past the first 16 KiB
+    let plaintext = format!("{} flag{{at_the_very_end}}", "padding ".repeat(4096));
+    let encoded = general_purpose::STANDARD.encode(&plaintext);
+    let checker =
+        CheckerTypes::CheckRegex(Checker::<RegexChecker>::with_pattern(r"flag\{[^}]*\}$").unwrap());
+    let result = perform_cracking_with_checker(&encoded, Config::default(), checker)
+        .found()
+        .unwrap();
+    assert_eq!(result.plaintext, plaintext);
+}
+
+#[test]
+fn base64_with_homoglyphs_in_it_is_cracked() {
+    // "hello there general" in base64, with the "a" and "c" swapped for Cyrillic lookalikes
+    let result = perform_cracking("аGVsbG8gdGhlсmUgZ2VuZXJhbA==", Config::default())
+        .found()
+        .unwrap();
+    assert_eq!(result.plaintext, "hello there general");
+    let decoders: Vec<&str> = result.path.iter().map(|step| step.decoder).collect();
+    assert_eq!(decoders, ["Homoglyph Normalizer", "Base64"]);
+}
+
+#[test]
+fn a_powershell_encoded_command_is_cracked() {
+    // PowerShell's -EncodedCommand is base64 of UTF-16LE
+    let command = "Write-Output 'the quick brown fox jumps over the lazy dog'";
+    let utf16: Vec<u8> = command.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let encoded = general_purpose::STANDARD.encode(utf16);
+    let result = perform_cracking(&encoded, Config::default())
+        .found()
+        .unwrap();
+    assert_eq!(result.plaintext, command);
+    let decoders: Vec<&str> = result.path.iter().map(|step| step.decoder).collect();
+    assert_eq!(decoders, ["Base64", "Wide Characters"]);
+    assert_eq!(result.path[1].key.as_deref(), Some("UTF-16LE"));
+}
+
+#[test]
+fn a_javascript_string_literal_is_cracked() {
+    let result = perform_cracking(
+        r"\u0068\u0065\u006c\u006c\u006f there \ud83d\ude00\n",
+        Config::default(),
+    )
+    .found()
+    .unwrap();
+    assert_eq!(result.plaintext, "hello there 😀\n");
+    let decoders: Vec<&str> = result.path.iter().map(|step| step.decoder).collect();
+    assert_eq!(decoders, ["Escaped String"]);
+}
+
+// "flag{zqxv_9}" in base64, then in hex
+const FLAG_IN_HEX: &str = "5a6d78685a33743663586832587a6c39";
+
+#[test]
+fn each_crack_uses_the_regex_it_is_given() {
+    let config = Config {
+        regex: Some(r"^flag\{".to_string()),
+        ..Config::default()
+    };
+    let found = perform_cracking(FLAG_IN_HEX, config).found().unwrap();
+    assert_eq!(found.plaintext, "flag{zqxv_9}");
+    assert_eq!(found.path.last().unwrap().checker_name, "Regex Checker");
+
+    // The regex from the crack before isn't kept
+    let config = Config {
+        regex: Some(r"^nothing here$".to_string()),
+        max_depth: 3,
+        timeout: Duration::from_secs(2),
+        ..Config::default()
+    };
+    let outcome = perform_cracking(FLAG_IN_HEX, config);
+    assert!(outcome.found().is_none());
+}
+
+#[test]
+fn each_crack_uses_the_limits_it_is_given() {
+    // "hello there general" in base64, twice
+    let input = "YUdWc2JHOGdkR2hsY21VZ1oyVnVaWEpoYkE9PQ==";
+    let config = Config {
+        max_depth: 1,
+        ..Config::default()
+    };
+    let outcome = perform_cracking(input, config);
+    assert!(
+        matches!(outcome, CrackOutcome::DepthLimited(_)),
+        "{outcome:?}"
+    );
+
+    let found = perform_cracking(input, Config::default()).found().unwrap();
+    assert_eq!(found.plaintext, "hello there general");
 }
@@ -0,0 +1,76 @@
+// Drives an `ares repl` session with a script, as someone at the terminal would.
+// It's a test file of its own, as the session replaces the global config.
+use ares::cli::repl::Repl;
+use ares::config::Config;
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+
+#[test]
+fn a_scripted_session() {
+    // Stops at the base64, a layer too early, and at the plaintext under it
+    let config = Config {
+        regex: Some("^aGVs|general$".to_string()),
+        ..Config::default()
+    };
+    let saved = std::env::temp_dir().join("ares_repl_session.txt");
+    let _ = std::fs::remove_file(&saved);
+    let script = format!(
+        "61475673624738676447686c636d55675a3256755a584a6862413d3d
+:set max-depth 3
+:use 1
+:save {}
+:quit
+this line is never read
+",
+        saved.display()
+    );
+    let mut repl = Repl::new(config);
+    let mut out = Vec::new();
+    repl.run(Cursor::new(script), &mut out, false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert_eq!(
+        out,
+        format!(
+            "#1 aGVsbG8gdGhlcmUgZ2VuZXJhbA==
+   Hexadecimal
+max-depth is 3
+#2 hello there general
+   Hexadecimal → Base64
+wrote 2 plaintexts to {}
+",
+            saved.display()
+        )
+    );
+    assert_eq!(repl.config().max_depth, 3);
+    assert_eq!(ares::config::get_config().max_depth, 3);
+    assert_eq!(repl.history().len(), 2);
+    assert_eq!(
+        std::fs::read_to_string(&saved).unwrap(),
+        "aGVsbG8gdGhlcmUgZ2VuZXJhbA==\nhello there general\n"
+    );
+}
+
+#[test]
+fn ares_repl_reads_stdin_without_a_prompt() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ares"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the ares binary runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"aGVsbG8gdGhlcmUgZ2VuZXJhbA==\n:set timeout 10\n")
+        .unwrap();
+    // The end of stdin ends the session, like :quit
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "#1 hello there general\n   Base64\ntimeout is 10\n"
+    );
+}
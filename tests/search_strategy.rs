@@ -0,0 +1,31 @@
+// It has a test file of its own, so other tests' cracks don't slow it down before it's gone deep
+use ares::cancellation::CancellationToken;
+use ares::config::{Config, SearchStrategy};
+use ares::perform_cracking_with_progress;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn the_search_strategy_is_taken_from_the_config() {
+    let config = Config {
+        search_strategy: SearchStrategy::DepthFirst,
+        timeout: Duration::from_secs(2),
+        ..Config::default()
+    };
+    let (sender, receiver) = mpsc::channel();
+    perform_cracking_with_progress(
+        "d649882406c3fb4b62cadfccf888b0057a66be373f933c0ad353c4c374779d19373a8abd93198421d016887461fa1b1a",
+        config,
+        CancellationToken::new(),
+        Some(Box::new(move |event| sender.send(event).unwrap())),
+    );
+    let events: Vec<_> = receiver.iter().collect();
+    // Breadth first search decodes everything the input decodes to before it goes any deeper,
+    // depth first goes straight down
+    assert!(
+        events
+            .iter()
+            .any(|event| event.depth >= 3 && event.nodes_expanded <= 10),
+        "{events:?}"
+    );
+}
@@ -0,0 +1,45 @@
+// Calls `crack` as JavaScript would.
+// Natively it's an ordinary test, and on wasm32 it runs with `wasm-pack test --node -- --no-default-features --features wasm`.
+#![cfg(feature = "serde")]
+
+use ares::wasm::crack;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn cracks_two_layers() {
+    // "hello there general" in base64, then hexadecimal
+    let json = crack(
+        "61475673624738676447686c636d55675a3256755a584a6862413d3d",
+        r#"{"timeout_ms": 10000}"#,
+    );
+    let outcome: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(outcome["status"], "found", "{json}");
+    assert_eq!(outcome["plaintext"], "hello there general");
+    let decoders: Vec<&str> = outcome["path"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|step| step["decoder"].as_str().unwrap())
+        .collect();
+    assert_eq!(decoders, ["Hexadecimal", "Base64"]);
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn words_stand_in_for_a_wordlist_file() {
+    // "hunter2" in base64, which isn't English
+    let json = crack("aHVudGVyMg==", r#"{"words": ["hunter2"], "max_depth": 3}"#);
+    let outcome: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(outcome["plaintext"], "hunter2", "{json}");
+    assert_eq!(
+        outcome["results"][0]["checker"], "Wordlist Checker",
+        "{json}"
+    );
+
+    // The options changed, so the words are forgotten
+    let json = crack("aHVudGVyMg==", r#"{"max_depth": 3}"#);
+    let outcome: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_ne!(outcome["plaintext"], "hunter2", "{json}");
+}